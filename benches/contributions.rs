@@ -0,0 +1,76 @@
+// Compares the sparse (BTreeMap) and dense (Vec + bitset) layouts of transcript
+// contributions on the loops that dominate aggregation and verification: merging
+// the contributions of two transcripts, and walking all contributions.
+//
+//     cargo bench --bench contributions
+
+use ark_bls12_381::Bls12_381 as E;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_std::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use optrand_pvss::modified_scrape::{
+    contributions::{Contributions, ContributionsLayout},
+    decomp::DecompProof,
+    errors::PVSSError,
+    share::PVSSTranscriptParticipant,
+    srs::SRS,
+};
+use optrand_pvss::ceremony::SSIG;
+use optrand_pvss::{Digest, Scalar};
+
+use rand::thread_rng;
+
+type Participant = PVSSTranscriptParticipant<E, SSIG<E>>;
+
+// Contributions of every step-th dealer of a committee of size n, in the given layout.
+fn contributions(layout: ContributionsLayout, n: usize, step: usize) -> Contributions<Participant> {
+    let rng = &mut thread_rng();
+    let srs = SRS::<E>::setup(rng).unwrap();
+    let s = Scalar::<E>::rand(rng);
+    let contribution = Participant {
+        decomp_proof: DecompProof::<E>::new((srs.g2, s, s), srs.g2.mul(s.into_repr()).into_affine(), Digest::default()),
+        signature_on_decomp: (srs.g1, s),
+        weight: 1,
+    };
+
+    let mut contributions = Contributions::with_layout(layout);
+    for id in (0..n).step_by(step) {
+        contributions.insert(id, contribution.clone());
+    }
+    contributions
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contributions");
+
+    for n in [64, 128, 256, 512, 1024].iter() {
+        for layout in [ContributionsLayout::Sparse, ContributionsLayout::Dense].iter() {
+            let a = contributions(*layout, *n, 2);
+            let b = contributions(*layout, *n, 3);
+            let name = format!("{:?}", layout);
+
+            group.bench_with_input(BenchmarkId::new(format!("union/{}", name), n), n, |bench, n| {
+                bench.iter(|| {
+                    a.union_with(&b, *n, |x, y| -> Result<_, PVSSError<E>> {
+                        Ok(Participant { weight: x.weight + y.weight, ..x.clone() })
+                    })
+                })
+            });
+
+            group.bench_with_input(BenchmarkId::new(format!("lookup/{}", name), n), n, |bench, n| {
+                bench.iter(|| (0..*n).filter(|id| a.contains_key(id)).count())
+            });
+
+            group.bench_with_input(BenchmarkId::new(format!("walk/{}", name), n), n, |bench, _| {
+                bench.iter(|| a.values().map(|x| black_box(x.weight)).sum::<u64>())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_layouts);
+criterion_main!(benches);
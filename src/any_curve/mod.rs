@@ -0,0 +1,308 @@
+use crate::modified_scrape::{
+    aggregator::PVSSAggregator,
+    config::{Config, EvaluationPoints, ShareLayout},
+    errors::PVSSError,
+    share::PVSSTranscript,
+    srs::SRS,
+};
+use crate::signature::schnorr::SchnorrSignature;
+
+use ark_bls12_377::Bls12_377;
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+use rand::{CryptoRng, Rng};
+use thiserror::Error;
+
+/* Runtime curve selection. The PVSS types are generic over the pairing engine E,
+   which forces a binary to commit to a curve at compile time. The enum wrappers
+   below fix one instantiation per supported curve and dispatch on the variant,
+   so that a single service can serve committees on different curves.
+
+   Wrapped transcripts use Schnorr keys over G_1, which double as encryption keys.
+*/
+
+// Signature scheme used by the wrapped transcripts on curve E.
+pub type SchnorrSSIG<E> = SchnorrSignature<<E as PairingEngine>::G1Affine>;
+
+// Enumeration of the curves that can be selected at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Bls12_381,
+    Bls12_377,
+    Bn254,
+}
+
+impl Curve {
+
+    // Method returning the canonical (lowercase) name of the curve.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Curve::Bls12_381 => "bls12_381",
+            Curve::Bls12_377 => "bls12_377",
+            Curve::Bn254 => "bn254",
+        }
+    }
+
+    // Function for looking up a curve by its (case-insensitive) name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bls12_381" => Some(Curve::Bls12_381),
+            "bls12_377" => Some(Curve::Bls12_377),
+            "bn254" => Some(Curve::Bn254),
+            _ => None,
+        }
+    }
+
+    // Method returning the one-byte tag prefixed to serialized wrappers.
+    fn tag(&self) -> u8 {
+        match self {
+            Curve::Bls12_381 => 0,
+            Curve::Bls12_377 => 1,
+            Curve::Bn254 => 2,
+        }
+    }
+
+    // Function for recovering a curve from its one-byte tag.
+    fn from_tag(tag: u8) -> Result<Self, AnyCurveError> {
+        match tag {
+            0 => Ok(Curve::Bls12_381),
+            1 => Ok(Curve::Bls12_377),
+            2 => Ok(Curve::Bn254),
+            _ => Err(AnyCurveError::UnknownCurveTag(tag)),
+        }
+    }
+}
+
+// Enumeration defining the errors raised by the runtime-dispatched API.
+#[derive(Error, Debug)]
+pub enum AnyCurveError {
+    #[error("Curve mismatch: expected {0:?}, found {1:?}")]
+    CurveMismatch(Curve, Curve),
+    #[error("Unknown curve tag: {0}")]
+    UnknownCurveTag(u8),
+    #[error("Empty encoding")]
+    EmptyEncoding,
+    #[error("BLS12-381: {0}")]
+    Bls12_381(PVSSError<Bls12_381>),
+    #[error("BLS12-377: {0}")]
+    Bls12_377(PVSSError<Bls12_377>),
+    #[error("BN254: {0}")]
+    Bn254(PVSSError<Bn254>),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SerializationError),
+}
+
+
+// AnyCurveConfig wraps a Config over any of the supported curves.
+#[derive(Clone)]
+pub enum AnyCurveConfig {
+    Bls12_381(Config<Bls12_381>),
+    Bls12_377(Config<Bls12_377>),
+    Bn254(Config<Bn254>),
+}
+
+impl AnyCurveConfig {
+
+    // Function for setting up a fresh configuration on the selected curve.
+    pub fn setup<R: Rng + CryptoRng>(curve: Curve, rng: &mut R, degree: usize, num_participants: usize) -> Result<Self, AnyCurveError> {
+        Ok(match curve {
+            Curve::Bls12_381 => AnyCurveConfig::Bls12_381(Config {
+                srs: SRS::setup(rng).map_err(AnyCurveError::Bls12_381)?,
+                degree,
+                num_participants,
+                layout: ShareLayout::Dense,
+                points: EvaluationPoints::Integers,
+            }),
+            Curve::Bls12_377 => AnyCurveConfig::Bls12_377(Config {
+                srs: SRS::setup(rng).map_err(AnyCurveError::Bls12_377)?,
+                degree,
+                num_participants,
+                layout: ShareLayout::Dense,
+                points: EvaluationPoints::Integers,
+            }),
+            Curve::Bn254 => AnyCurveConfig::Bn254(Config {
+                srs: SRS::setup(rng).map_err(AnyCurveError::Bn254)?,
+                degree,
+                num_participants,
+                layout: ShareLayout::Dense,
+                points: EvaluationPoints::Integers,
+            }),
+        })
+    }
+
+    // Method returning the curve underlying the wrapped configuration.
+    pub fn curve(&self) -> Curve {
+        match self {
+            AnyCurveConfig::Bls12_381(_) => Curve::Bls12_381,
+            AnyCurveConfig::Bls12_377(_) => Curve::Bls12_377,
+            AnyCurveConfig::Bn254(_) => Curve::Bn254,
+        }
+    }
+
+    // Method returning the polynomial degree (t).
+    pub fn degree(&self) -> usize {
+        match self {
+            AnyCurveConfig::Bls12_381(config) => config.degree,
+            AnyCurveConfig::Bls12_377(config) => config.degree,
+            AnyCurveConfig::Bn254(config) => config.degree,
+        }
+    }
+
+    // Method returning the total number of participants (n).
+    pub fn num_participants(&self) -> usize {
+        match self {
+            AnyCurveConfig::Bls12_381(config) => config.num_participants,
+            AnyCurveConfig::Bls12_377(config) => config.num_participants,
+            AnyCurveConfig::Bn254(config) => config.num_participants,
+        }
+    }
+}
+
+
+// AnyCurveTranscript wraps an aggregated PVSS transcript over any of the supported curves.
+#[derive(Clone)]
+pub enum AnyCurveTranscript {
+    Bls12_381(PVSSTranscript<Bls12_381, SchnorrSSIG<Bls12_381>>),
+    Bls12_377(PVSSTranscript<Bls12_377, SchnorrSSIG<Bls12_377>>),
+    Bn254(PVSSTranscript<Bn254, SchnorrSSIG<Bn254>>),
+}
+
+impl AnyCurveTranscript {
+
+    // Function for generating an empty transcript w.r.t. the given configuration.
+    pub fn empty(config: &AnyCurveConfig) -> Self {
+        let (degree, num_participants) = (config.degree(), config.num_participants());
+        match config.curve() {
+            Curve::Bls12_381 => AnyCurveTranscript::Bls12_381(PVSSTranscript::empty(degree, num_participants)),
+            Curve::Bls12_377 => AnyCurveTranscript::Bls12_377(PVSSTranscript::empty(degree, num_participants)),
+            Curve::Bn254 => AnyCurveTranscript::Bn254(PVSSTranscript::empty(degree, num_participants)),
+        }
+    }
+
+    // Method returning the curve underlying the wrapped transcript.
+    pub fn curve(&self) -> Curve {
+        match self {
+            AnyCurveTranscript::Bls12_381(_) => Curve::Bls12_381,
+            AnyCurveTranscript::Bls12_377(_) => Curve::Bls12_377,
+            AnyCurveTranscript::Bn254(_) => Curve::Bn254,
+        }
+    }
+
+    // Method for serializing the transcript as its curve tag followed by its
+    // canonical encoding.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, AnyCurveError> {
+        let mut bytes = vec![self.curve().tag()];
+        match self {
+            AnyCurveTranscript::Bls12_381(transcript) => transcript.serialize(&mut bytes)?,
+            AnyCurveTranscript::Bls12_377(transcript) => transcript.serialize(&mut bytes)?,
+            AnyCurveTranscript::Bn254(transcript) => transcript.serialize(&mut bytes)?,
+        }
+        Ok(bytes)
+    }
+
+    // Function for deserializing a transcript produced by to_bytes, selecting
+    // the curve from the leading tag.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AnyCurveError> {
+        let (tag, rest) = bytes.split_first().ok_or(AnyCurveError::EmptyEncoding)?;
+        Ok(match Curve::from_tag(*tag)? {
+            Curve::Bls12_381 => AnyCurveTranscript::Bls12_381(PVSSTranscript::deserialize(rest)?),
+            Curve::Bls12_377 => AnyCurveTranscript::Bls12_377(PVSSTranscript::deserialize(rest)?),
+            Curve::Bn254 => AnyCurveTranscript::Bn254(PVSSTranscript::deserialize(rest)?),
+        })
+    }
+}
+
+
+// AnyCurveAggregator wraps a PVSS aggregator over any of the supported curves.
+pub enum AnyCurveAggregator {
+    Bls12_381(PVSSAggregator<Bls12_381, SchnorrSSIG<Bls12_381>>),
+    Bls12_377(PVSSAggregator<Bls12_377, SchnorrSSIG<Bls12_377>>),
+    Bn254(PVSSAggregator<Bn254, SchnorrSSIG<Bn254>>),
+}
+
+impl AnyCurveAggregator {
+
+    // Method returning the curve underlying the wrapped aggregator.
+    pub fn curve(&self) -> Curve {
+        match self {
+            AnyCurveAggregator::Bls12_381(_) => Curve::Bls12_381,
+            AnyCurveAggregator::Bls12_377(_) => Curve::Bls12_377,
+            AnyCurveAggregator::Bn254(_) => Curve::Bn254,
+        }
+    }
+
+    // Method for verifying a transcript, provided that it is over the same curve
+    // as the aggregator.
+    pub fn transcript_verify<R: Rng + CryptoRng>(&self, rng: &mut R, transcript: &AnyCurveTranscript) -> Result<(), AnyCurveError> {
+        match (self, transcript) {
+            (AnyCurveAggregator::Bls12_381(aggregator), AnyCurveTranscript::Bls12_381(transcript)) =>
+                aggregator.transcript_verify(rng, transcript).map_err(AnyCurveError::Bls12_381),
+            (AnyCurveAggregator::Bls12_377(aggregator), AnyCurveTranscript::Bls12_377(transcript)) =>
+                aggregator.transcript_verify(rng, transcript).map_err(AnyCurveError::Bls12_377),
+            (AnyCurveAggregator::Bn254(aggregator), AnyCurveTranscript::Bn254(transcript)) =>
+                aggregator.transcript_verify(rng, transcript).map_err(AnyCurveError::Bn254),
+            _ => Err(AnyCurveError::CurveMismatch(self.curve(), transcript.curve())),
+        }
+    }
+
+    // Method for verifying and aggregating a transcript, provided that it is over
+    // the same curve as the aggregator.
+    pub fn receive_transcript<R: Rng + CryptoRng>(&mut self, rng: &mut R, transcript: &AnyCurveTranscript) -> Result<(), AnyCurveError> {
+        let curve = self.curve();
+        match (self, transcript) {
+            (AnyCurveAggregator::Bls12_381(aggregator), AnyCurveTranscript::Bls12_381(transcript)) =>
+                aggregator.receive_transcript(rng, transcript).map_err(AnyCurveError::Bls12_381),
+            (AnyCurveAggregator::Bls12_377(aggregator), AnyCurveTranscript::Bls12_377(transcript)) =>
+                aggregator.receive_transcript(rng, transcript).map_err(AnyCurveError::Bls12_377),
+            (AnyCurveAggregator::Bn254(aggregator), AnyCurveTranscript::Bn254(transcript)) =>
+                aggregator.receive_transcript(rng, transcript).map_err(AnyCurveError::Bn254),
+            _ => Err(AnyCurveError::CurveMismatch(curve, transcript.curve())),
+        }
+    }
+
+    // Method returning a copy of the aggregator's current transcript.
+    pub fn transcript(&self) -> AnyCurveTranscript {
+        match self {
+            AnyCurveAggregator::Bls12_381(aggregator) => AnyCurveTranscript::Bls12_381(aggregator.transcript.clone()),
+            AnyCurveAggregator::Bls12_377(aggregator) => AnyCurveTranscript::Bls12_377(aggregator.transcript.clone()),
+            AnyCurveAggregator::Bn254(aggregator) => AnyCurveTranscript::Bn254(aggregator.transcript.clone()),
+        }
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::any_curve::{AnyCurveConfig, AnyCurveTranscript, Curve};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_transcript_roundtrip_all_curves() {
+        let rng = &mut thread_rng();
+
+        for curve in [Curve::Bls12_381, Curve::Bls12_377, Curve::Bn254].iter() {
+            let config = AnyCurveConfig::setup(*curve, rng, 3, 10).unwrap();
+            let transcript = AnyCurveTranscript::empty(&config);
+
+            let bytes = transcript.to_bytes().unwrap();
+            let decoded = AnyCurveTranscript::from_bytes(&bytes).unwrap();
+
+            assert_eq!(decoded.curve(), *curve);
+            assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_curve_names() {
+        for curve in [Curve::Bls12_381, Curve::Bls12_377, Curve::Bn254].iter() {
+            assert_eq!(Curve::from_name(curve.name()), Some(*curve));
+        }
+        assert_eq!(Curve::from_name("secp256k1"), None);
+    }
+}
@@ -0,0 +1,22 @@
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while reading or writing the epoch archive.
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Epoch {0} is not after the latest archived epoch {1}")]
+    NonIncreasingEpoch(u64, u64),
+    #[error("Epoch {0} is not in the archive")]
+    MissingEpoch(u64),
+    #[error("Epoch {0} only retains its digests")]
+    PrunedEpoch(u64),
+    #[error("Entry of epoch {0} is corrupt")]
+    CorruptEntry(u64),
+    #[error("Entry of epoch {0} does not extend the hash chain")]
+    BrokenChain(u64),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SerializationError),
+}
@@ -0,0 +1,377 @@
+use crate::archive::errors::ArchiveError;
+use crate::beacon::proof::BeaconProof;
+use crate::modified_scrape::finalized::FinalizedTranscript;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{digest_bytes, Digest, GT, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::BTreeMap;
+
+use std::fs::{self, File, OpenOptions};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+
+pub mod errors;
+
+/* Persistent archive of the outcome of each epoch: the finalized transcript (along
+   with the signatures certifying it) and the beacon with its proof. Every epoch is
+   stored in its own append-only file in the archive's directory:
+
+   header: magic (8 bytes) || epoch || prev || body digest || body length || pruned flag
+   body:   canonical serialization of the ArchiveEntry (absent once pruned)
+
+   where prev is the digest of the previous epoch's header (all zeroes for the first
+   epoch), so that the headers form a hash chain. The pruned flag is not covered by
+   the header digest, so that bodies can be dropped without breaking the chain. The
+   chain and the bodies are verified whenever the archive is opened; after older
+   epochs have been deleted, the oldest remaining header anchors the chain.
+*/
+
+const MAGIC: &[u8; 8] = b"OPTRARCH";                       // marks archive files
+const HEADER_LENGTH: usize = 8 + 8 + 32 + 32 + 8 + 1;     // magic || epoch || prev || body digest || body length || flag
+const EXTENSION: &str = "epoch";                           // extension of archive files
+
+// Enumeration PruningPolicy selects which epochs the archive retains in full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PruningPolicy {
+    KeepAll,               // retain every epoch
+    KeepLast(u64),         // retain the last k epochs, deleting older ones
+    KeepDigestsOnly(u64),  // retain the last k epochs, and only the headers of older ones
+}
+
+// Struct ArchiveEntry holds everything archived for an epoch.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct ArchiveEntry<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub finalized: FinalizedTranscript<E, SSIG>,   // the certified transcript of the epoch
+    pub beacon: GT<E>,                             // the epoch's beacon value
+    pub proof: BeaconProof<E>,                     // proof of the beacon against the transcript
+}
+
+// Struct EntryHeader models the header of an archived epoch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryHeader {
+    pub epoch: u64,            // the archived epoch
+    pub prev: Digest,          // digest of the previous epoch's header
+    pub body_digest: Digest,   // digest of the body
+    pub body_length: u64,      // length of the body in bytes
+    pub pruned: bool,          // whether the body was dropped
+}
+
+impl EntryHeader {
+
+    // Method returning the digest chaining the header to the next one.
+    pub fn digest(&self) -> Digest {
+        digest_bytes(&self.to_bytes()[..HEADER_LENGTH - 1])
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&self.prev.0);
+        bytes.extend_from_slice(&self.body_digest.0);
+        bytes.extend_from_slice(&self.body_length.to_be_bytes());
+        bytes.push(self.pruned as u8);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8], epoch: u64) -> Result<Self, ArchiveError> {
+        if bytes.len() < HEADER_LENGTH || &bytes[..8] != MAGIC || bytes[HEADER_LENGTH - 1] > 1 {
+            return Err(ArchiveError::CorruptEntry(epoch));
+        }
+
+        let mut word = [0u8; 8];
+        let mut prev = [0u8; 32];
+        let mut body_digest = [0u8; 32];
+        word.copy_from_slice(&bytes[8..16]);
+        let header_epoch = u64::from_be_bytes(word);
+        prev.copy_from_slice(&bytes[16..48]);
+        body_digest.copy_from_slice(&bytes[48..80]);
+        word.copy_from_slice(&bytes[80..88]);
+
+        if header_epoch != epoch {
+            return Err(ArchiveError::CorruptEntry(epoch));
+        }
+
+        Ok(Self {
+            epoch,
+            prev: Digest(prev),
+            body_digest: Digest(body_digest),
+            body_length: u64::from_be_bytes(word),
+            pruned: bytes[HEADER_LENGTH - 1] == 1,
+        })
+    }
+}
+
+// Struct Archive models an epoch archive stored in a directory.
+pub struct Archive<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    dir: PathBuf,                               // directory holding the archive files
+    pub policy: PruningPolicy,                  // which epochs are retained in full
+    headers: BTreeMap<u64, EntryHeader>,        // headers of the archived epochs
+    entry_type: PhantomData<(E, SSIG)>,
+}
+
+impl<E, SSIG> Archive<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for opening (or creating) the archive in dir. The headers, the chain
+    // they form and the bodies still retained are all verified.
+    pub fn open<P: AsRef<Path>>(dir: P, policy: PruningPolicy) -> Result<Self, ArchiveError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut headers = BTreeMap::new();
+        for file in fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != EXTENSION) {
+                continue;
+            }
+            let epoch = match path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse::<u64>().ok()) {
+                Some(epoch) => epoch,
+                None => continue,
+            };
+
+            let (header, _) = Self::read_file(&path, epoch)?;
+            headers.insert(epoch, header);
+        }
+
+        // Every header must link to its predecessor.
+        let mut prev: Option<&EntryHeader> = None;
+        for header in headers.values() {
+            if prev.is_some_and(|prev| header.prev != prev.digest()) {
+                return Err(ArchiveError::BrokenChain(header.epoch));
+            }
+            prev = Some(header);
+        }
+
+        let mut archive = Self { dir, policy, headers, entry_type: PhantomData };
+        archive.prune()?;
+        Ok(archive)
+    }
+
+    // Utility method returning the path of the file of the given epoch.
+    fn path(&self, epoch: u64) -> PathBuf {
+        self.dir.join(format!("{:020}.{}", epoch, EXTENSION))
+    }
+
+    // Utility function reading and checking an archive file, returning its header and body.
+    fn read_file(path: &Path, epoch: u64) -> Result<(EntryHeader, Vec<u8>), ArchiveError> {
+        let bytes = fs::read(path)?;
+        let header = EntryHeader::from_bytes(&bytes, epoch)?;
+        let body = bytes[HEADER_LENGTH..].to_vec();
+
+        let intact = if header.pruned {
+            body.is_empty()
+        } else {
+            body.len() as u64 == header.body_length && digest_bytes(&body) == header.body_digest
+        };
+        if !intact {
+            return Err(ArchiveError::CorruptEntry(epoch));
+        }
+
+        Ok((header, body))
+    }
+
+    // Utility function atomically writing an archive file.
+    fn write_file(path: &Path, bytes: &[u8]) -> Result<(), ArchiveError> {
+        let tmp = path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            file.write_all(bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    // Method returning the digest of the latest header, i.e., the head of the chain.
+    pub fn head(&self) -> Option<Digest> {
+        self.headers.values().next_back().map(|header| header.digest())
+    }
+
+    // Method returning the latest archived epoch.
+    pub fn latest_epoch(&self) -> Option<u64> {
+        self.headers.keys().next_back().cloned()
+    }
+
+    // Method returning the headers of the archived epochs within range.
+    pub fn headers<R: RangeBounds<u64>>(&self, range: R) -> Vec<&EntryHeader> {
+        self.headers.range(range).map(|(_, header)| header).collect()
+    }
+
+    // Method for appending the entry of an epoch later than all archived ones.
+    // Returns the new head of the chain.
+    pub fn append(&mut self, entry: &ArchiveEntry<E, SSIG>) -> Result<Digest, ArchiveError> {
+        let epoch = entry.finalized.epoch;
+        if let Some(latest) = self.latest_epoch() {
+            if epoch <= latest {
+                return Err(ArchiveError::NonIncreasingEpoch(epoch, latest));
+            }
+        }
+
+        let mut body = Vec::with_capacity(entry.serialized_size());
+        entry.serialize(&mut body)?;
+
+        let header = EntryHeader {
+            epoch,
+            prev: self.head().unwrap_or_default(),
+            body_digest: digest_bytes(&body),
+            body_length: body.len() as u64,
+            pruned: false,
+        };
+
+        // Archive files are never overwritten with new content.
+        let mut file = OpenOptions::new().write(true).create_new(true).open(self.path(epoch))?;
+        file.write_all(&header.to_bytes())?;
+        file.write_all(&body)?;
+        file.sync_all()?;
+
+        let head = header.digest();
+        self.headers.insert(epoch, header);
+        self.prune()?;
+
+        Ok(head)
+    }
+
+    // Method returning the entry of an epoch, provided its body is still retained.
+    pub fn get(&self, epoch: u64) -> Result<ArchiveEntry<E, SSIG>, ArchiveError> {
+        let header = self.headers.get(&epoch).ok_or(ArchiveError::MissingEpoch(epoch))?;
+        if header.pruned {
+            return Err(ArchiveError::PrunedEpoch(epoch));
+        }
+
+        let (read, body) = Self::read_file(&self.path(epoch), epoch)?;
+        if read != *header {
+            return Err(ArchiveError::CorruptEntry(epoch));
+        }
+
+        Ok(ArchiveEntry::deserialize(&body[..])?)
+    }
+
+    // Method returning the entries of the epochs within range whose bodies are retained.
+    pub fn range<R: RangeBounds<u64>>(&self, range: R) -> Result<Vec<ArchiveEntry<E, SSIG>>, ArchiveError> {
+        self.headers
+            .range(range)
+            .filter(|(_, header)| !header.pruned)
+            .map(|(epoch, _)| self.get(*epoch))
+            .collect()
+    }
+
+    // Utility method applying the pruning policy to the epochs before the last k.
+    fn prune(&mut self) -> Result<(), ArchiveError> {
+        let (k, delete) = match self.policy {
+            PruningPolicy::KeepAll => return Ok(()),
+            PruningPolicy::KeepLast(k) => (k, true),
+            PruningPolicy::KeepDigestsOnly(k) => (k, false),
+        };
+
+        let expired = self.headers
+            .keys()
+            .rev()
+            .skip(k as usize)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for epoch in expired {
+            let path = self.path(epoch);
+            if delete {
+                fs::remove_file(&path)?;
+                self.headers.remove(&epoch);
+                continue;
+            }
+
+            let header = self.headers.get_mut(&epoch).ok_or(ArchiveError::MissingEpoch(epoch))?;
+            if !header.pruned {
+                header.pruned = true;
+                Self::write_file(&path, &header.to_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::AffineCurve;
+
+    use crate::archive::{errors::ArchiveError, Archive, ArchiveEntry, PruningPolicy};
+    use crate::beacon::proof::BeaconProof;
+    use crate::ceremony::{run_local, SSIG};
+
+    use rand::thread_rng;
+    use std::{env, fs};
+
+    #[test]
+    fn test_archive() {
+        let rng = &mut thread_rng();
+        let dir = env::temp_dir().join(format!("optrand-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let (finalized, _, beacon) = run_local::<E, _>(3, 1, rng).unwrap();
+        let proof = BeaconProof {
+            point: <E as ark_ec::PairingEngine>::G1Affine::prime_subgroup_generator(),
+            commitment: <E as ark_ec::PairingEngine>::G2Affine::prime_subgroup_generator(),
+        };
+        let entry = |epoch| {
+            let mut finalized = finalized.clone();
+            finalized.epoch = epoch;
+            ArchiveEntry::<E, SSIG<E>> { finalized, beacon, proof: proof.clone() }
+        };
+
+        // Append four epochs; all but the last two keep their digests only.
+        let head = {
+            let mut archive = Archive::<E, SSIG<E>>::open(&dir, PruningPolicy::KeepDigestsOnly(2)).unwrap();
+            for epoch in 1..5 {
+                archive.append(&entry(epoch)).unwrap();
+            }
+            assert!(matches!(archive.append(&entry(4)), Err(ArchiveError::NonIncreasingEpoch(4, 4))));
+            archive.head().unwrap()
+        };
+
+        // The archive survives a restart, with its chain intact.
+        let mut archive = Archive::<E, SSIG<E>>::open(&dir, PruningPolicy::KeepDigestsOnly(2)).unwrap();
+        assert_eq!(archive.head(), Some(head));
+        assert_eq!(archive.headers(..).len(), 4);
+        assert!(matches!(archive.get(1), Err(ArchiveError::PrunedEpoch(1))));
+        let entries = archive.range(2..=4).unwrap();
+        assert_eq!(entries.iter().map(|e| e.finalized.epoch).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(archive.get(4).unwrap().beacon, beacon);
+
+        // Keeping only the last epoch deletes the others; the chain remains anchored.
+        archive.policy = PruningPolicy::KeepLast(1);
+        archive.append(&entry(5)).unwrap();
+        drop(archive);
+        let archive = Archive::<E, SSIG<E>>::open(&dir, PruningPolicy::KeepAll).unwrap();
+        assert_eq!(archive.headers(..).len(), 1);
+        assert_eq!(archive.latest_epoch(), Some(5));
+
+        // Tampering with a body is detected on open.
+        let path = dir.join(format!("{:020}.epoch", 5));
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, bytes).unwrap();
+        assert!(matches!(
+            Archive::<E, SSIG<E>>::open(&dir, PruningPolicy::KeepAll),
+            Err(ArchiveError::CorruptEntry(5))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
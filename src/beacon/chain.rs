@@ -0,0 +1,106 @@
+use crate::modified_scrape::{config::Config, errors::PVSSError};
+use crate::GT;
+
+use ark_ec::PairingEngine;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+
+pub use super::proof::BeaconProof;
+
+/* A chain of verified beacon values, one per epoch. The protocol driver publishes
+   each epoch's beacon along with its proof, and downstream services (lotteries,
+   leader election, ...) subscribe to an async stream of the values that passed
+   verification, instead of reaching into aggregator internals.
+*/
+
+pub type Epoch = u64;
+pub type BeaconValue<E> = GT<E>;
+pub type ChainEntry<E> = (Epoch, BeaconValue<E>, BeaconProof<E>);   // a verified beacon, as published
+
+// Struct BeaconChain keeps the latest verified beacon and feeds subscribers.
+pub struct BeaconChain<E: PairingEngine> {
+    pub config: Config<E>,
+    latest: Option<ChainEntry<E>>,
+    subscribers: Vec<UnboundedSender<ChainEntry<E>>>,
+}
+
+impl<E: PairingEngine> BeaconChain<E> {
+
+    // Function for creating an empty chain under some configuration.
+    pub fn new(config: Config<E>) -> Self {
+        Self {
+            config,
+            latest: None,
+            subscribers: vec![],
+        }
+    }
+
+    // Method returning a stream of all beacon values published from now on.
+    pub fn subscribe(&mut self) -> UnboundedReceiver<ChainEntry<E>> {
+        let (sender, receiver) = unbounded();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    // Method returning the latest verified beacon, if any.
+    pub fn latest(&self) -> Option<&ChainEntry<E>> {
+        self.latest.as_ref()
+    }
+
+    // Method used by the protocol driver to publish an epoch's beacon. The beacon is
+    // verified and epochs must strictly increase; subscribers that went away are dropped.
+    pub fn publish(&mut self, epoch: Epoch, beacon: BeaconValue<E>, proof: BeaconProof<E>) -> Result<(), PVSSError<E>> {
+        if let Some((latest, _, _)) = &self.latest {
+            if epoch <= *latest {
+                return Err(PVSSError::StaleEpochError(epoch, *latest));
+            }
+        }
+
+        proof.verify(&self.config, &beacon)?;
+
+        let item = (epoch, beacon, proof);
+        self.subscribers.retain(|s| s.unbounded_send(item.clone()).is_ok());
+        self.latest = Some(item);
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+
+    use crate::beacon::chain::{BeaconChain, BeaconProof};
+    use crate::modified_scrape::{config::{Config, EvaluationPoints, ShareLayout}, srs::SRS};
+    use crate::Scalar;
+
+    use futures::{executor::block_on, StreamExt};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_subscribe() {
+        let rng = &mut thread_rng();
+        let config = Config { srs: SRS::<E>::setup(rng).unwrap(), degree: 1, num_participants: 4, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+        let mut chain = BeaconChain::new(config.clone());
+        let mut stream = chain.subscribe();
+
+        let s = Scalar::<E>::rand(rng).into_repr();
+        let proof = BeaconProof::<E> {
+            point: config.srs.g1.mul(s).into_affine(),
+            commitment: config.srs.g2.mul(s).into_affine(),
+        };
+        let beacon = E::pairing(proof.point, config.srs.g2_prime);
+
+        // an invalid beacon is not published
+        assert!(chain.publish(1, beacon * beacon, proof.clone()).is_err());
+
+        chain.publish(1, beacon, proof).unwrap();
+        let (epoch, value, _) = block_on(stream.next()).unwrap();
+        assert_eq!((epoch, value), (1, beacon));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::beacon::proof::BeaconProof;
+use crate::modified_scrape::{config::Config, errors::PVSSError, escrow::committee_public_key, share::PVSSTranscript};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Digest, Hash, GT, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::*;
+use blake2s_simd::Params;
+
+const PERSONALIZATION: &[u8] = b"OPTRCOMB";   // persona for combining beacons of several committees
+
+/* Combination of the beacons of several independent committees into a single
+   32-byte value, by hashing all of them (in order, each prefixed by its position and
+   the total count) under a dedicated persona. The combined value is unpredictable
+   and unbiasable as long as at least one of the committees is honest, so mixing
+   outputs lets applications hedge against the compromise of any single committee.
+*/
+
+// Function for combining the beacon values of several committees.
+pub fn combine_beacons<E: PairingEngine>(beacons: &[GT<E>]) -> Result<Digest, PVSSError<E>> {
+    if beacons.is_empty() {
+        return Err(PVSSError::CombinedBeaconError);
+    }
+
+    let mut bytes = (beacons.len() as u64).to_be_bytes().to_vec();
+    for (i, beacon) in beacons.iter().enumerate() {
+        bytes.extend_from_slice(&(i as u64).to_be_bytes());
+        beacon.serialize(&mut bytes)?;
+    }
+
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(PERSONALIZATION)
+        .to_state()
+        .update(&bytes)
+        .finalize();
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(hash.as_bytes());
+    Ok(Digest(digest))
+}
+
+// Struct CommitteeBeacon records one committee's part in a combined beacon.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CommitteeBeacon<E: PairingEngine> {
+    pub transcript_digest: Digest,   // digest of the committee's transcript
+    pub beacon: GT<E>,               // the committee's beacon value
+    pub proof: BeaconProof<E>,       // proof of the beacon against the transcript
+}
+
+// Struct CombinedBeaconProof allows anyone holding the committees' transcripts (or
+// just their digests and configs) to check a combined beacon value.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CombinedBeaconProof<E: PairingEngine> {
+    pub committees: Vec<CommitteeBeacon<E>>,   // the committees' parts, in combination order
+}
+
+impl<E: PairingEngine> CombinedBeaconProof<E> {
+
+    // Method returning the combined beacon value the proof attests to.
+    pub fn combined(&self) -> Result<Digest, PVSSError<E>> {
+        combine_beacons::<E>(&self.committees.iter().map(|c| c.beacon).collect::<Vec<_>>())
+    }
+
+    // Method for verifying a combined beacon value, given each committee's config and
+    // transcript (in combination order): every transcript must match its digest and the
+    // commitment of its beacon proof, every beacon must verify against its proof, and
+    // the beacons must combine into the given value.
+    pub fn verify<SSIG>(
+        &self,
+        configs: &[Config<E>],
+        transcripts: &[PVSSTranscript<E, SSIG>],
+        combined: &Digest,
+    ) -> Result<(), PVSSError<E>>
+    where
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        if configs.len() != self.committees.len() || transcripts.len() != self.committees.len() {
+            return Err(PVSSError::CombinedBeaconError);
+        }
+
+        for ((committee, config), transcript) in self.committees.iter().zip(configs.iter()).zip(transcripts.iter()) {
+            if transcript.digest() != committee.transcript_digest
+                || committee_public_key(transcript) != committee.proof.commitment {
+                return Err(PVSSError::CombinedBeaconError);
+            }
+            committee.proof.verify(config, &committee.beacon)?;
+        }
+
+        if self.combined()? != *combined {
+            return Err(PVSSError::CombinedBeaconError);
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::beacon::{combine::{combine_beacons, CombinedBeaconProof, CommitteeBeacon}, proof::BeaconProof};
+    use crate::modified_scrape::tests::setup_nodes;
+    use crate::Hash;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_combine_beacons() {
+        let rng = &mut thread_rng();
+        let (n, t) = (3, 1);
+
+        // Two independent committees each produce a beacon.
+        let mut configs = vec![];
+        let mut transcripts = vec![];
+        let mut committees = vec![];
+        for _ in 0..2 {
+            let (mut nodes, _) = setup_nodes(n, t);
+            let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+            for node in nodes.iter_mut() {
+                for share in shares.iter() {
+                    node.receive_share_and_decrypt(rng, share.clone()).unwrap();
+                }
+            }
+            let decryptions = nodes
+                .iter()
+                .map(|node| node.decrypt(rng, &nodes[0].aggregator.transcript).unwrap())
+                .collect::<Vec<_>>();
+            let (point, beacon) = nodes[0].reconstruct(&decryptions).unwrap();
+
+            let transcript = nodes[0].aggregator.transcript.clone();
+            committees.push(CommitteeBeacon {
+                transcript_digest: transcript.digest(),
+                beacon,
+                proof: BeaconProof::new(point, &transcript),
+            });
+            configs.push(nodes[0].aggregator.config.clone());
+            transcripts.push(transcript);
+        }
+
+        let proof = CombinedBeaconProof { committees };
+        let combined = proof.combined().unwrap();
+        proof.verify(&configs, &transcripts, &combined).unwrap();
+
+        // The combination depends on the order of the beacons, and on all of them.
+        let beacons = proof.committees.iter().map(|c| c.beacon).collect::<Vec<_>>();
+        assert_ne!(combine_beacons::<E>(&[beacons[1], beacons[0]]).unwrap(), combined);
+        assert_ne!(combine_beacons::<E>(&beacons[..1]).unwrap(), combined);
+        assert!(combine_beacons::<E>(&[]).is_err());
+
+        // Swapped transcripts are caught.
+        transcripts.swap(0, 1);
+        assert!(proof.verify(&configs, &transcripts, &combined).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use crate::modified_scrape::{config::Config, errors::PVSSError, poly::lagrange_coefficients};
+use crate::secrets::SecretScalar;
+use crate::signature::utils::hash::{hash_to_field, hash_to_group};
+use crate::{Digest, GT, Scalar};
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_poly::Radix2EvaluationDomain;
+use ark_serialize::*;
+use ark_std::collections::BTreeMap;
+use blake2s_simd::Params;
+use zeroize::Zeroize;
+
+const GENERATOR_PERSONALIZATION: &[u8] = b"OPTREPCH";   // persona for deriving the epoch generators
+const OUTPUT_PERSONALIZATION: &[u8] = b"OPTRBOUT";      // persona for hashing beacon values into outputs
+const NONCE_PERSONALIZATION: &[u8] = b"OPTRCNON";       // persona for deriving the nonces of contribution proofs
+const CHALLENGE_PERSONALIZATION: &[u8] = b"OPTRCCHL";   // persona for the challenges of contribution proofs
+
+/* The Optrand epoch loop. Once a transcript has been agreed upon, each node i holds
+   its decrypted share SK_i = g_1^{p(i)} of the committee secret g_1^s, and reuses it
+   in every epoch without ever revealing it. In epoch e:
+
+       g_e     := hash_to_group(persona, e)                  (in G2)
+       sigma_i := e(SK_i, g_e)                               (released by node i)
+       sigma   := prod_{i in I} sigma_i^{lambda_i} = e(g_1^s, g_e)   for |I| = t + 1
+       output  := H(e || sigma)
+
+   where the lambda_i are the Lagrange coefficients at 0 of the points in I. The
+   output is unpredictable until t + 1 nodes have released their contributions.
+
+   Each contribution carries a proof that sigma_i = e(X, g_e) for the X such that
+   e(X, g_2) = e(g_1, comm_i), where comm_i = g_2^{p(i)} is the transcript's
+   commitment to node i's share; since pairing with g_2 is injective, X = SK_i. The
+   proof is a Fiat-Shamir transformed Schnorr proof for the map X -> (e(X, g_2),
+   e(X, g_e)), with nonce R = g_1^r, challenge c and response Z = R + c * SK_i:
+
+       e(Z, g_2) == e(R, g_2) * e(g_1, comm_i)^c
+       e(Z, g_e) == e(R, g_e) * sigma_i^c
+
+   so that bad contributions are identified (see EpochContribution::verify), rather
+   than merely exposed once they disagree with t + 1 others (see EpochBeacon::output).
+*/
+
+// Struct BeaconOutput models the 32-byte randomness output of an epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaconOutput {
+    pub epoch: u64,       // the epoch the output belongs to
+    pub value: Digest,    // the output itself
+}
+
+// Type ContributionProof models the (challenge, response) proof of a contribution.
+pub type ContributionProof<E> = (Scalar<E>, <E as PairingEngine>::G1Affine);
+
+// Struct EpochContribution models a node's contribution to an epoch's beacon.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct EpochContribution<E: PairingEngine> {
+    pub epoch: u64,                      // the epoch contributed to
+    pub origin: usize,                   // id of the contributing node
+    pub sigma: GT<E>,                    // e(SK_origin, g_epoch)
+    pub proof: ContributionProof<E>,     // proof that sigma is computed from SK_origin
+}
+
+// Function deriving the generator of an epoch.
+pub fn epoch_generator<E: PairingEngine>(epoch: u64) -> Result<E::G2Affine, PVSSError<E>> {
+    Ok(hash_to_group::<E::G2Affine>(GENERATOR_PERSONALIZATION, &epoch.to_be_bytes())?.into_affine())
+}
+
+// Function hashing an epoch's beacon value into its output.
+pub fn beacon_output<E: PairingEngine>(epoch: u64, sigma: &GT<E>) -> Result<BeaconOutput, PVSSError<E>> {
+    let mut bytes = epoch.to_be_bytes().to_vec();
+    sigma.serialize(&mut bytes)?;
+
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(OUTPUT_PERSONALIZATION)
+        .to_state()
+        .update(&bytes)
+        .finalize();
+    let mut value = [0u8; 32];
+    value.copy_from_slice(hash.as_bytes());
+    Ok(BeaconOutput { epoch, value: Digest(value) })
+}
+
+// Utility function interpolating values in GT, given as (point, value) pairs, and
+// evaluating the result at x in the exponent.
+fn interpolate_in_gt<E: PairingEngine>(points: &[(Scalar<E>, GT<E>)], x: Scalar<E>) -> Result<GT<E>, PVSSError<E>> {
+    let xs = points.iter().map(|(point, _)| *point).collect::<Vec<_>>();
+    let lambdas = lagrange_coefficients::<E>(&xs, x)?;
+
+    let mut result = GT::<E>::one();
+    for ((_, value), lambda) in points.iter().zip(lambdas.iter()) {
+        result *= value.pow(lambda.into_repr());
+    }
+    Ok(result)
+}
+
+// Utility function computing the challenge of a contribution proof, given the values
+// e(g_1, comm_origin), sigma, e(R, g_2) and e(R, g_e).
+fn contribution_challenge<E: PairingEngine>(epoch: u64, origin: usize, values: &[&GT<E>]) -> Result<Scalar<E>, PVSSError<E>> {
+    let mut bytes = epoch.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&(origin as u64).to_be_bytes());
+    for value in values.iter() {
+        value.serialize(&mut bytes)?;
+    }
+    Ok(hash_to_field::<Scalar<E>>(CHALLENGE_PERSONALIZATION, &bytes)?)
+}
+
+impl<E: PairingEngine> EpochContribution<E> {
+
+    // Function for computing a node's contribution to an epoch from its secret share,
+    // along with its proof. The nonce is derived from the secret share and the
+    // statement, which fix the challenge, so that it is never reused with another.
+    pub fn new(config: &Config<E>, epoch: u64, origin: usize, secret_share: &E::G1Affine) -> Result<Self, PVSSError<E>> {
+        let generator = epoch_generator::<E>(epoch)?;
+        let sigma = E::pairing(*secret_share, generator);
+        let public = E::pairing(*secret_share, config.srs.g2);
+
+        let mut input = vec![];
+        secret_share.serialize(&mut input)?;
+        input.extend_from_slice(&epoch.to_be_bytes());
+        input.extend_from_slice(&(origin as u64).to_be_bytes());
+        let r = hash_to_field::<Scalar<E>>(NONCE_PERSONALIZATION, &input);
+        input.zeroize();
+        let r = SecretScalar::new(r?);
+
+        let nonce = config.srs.g1.mul(r.expose().into_repr());
+        let nonce_affine = nonce.into_affine();
+        let (t_g2, t_epoch) = (E::pairing(nonce_affine, config.srs.g2), E::pairing(nonce_affine, generator));
+        let c = contribution_challenge::<E>(epoch, origin, &[&public, &sigma, &t_g2, &t_epoch])?;
+        let z = (nonce + secret_share.mul(c.into_repr())).into_affine();
+
+        Ok(Self { epoch, origin, sigma, proof: (c, z) })
+    }
+
+    // Method for verifying a contribution against the commitments of the transcript
+    // it was computed from.
+    pub fn verify(&self, config: &Config<E>, comms: &[E::G2Projective]) -> Result<(), PVSSError<E>> {
+        let comm = comms.get(self.origin).ok_or(PVSSError::<E>::InvalidParticipantId(self.origin))?;
+
+        // sigma must lie in the prime order subgroup for the proof to be sound
+        if !self.sigma.pow(Scalar::<E>::characteristic()).is_one() {
+            return Err(PVSSError::InvalidEpochContributionError(self.origin));
+        }
+
+        let generator = epoch_generator::<E>(self.epoch)?;
+        let public = E::pairing(config.srs.g1, *comm);
+        let (c, z) = self.proof;
+        let minus_c = (-c).into_repr();
+        let t_g2 = E::pairing(z, config.srs.g2) * public.pow(minus_c);
+        let t_epoch = E::pairing(z, generator) * self.sigma.pow(minus_c);
+
+        if contribution_challenge::<E>(self.epoch, self.origin, &[&public, &self.sigma, &t_g2, &t_epoch])? != c {
+            return Err(PVSSError::InvalidEpochContributionError(self.origin));
+        }
+        Ok(())
+    }
+}
+
+// Struct EpochBeacon collects the contributions to an epoch's beacon.
+#[derive(Clone, Debug)]
+pub struct EpochBeacon<E: PairingEngine> {
+    pub epoch: u64,                                        // the epoch being collected
+    pub degree: usize,                                     // the threshold t
+    domain: Option<Radix2EvaluationDomain<Scalar<E>>>,     // the domain of the nodes' points, if any (see EvaluationPoints)
+    contributions: BTreeMap<usize, GT<E>>,                 // sigma values by node id
+}
+
+impl<E: PairingEngine> EpochBeacon<E> {
+
+    // Function for starting the collection of an epoch's contributions, for threshold t
+    // and node i holding the share at i + 1.
+    pub fn new(epoch: u64, degree: usize) -> Self {
+        Self { epoch, degree, domain: None, contributions: BTreeMap::new() }
+    }
+
+    // Function for starting the collection of an epoch's contributions, for the
+    // threshold and evaluation points of config.
+    pub fn for_config(epoch: u64, config: &Config<E>) -> Self {
+        Self { epoch, degree: config.degree, domain: config.domain(), contributions: BTreeMap::new() }
+    }
+
+    // Utility method returning the point node id holds its share at.
+    fn point(&self, id: usize) -> Scalar<E> {
+        Config::<E>::point_in(self.domain.as_ref(), id)
+    }
+
+    // Method for adding a contribution. Returns whether enough have been collected.
+    // A node's first contribution is kept, later ones are ignored.
+    pub fn add(&mut self, contribution: &EpochContribution<E>) -> Result<bool, PVSSError<E>> {
+        if contribution.epoch != self.epoch {
+            return Err(PVSSError::EpochMismatchError(contribution.epoch, self.epoch));
+        }
+        self.contributions.entry(contribution.origin).or_insert(contribution.sigma);
+        Ok(self.is_ready())
+    }
+
+    // Method returning whether t + 1 contributions have been collected.
+    pub fn is_ready(&self) -> bool {
+        self.contributions.len() > self.degree
+    }
+
+    // Method for computing the epoch's output from the collected contributions. The
+    // first t + 1 are interpolated, and all others must agree with them.
+    pub fn output(&self) -> Result<BeaconOutput, PVSSError<E>> {
+        if !self.is_ready() {
+            return Err(PVSSError::InsufficientEpochContributionsError(self.contributions.len(), self.degree + 1));
+        }
+
+        let points = self.contributions.iter().map(|(id, sigma)| (self.point(*id), *sigma)).collect::<Vec<_>>();
+        let (basis, rest) = points.split_at(self.degree + 1);
+        for (point, sigma) in rest.iter() {
+            if interpolate_in_gt::<E>(basis, *point)? != *sigma {
+                return Err(PVSSError::InconsistentEpochContributionsError);
+            }
+        }
+
+        beacon_output::<E>(self.epoch, &interpolate_in_gt::<E>(basis, Scalar::<E>::zero())?)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::beacon::epoch::{beacon_output, epoch_generator, EpochBeacon};
+    use crate::modified_scrape::{config::EvaluationPoints, errors::PVSSError, tests::setup_nodes};
+
+    use ark_ec::PairingEngine;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_epoch_loop() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for node in nodes.iter_mut() {
+            for share in shares.iter() {
+                node.aggregator.receive_share(rng, share).unwrap();
+            }
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+
+        // The committee secret g_1^s, for reference.
+        let decryptions = nodes.iter().map(|node| node.decrypt(rng, &transcript).unwrap()).collect::<Vec<_>>();
+        let (point, _) = nodes[0].reconstruct(&decryptions).unwrap();
+
+        for epoch in 1..3 {
+            let contributions = nodes
+                .iter()
+                .map(|node| node.epoch_contribution(&transcript, epoch).unwrap())
+                .collect::<Vec<_>>();
+            let expected = beacon_output::<E>(epoch, &E::pairing(point, epoch_generator::<E>(epoch).unwrap())).unwrap();
+
+            // Any t + 1 contributions yield the same output, and more are cross-checked.
+            let mut beacon = EpochBeacon::<E>::new(epoch, t);
+            assert!(!beacon.add(&contributions[3]).unwrap());
+            assert!(beacon.add(&contributions[1]).unwrap());
+            assert_eq!(beacon.output().unwrap(), expected);
+            beacon.add(&contributions[0]).unwrap();
+            assert_eq!(beacon.output().unwrap(), expected);
+
+            // A bad contribution beyond the first t + 1 is exposed.
+            let mut forged = contributions[2].clone();
+            forged.sigma = contributions[0].sigma;
+            beacon.add(&forged).unwrap();
+            assert!(matches!(beacon.output(), Err(PVSSError::InconsistentEpochContributionsError)));
+
+            let mut other = EpochBeacon::<E>::new(epoch + 1, t);
+            assert!(matches!(other.add(&contributions[0]), Err(PVSSError::EpochMismatchError(_, _))));
+            assert!(matches!(other.output(), Err(PVSSError::InsufficientEpochContributionsError(0, 2))));
+        }
+    }
+
+    #[test]
+    fn test_contribution_proof() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[0].aggregator.receive_share(rng, share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        let (config, comms) = (&nodes[0].aggregator.config, &transcript.pvss_share.comms);
+
+        // Honest contributions verify against the transcript's commitments.
+        let contributions = nodes.iter().map(|node| node.epoch_contribution(&transcript, 3).unwrap()).collect::<Vec<_>>();
+        assert!(contributions.iter().all(|contribution| contribution.verify(config, comms).is_ok()));
+
+        // Another node's sigma, or sigma for another epoch, does not.
+        let mut forged = contributions[1].clone();
+        forged.sigma = contributions[2].sigma;
+        assert!(matches!(forged.verify(config, comms), Err(PVSSError::InvalidEpochContributionError(1))));
+        let mut replayed = nodes[1].epoch_contribution(&transcript, 4).unwrap();
+        replayed.epoch = 3;
+        assert!(matches!(replayed.verify(config, comms), Err(PVSSError::InvalidEpochContributionError(1))));
+
+        // Nor does a contribution claimed by another node.
+        let mut stolen = contributions[1].clone();
+        stolen.origin = 2;
+        assert!(matches!(stolen.verify(config, comms), Err(PVSSError::InvalidEpochContributionError(2))));
+    }
+
+    #[test]
+    fn test_points() {
+        let (n, t) = (4, 1);
+        let (nodes, _) = setup_nodes(n, t);
+        let mut config = nodes[0].aggregator.config.clone();
+
+        // Beacons place the nodes at the points of their config.
+        let beacon = EpochBeacon::<E>::new(1, t);
+        assert!((0..n).all(|id| beacon.point(id) == config.point(id)));
+
+        config.points = EvaluationPoints::Domain;
+        let beacon = EpochBeacon::<E>::for_config(1, &config);
+        assert_eq!((0..n).map(|id| beacon.point(id)).collect::<Vec<_>>(), config.points());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod proof;
+pub mod combine;
+pub mod rng;
+pub mod subcommittee;
+pub mod epoch;
+
+#[cfg(feature = "async")]
+pub mod chain;
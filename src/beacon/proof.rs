@@ -0,0 +1,42 @@
+use crate::modified_scrape::{config::Config, errors::PVSSError, escrow::committee_public_key, share::PVSSTranscript};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{GT, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_ff::One;
+use ark_serialize::*;
+use std::ops::Neg;
+
+// Struct BeaconProof allows anyone to check a beacon value against the commitment
+// gs_total = g_2^s to the secret s of the epoch's transcript: the reconstructed point
+// must satisfy e(point, g_2) == e(g_1, gs_total), and the beacon is e(point, g_2').
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaconProof<E: PairingEngine> {
+    pub point: E::G1Affine,         // the reconstructed secret g_1^s
+    pub commitment: E::G2Affine,    // the transcript's combined commitment g_2^s
+}
+
+impl<E: PairingEngine> BeaconProof<E> {
+
+    // Function for creating the proof of a beacon reconstructed from a transcript.
+    pub fn new<SSIG>(point: E::G1Affine, transcript: &PVSSTranscript<E, SSIG>) -> Self
+    where
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        Self { point, commitment: committee_public_key(transcript) }
+    }
+
+    // Method for verifying a beacon value against the proof.
+    pub fn verify(&self, config: &Config<E>, beacon: &GT<E>) -> Result<(), PVSSError<E>> {
+        let pairs = [
+            (self.point.into(), config.srs.g2.into()),
+            (config.srs.g1.neg().into(), self.commitment.into()),
+        ];
+
+        if !E::product_of_pairings(pairs.iter()).is_one() || E::pairing(self.point, config.srs.g2_prime) != *beacon {
+            return Err(PVSSError::BeaconProofError);
+        }
+
+        Ok(())
+    }
+}
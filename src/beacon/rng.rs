@@ -0,0 +1,119 @@
+use crate::modified_scrape::errors::PVSSError;
+use crate::GT;
+
+use ark_ec::PairingEngine;
+use ark_serialize::CanonicalSerialize;
+use blake2s_simd::Params;
+use rand::{CryptoRng, Error, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+const PERSONALIZATION: &[u8] = b"OPTRBRNG";   // persona for expanding beacon values into seeds
+
+/* Adapter exposing the committee's randomness through rand's traits. Each epoch's
+   beacon value is hashed (together with the epoch number) into a 32-byte seed, which
+   is expanded into a stream by ChaCha20. The stream is re-seeded from scratch at every
+   epoch, so outputs of different epochs are independent of each other.
+*/
+
+// Struct BeaconRng models the randomness stream of the current epoch.
+pub struct BeaconRng {
+    epoch: u64,          // the epoch whose beacon seeds the stream
+    inner: ChaChaRng,    // the expanded stream
+}
+
+// Utility function for deriving the seed of an epoch's stream from its beacon value.
+fn seed<E: PairingEngine>(epoch: u64, beacon: &GT<E>) -> Result<[u8; 32], PVSSError<E>> {
+    let mut bytes = epoch.to_be_bytes().to_vec();
+    beacon.serialize(&mut bytes)?;
+
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(PERSONALIZATION)
+        .to_state()
+        .update(&bytes)
+        .finalize();
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(hash.as_bytes());
+    Ok(seed)
+}
+
+impl BeaconRng {
+
+    // Function for creating the randomness stream of an epoch from its beacon value.
+    pub fn new<E: PairingEngine>(epoch: u64, beacon: &GT<E>) -> Result<Self, PVSSError<E>> {
+        Ok(Self {
+            epoch,
+            inner: ChaChaRng::from_seed(seed::<E>(epoch, beacon)?),
+        })
+    }
+
+    // Method for re-seeding the stream with the beacon value of a later epoch.
+    pub fn reseed<E: PairingEngine>(&mut self, epoch: u64, beacon: &GT<E>) -> Result<(), PVSSError<E>> {
+        if epoch <= self.epoch {
+            return Err(PVSSError::StaleEpochError(epoch, self.epoch));
+        }
+
+        self.inner = ChaChaRng::from_seed(seed::<E>(epoch, beacon)?);
+        self.epoch = epoch;
+        Ok(())
+    }
+
+    // Method returning the epoch the stream currently belongs to.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+// BeaconRng implements the RngCore trait by delegating to the expanded stream.
+impl RngCore for BeaconRng {
+    fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.inner.try_fill_bytes(dest)
+    }
+}
+
+// The stream is a ChaCha20 keystream under a uniformly random key.
+impl CryptoRng for BeaconRng {}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_std::UniformRand;
+
+    use crate::beacon::rng::BeaconRng;
+    use crate::GT;
+
+    use rand::{thread_rng, RngCore};
+
+    #[test]
+    fn test_beacon_rng() {
+        let rng = &mut thread_rng();
+        let beacon = GT::<E>::rand(rng);
+
+        // the same epoch and beacon give the same stream
+        let mut a = BeaconRng::new::<E>(1, &beacon).unwrap();
+        let mut b = BeaconRng::new::<E>(1, &beacon).unwrap();
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        // re-seeding moves to an unrelated stream
+        b.reseed::<E>(2, &beacon).unwrap();
+        assert_ne!(a.next_u64(), b.next_u64());
+
+        // epochs never go back
+        assert!(b.reseed::<E>(1, &beacon).is_err());
+    }
+}
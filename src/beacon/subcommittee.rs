@@ -0,0 +1,183 @@
+use crate::beacon::proof::BeaconProof;
+use crate::modified_scrape::{config::Config, errors::PVSSError};
+use crate::{digest_bytes, Digest, GT};
+
+use ark_ec::PairingEngine;
+use ark_serialize::*;
+use blake2s_simd::Params;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+const PERSONALIZATION: &[u8] = b"OPTRSUBC";   // persona for deriving sub-committee seeds
+
+/* Sampling of a random sub-committee of k out of the n roster members from an epoch's
+   beacon. The beacon value, the epoch, a digest of the roster and k are hashed into a
+   seed, which drives a partial Fisher-Yates shuffle of the roster indices. Sampling is
+   deterministic, so anyone holding the beacon (and its BeaconProof) and the roster can
+   re-derive the sub-committee and check a claimed one.
+*/
+
+// Struct SubcommitteeProof records everything the sampling was derived from, along
+// with the resulting (ascending) roster indices.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SubcommitteeProof<E: PairingEngine> {
+    pub epoch: u64,               // the epoch whose beacon was sampled from
+    pub beacon: GT<E>,            // the epoch's beacon value
+    pub roster_digest: Digest,    // digest of the committee roster
+    pub roster_size: u64,         // number of roster members n
+    pub indices: Vec<usize>,      // the sampled roster indices, in ascending order
+}
+
+// Utility function for deriving the sampling seed.
+fn seed<E: PairingEngine>(
+    epoch: u64,
+    beacon: &GT<E>,
+    roster_digest: &Digest,
+    k: usize,
+) -> Result<[u8; 32], PVSSError<E>> {
+    let mut bytes = epoch.to_be_bytes().to_vec();
+    beacon.serialize(&mut bytes)?;
+    bytes.extend_from_slice(&roster_digest.0);
+    bytes.extend_from_slice(&(k as u64).to_be_bytes());
+
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(PERSONALIZATION)
+        .to_state()
+        .update(&bytes)
+        .finalize();
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(hash.as_bytes());
+    Ok(seed)
+}
+
+// Utility function hashing the roster, encoded the same way as a Vec holding it.
+pub fn roster_digest<E: PairingEngine, K: CanonicalSerialize>(roster: &[K]) -> Result<Digest, PVSSError<E>> {
+    let mut bytes = vec![];
+    (roster.len() as u64).serialize(&mut bytes)?;
+    for key in roster {
+        key.serialize(&mut bytes)?;
+    }
+    Ok(digest_bytes(&bytes))
+}
+
+// Utility function drawing k distinct indices out of 0..n from the seed.
+fn derive_indices(seed: [u8; 32], n: usize, k: usize) -> Vec<usize> {
+    let mut rng = ChaChaRng::from_seed(seed);
+    let mut pool = (0..n).collect::<Vec<_>>();
+
+    // Only the first k positions of the shuffle are needed.
+    for i in 0..k {
+        let j = rng.gen_range(i, n);
+        pool.swap(i, j);
+    }
+
+    let mut indices = pool[..k].to_vec();
+    indices.sort_unstable();
+    indices
+}
+
+// Function for sampling a sub-committee of k members of the roster from the beacon
+// value of an epoch.
+pub fn sample<E: PairingEngine, K: CanonicalSerialize>(
+    epoch: u64,
+    beacon: &GT<E>,
+    roster: &[K],
+    k: usize,
+) -> Result<SubcommitteeProof<E>, PVSSError<E>> {
+    if k == 0 || k > roster.len() {
+        return Err(PVSSError::SubcommitteeSizeError(k, roster.len()));
+    }
+
+    let roster_digest = roster_digest::<E, K>(roster)?;
+    let indices = derive_indices(seed::<E>(epoch, beacon, &roster_digest, k)?, roster.len(), k);
+
+    Ok(SubcommitteeProof {
+        epoch,
+        beacon: *beacon,
+        roster_digest,
+        roster_size: roster.len() as u64,
+        indices,
+    })
+}
+
+impl<E: PairingEngine> SubcommitteeProof<E> {
+
+    // Method returning the size k of the sampled sub-committee.
+    pub fn size(&self) -> usize {
+        self.indices.len()
+    }
+
+    // Method for checking the sub-committee against the roster and the beacon's proof:
+    // the beacon must verify under the config, and re-sampling must give the same indices.
+    pub fn verify<K: CanonicalSerialize>(
+        &self,
+        config: &Config<E>,
+        beacon_proof: &BeaconProof<E>,
+        roster: &[K],
+    ) -> Result<(), PVSSError<E>> {
+        beacon_proof.verify(config, &self.beacon)?;
+
+        let expected = sample::<E, K>(self.epoch, &self.beacon, roster, self.size())?;
+        if expected != *self {
+            return Err(PVSSError::SubcommitteeProofError);
+        }
+
+        Ok(())
+    }
+}
+
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+
+    use crate::beacon::{proof::BeaconProof, subcommittee::sample};
+    use crate::modified_scrape::{config::{Config, EvaluationPoints, ShareLayout}, srs::SRS};
+    use crate::Scalar;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_subcommittee() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+        let config = Config { srs: srs.clone(), degree: 3, num_participants: 10, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+
+        // a beacon e(g_1^s, g_2') along with its proof
+        let s = Scalar::<E>::rand(rng);
+        let point = srs.g1.mul(s.into_repr()).into_affine();
+        let proof = BeaconProof::<E> { point, commitment: srs.g2.mul(s.into_repr()).into_affine() };
+        let beacon = E::pairing(point, srs.g2_prime);
+
+        let roster = (0..10u64).collect::<Vec<_>>();
+        let sub = sample::<E, _>(7, &beacon, &roster, 4).unwrap();
+        assert_eq!(sub.size(), 4);
+        assert!(sub.indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(sub.indices.iter().all(|i| *i < roster.len()));
+        sub.verify(&config, &proof, &roster).unwrap();
+
+        // sampling is deterministic
+        assert_eq!(sample::<E, _>(7, &beacon, &roster, 4).unwrap(), sub);
+
+        // tampered indices, another roster or a bad beacon are rejected
+        let mut forged = sub.clone();
+        forged.indices[0] = (0..10).find(|i| !sub.indices.contains(i)).unwrap();
+        forged.indices.sort_unstable();
+        assert!(forged.verify(&config, &proof, &roster).is_err());
+        assert!(sub.verify(&config, &proof, &roster[1..]).is_err());
+        let mut forged = sub.clone();
+        forged.beacon = <E as PairingEngine>::Fqk::rand(rng);
+        assert!(forged.verify(&config, &proof, &roster).is_err());
+
+        // k must be in 1..=n
+        assert!(sample::<E, _>(7, &beacon, &roster, 0).is_err());
+        assert!(sample::<E, _>(7, &beacon, &roster, 11).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while reliably broadcasting a payload.
+#[derive(Error, Debug)]
+pub enum BroadcastError {
+    #[error("Invalid node id: {0}")]
+    InvalidNodeId(usize),
+    #[error("Node {0} relayed the initial message of node {1}")]
+    ForgedSend(usize, usize),
+    #[error("Unknown message kind: {0}")]
+    UnknownMessageKind(u8),
+    #[error("Transport error: {0}")]
+    TransportError(String),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SerializationError),
+}
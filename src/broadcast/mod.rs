@@ -0,0 +1,346 @@
+use crate::broadcast::errors::BroadcastError;
+use crate::modified_scrape::{finalized::FinalizedTranscript, pvss::PVSSShare};
+use crate::{digest_bytes, Digest};
+
+use ark_serialize::*;
+use ark_std::collections::{BTreeMap, BTreeSet};
+
+use std::marker::PhantomData;
+
+pub mod errors;
+
+/* Bracha-style reliable broadcast. Out of n nodes, up to f = (n - 1) / 3 may be
+   Byzantine, either as the originator of a payload or as relays. An instance is
+   identified by its originator and a tag (e.g., the epoch), and proceeds as:
+
+       originator -> all:  Send(payload)
+       on Send from the originator:                     Echo(payload) to all (once)
+       on ceil((n + f + 1) / 2) Echoes for a payload:   Ready(payload) to all (once)
+       on f + 1 Readies for a payload:                  Ready(payload) to all (once)
+       on 2f + 1 Readies for a payload:                 deliver it (once)
+
+   If an honest node delivers a payload, every honest node eventually delivers the
+   same payload, even if the originator equivocates. Payloads are told apart by
+   their digests, and each node is counted at most once per phase and instance.
+   Delivery only guarantees agreement: the delivered shares and transcripts still
+   have to be verified as usual.
+*/
+
+// Trait Transport abstracts the point-to-point channel the broadcast runs over.
+// Channels are assumed to be authenticated, i.e., the receiving end knows which
+// node each message comes from.
+pub trait Transport {
+    fn send(&mut self, to: usize, message: Vec<u8>) -> Result<(), BroadcastError>;
+}
+
+// The phases of a broadcast instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageKind {
+    Send,
+    Echo,
+    Ready,
+}
+
+impl MessageKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            MessageKind::Send => 0,
+            MessageKind::Echo => 1,
+            MessageKind::Ready => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, BroadcastError> {
+        match byte {
+            0 => Ok(MessageKind::Send),
+            1 => Ok(MessageKind::Echo),
+            2 => Ok(MessageKind::Ready),
+            _ => Err(BroadcastError::UnknownMessageKind(byte)),
+        }
+    }
+}
+
+// Struct BroadcastMessage represents a message of some broadcast instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BroadcastMessage {
+    pub kind: MessageKind,   // phase the message belongs to
+    pub origin: usize,       // originator of the instance
+    pub tag: u64,            // tag of the instance
+    pub payload: Vec<u8>,    // canonical serialization of the payload
+}
+
+// Struct Delivery represents a payload delivered by a broadcast instance.
+#[derive(Clone, Debug)]
+pub struct Delivery<T> {
+    pub origin: usize,   // originator of the instance
+    pub tag: u64,        // tag of the instance
+    pub payload: T,      // the delivered payload
+}
+
+// Struct Instance holds a node's view of a single broadcast instance.
+#[derive(Default)]
+struct Instance {
+    echoed: bool,                                  // whether we sent our Echo
+    readied: bool,                                 // whether we sent our Ready
+    delivered: bool,                               // whether we delivered a payload
+    echo_senders: BTreeSet<usize>,                 // nodes whose Echo was counted
+    ready_senders: BTreeSet<usize>,                // nodes whose Ready was counted
+    echoes: BTreeMap<Digest, BTreeSet<usize>>,     // Echo senders per payload digest
+    readies: BTreeMap<Digest, BTreeSet<usize>>,    // Ready senders per payload digest
+}
+
+// Struct ReliableBroadcast represents a node's endpoint of the broadcast, for
+// payloads of type T.
+pub struct ReliableBroadcast<T> {
+    pub id: usize,                                   // our node id
+    pub num_nodes: usize,                            // number of nodes n
+    instances: BTreeMap<(usize, u64), Instance>,     // instances by (origin, tag)
+    payload_type: PhantomData<T>,
+}
+
+// Reliable broadcast of the shares dealt by each node.
+pub type ShareBroadcast<E> = ReliableBroadcast<PVSSShare<E>>;
+
+// Reliable broadcast of the finalized transcript of an epoch.
+pub type TranscriptBroadcast<E, SSIG> = ReliableBroadcast<FinalizedTranscript<E, SSIG>>;
+
+// Messages are encoded as kind (1 byte) || origin || tag || payload.
+impl CanonicalSerialize for BroadcastMessage {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.kind.to_byte().serialize(&mut writer)?;
+        (self.origin as u64).serialize(&mut writer)?;
+        self.tag.serialize(&mut writer)?;
+        self.payload.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        1 + 8 + 8 + self.payload.serialized_size()
+    }
+}
+
+impl BroadcastMessage {
+
+    // Function for decoding a message received over the transport.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BroadcastError> {
+        let mut reader = bytes;
+        let kind = MessageKind::from_byte(u8::deserialize(&mut reader)?)?;
+        let origin = u64::deserialize(&mut reader)? as usize;
+        let tag = u64::deserialize(&mut reader)?;
+        let payload = Vec::<u8>::deserialize(&mut reader)?;
+        Ok(Self { kind, origin, tag, payload })
+    }
+
+    // Method for encoding the message for the transport.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        // serializing into a Vec cannot fail
+        self.serialize(&mut bytes).unwrap();
+        bytes
+    }
+}
+
+// Utility function sending a message to every node, ourselves included.
+fn send_all<Tr: Transport>(transport: &mut Tr, num_nodes: usize, message: &BroadcastMessage) -> Result<(), BroadcastError> {
+    let bytes = message.to_bytes();
+    for to in 0..num_nodes {
+        transport.send(to, bytes.clone())?;
+    }
+    Ok(())
+}
+
+impl<T: CanonicalSerialize + CanonicalDeserialize> ReliableBroadcast<T> {
+
+    // Function for creating the endpoint of node id among num_nodes nodes.
+    pub fn new(id: usize, num_nodes: usize) -> Result<Self, BroadcastError> {
+        if id >= num_nodes {
+            return Err(BroadcastError::InvalidNodeId(id));
+        }
+
+        Ok(Self {
+            id,
+            num_nodes,
+            instances: BTreeMap::new(),
+            payload_type: PhantomData,
+        })
+    }
+
+    // Method returning the number f of Byzantine nodes tolerated.
+    pub fn faults(&self) -> usize {
+        (self.num_nodes - 1) / 3
+    }
+
+    // Method returning the number of Echoes after which a node sends its Ready.
+    pub fn echo_threshold(&self) -> usize {
+        (self.num_nodes + self.faults()) / 2 + 1
+    }
+
+    // Method for initiating an instance broadcasting our payload under the given tag.
+    pub fn broadcast<Tr: Transport>(&mut self, transport: &mut Tr, tag: u64, payload: &T) -> Result<(), BroadcastError> {
+        let mut bytes = Vec::with_capacity(payload.serialized_size());
+        payload.serialize(&mut bytes)?;
+
+        let message = BroadcastMessage { kind: MessageKind::Send, origin: self.id, tag, payload: bytes };
+        send_all(transport, self.num_nodes, &message)
+    }
+
+    // Method for handling a message received from node from. Returns the payload
+    // delivered by the message's instance, if the message completes it.
+    pub fn handle<Tr: Transport>(
+        &mut self,
+        transport: &mut Tr,
+        from: usize,
+        bytes: &[u8],
+    ) -> Result<Option<Delivery<T>>, BroadcastError> {
+        let message = BroadcastMessage::from_bytes(bytes)?;
+        if from >= self.num_nodes {
+            return Err(BroadcastError::InvalidNodeId(from));
+        }
+        if message.origin >= self.num_nodes {
+            return Err(BroadcastError::InvalidNodeId(message.origin));
+        }
+
+        let (n, f, echo_threshold) = (self.num_nodes, self.faults(), self.echo_threshold());
+        let digest = digest_bytes(&message.payload);
+        let instance = self.instances.entry((message.origin, message.tag)).or_default();
+
+        match message.kind {
+            MessageKind::Send => {
+                // Only the originator may start its instance.
+                if from != message.origin {
+                    return Err(BroadcastError::ForgedSend(from, message.origin));
+                }
+                if instance.echoed {
+                    return Ok(None);
+                }
+
+                // Only echo payloads that decode.
+                T::deserialize(&message.payload[..])?;
+                instance.echoed = true;
+                send_all(transport, n, &BroadcastMessage { kind: MessageKind::Echo, ..message })?;
+                Ok(None)
+            },
+            MessageKind::Echo => {
+                if !instance.echo_senders.insert(from) {
+                    return Ok(None);
+                }
+
+                let echoes = instance.echoes.entry(digest).or_default();
+                echoes.insert(from);
+                if echoes.len() >= echo_threshold && !instance.readied {
+                    instance.readied = true;
+                    send_all(transport, n, &BroadcastMessage { kind: MessageKind::Ready, ..message })?;
+                }
+                Ok(None)
+            },
+            MessageKind::Ready => {
+                if !instance.ready_senders.insert(from) {
+                    return Ok(None);
+                }
+
+                let readies = instance.readies.entry(digest).or_default();
+                readies.insert(from);
+                let count = readies.len();
+
+                // Amplification: f + 1 Readies include at least one honest node's.
+                if count > f && !instance.readied {
+                    instance.readied = true;
+                    send_all(transport, n, &BroadcastMessage { kind: MessageKind::Ready, ..message.clone() })?;
+                }
+
+                if count > 2 * f && !instance.delivered {
+                    instance.delivered = true;
+                    let payload = T::deserialize(&message.payload[..])?;
+                    return Ok(Some(Delivery { origin: message.origin, tag: message.tag, payload }));
+                }
+                Ok(None)
+            },
+        }
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::broadcast::{errors::BroadcastError, BroadcastMessage, MessageKind, ShareBroadcast, Transport};
+    use crate::modified_scrape::{pvss::PVSSShare, tests::setup_nodes};
+    use crate::{Digest, Hash};
+
+    use ark_serialize::CanonicalSerialize;
+    use rand::thread_rng;
+    use std::collections::VecDeque;
+
+    // In-memory transport queueing (from, to, message) triples.
+    struct Endpoint<'a> {
+        id: usize,
+        queue: &'a mut VecDeque<(usize, usize, Vec<u8>)>,
+    }
+
+    impl<'a> Transport for Endpoint<'a> {
+        fn send(&mut self, to: usize, message: Vec<u8>) -> Result<(), BroadcastError> {
+            self.queue.push_back((self.id, to, message));
+            Ok(())
+        }
+    }
+
+    // Delivers queued messages to every node but the silent ones, returning the
+    // digests of the payloads each node delivered.
+    fn run(
+        endpoints: &mut [ShareBroadcast<E>],
+        queue: &mut VecDeque<(usize, usize, Vec<u8>)>,
+        silent: &[usize],
+    ) -> Vec<Vec<Digest>> {
+        let mut delivered = vec![vec![]; endpoints.len()];
+        while let Some((from, to, message)) = queue.pop_front() {
+            if silent.contains(&to) {
+                continue;
+            }
+            let mut transport = Endpoint { id: to, queue: &mut *queue };
+            if let Some(delivery) = endpoints[to].handle(&mut transport, from, &message).unwrap() {
+                delivered[to].push(delivery.payload.digest());
+            }
+        }
+        delivered
+    }
+
+    fn send_message(origin: usize, share: &PVSSShare<E>) -> Vec<u8> {
+        let mut payload = vec![];
+        share.serialize(&mut payload).unwrap();
+        BroadcastMessage { kind: MessageKind::Send, origin, tag: 0, payload }.to_bytes()
+    }
+
+    #[test]
+    fn test_reliable_broadcast() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..2).map(|i| nodes[i].share(rng).unwrap().pvss_share).collect::<Vec<_>>();
+
+        // An honest originator: every honest node delivers its share, even with node 3 silent.
+        let mut endpoints = (0..n).map(|id| ShareBroadcast::<E>::new(id, n).unwrap()).collect::<Vec<_>>();
+        let mut queue = VecDeque::new();
+        endpoints[0].broadcast(&mut Endpoint { id: 0, queue: &mut queue }, 0, &shares[0]).unwrap();
+        let delivered = run(&mut endpoints, &mut queue, &[3]);
+        for digests in &delivered[..3] {
+            assert_eq!(*digests, vec![shares[0].digest()]);
+        }
+
+        // An equivocating originator (node 3): no two honest nodes deliver different shares.
+        let mut endpoints = (0..n).map(|id| ShareBroadcast::<E>::new(id, n).unwrap()).collect::<Vec<_>>();
+        let mut queue = VecDeque::new();
+        queue.push_back((3, 0, send_message(3, &shares[0])));
+        queue.push_back((3, 1, send_message(3, &shares[0])));
+        queue.push_back((3, 2, send_message(3, &shares[1])));
+        let delivered = run(&mut endpoints, &mut queue, &[3]);
+        let digests = delivered.iter().flatten().collect::<Vec<_>>();
+        assert!(digests.windows(2).all(|w| w[0] == w[1]));
+
+        // A relay cannot start an instance on behalf of another node.
+        let mut queue = VecDeque::new();
+        let result = endpoints[0].handle(&mut Endpoint { id: 0, queue: &mut queue }, 3, &send_message(1, &shares[0]));
+        assert!(matches!(result, Err(BroadcastError::ForgedSend(3, 1))));
+    }
+}
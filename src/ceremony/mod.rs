@@ -0,0 +1,164 @@
+use crate::beacon::proof::BeaconProof;
+use crate::modified_scrape::{
+    config::{Config, EvaluationPoints, ShareLayout},
+    dealer::Dealer,
+    errors::PVSSError,
+    finalized::FinalizedTranscript,
+    node::Node,
+    participant::Participant,
+    srs::SRS,
+};
+use crate::signature::{
+    schnorr::{srs::SRS as SchnorrSRS, SchnorrSignature, SchnorrVersion},
+    scheme::SignatureScheme,
+};
+use crate::{GT, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+
+/* One-call ceremony for small committees. Runs the complete protocol in-process:
+   key generation, dealing (every node deals to everyone), verification and
+   aggregation (every node verifies and decrypts every share), certification of
+   the aggregated transcript, decryption and reconstruction of the beacon. Doubles
+   as a worked, end-to-end example of how the pieces fit together; every node does
+   O(n) verifications, so this is not meant for large committees.
+*/
+
+pub type SSIG<E> = SchnorrSignature<<E as PairingEngine>::G1Affine>;
+pub type BeaconValue<E> = GT<E>;
+
+// The keys of all nodes of a committee, along with the nodes themselves.
+pub type Committee<E> = (Vec<NodeKeys<E>>, Vec<Node<E, SSIG<E>>>);
+
+// The outcome of a ceremony: the certified transcript, the keys of all nodes, and the beacon value.
+pub type Ceremony<E> = (FinalizedTranscript<E, SSIG<E>>, Vec<NodeKeys<E>>, BeaconValue<E>);
+
+const CEREMONY_EPOCH: u64 = 0;   // epoch the local ceremony's transcript is certified for
+
+// Struct NodeKeys holds a committee member's key material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeKeys<E: PairingEngine> {
+    pub id: usize,                      // the member's id in the committee
+    pub secret_key: Scalar<E>,          // signing (and decryption) key
+    pub public_key: E::G1Affine,        // matching public key
+}
+
+// Function for setting up a committee of n nodes with threshold t: generates the
+// system-wide parameters and every node's keys, and creates the nodes. Returns the
+// keys of all nodes, and the nodes themselves (node i having id i).
+pub fn setup<E: PairingEngine, R: Rng + CryptoRng>(
+    n: usize,
+    t: usize,
+    rng: &mut R,
+) -> Result<Committee<E>, PVSSError<E>> {
+    if n < 2 || t == 0 || t >= n {
+        return Err(PVSSError::InsufficientIdsError);
+    }
+
+    let srs = SRS::<E>::setup(rng)?;
+    let config = Config { srs: srs.clone(), degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+    let schnorr = SchnorrSignature::<E::G1Affine> { srs: SchnorrSRS { g_public_key: srs.g1 }, version: SchnorrVersion::KeyBound };
+
+    let keys = (0..n)
+        .map(|id| {
+            let (secret_key, public_key) = schnorr.generate_keypair(rng)?;
+            Ok(NodeKeys { id, secret_key, public_key })
+        })
+        .collect::<Result<Vec<_>, PVSSError<E>>>()?;
+
+    let participants = keys
+        .iter()
+        .map(|k| (k.id, Participant::new(k.id, k.public_key)))
+        .collect::<BTreeMap<_, _>>();
+
+    let nodes = keys
+        .iter()
+        .map(|k| {
+            let dealer = Dealer::new(&schnorr, k.secret_key, participants[&k.id].clone())?;
+            Node::new(config.clone(), schnorr.clone(), dealer, participants.clone())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((keys, nodes))
+}
+
+// Function for running a complete ceremony with n nodes and threshold t. Returns
+// the certified transcript, the keys of all nodes, and the resulting beacon value.
+pub fn run_local<E: PairingEngine, R: Rng + CryptoRng>(
+    n: usize,
+    t: usize,
+    rng: &mut R,
+) -> Result<Ceremony<E>, PVSSError<E>> {
+    // 1. Key generation and setup of the system-wide parameters.
+    let (keys, mut nodes) = setup::<E, R>(n, t, rng)?;
+    let config = nodes[0].aggregator.config.clone();
+    let schnorr = nodes[0].aggregator.scheme_sig.clone();
+    let participants = nodes[0].aggregator.participants.clone();
+
+    // 2. Dealing.
+    let shares = nodes
+        .iter_mut()
+        .map(|node| node.share(rng))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 3. Verification, aggregation and decryption of every share by every node.
+    for node in nodes.iter_mut() {
+        for share in shares.iter() {
+            node.receive_share_and_decrypt(rng, share.clone())?;
+        }
+    }
+
+    // 4. Certification: every node checks the transcript it is asked to sign against
+    // its own view, and signs it.
+    let transcript = nodes[0].aggregator.finalize()?.clone();
+    let mut finalized = FinalizedTranscript::new(CEREMONY_EPOCH, transcript);
+    for (node, k) in nodes.iter().zip(keys.iter()) {
+        node.aggregator.transcript_verify(rng, &finalized.transcript)?;
+        finalized.sign(rng, &node.aggregator.scheme_sig, k.id, &k.secret_key)?;
+    }
+    finalized.verify_certificate(rng, &schnorr, &participants)?;
+
+    // 5. Decryption: every node decrypts its share of the certified transcript, and
+    // the released decryptions are checked before being used.
+    let decryptions = nodes
+        .iter()
+        .map(|node| node.decrypt(rng, &finalized.transcript))
+        .collect::<Result<Vec<_>, _>>()?;
+    for decryption in decryptions.iter() {
+        nodes[0].verify_decryption(&finalized.transcript, decryption)?;
+    }
+
+    // 6. Reconstruction, checked against the certified transcript.
+    let (point, beacon) = nodes[0].reconstruct(&decryptions)?;
+    BeaconProof::new(point, &finalized.transcript).verify(&config, &beacon)?;
+
+    Ok((finalized, keys, beacon))
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::ceremony::run_local;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_run_local() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+
+        let (finalized, keys, _) = run_local::<E, _>(n, t, rng).unwrap();
+        assert_eq!(keys.len(), n);
+        assert_eq!(finalized.signatures.len(), n);
+        assert_eq!(finalized.transcript.contributions.len(), n);
+
+        assert!(run_local::<E, _>(n, n, rng).is_err());
+    }
+}
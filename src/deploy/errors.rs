@@ -0,0 +1,26 @@
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while loading a deployment descriptor.
+#[derive(Error, Debug)]
+pub enum DeployError {
+    #[error("Could not read deployment file: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Unrecognized deployment file format for {0} (expected .toml, .yaml or .yml)")]
+    UnknownFormat(String),
+    #[error("Malformed TOML descriptor: {0}")]
+    TomlError(#[from] toml::de::Error),
+    #[error("Malformed YAML descriptor: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+    #[error("Participant {0} has a malformed public key encoding: {1}")]
+    KeyEncodingError(usize, String),
+    #[error("Participant {0} has an invalid public key: {1}")]
+    PublicKeyError(usize, SerializationError),
+    #[error("Descriptor is for curve `{0}`, but the loader was instantiated for `{1}`")]
+    CurveMismatch(String, String),
+    #[error("Descriptor field `{0}` is invalid: {1}")]
+    SchemaError(&'static str, String),
+    #[error("Could not set up the SRS from the provided seed")]
+    SRSSetupError,
+}
@@ -0,0 +1,292 @@
+use crate::modified_scrape::{
+    config::{Config, EvaluationPoints, ShareLayout},
+    participant::{Participant, ParticipantSet},
+    srs::SRS,
+};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+use errors::DeployError;
+
+use ark_ec::PairingEngine;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::collections::BTreeMap;
+
+use blake2s_simd::Params;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde::Deserialize;
+
+use std::any::TypeId;
+use std::fs;
+use std::path::Path;
+
+pub mod errors;
+
+const PERSONALIZATION: &[u8] = b"OPTRSRSD";   // persona for deriving the SRS from a seed
+
+/* A deployment descriptor describes a committee as operators write it down:
+
+   curve = "bls12_381"
+   n = 4
+   t = 1
+   srs_seed = "optrand-testnet-epoch-0"
+
+   [[participants]]
+   id = 0
+   public_key = "<encoding of the compressed public key>"
+   ...
+
+   The same fields are accepted in YAML. Participant public keys are the
+   canonical (compressed) arkworks encoding of the signature scheme's key,
+   written in base64 with the `base64-encodings` feature and in hex otherwise.
+*/
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Descriptor {
+    curve: String,                   // name of the pairing-friendly curve
+    n: usize,                        // the total number of participants
+    t: usize,                        // polynomial degree
+    srs_seed: String,                // seed from which all nodes derive the same SRS
+    participants: Vec<RosterEntry>,  // the participant roster
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RosterEntry {
+    id: usize,                       // participant id
+    public_key: String,              // base64- or hex-encoded public key
+}
+
+// Struct Deployment bundles the system-wide parameters and roster obtained
+// from a deployment descriptor.
+pub struct Deployment<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub curve: String,                             // curve name, as written in the descriptor
+    pub config: Config<E>,                         // the resulting configuration
+    pub participants: ParticipantSet<E, SSIG>,     // the resulting roster
+}
+
+
+// Function for encoding a serialized public key as it appears in a descriptor.
+#[cfg(feature = "base64-encodings")]
+pub fn encode_public_key(bytes: &[u8]) -> String {
+    base64::encode(bytes)
+}
+
+// Function for encoding a serialized public key as it appears in a descriptor.
+#[cfg(not(feature = "base64-encodings"))]
+pub fn encode_public_key(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Function for decoding a public key as it appears in a descriptor.
+#[cfg(feature = "base64-encodings")]
+pub fn decode_public_key(encoded: &str) -> Result<Vec<u8>, String> {
+    base64::decode(encoded).map_err(|e| e.to_string())
+}
+
+// Function for decoding a public key as it appears in a descriptor.
+#[cfg(not(feature = "base64-encodings"))]
+pub fn decode_public_key(encoded: &str) -> Result<Vec<u8>, String> {
+    if encoded.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+
+    (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(encoded.get(i..i + 2).ok_or("invalid hex digit")?, 16)
+            .map_err(|e| e.to_string()))
+        .collect()
+}
+
+
+// Function returning the descriptor name of the curve underlying E, if supported.
+pub fn curve_name<E: PairingEngine>() -> Option<&'static str> {
+    if TypeId::of::<E>() == TypeId::of::<ark_bls12_381::Bls12_381>() {
+        return Some("bls12_381");
+    }
+
+    #[cfg(feature = "any-curve")]
+    if TypeId::of::<E>() == TypeId::of::<ark_bls12_377::Bls12_377>() {
+        return Some("bls12_377");
+    }
+
+    #[cfg(feature = "any-curve")]
+    if TypeId::of::<E>() == TypeId::of::<ark_bn254::Bn254>() {
+        return Some("bn254");
+    }
+
+    None
+}
+
+// Function for loading a deployment descriptor from a .toml, .yaml or .yml file.
+pub fn load<E, SSIG, P>(path: P) -> Result<Deployment<E, SSIG>, DeployError>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => from_toml_str(&contents),
+        Some("yaml") | Some("yml") => from_yaml_str(&contents),
+        _ => Err(DeployError::UnknownFormat(path.display().to_string())),
+    }
+}
+
+// Function for parsing a deployment descriptor given in TOML.
+pub fn from_toml_str<E, SSIG>(contents: &str) -> Result<Deployment<E, SSIG>, DeployError>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    build(toml::from_str(contents)?)
+}
+
+// Function for parsing a deployment descriptor given in YAML.
+pub fn from_yaml_str<E, SSIG>(contents: &str) -> Result<Deployment<E, SSIG>, DeployError>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    build(serde_yaml::from_str(contents)?)
+}
+
+// Function for validating a parsed descriptor against the schema and turning
+// it into a Config and a roster.
+fn build<E, SSIG>(descriptor: Descriptor) -> Result<Deployment<E, SSIG>, DeployError>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // The descriptor must be meant for the curve the caller instantiated us with.
+    let expected = curve_name::<E>().unwrap_or("unknown");
+    if descriptor.curve.to_lowercase() != expected {
+        return Err(DeployError::CurveMismatch(descriptor.curve, expected.to_string()));
+    }
+
+    if descriptor.n == 0 {
+        return Err(DeployError::SchemaError("n", "must be positive".to_string()));
+    }
+
+    if descriptor.t == 0 || descriptor.t >= descriptor.n {
+        return Err(DeployError::SchemaError("t", format!("must satisfy 0 < t < n = {}, got {}", descriptor.n, descriptor.t)));
+    }
+
+    if descriptor.participants.len() != descriptor.n {
+        return Err(DeployError::SchemaError("participants",
+            format!("expected {} entries, found {}", descriptor.n, descriptor.participants.len())));
+    }
+
+    if descriptor.srs_seed.is_empty() {
+        return Err(DeployError::SchemaError("srs_seed", "must not be empty".to_string()));
+    }
+
+    // Decode and validate the roster.
+    let mut participants = BTreeMap::new();
+    for entry in descriptor.participants.iter() {
+        if entry.id >= descriptor.n {
+            return Err(DeployError::SchemaError("participants",
+                format!("id {} is out of range 0..{}", entry.id, descriptor.n)));
+        }
+
+        let bytes = decode_public_key(&entry.public_key).map_err(|e| DeployError::KeyEncodingError(entry.id, e))?;
+        let public_key_sig = SSIG::PublicKey::deserialize(&bytes[..])
+            .map_err(|e| DeployError::PublicKeyError(entry.id, e))?;
+
+        let participant = Participant::new(entry.id, public_key_sig);
+
+        if participants.insert(entry.id, participant).is_some() {
+            return Err(DeployError::SchemaError("participants", format!("id {} appears more than once", entry.id)));
+        }
+    }
+
+    // Every node derives the same SRS from the shared seed.
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(PERSONALIZATION)
+        .to_state()
+        .update(descriptor.srs_seed.as_bytes())
+        .finalize();
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(hash.as_bytes());
+    let srs = SRS::<E>::setup(&mut ChaChaRng::from_seed(seed)).map_err(|_| DeployError::SRSSetupError)?;
+
+    Ok(Deployment {
+        curve: descriptor.curve,
+        config: Config {
+            srs,
+            degree: descriptor.t,
+            num_participants: descriptor.n,
+            layout: ShareLayout::Dense,
+            points: EvaluationPoints::Integers,
+        },
+        participants,
+    })
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E, G1Affine};
+    use ark_serialize::CanonicalSerialize;
+
+    use crate::deploy::{encode_public_key, errors::DeployError, from_toml_str, from_yaml_str, Deployment};
+    use crate::modified_scrape::tests::SSIG;
+    use crate::signature::{schnorr::srs::SRS, scheme::SignatureScheme};
+
+    use rand::thread_rng;
+
+    fn encoded_keys(n: usize) -> Vec<String> {
+        let rng = &mut thread_rng();
+        let schnorr = SSIG::from_srs(SRS::<G1Affine>::setup(rng).unwrap()).unwrap();
+
+        (0..n)
+            .map(|_| {
+                let (_, pk) = schnorr.generate_keypair(rng).unwrap();
+                let mut bytes = vec![];
+                pk.serialize(&mut bytes).unwrap();
+                encode_public_key(&bytes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_load_toml() {
+        let keys = encoded_keys(4);
+        let mut contents = "curve = \"bls12_381\"\nn = 4\nt = 1\nsrs_seed = \"seed\"\n".to_string();
+        for (id, key) in keys.iter().enumerate() {
+            contents += &format!("\n[[participants]]\nid = {}\npublic_key = \"{}\"\n", id, key);
+        }
+
+        let deployment: Deployment<E, SSIG> = from_toml_str(&contents).unwrap();
+        assert_eq!(deployment.config.num_participants, 4);
+        assert_eq!(deployment.config.degree, 1);
+        assert_eq!(deployment.participants.len(), 4);
+
+        // the SRS is a deterministic function of the seed
+        let again: Deployment<E, SSIG> = from_toml_str(&contents).unwrap();
+        assert_eq!(deployment.config.srs.g2, again.config.srs.g2);
+    }
+
+    #[test]
+    fn test_load_yaml_wrong_roster_size() {
+        let keys = encoded_keys(2);
+        let mut contents = "curve: bls12_381\nn: 4\nt: 1\nsrs_seed: seed\nparticipants:\n".to_string();
+        for (id, key) in keys.iter().enumerate() {
+            contents += &format!("  - id: {}\n    public_key: \"{}\"\n", id, key);
+        }
+
+        match from_yaml_str::<E, SSIG>(&contents) {
+            Err(DeployError::SchemaError("participants", _)) => {}
+            _ => panic!("roster size mismatch was not detected"),
+        }
+    }
+}
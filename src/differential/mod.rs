@@ -0,0 +1,241 @@
+use crate::modified_scrape::{
+    dealing::{commit_evals, compute_evals, encrypt_evals},
+    errors::PVSSError,
+    kernel,
+    poly::Polynomial,
+};
+use crate::Scalar;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_poly::UVPolynomial;
+use ark_std::UniformRand;
+
+use rand::{CryptoRng, Rng};
+use std::fmt;
+
+/* Differential testing of the verification kernel. The encryption and coding checks
+   are implemented here a second time, in the most direct way possible: one pairing
+   equation per recipient instead of a randomized batch, and explicit interpolation
+   of the first t+1 commitments instead of a random dual codeword. Both versions are
+   run on randomly generated (and randomly corrupted) shares, and any disagreement
+   between their verdicts is reported. The reference versions are slow and are meant
+   for testing only, hence the feature gate.
+*/
+
+// Enum Check names the checks compared by the harness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    Encryption,            // kernel::encryptions_check vs per-recipient pairings
+    Coding,                // kernel::coding_check vs explicit interpolation
+    CodingFiatShamir,      // kernel::coding_check_fiat_shamir vs explicit interpolation
+}
+
+// Enum Corruption describes how a random test case deviates from an honest share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corruption {
+    None,                  // an honestly dealt share
+    Encryption(usize),     // the encryption of the given recipient is random
+    Commitment(usize),     // the commitment of the given recipient is random
+    Degree,                // the share was dealt from a polynomial of degree t+1
+}
+
+// A test case: its corruption, the recipients' public keys, and the share's
+// encryptions and commitments.
+pub type TestCase<E> = (
+    Corruption,
+    Vec<<E as PairingEngine>::G1Affine>,
+    Vec<<E as PairingEngine>::G1Projective>,
+    Vec<<E as PairingEngine>::G2Projective>,
+);
+
+// Struct Divergence records a test case on which the two implementations disagree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    pub case: usize,               // index of the test case
+    pub check: Check,              // the check that diverged
+    pub corruption: Corruption,    // how the test case was generated
+    pub optimized: bool,           // verdict of the kernel
+    pub reference: bool,           // verdict of the reference implementation
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "case {} ({:?}): {:?} check says {}, reference says {}",
+               self.case, self.corruption, self.check, self.optimized, self.reference)
+    }
+}
+
+// Struct DifferentialReport summarizes a differential testing run.
+#[derive(Clone, Debug, Default)]
+pub struct DifferentialReport {
+    pub cases: usize,                     // number of test cases run
+    pub rejected: usize,                  // number of cases the reference rejected
+    pub divergences: Vec<Divergence>,     // cases the two implementations disagree on
+}
+
+impl DifferentialReport {
+
+    // Method checking whether both implementations agreed on every case.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+
+// Reference encryption check: e(pk_i, comm_i) == e(enc_i, g_2) for each i separately.
+pub fn reference_encryptions_check<E: PairingEngine>(
+    g2: E::G2Affine,
+    entries: &[(E::G1Affine, &E::G1Projective, &E::G2Projective)],
+) -> bool {
+    entries
+        .iter()
+        .all(|(pk, enc, comm)| E::pairing(*pk, comm.into_affine()) == E::pairing(enc.into_affine(), g2))
+}
+
+// Reference coding check: interpolate the polynomial through the commitments at
+// 1, ..., t+1 and check that every remaining commitment lies on it.
+pub fn reference_coding_check<E: PairingEngine>(comms: &[E::G2Projective], degree: usize) -> bool {
+    let n = comms.len();
+    if n <= degree + 1 {
+        return n == degree + 1;
+    }
+
+    let xs = (1..degree + 2).map(|j| Scalar::<E>::from(j as u64)).collect::<Vec<_>>();
+
+    (degree + 1..n).all(|i| {
+        let x = Scalar::<E>::from((i + 1) as u64);
+
+        // sum_j L_j(x) * comm_j, with L_j the Lagrange basis over xs
+        let interpolated = xs.iter().enumerate().fold(E::G2Projective::zero(), |acc, (j, x_j)| {
+            let mut l_j = Scalar::<E>::from(1u64);
+            for (m, x_m) in xs.iter().enumerate() {
+                if m != j {
+                    l_j *= (x - x_m) * (*x_j - x_m).inverse().unwrap();
+                }
+            }
+            acc + comms[j].mul(l_j.into_repr())
+        });
+
+        interpolated == comms[i]
+    })
+}
+
+
+// Function generating a random test case: an (optionally corrupted) share with n
+// recipients and threshold t, along with the recipients' public keys.
+pub fn random_case<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    g1: E::G1Affine,
+    g2: E::G2Affine,
+    n: usize,
+    t: usize,
+) -> Result<TestCase<E>, PVSSError<E>> {
+    let corruption = match rng.gen_range(0, 4) {
+        0 => Corruption::None,
+        1 => Corruption::Encryption(rng.gen_range(0, n)),
+        2 => Corruption::Commitment(rng.gen_range(0, n)),
+        _ => Corruption::Degree,
+    };
+
+    let pks = (0..n)
+        .map(|_| g1.mul(Scalar::<E>::rand(rng).into_repr()).into_affine())
+        .collect::<Vec<_>>();
+
+    let degree = if corruption == Corruption::Degree { t + 1 } else { t };
+    let evals = compute_evals::<E>(&Polynomial::<E>::rand(degree, rng), n);
+    let mut comms = commit_evals::<E>(&g2, &evals);
+    let mut encs = encrypt_evals::<E>(&pks, &evals)?;
+
+    match corruption {
+        Corruption::Encryption(i) => encs[i] = E::G1Projective::rand(rng),
+        Corruption::Commitment(i) => comms[i] = E::G2Projective::rand(rng),
+        _ => {},
+    }
+
+    Ok((corruption, pks, encs, comms))
+}
+
+// Function running both implementations of every check on the given number of
+// random test cases, with n recipients and threshold t (n >= t+2).
+pub fn run<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    g1: E::G1Affine,
+    g2: E::G2Affine,
+    n: usize,
+    t: usize,
+    cases: usize,
+) -> Result<DifferentialReport, PVSSError<E>> {
+    if n < t + 2 {
+        return Err(PVSSError::InsufficientEvaluationsError);
+    }
+
+    let mut report = DifferentialReport { cases, ..Default::default() };
+
+    for case in 0..cases {
+        let (corruption, pks, encs, comms) = random_case::<E, R>(rng, g1, g2, n, t)?;
+        let entries = (0..n).map(|i| (pks[i], &encs[i], &comms[i])).collect::<Vec<_>>();
+
+        let verdicts = [
+            (Check::Encryption,
+             kernel::encryptions_check::<E, R>(rng, g2, &entries).is_ok(),
+             reference_encryptions_check::<E>(g2, &entries)),
+            (Check::Coding,
+             kernel::coding_check::<E, R>(rng, &comms, t).is_ok(),
+             reference_coding_check::<E>(&comms, t)),
+            (Check::CodingFiatShamir,
+             kernel::coding_check_fiat_shamir::<E>(&comms, t).is_ok(),
+             reference_coding_check::<E>(&comms, t)),
+        ];
+
+        if verdicts.iter().any(|(_, _, reference)| !reference) {
+            report.rejected += 1;
+        }
+
+        for (check, optimized, reference) in verdicts.iter() {
+            if optimized != reference {
+                report.divergences.push(Divergence {
+                    case,
+                    check: *check,
+                    corruption,
+                    optimized: *optimized,
+                    reference: *reference,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::PairingEngine;
+    use ark_std::UniformRand;
+
+    use crate::differential::{reference_coding_check, run};
+    use crate::modified_scrape::srs::SRS;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_differential() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+
+        let report = run::<E, _>(rng, srs.g1, srs.g2, 6, 2, 12).unwrap();
+        assert_eq!(report.cases, 12);
+        assert!(report.is_consistent(), "{:?}", report.divergences);
+
+        // the reference coding check does reject random commitments
+        let comms = (0..6).map(|_| <E as PairingEngine>::G2Projective::rand(rng)).collect::<Vec<_>>();
+        assert!(!reference_coding_check::<E>(&comms, 2));
+
+        // and n must leave room for a dual codeword
+        assert!(run::<E, _>(rng, srs.g1, srs.g2, 3, 2, 1).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while establishing a session.
+#[derive(Error, Debug)]
+pub enum HandshakeError {
+    #[error("Invalid ephemeral key: {0}")]
+    InvalidEphemeralKey(&'static str),
+    #[error("Peer failed to confirm the session keys")]
+    KeyConfirmationError,
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SerializationError),
+}
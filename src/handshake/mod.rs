@@ -0,0 +1,276 @@
+use crate::handshake::errors::HandshakeError;
+use crate::Digest;
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{FpParameters, PrimeField, UniformRand, Zero};
+use ark_serialize::*;
+use blake2s_simd::Params;
+use rand::{CryptoRng, Rng};
+
+pub mod errors;
+
+const KEY_PERSONALIZATION: &[u8] = b"OPTRHSKY";    // persona for deriving session keys
+const MAC_PERSONALIZATION: &[u8] = b"OPTRHSMC";    // persona for key-confirmation tags
+
+/* Authenticated key exchange between two participants, reusing their Schnorr key
+   pairs (sk, pk = g^sk) as static Diffie-Hellman keys. Both parties contribute a
+   fresh ephemeral key, and the session keys are derived from three DH values
+   (as in 3DH / Noise KK):
+       s_I * E_R,   e_I * S_R,   e_I * E_R
+   so that only the holders of both static secret keys can derive them, and past
+   sessions stay secret if the static keys leak later. Key confirmation:
+
+       initiator -> responder:  HandshakeInit { E_I }
+       responder -> initiator:  HandshakeResponse { E_R, tag_R }
+       initiator -> responder:  HandshakeFinish { tag_I }
+
+   with tags being MACs over the handshake transcript under direction-specific keys.
+   Each party uses its own session keys only after checking the peer's tag.
+*/
+
+// Struct SessionKeys holds the keys protecting the traffic of an established session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionKeys {
+    pub send: [u8; 32],     // key for the traffic we send
+    pub receive: [u8; 32],  // key for the traffic we receive
+}
+
+// First message of the handshake, sent by the initiator.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HandshakeInit<C: AffineCurve> {
+    pub ephemeral: C,   // the initiator's ephemeral public key E_I
+}
+
+// Second message of the handshake, sent by the responder.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HandshakeResponse<C: AffineCurve> {
+    pub ephemeral: C,   // the responder's ephemeral public key E_R
+    pub tag: Digest,    // the responder's key-confirmation tag
+}
+
+// Third message of the handshake, sent by the initiator.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HandshakeFinish {
+    pub tag: Digest,   // the initiator's key-confirmation tag
+}
+
+// Struct KeySchedule holds the keys derived from a handshake.
+struct KeySchedule {
+    initiator_to_responder: [u8; 32],
+    responder_to_initiator: [u8; 32],
+    initiator_confirm: [u8; 32],
+    responder_confirm: [u8; 32],
+    transcript: Vec<u8>,
+}
+
+// Utility function hashing a label and the given bytes into a 32-byte key.
+fn derive(label: &[u8], bytes: &[u8]) -> [u8; 32] {
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(KEY_PERSONALIZATION)
+        .to_state()
+        .update(&[label.len() as u8])
+        .update(label)
+        .update(bytes)
+        .finalize();
+    let mut key = [0u8; 32];
+    key.copy_from_slice(hash.as_bytes());
+    key
+}
+
+// Utility function computing a key-confirmation tag over the handshake transcript.
+fn mac(key: &[u8; 32], transcript: &[u8]) -> Digest {
+    let hash = Params::new()
+        .hash_length(32)
+        .key(key)
+        .personal(MAC_PERSONALIZATION)
+        .to_state()
+        .update(transcript)
+        .finalize();
+    let mut tag = [0u8; 32];
+    tag.copy_from_slice(hash.as_bytes());
+    Digest(tag)
+}
+
+// Utility function comparing tags in constant time.
+fn tags_equal(a: &Digest, b: &Digest) -> bool {
+    a.0.iter().zip(b.0.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Utility function rejecting ephemeral keys that are the identity or lie outside
+// the prime-order subgroup (which would leak bits of our static secret key).
+fn check_ephemeral<C: AffineCurve>(point: &C) -> Result<(), HandshakeError> {
+    if point.is_zero() {
+        return Err(HandshakeError::InvalidEphemeralKey("identity element"));
+    }
+    if !point.mul(<C::ScalarField as PrimeField>::Params::MODULUS).is_zero() {
+        return Err(HandshakeError::InvalidEphemeralKey("not in the prime-order subgroup"));
+    }
+    Ok(())
+}
+
+// Utility function deriving the key schedule from the public keys and DH values.
+fn key_schedule<C: AffineCurve>(
+    initiator_pk: &C,
+    responder_pk: &C,
+    initiator_ephemeral: &C,
+    responder_ephemeral: &C,
+    dhs: [C::Projective; 3],
+) -> Result<KeySchedule, HandshakeError> {
+    let mut transcript = vec![];
+    initiator_pk.serialize(&mut transcript)?;
+    responder_pk.serialize(&mut transcript)?;
+    initiator_ephemeral.serialize(&mut transcript)?;
+    responder_ephemeral.serialize(&mut transcript)?;
+
+    let mut secret = transcript.clone();
+    for dh in dhs.iter() {
+        dh.into_affine().serialize(&mut secret)?;
+    }
+
+    Ok(KeySchedule {
+        initiator_to_responder: derive(b"initiator to responder", &secret),
+        responder_to_initiator: derive(b"responder to initiator", &secret),
+        initiator_confirm: derive(b"initiator confirmation", &secret),
+        responder_confirm: derive(b"responder confirmation", &secret),
+        transcript,
+    })
+}
+
+// Struct Initiator holds the state of the party starting a handshake.
+pub struct Initiator<C: AffineCurve> {
+    sk: C::ScalarField,          // our static (Schnorr) secret key
+    pk: C,                       // our static public key
+    peer_pk: C,                  // the responder's static public key
+    ephemeral: C::ScalarField,   // our ephemeral secret key
+    init: HandshakeInit<C>,      // the first message, as sent
+}
+
+impl<C: AffineCurve> Initiator<C> {
+
+    // Function for starting a handshake with the holder of peer_pk, where g is the
+    // generator the Schnorr keys are defined w.r.t. (the Schnorr SRS).
+    pub fn start<R: Rng + CryptoRng>(rng: &mut R, g: C, sk: C::ScalarField, peer_pk: C) -> (Self, HandshakeInit<C>) {
+        let ephemeral = C::ScalarField::rand(rng);
+        let init = HandshakeInit { ephemeral: g.mul(ephemeral.into_repr()).into_affine() };
+        let pk = g.mul(sk.into_repr()).into_affine();
+
+        (Self { sk, pk, peer_pk, ephemeral, init: init.clone() }, init)
+    }
+
+    // Method for processing the responder's answer. On success, returns our session
+    // keys and the final message to send to the responder.
+    pub fn finish(self, response: &HandshakeResponse<C>) -> Result<(SessionKeys, HandshakeFinish), HandshakeError> {
+        check_ephemeral(&response.ephemeral)?;
+
+        let dhs = [
+            response.ephemeral.mul(self.sk.into_repr()),
+            self.peer_pk.mul(self.ephemeral.into_repr()),
+            response.ephemeral.mul(self.ephemeral.into_repr()),
+        ];
+        let keys = key_schedule(&self.pk, &self.peer_pk, &self.init.ephemeral, &response.ephemeral, dhs)?;
+
+        if !tags_equal(&mac(&keys.responder_confirm, &keys.transcript), &response.tag) {
+            return Err(HandshakeError::KeyConfirmationError);
+        }
+
+        Ok((
+            SessionKeys { send: keys.initiator_to_responder, receive: keys.responder_to_initiator },
+            HandshakeFinish { tag: mac(&keys.initiator_confirm, &keys.transcript) },
+        ))
+    }
+}
+
+// Struct Responder holds the state of the party answering a handshake, until the
+// initiator's confirmation arrives.
+pub struct Responder {
+    keys: SessionKeys,              // our session keys, not to be used before confirmation
+    initiator_confirm: [u8; 32],    // key for checking the initiator's tag
+    transcript: Vec<u8>,            // the handshake transcript
+}
+
+impl Responder {
+
+    // Function for answering a handshake started by the holder of peer_pk, where g
+    // is the generator the Schnorr keys are defined w.r.t.
+    pub fn respond<C: AffineCurve, R: Rng + CryptoRng>(
+        rng: &mut R,
+        g: C,
+        sk: C::ScalarField,
+        peer_pk: C,
+        init: &HandshakeInit<C>,
+    ) -> Result<(Self, HandshakeResponse<C>), HandshakeError> {
+        check_ephemeral(&init.ephemeral)?;
+
+        let ephemeral = C::ScalarField::rand(rng);
+        let ephemeral_pk = g.mul(ephemeral.into_repr()).into_affine();
+        let pk = g.mul(sk.into_repr()).into_affine();
+
+        let dhs = [
+            peer_pk.mul(ephemeral.into_repr()),
+            init.ephemeral.mul(sk.into_repr()),
+            init.ephemeral.mul(ephemeral.into_repr()),
+        ];
+        let keys = key_schedule(&peer_pk, &pk, &init.ephemeral, &ephemeral_pk, dhs)?;
+
+        let response = HandshakeResponse { ephemeral: ephemeral_pk, tag: mac(&keys.responder_confirm, &keys.transcript) };
+        let responder = Self {
+            keys: SessionKeys { send: keys.responder_to_initiator, receive: keys.initiator_to_responder },
+            initiator_confirm: keys.initiator_confirm,
+            transcript: keys.transcript,
+        };
+
+        Ok((responder, response))
+    }
+
+    // Method for checking the initiator's confirmation, releasing our session keys.
+    pub fn finish(self, finish: &HandshakeFinish) -> Result<SessionKeys, HandshakeError> {
+        if !tags_equal(&mac(&self.initiator_confirm, &self.transcript), &finish.tag) {
+            return Err(HandshakeError::KeyConfirmationError);
+        }
+
+        Ok(self.keys)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::handshake::{HandshakeFinish, Initiator, Responder};
+    use crate::Digest;
+    use crate::signature::{schnorr::{srs::SRS, SchnorrSignature, SchnorrVersion}, scheme::SignatureScheme};
+
+    use ark_bls12_381::G1Affine;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_handshake() {
+        let rng = &mut thread_rng();
+        let schnorr = SchnorrSignature::<G1Affine> { srs: SRS::setup(rng).unwrap(), version: SchnorrVersion::KeyBound };
+        let g = schnorr.srs.g_public_key;
+        let (alice_sk, alice_pk) = schnorr.generate_keypair(rng).unwrap();
+        let (bob_sk, bob_pk) = schnorr.generate_keypair(rng).unwrap();
+        let (eve_sk, _) = schnorr.generate_keypair(rng).unwrap();
+
+        // Alice and Bob agree on mirrored session keys.
+        let (alice, init) = Initiator::start(rng, g, alice_sk, bob_pk);
+        let (bob, response) = Responder::respond(rng, g, bob_sk, alice_pk, &init).unwrap();
+        let (alice_keys, finish) = alice.finish(&response).unwrap();
+        let bob_keys = bob.finish(&finish).unwrap();
+        assert_eq!(alice_keys.send, bob_keys.receive);
+        assert_eq!(alice_keys.receive, bob_keys.send);
+        assert_ne!(alice_keys.send, alice_keys.receive);
+
+        // Eve cannot impersonate Bob to Alice, nor Alice to Bob.
+        let (alice, init) = Initiator::start(rng, g, alice_sk, bob_pk);
+        let (_, response) = Responder::respond(rng, g, eve_sk, alice_pk, &init).unwrap();
+        assert!(alice.finish(&response).is_err());
+
+        let (eve, init) = Initiator::start(rng, g, eve_sk, bob_pk);
+        let (bob, response) = Responder::respond(rng, g, bob_sk, alice_pk, &init).unwrap();
+        assert!(eve.finish(&response).is_err());
+        assert!(bob.finish(&HandshakeFinish { tag: Digest([0u8; 32]) }).is_err());
+    }
+}
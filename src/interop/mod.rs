@@ -0,0 +1 @@
+pub mod optrand_reference;
@@ -0,0 +1,338 @@
+use crate::modified_scrape::{
+    contributions::Contributions,
+    decomp::DecompProof,
+    errors::PVSSError,
+    pvss::PVSSShare,
+    share::{PVSSTranscript, PVSSTranscriptParticipant},
+};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Digest, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+/* Conversion between this crate's PVSS types and a fixed, self-describing
+   reference layout, meant for exchanging shares and transcripts with other
+   implementations. Unlike the canonical serialization, the layout does not
+   depend on arkworks' encoding of counts: all group elements are stored
+   uncompressed, and every count and participant id is framed as a big-endian u32:
+
+   share      := MAGIC || VERSION || 0x00 || n || comms[n] || encs[n]
+   transcript := MAGIC || VERSION || 0x01 || t || n || comms[n] || encs[n]
+                 || k || k * (id || dlk_proof || gs || signature_on_decomp)
+
+   The layout has no room for the binding of decomposition proofs (nor for
+   contribution weights), so only unbound proofs can be exported, and imported
+   proofs are unbound.
+
+   The original Optrand prototype's serialization is not available to this crate,
+   so the layout above is not a transcription of it: it is this crate's own fixed
+   layout, pinned by the known-answer test below, which other implementations
+   (including the prototype, through an adapter) can target.
+*/
+
+const MAGIC: &[u8; 4] = b"OPTR";   // leading bytes of every reference encoding
+const VERSION: u8 = 1;             // layout version understood by this module
+
+const KIND_SHARE: u8 = 0;          // tag for an encoded PVSSShare
+const KIND_TRANSCRIPT: u8 = 1;     // tag for an encoded PVSSTranscript
+
+
+// Utility function for writing a big-endian u32 into a writer.
+fn write_u32<E: PairingEngine, W: Write>(writer: &mut W, value: usize) -> Result<(), PVSSError<E>> {
+    if value > u32::MAX as usize {
+        return Err(PVSSError::ReferenceEncodingError(format!("value {} does not fit in a u32", value)));
+    }
+    writer.write_all(&(value as u32).to_be_bytes()).map_err(SerializationError::from)?;
+    Ok(())
+}
+
+// Utility function for reading a big-endian u32 from a reader.
+fn read_u32<E: PairingEngine, R: Read>(reader: &mut R) -> Result<usize, PVSSError<E>> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes).map_err(SerializationError::from)?;
+    Ok(u32::from_be_bytes(bytes) as usize)
+}
+
+// Utility function for writing the common header of a reference encoding.
+fn write_header<E: PairingEngine, W: Write>(writer: &mut W, kind: u8) -> Result<(), PVSSError<E>> {
+    writer.write_all(MAGIC).map_err(SerializationError::from)?;
+    writer.write_all(&[VERSION, kind]).map_err(SerializationError::from)?;
+    Ok(())
+}
+
+// Utility function for reading and checking the common header of a reference encoding.
+fn read_header<E: PairingEngine, R: Read>(reader: &mut R, kind: u8) -> Result<(), PVSSError<E>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).map_err(SerializationError::from)?;
+    if &magic != MAGIC {
+        return Err(PVSSError::ReferenceEncodingError("bad magic bytes".to_string()));
+    }
+
+    let mut version_kind = [0u8; 2];
+    reader.read_exact(&mut version_kind).map_err(SerializationError::from)?;
+    if version_kind[0] != VERSION {
+        return Err(PVSSError::ReferenceEncodingError(format!("unsupported version {}", version_kind[0])));
+    }
+    if version_kind[1] != kind {
+        return Err(PVSSError::ReferenceEncodingError(format!("unexpected object kind {}", version_kind[1])));
+    }
+
+    Ok(())
+}
+
+// Utility function for writing the commitment and encryption vectors of a core share.
+fn write_core<E: PairingEngine, W: Write>(writer: &mut W, share: &PVSSShare<E>) -> Result<(), PVSSError<E>> {
+    if share.comms.len() != share.encs.len() {
+        return Err(PVSSError::MismatchedCommitmentsEncryptionsError(share.comms.len(), share.encs.len()));
+    }
+
+    write_u32(writer, share.comms.len())?;
+    for comm in share.comms.iter() {
+        comm.serialize_uncompressed(&mut *writer)?;
+    }
+    for enc in share.encs.iter() {
+        enc.serialize_uncompressed(&mut *writer)?;
+    }
+
+    Ok(())
+}
+
+// Utility function for reading the commitment and encryption vectors of a core share.
+fn read_core<E: PairingEngine, R: Read>(reader: &mut R) -> Result<PVSSShare<E>, PVSSError<E>> {
+    let n = read_u32(reader)?;
+
+    let comms = (0..n)
+        .map(|_| E::G2Projective::deserialize_uncompressed(&mut *reader))
+        .collect::<Result<Vec<_>, _>>()?;
+    let encs = (0..n)
+        .map(|_| E::G1Projective::deserialize_uncompressed(&mut *reader))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PVSSShare { comms, encs })
+}
+
+
+// Function for exporting a core PVSS share into the reference layout.
+pub fn export_share<E: PairingEngine>(share: &PVSSShare<E>) -> Result<Vec<u8>, PVSSError<E>> {
+    let mut bytes = vec![];
+    write_header(&mut bytes, KIND_SHARE)?;
+    write_core(&mut bytes, share)?;
+    Ok(bytes)
+}
+
+// Function for importing a core PVSS share from the reference layout.
+pub fn import_share<E: PairingEngine>(bytes: &[u8]) -> Result<PVSSShare<E>, PVSSError<E>> {
+    let mut reader = bytes;
+    read_header(&mut reader, KIND_SHARE)?;
+    let share = read_core(&mut reader)?;
+
+    if !reader.is_empty() {
+        return Err(PVSSError::ReferenceEncodingError(format!("{} trailing bytes", reader.len())));
+    }
+
+    Ok(share)
+}
+
+// Function for exporting an aggregated PVSS transcript into the reference layout.
+pub fn export_transcript<E, SSIG>(transcript: &PVSSTranscript<E, SSIG>) -> Result<Vec<u8>, PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    let mut bytes = vec![];
+    write_header(&mut bytes, KIND_TRANSCRIPT)?;
+    write_u32(&mut bytes, transcript.degree)?;
+
+    if transcript.pvss_share.comms.len() != transcript.num_participants {
+        return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(transcript.pvss_share.encs.len(),
+            transcript.pvss_share.comms.len(), transcript.num_participants));
+    }
+    write_core(&mut bytes, &transcript.pvss_share)?;
+
+    // Contributions are written in ascending id order (their iteration order).
+    // The layout has no notion of weights, so every contribution must count once.
+    write_u32(&mut bytes, transcript.contributions.len())?;
+    for (id, contribution) in transcript.contributions.iter() {
+        if contribution.weight != 1 {
+            return Err(PVSSError::ReferenceEncodingError(format!("contribution {} has weight {}", id, contribution.weight)));
+        }
+        if contribution.decomp_proof.is_bound() {
+            return Err(PVSSError::ReferenceEncodingError(format!("decomposition proof of contribution {} is bound to its share", id)));
+        }
+        write_u32(&mut bytes, *id)?;
+        contribution.decomp_proof.proof().serialize_uncompressed(&mut bytes)?;
+        contribution.decomp_proof.gs().serialize_uncompressed(&mut bytes)?;
+        contribution.signature_on_decomp.serialize_uncompressed(&mut bytes)?;
+    }
+
+    Ok(bytes)
+}
+
+// Function for importing an aggregated PVSS transcript from the reference layout.
+pub fn import_transcript<E, SSIG>(bytes: &[u8]) -> Result<PVSSTranscript<E, SSIG>, PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    let mut reader = bytes;
+    read_header(&mut reader, KIND_TRANSCRIPT)?;
+
+    let degree = read_u32(&mut reader)?;
+    let pvss_share = read_core(&mut reader)?;
+    let num_participants = pvss_share.comms.len();
+
+    let k = read_u32(&mut reader)?;
+    let mut contributions = Contributions::for_participants(num_participants);
+    for _ in 0..k {
+        let id = read_u32(&mut reader)?;
+        if id >= num_participants {
+            return Err(PVSSError::InvalidParticipantId(id));
+        }
+
+        let decomp_proof = DecompProof::<E>::new(
+            CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            E::G2Affine::deserialize_uncompressed(&mut reader)?,
+            Digest::default(),
+        );
+        let signature_on_decomp = SSIG::Signature::deserialize_uncompressed(&mut reader)?;
+
+        if contributions.insert(id, PVSSTranscriptParticipant { decomp_proof, signature_on_decomp, weight: 1 }).is_some() {
+            return Err(PVSSError::ReferenceEncodingError(format!("duplicate contribution for id {}", id)));
+        }
+    }
+
+    if !reader.is_empty() {
+        return Err(PVSSError::ReferenceEncodingError(format!("{} trailing bytes", reader.len())));
+    }
+
+    Ok(PVSSTranscript {
+        degree,
+        num_participants,
+        contributions,
+        pvss_share,
+    })
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};   // implements PairingEngine
+    use ark_ec::{PairingEngine, ProjectiveCurve};
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+
+    use crate::interop::optrand_reference::{export_share, export_transcript, import_share, import_transcript};
+    use crate::modified_scrape::{
+        contributions::Contributions,
+        decomp::Decomp,
+        errors::PVSSError,
+        pvss::PVSSShare,
+        share::{PVSSTranscript, PVSSTranscriptParticipant},
+        tests::{setup_nodes, SSIG},
+    };
+    use crate::Scalar;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_share_roundtrip() {
+        let rng = &mut thread_rng();
+        let n = 10;
+
+        // we use random group elements since it doesn't matter here
+        let share = PVSSShare::<E> {
+            comms: (0..n).map(|_| <E as PairingEngine>::G2Projective::rand(rng)).collect(),
+            encs: (0..n).map(|_| <E as PairingEngine>::G1Projective::rand(rng)).collect(),
+        };
+
+        let bytes = export_share(&share).unwrap();
+        let imported = import_share::<E>(&bytes).unwrap();
+
+        assert_eq!(share.comms, imported.comms);
+        assert_eq!(share.encs, imported.encs);
+    }
+
+    #[test]
+    fn test_share_known_answer() {
+        // An empty share is just the framing.
+        let empty = PVSSShare::<E> { comms: vec![], encs: vec![] };
+        assert_eq!(export_share(&empty).unwrap(), b"OPTR\x01\x00\x00\x00\x00\x00".to_vec());
+
+        // Otherwise, the count is followed by the uncompressed commitments, then the
+        // uncompressed encryptions (192 and 96 bytes each over BLS12-381).
+        let share = PVSSShare::<E> {
+            comms: vec![<E as PairingEngine>::G2Projective::prime_subgroup_generator(); 2],
+            encs: vec![<E as PairingEngine>::G1Projective::prime_subgroup_generator(); 2],
+        };
+        let bytes = export_share(&share).unwrap();
+        assert_eq!(&bytes[..10], b"OPTR\x01\x00\x00\x00\x00\x02");
+        assert_eq!(bytes.len(), 10 + 2 * 192 + 2 * 96);
+
+        let mut g2 = vec![];
+        share.comms[0].serialize_uncompressed(&mut g2).unwrap();
+        assert_eq!(&bytes[10..202], &g2[..]);
+        let mut g1 = vec![];
+        share.encs[0].serialize_uncompressed(&mut g1).unwrap();
+        assert_eq!(&bytes[394..490], &g1[..]);
+    }
+
+    #[test]
+    fn test_share_trailing_bytes() {
+        let rng = &mut thread_rng();
+
+        let share = PVSSShare::<E> {
+            comms: vec![<E as PairingEngine>::G2Projective::rand(rng); 4],
+            encs: vec![<E as PairingEngine>::G1Projective::rand(rng); 4],
+        };
+
+        let mut bytes = export_share(&share).unwrap();
+        bytes.push(0);
+
+        assert!(matches!(import_share::<E>(&bytes), Err(PVSSError::ReferenceEncodingError(_))));
+    }
+
+    #[test]
+    fn test_transcript_roundtrip() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let config = nodes[0].aggregator.config.clone();
+
+        // The layout only carries unbound proofs counted once.
+        let mut contributions = Contributions::for_participants(n);
+        for id in [0, 2].iter() {
+            let secret = Scalar::<E>::rand(rng);
+            let decomp_proof = Decomp::<E>::generate(rng, &config, &secret).unwrap();
+            let signature_on_decomp = nodes[*id].share(rng).unwrap().signature_on_decomp;
+            contributions.insert(*id, PVSSTranscriptParticipant { decomp_proof, signature_on_decomp, weight: 1 });
+        }
+        let transcript = PVSSTranscript::<E, SSIG> {
+            degree: t,
+            num_participants: n,
+            contributions,
+            pvss_share: PVSSShare {
+                comms: (0..n).map(|_| <E as PairingEngine>::G2Projective::rand(rng)).collect(),
+                encs: (0..n).map(|_| <E as PairingEngine>::G1Projective::rand(rng)).collect(),
+            },
+        };
+
+        let bytes = export_transcript(&transcript).unwrap();
+        let imported = import_transcript::<E, SSIG>(&bytes).unwrap();
+
+        assert_eq!((imported.degree, imported.num_participants), (t, n));
+        assert_eq!(imported.pvss_share.comms, transcript.pvss_share.comms);
+        assert_eq!(imported.pvss_share.encs, transcript.pvss_share.encs);
+        for id in [0, 2].iter() {
+            assert_eq!(imported.contributions.get(id).unwrap().decomp_proof, transcript.contributions.get(id).unwrap().decomp_proof);
+        }
+        assert!(imported.contributions.get(&1).is_none());
+        assert_eq!(export_transcript(&imported).unwrap(), bytes);
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(matches!(import_transcript::<E, SSIG>(&extended), Err(PVSSError::ReferenceEncodingError(_))));
+    }
+}
@@ -0,0 +1,18 @@
+use crate::broadcast::errors::BroadcastError;
+use crate::modified_scrape::errors::PVSSError;
+use ark_ec::PairingEngine;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while running a simulated cluster.
+#[derive(Error, Debug)]
+pub enum HarnessError<E: PairingEngine> {
+    #[error("Invalid node id: {0}")]
+    InvalidNodeId(usize),
+    #[error("Network did not quiesce within {0} ticks")]
+    Timeout(u64),
+    #[error("Broadcast error: {0}")]
+    BroadcastError(#[from] BroadcastError),
+    #[error("PVSS error: {0}")]
+    PVSSError(#[from] PVSSError<E>),
+}
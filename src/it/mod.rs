@@ -0,0 +1,370 @@
+use crate::broadcast::{errors::BroadcastError, Delivery, ReliableBroadcast, Transport};
+use crate::ceremony::{self, SSIG};
+use crate::it::errors::HarnessError;
+use crate::modified_scrape::{node::Node, share::PVSSAugmentedShare};
+
+use ark_ec::PairingEngine;
+use ark_std::collections::BTreeSet;
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+pub mod errors;
+
+/* Integration harness. Runs multi-node ceremonies in-process, over a simulated
+   network which injects faults into the point-to-point channels of the Transport
+   abstraction: messages may be dropped, delayed, reordered or duplicated, and the
+   nodes may be partitioned into groups which cannot reach each other (until the
+   partition heals, or for good). Time advances in discrete ticks, and all faults
+   are drawn from a seeded generator, so every run is reproducible.
+
+   Network and Outbox only deal in bytes, and can carry any protocol built on the
+   Transport trait. Cluster wires them up with a committee running the dealing
+   phase: every node reliably broadcasts its share, and verifies and aggregates the
+   shares it delivers. Tests then check convergence properties, e.g., that all
+   nodes ended up with the same contributions despite the faults.
+*/
+
+// Struct Faults describes the faults injected by the network. The default
+// configuration injects none.
+#[derive(Clone, Debug, Default)]
+pub struct Faults {
+    pub drop_rate: f64,                      // probability that a message is lost
+    pub duplicate_rate: f64,                 // probability that a message is delivered twice
+    pub max_delay: u64,                      // messages take 1 to 1 + max_delay ticks to arrive
+    pub reorder: bool,                       // whether messages arriving at the same tick are shuffled
+    pub partitions: Vec<BTreeSet<usize>>,    // groups of nodes cut off from each other
+    pub heal_at: Option<u64>,                // tick at which the partition heals, if ever
+}
+
+impl Faults {
+
+    // Function for partitioning the nodes into the given groups, until the given tick.
+    // Nodes left out of all groups form a group of their own.
+    pub fn partitioned(groups: Vec<Vec<usize>>, heal_at: Option<u64>) -> Self {
+        Self {
+            partitions: groups.into_iter().map(|group| group.into_iter().collect()).collect(),
+            heal_at,
+            ..Default::default()
+        }
+    }
+
+    // Method returning whether node from can reach node to at the given tick.
+    pub fn connected(&self, from: usize, to: usize, now: u64) -> bool {
+        if from == to || self.heal_at.is_some_and(|heal_at| now >= heal_at) {
+            return true;
+        }
+        let group = |id: usize| self.partitions.iter().position(|group| group.contains(&id));
+        group(from) == group(to)
+    }
+}
+
+// Struct NetworkStats counts what happened to the messages submitted to the network.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkStats {
+    pub sent: usize,         // messages submitted
+    pub dropped: usize,      // messages lost, including those across a partition that never heals
+    pub duplicated: usize,   // messages delivered twice
+    pub held: usize,         // copies held back until a partition healed
+    pub delivered: usize,    // copies handed to their recipients
+}
+
+// Struct Envelope represents a message in flight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope {
+    pub from: usize,         // sender of the message
+    pub to: usize,           // recipient of the message
+    pub deliver_at: u64,     // tick at which the message arrives
+    pub message: Vec<u8>,    // the message itself
+}
+
+// Struct Network models a simulated network with fault injection.
+pub struct Network {
+    pub faults: Faults,           // faults injected into the channels
+    pub stats: NetworkStats,      // what happened to the messages so far
+    now: u64,                     // current tick
+    in_flight: Vec<Envelope>,     // messages submitted but not delivered yet
+    rng: ChaChaRng,               // source of the faults
+}
+
+impl Network {
+
+    // Function for creating a network injecting the given faults, drawn from the given seed.
+    pub fn new(faults: Faults, seed: u64) -> Self {
+        Self {
+            faults,
+            stats: NetworkStats::default(),
+            now: 0,
+            in_flight: vec![],
+            rng: ChaChaRng::seed_from_u64(seed),
+        }
+    }
+
+    // Method returning the current tick.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    // Method returning whether no message is in flight.
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_empty()
+    }
+
+    // Method for submitting a message from node from to node to. Messages a node
+    // sends to itself are never subject to faults.
+    pub fn submit(&mut self, from: usize, to: usize, message: Vec<u8>) {
+        self.stats.sent += 1;
+        if from == to {
+            self.in_flight.push(Envelope { from, to, deliver_at: self.now + 1, message });
+            return;
+        }
+
+        if self.rng.gen::<f64>() < self.faults.drop_rate {
+            self.stats.dropped += 1;
+            return;
+        }
+        let copies = if self.rng.gen::<f64>() < self.faults.duplicate_rate {
+            self.stats.duplicated += 1;
+            2
+        } else {
+            1
+        };
+
+        for _ in 0..copies {
+            let mut deliver_at = self.now + 1 + self.rng.gen_range(0, self.faults.max_delay + 1);
+            if !self.faults.connected(from, to, self.now) {
+                match self.faults.heal_at {
+                    Some(heal_at) => {
+                        self.stats.held += 1;
+                        deliver_at = deliver_at.max(heal_at);
+                    },
+                    None => {
+                        self.stats.dropped += 1;
+                        continue;
+                    },
+                }
+            }
+            self.in_flight.push(Envelope { from, to, deliver_at, message: message.clone() });
+        }
+    }
+
+    // Method for submitting all messages collected in an outbox.
+    pub fn flush(&mut self, outbox: Outbox) {
+        for (to, message) in outbox.messages {
+            self.submit(outbox.from, to, message);
+        }
+    }
+
+    // Method for advancing the clock by one tick. Returns the messages arriving at the
+    // new tick, in submission order unless reordering is enabled.
+    pub fn tick(&mut self) -> Vec<Envelope> {
+        self.now += 1;
+        let now = self.now;
+        let (mut due, pending): (Vec<_>, Vec<_>) = self.in_flight.drain(..).partition(|envelope| envelope.deliver_at <= now);
+        self.in_flight = pending;
+
+        if self.faults.reorder {
+            due.shuffle(&mut self.rng);
+        }
+        self.stats.delivered += due.len();
+        due
+    }
+}
+
+// Struct Outbox collects the messages a node sends while handling a message, to be
+// submitted to the network afterwards.
+#[derive(Clone, Debug, Default)]
+pub struct Outbox {
+    pub from: usize,                         // the sending node
+    pub messages: Vec<(usize, Vec<u8>)>,     // (recipient, message) pairs
+}
+
+impl Outbox {
+
+    // Function for creating an empty outbox for the given node.
+    pub fn new(from: usize) -> Self {
+        Self { from, messages: vec![] }
+    }
+}
+
+impl Transport for Outbox {
+    fn send(&mut self, to: usize, message: Vec<u8>) -> Result<(), BroadcastError> {
+        self.messages.push((to, message));
+        Ok(())
+    }
+}
+
+// Reliable broadcast of augmented shares, as run by the nodes of a cluster.
+pub type AugmentedShareBroadcast<E> = ReliableBroadcast<PVSSAugmentedShare<E, SSIG<E>>>;
+
+// Struct Cluster models a committee of nodes running the dealing phase over a
+// simulated network.
+pub struct Cluster<E: PairingEngine> {
+    pub nodes: Vec<Node<E, SSIG<E>>>,                   // the nodes, node i having id i
+    pub network: Network,                               // the network connecting them
+    pub crashed: BTreeSet<usize>,                       // nodes which neither send nor receive
+    pub rejected: Vec<usize>,                           // number of delivered shares each node rejected
+    endpoints: Vec<AugmentedShareBroadcast<E>>,         // each node's broadcast endpoint
+    rng: ChaChaRng,                                     // source of the nodes' randomness
+}
+
+impl<E: PairingEngine> Cluster<E> {
+
+    // Function for setting up a cluster of n nodes with threshold t, over a network
+    // injecting the given faults. All randomness is drawn from the given seed.
+    pub fn new(n: usize, t: usize, faults: Faults, seed: u64) -> Result<Self, HarnessError<E>> {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let (_, nodes) = ceremony::setup::<E, _>(n, t, &mut rng)?;
+        let network = Network::new(faults, rng.gen());
+        let endpoints = (0..n)
+            .map(|id| ReliableBroadcast::new(id, n))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { nodes, network, crashed: BTreeSet::new(), rejected: vec![0; n], endpoints, rng })
+    }
+
+    // Method returning the ids of the nodes which have not crashed.
+    pub fn live(&self) -> Vec<usize> {
+        (0..self.nodes.len()).filter(|id| !self.crashed.contains(id)).collect()
+    }
+
+    // Method for crashing a node: it stops sending, and messages to it are discarded.
+    pub fn crash(&mut self, id: usize) -> Result<(), HarnessError<E>> {
+        if id >= self.nodes.len() {
+            return Err(HarnessError::InvalidNodeId(id));
+        }
+        self.crashed.insert(id);
+        Ok(())
+    }
+
+    // Method for having every live node deal a share and broadcast it under the given tag.
+    pub fn deal(&mut self, tag: u64) -> Result<(), HarnessError<E>> {
+        for id in self.live() {
+            let share = self.nodes[id].share(&mut self.rng)?;
+            let mut outbox = Outbox::new(id);
+            self.endpoints[id].broadcast(&mut outbox, tag, &share)?;
+            self.network.flush(outbox);
+        }
+        Ok(())
+    }
+
+    // Method for advancing the network by one tick, having the recipients handle the
+    // messages which arrive. Returns whether messages are still in flight.
+    pub fn step(&mut self) -> Result<bool, HarnessError<E>> {
+        for envelope in self.network.tick() {
+            if self.crashed.contains(&envelope.to) {
+                continue;
+            }
+
+            let mut outbox = Outbox::new(envelope.to);
+            let delivery = self.endpoints[envelope.to].handle(&mut outbox, envelope.from, &envelope.message)?;
+            self.network.flush(outbox);
+
+            if let Some(delivery) = delivery {
+                self.receive(envelope.to, delivery);
+            }
+        }
+        Ok(!self.network.is_idle())
+    }
+
+    // Method for running the network until no message is in flight. Returns the
+    // number of ticks it took, or an error if it took more than max_ticks.
+    pub fn run(&mut self, max_ticks: u64) -> Result<u64, HarnessError<E>> {
+        let start = self.network.now();
+        while !self.network.is_idle() {
+            if self.network.now() - start >= max_ticks {
+                return Err(HarnessError::Timeout(max_ticks));
+            }
+            self.step()?;
+        }
+        Ok(self.network.now() - start)
+    }
+
+    // Utility method handing a delivered share to a node's aggregator. Shares not
+    // dealt by their originator, or failing verification, are counted as rejected.
+    fn receive(&mut self, id: usize, delivery: Delivery<PVSSAugmentedShare<E, SSIG<E>>>) {
+        let accepted = delivery.payload.participant_id == delivery.origin
+            && self.nodes[id]
+                .aggregator
+                .receive_share_from(&mut self.rng, &delivery.payload, Some(delivery.origin))
+                .is_ok();
+        if !accepted {
+            self.rejected[id] += 1;
+        }
+    }
+
+    // Method returning the ids of the dealers whose shares are part of a node's transcript.
+    pub fn contributors(&self, id: usize) -> BTreeSet<usize> {
+        self.nodes[id].aggregator.transcript.contributions.keys().cloned().collect()
+    }
+
+    // Method returning whether the given nodes converged: each of them holds a
+    // transcript that can be finalized, and all of them hold the same contributions.
+    pub fn converged_among(&self, ids: &[usize]) -> bool {
+        let first = match ids.first() {
+            Some(first) => self.contributors(*first),
+            None => return true,
+        };
+        ids.iter().all(|id| self.nodes[*id].aggregator.finalize().is_ok() && self.contributors(*id) == first)
+    }
+
+    // Method returning whether all live nodes converged.
+    pub fn converged(&self) -> bool {
+        self.converged_among(&self.live())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::it::{Cluster, Faults};
+
+    #[test]
+    fn test_faulty_network() {
+        let (n, t) = (4, 1);
+
+        // Delays, reorders and duplicates do not prevent convergence.
+        let faults = Faults { duplicate_rate: 0.2, max_delay: 3, reorder: true, ..Default::default() };
+        let mut cluster = Cluster::<E>::new(n, t, faults, 1).unwrap();
+        cluster.deal(0).unwrap();
+        cluster.run(1_000).unwrap();
+        assert!(cluster.converged());
+        assert_eq!(cluster.contributors(0).len(), n);
+        assert!(cluster.network.stats.duplicated > 0);
+        assert_eq!(cluster.rejected, vec![0; n]);
+
+        // An A/B-C/D partition: neither half has a quorum, so no share is delivered
+        // until the partition heals, and all of them are afterwards.
+        let faults = Faults::partitioned(vec![vec![0, 1], vec![2, 3]], Some(20));
+        let mut cluster = Cluster::<E>::new(n, t, faults, 2).unwrap();
+        cluster.deal(0).unwrap();
+        for _ in 0..10 {
+            cluster.step().unwrap();
+        }
+        assert!((0..n).all(|id| cluster.contributors(id).is_empty()));
+        cluster.run(1_000).unwrap();
+        assert!(cluster.converged());
+        assert_eq!(cluster.contributors(3).len(), n);
+
+        // A node cut off for good is tolerated, as is a crashed one.
+        let faults = Faults::partitioned(vec![vec![0, 1, 2], vec![3]], None);
+        let mut cluster = Cluster::<E>::new(n, t, faults, 3).unwrap();
+        cluster.deal(0).unwrap();
+        cluster.run(1_000).unwrap();
+        assert!(cluster.converged_among(&[0, 1, 2]));
+        assert_eq!(cluster.contributors(0).len(), n - 1);
+        assert!(cluster.network.stats.dropped > 0);
+
+        let mut cluster = Cluster::<E>::new(n, t, Faults::default(), 4).unwrap();
+        cluster.crash(3).unwrap();
+        cluster.deal(0).unwrap();
+        cluster.run(1_000).unwrap();
+        assert!(cluster.converged());
+        assert_eq!(cluster.contributors(1).len(), n - 1);
+        assert!(cluster.crash(n).is_err());
+    }
+}
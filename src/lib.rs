@@ -4,11 +4,161 @@ extern crate ark_std;
 pub mod modified_scrape;
 pub mod signature;
 pub mod nizk;
+pub mod transcript;
+pub mod secrets;
+pub mod beacon;
+pub mod poseidon;
+pub mod interop;
+pub mod wire;
+pub mod loadtest;
+pub mod ceremony;
+pub mod handshake;
+pub mod broadcast;
+pub mod archive;
+pub mod it;
+pub mod simulation;
+
+#[cfg(feature = "deploy")]
+pub mod deploy;
+
+#[cfg(feature = "any-curve")]
+pub mod any_curve;
+
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
+#[cfg(feature = "differential")]
+pub mod differential;
+
+#[cfg(feature = "serde")]
+pub mod serde_support;
+
+#[cfg(feature = "eddsa")]
+pub mod message;
+
+#[cfg(feature = "net")]
+pub mod net;
+
+// EdDSA identity types; PublicKey/SecretKey are not re-exported at the root
+// since those names denote the pairing-group key aliases below.
+#[cfg(feature = "eddsa")]
+pub use signature::eddsa::{Ed25519Scheme, EdDSASignature, Signature};
 
 
 
 use ark_poly::univariate::DensePolynomial;
 use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use blake2s_simd::Params;
+
+const DIGEST_PERSONALIZATION: &[u8] = b"OPTRDGST";   // persona for digests of protocol objects
+
+
+///////////////////////////////////////////////////////////////////
+
+/// A 32-byte digest of a protocol object
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Digest(pub [u8; 32]);
+
+impl std::fmt::Display for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+// Digests are encoded as their 32 raw bytes.
+impl CanonicalSerialize for Digest {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0)?)
+    }
+
+    fn serialized_size(&self) -> usize {
+        32
+    }
+}
+
+impl CanonicalDeserialize for Digest {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut digest = [0u8; 32];
+        reader.read_exact(&mut digest)?;
+        Ok(Digest(digest))
+    }
+}
+
+/// Protocol objects that can be hashed into a Digest
+pub trait Hash {
+    fn digest(&self) -> Digest;
+}
+
+/// The hash functions a Digest can be computed with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Blake2s,
+    #[cfg(feature = "shake-digest")]
+    Shake256,
+}
+
+impl DigestAlgorithm {
+    /// Returns the algorithm the digests of a protocol version (see wire/mod.rs) are
+    /// computed with, if the version is known. Digests are visible on the wire, so
+    /// the algorithm is fixed by the version: a feature would change it for every
+    /// crate in the dependency graph at once. The shake-digest feature only makes
+    /// SHAKE256 available to digest_bytes_with.
+    pub fn for_version(version: u8) -> Option<Self> {
+        match version {
+            1 => Some(DigestAlgorithm::Blake2s),
+            _ => None,
+        }
+    }
+
+    /// Returns the algorithm of the protocol version this build speaks natively
+    pub fn native() -> Self {
+        Self::for_version(wire::PROTOCOL_VERSION).expect("the native protocol version has a digest algorithm")
+    }
+}
+
+/// Hashes a byte string into a Digest, with the algorithm of the native protocol version
+pub fn digest_bytes(bytes: &[u8]) -> Digest {
+    digest_bytes_with(DigestAlgorithm::native(), bytes)
+}
+
+/// Hashes a byte string into a Digest, with the given algorithm
+pub fn digest_bytes_with(algorithm: DigestAlgorithm, bytes: &[u8]) -> Digest {
+    let mut digest = [0u8; 32];
+    match algorithm {
+        DigestAlgorithm::Blake2s => {
+            let hash = Params::new()
+                .hash_length(32)
+                .personal(DIGEST_PERSONALIZATION)
+                .to_state()
+                .update(bytes)
+                .finalize();
+            digest.copy_from_slice(hash.as_bytes());
+        },
+        #[cfg(feature = "shake-digest")]
+        DigestAlgorithm::Shake256 => {
+            use sha3::digest::{ExtendableOutput, Update, XofReader};
+
+            let mut hasher = sha3::Shake256::default();
+            hasher.update(DIGEST_PERSONALIZATION);
+            hasher.update(bytes);
+            XofReader::read(&mut hasher.finalize_xof(), &mut digest);
+        },
+    }
+    Digest(digest)
+}
+
+/// Hashes the canonical serialization of an object into a Digest
+pub fn canonical_digest<T: CanonicalSerialize>(obj: &T) -> Digest {
+    let mut bytes = Vec::with_capacity(obj.serialized_size());
+    // serializing into a Vec cannot fail
+    obj.serialize(&mut bytes).unwrap();
+    digest_bytes(&bytes)
+}
 
 
 ///////////////////////////////////////////////////////////////////
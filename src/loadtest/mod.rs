@@ -0,0 +1,158 @@
+use crate::modified_scrape::{
+    config::{Config, EvaluationPoints, ShareLayout},
+    dealer::Dealer,
+    errors::PVSSError,
+    node::Node,
+    participant::Participant,
+    srs::SRS,
+};
+use crate::signature::{
+    schnorr::{srs::SRS as SchnorrSRS, SchnorrSignature, SchnorrVersion},
+    scheme::SignatureScheme,
+};
+
+use ark_ec::PairingEngine;
+use ark_std::collections::BTreeMap;
+
+use rand::thread_rng;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/* Load-test harness. Spins up n in-process nodes and, for the requested duration,
+   has them deal PVSS shares to each other at a target rate (round-robin over the
+   dealers, each share being delivered to the next node). Only share verification
+   is timed, so the reported latencies reflect the cost a receiving node pays per
+   share on the given hardware.
+*/
+
+// Struct LoadTestReport summarizes the outcome of a load test.
+#[derive(Clone, Debug)]
+pub struct LoadTestReport {
+    pub shares_sent: usize,        // number of shares dealt and delivered
+    pub shares_verified: usize,    // number of shares that passed verification
+    pub shares_rejected: usize,    // number of shares that failed verification
+    pub elapsed: Duration,         // wall-clock duration of the test
+    pub throughput: f64,           // verified shares per second
+    pub p50_latency: Duration,     // median verification latency
+    pub p99_latency: Duration,     // 99th percentile verification latency
+    pub max_latency: Duration,     // worst observed verification latency
+}
+
+// Utility function returning the p-th percentile of an ascending list of latencies.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::from_secs(0);
+    }
+    sorted[(sorted.len() - 1) * p / 100]
+}
+
+// Function for running a load test with n nodes and threshold t, dealing
+// shares_per_sec shares per second for the given duration.
+pub fn run<E: PairingEngine>(
+    n: usize,
+    t: usize,
+    shares_per_sec: f64,
+    duration: Duration,
+) -> Result<LoadTestReport, PVSSError<E>> {
+    if n < 2 || t == 0 || t >= n {
+        return Err(PVSSError::InsufficientIdsError);
+    }
+
+    let rng = &mut thread_rng();
+
+    // Set up the system-wide parameters.
+    let srs = SRS::<E>::setup(rng)?;
+    let config = Config { srs: srs.clone(), degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+    let schnorr = SchnorrSignature::<E::G1Affine> { srs: SchnorrSRS { g_public_key: srs.g1 }, version: SchnorrVersion::KeyBound };
+
+    // Generate every node's keys and the shared roster.
+    let keypairs = (0..n)
+        .map(|_| schnorr.generate_keypair(rng))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let participants = keypairs
+        .iter()
+        .enumerate()
+        .map(|(id, (_, pk))| (id, Participant::new(id, *pk)))
+        .collect::<BTreeMap<_, _>>();
+
+    // Spin up the nodes.
+    let mut nodes = keypairs
+        .iter()
+        .enumerate()
+        .map(|(id, (sk, _))| {
+            let dealer = Dealer::new(&schnorr, *sk, participants[&id].clone())?;
+            Node::new(config.clone(), schnorr.clone(), dealer, participants.clone())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Drive share traffic, pacing the k-th share at start + k / shares_per_sec.
+    let interval = Duration::from_secs_f64(1.0 / shares_per_sec.max(f64::MIN_POSITIVE));
+    let mut latencies = vec![];
+    let mut shares_sent = 0;
+    let mut shares_rejected = 0;
+
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        let scheduled = interval.mul_f64(shares_sent as f64);
+        if let Some(wait) = scheduled.checked_sub(start.elapsed()) {
+            thread::sleep(wait);
+        }
+
+        let dealer = shares_sent % n;
+        let receiver = (dealer + 1) % n;
+        let share = nodes[dealer].share(rng)?;
+        shares_sent += 1;
+
+        let verification_start = Instant::now();
+        let result = nodes[receiver].aggregator.share_verify(rng, &share);
+        latencies.push(verification_start.elapsed());
+
+        if result.is_err() {
+            shares_rejected += 1;
+        }
+    }
+    let elapsed = start.elapsed();
+
+    latencies.sort();
+    let shares_verified = shares_sent - shares_rejected;
+
+    Ok(LoadTestReport {
+        shares_sent,
+        shares_verified,
+        shares_rejected,
+        elapsed,
+        throughput: shares_verified as f64 / elapsed.as_secs_f64(),
+        p50_latency: percentile(&latencies, 50),
+        p99_latency: percentile(&latencies, 99),
+        max_latency: latencies.last().cloned().unwrap_or_default(),
+    })
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::loadtest::run;
+    use crate::modified_scrape::errors::PVSSError;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_run() {
+        let report = run::<E>(3, 1, 50.0, Duration::from_millis(200)).unwrap();
+        assert!(report.shares_sent > 0);
+        assert_eq!(report.shares_verified + report.shares_rejected, report.shares_sent);
+        assert_eq!(report.shares_rejected, 0);
+        assert!(report.elapsed >= Duration::from_millis(200));
+        assert!(report.throughput > 0.0);
+        assert!(report.p50_latency > Duration::from_secs(0));
+        assert!(report.p50_latency <= report.p99_latency);
+        assert!(report.p99_latency <= report.max_latency);
+
+        assert!(matches!(run::<E>(3, 3, 50.0, Duration::from_millis(200)), Err(PVSSError::InsufficientIdsError)));
+    }
+}
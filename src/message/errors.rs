@@ -0,0 +1,17 @@
+use crate::signature::utils::errors::SignatureError;
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while encoding, decoding or authenticating a protocol message.
+#[derive(Error, Debug)]
+pub enum MessageError {
+    #[error("Unknown message type: {0}")]
+    UnknownMessageType(u8),
+    #[error("Message is followed by {0} trailing bytes")]
+    TrailingBytes(usize),
+    #[error("Signature error: {0}")]
+    SignatureError(#[from] SignatureError),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] SerializationError),
+}
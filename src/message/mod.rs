@@ -0,0 +1,274 @@
+use crate::beacon::epoch::EpochContribution;
+use crate::modified_scrape::{
+    certificate::Certificate,
+    complaint::Complaint,
+    decryption::DecryptedShare,
+    share::{PVSSAugmentedShare, PVSSTranscript},
+};
+use crate::signature::{
+    eddsa::{EdDSASignature, PublicKey, SecretKey, Signature},
+    scheme::BatchVerifiableSignatureScheme,
+};
+use crate::Scalar;
+use errors::MessageError;
+
+use ark_ec::PairingEngine;
+use ark_serialize::*;
+
+pub mod errors;
+
+const MESSAGE_DOMAIN: &[u8] = b"OPTRAND-PVSS-MESSAGE-V1";   // domain tag of signed protocol messages
+
+/* Protocol messages. Every message a node sends in the course of an epoch is one of
+   the PVSSMessage variants, and travels in a SignedMessage:
+
+   sender (8 bytes) || epoch (8 bytes) || type (1 byte) || canonical payload || signature
+
+   where the Ed25519 signature (see Signature::sign_bytes) is the sender's, on all
+   that precedes it prefixed with a fixed protocol and version tag, so that it
+   cannot be replayed as a signature in another protocol (or another version of
+   this one) using the same keys. Integrators only need to move the encoded bytes
+   between nodes (possibly inside a wire Envelope, see wire/mod.rs), and look up the
+   sender's Ed25519 key to authenticate them; decoding does not check the payload,
+   which is verified as usual once the message is handled.
+*/
+
+// Enumeration whose variants model the messages of the PVSS protocol.
+#[derive(Clone)]
+pub enum PVSSMessage<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    Share(PVSSAugmentedShare<E, SSIG>),           // a dealer's share
+    AggregatedShare(PVSSTranscript<E, SSIG>),     // an aggregated transcript
+    DecryptedShare(DecryptedShare<E>),            // a node's decryption of its share
+    Complaint(Complaint<E, SSIG>),                // a complaint against a dealing
+    Certificate(Certificate<EdDSASignature>),     // a certificate on a transcript
+    BeaconShare(EpochContribution<E>),            // a node's contribution to an epoch's beacon
+}
+
+impl<E, SSIG> PVSSMessage<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method returning the type byte of the message.
+    pub fn message_type(&self) -> u8 {
+        match self {
+            PVSSMessage::Share(_) => 0,
+            PVSSMessage::AggregatedShare(_) => 1,
+            PVSSMessage::DecryptedShare(_) => 2,
+            PVSSMessage::Complaint(_) => 3,
+            PVSSMessage::Certificate(_) => 4,
+            PVSSMessage::BeaconShare(_) => 5,
+        }
+    }
+
+    // Function for decoding a message of the given type from its payload.
+    fn deserialize_payload<R: Read>(message_type: u8, reader: R) -> Result<Self, MessageError> {
+        Ok(match message_type {
+            0 => PVSSMessage::Share(CanonicalDeserialize::deserialize(reader)?),
+            1 => PVSSMessage::AggregatedShare(CanonicalDeserialize::deserialize(reader)?),
+            2 => PVSSMessage::DecryptedShare(CanonicalDeserialize::deserialize(reader)?),
+            3 => PVSSMessage::Complaint(CanonicalDeserialize::deserialize(reader)?),
+            4 => PVSSMessage::Certificate(CanonicalDeserialize::deserialize(reader)?),
+            5 => PVSSMessage::BeaconShare(CanonicalDeserialize::deserialize(reader)?),
+            _ => return Err(MessageError::UnknownMessageType(message_type)),
+        })
+    }
+}
+
+// Messages are encoded as type (1 byte) || payload.
+impl<E, SSIG> CanonicalSerialize for PVSSMessage<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.message_type().serialize(&mut writer)?;
+        match self {
+            PVSSMessage::Share(share) => share.serialize(&mut writer),
+            PVSSMessage::AggregatedShare(transcript) => transcript.serialize(&mut writer),
+            PVSSMessage::DecryptedShare(decryption) => decryption.serialize(&mut writer),
+            PVSSMessage::Complaint(complaint) => complaint.serialize(&mut writer),
+            PVSSMessage::Certificate(certificate) => certificate.serialize(&mut writer),
+            PVSSMessage::BeaconShare(contribution) => contribution.serialize(&mut writer),
+        }
+    }
+
+    fn serialized_size(&self) -> usize {
+        1 + match self {
+            PVSSMessage::Share(share) => share.serialized_size(),
+            PVSSMessage::AggregatedShare(transcript) => transcript.serialized_size(),
+            PVSSMessage::DecryptedShare(decryption) => decryption.serialized_size(),
+            PVSSMessage::Complaint(complaint) => complaint.serialized_size(),
+            PVSSMessage::Certificate(certificate) => certificate.serialized_size(),
+            PVSSMessage::BeaconShare(contribution) => contribution.serialized_size(),
+        }
+    }
+}
+
+impl<E, SSIG> CanonicalDeserialize for PVSSMessage<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let message_type = u8::deserialize(&mut reader)?;
+        Self::deserialize_payload(message_type, reader).map_err(|e| match e {
+            MessageError::SerializationError(e) => e,
+            _ => SerializationError::InvalidData,
+        })
+    }
+}
+
+
+// Struct SignedMessage models a protocol message, as sent by a node in some epoch.
+#[derive(Clone)]
+pub struct SignedMessage<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub sender: usize,                    // id of the sending node
+    pub epoch: u64,                       // the epoch the message belongs to
+    pub message: PVSSMessage<E, SSIG>,    // the message itself
+    pub signature: Signature,             // the sender's signature on the above
+}
+
+impl<E, SSIG> SignedMessage<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for signing a message as node sender, in the given epoch.
+    pub fn sign(sender: usize, epoch: u64, message: PVSSMessage<E, SSIG>, sk: &SecretKey) -> Result<Self, MessageError> {
+        let signature = Signature::sign_bytes(&Self::signed_bytes(sender, epoch, &message)?, sk);
+        Ok(Self { sender, epoch, message, signature })
+    }
+
+    // Method for verifying the sender's signature, given its public key.
+    pub fn verify(&self, pk: &PublicKey) -> Result<(), MessageError> {
+        self.signature.verify_bytes(&Self::signed_bytes(self.sender, self.epoch, &self.message)?, pk)?;
+        Ok(())
+    }
+
+    // Method for encoding the message for the network.
+    pub fn encode(&self) -> Result<Vec<u8>, MessageError> {
+        let mut bytes = Self::message_bytes(self.sender, self.epoch, &self.message)?;
+        self.signature.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // Function for decoding a message received from the network. The whole input
+    // must be consumed.
+    pub fn decode(bytes: &[u8]) -> Result<Self, MessageError> {
+        let mut reader = bytes;
+        let sender = u64::deserialize(&mut reader)? as usize;
+        let epoch = u64::deserialize(&mut reader)?;
+        let message_type = u8::deserialize(&mut reader)?;
+        let message = PVSSMessage::deserialize_payload(message_type, &mut reader)?;
+        let signature = Signature::deserialize(&mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(MessageError::TrailingBytes(reader.len()));
+        }
+        Ok(Self { sender, epoch, message, signature })
+    }
+
+    // Utility function returning the encoding of a message, without its signature.
+    fn message_bytes(sender: usize, epoch: u64, message: &PVSSMessage<E, SSIG>) -> Result<Vec<u8>, MessageError> {
+        let mut bytes = Vec::with_capacity(16 + message.serialized_size());
+        (sender as u64).serialize(&mut bytes)?;
+        epoch.serialize(&mut bytes)?;
+        message.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // Utility function returning the bytes the sender signs: the domain tag,
+    // followed by the encoding of the message.
+    fn signed_bytes(sender: usize, epoch: u64, message: &PVSSMessage<E, SSIG>) -> Result<Vec<u8>, MessageError> {
+        let mut bytes = MESSAGE_DOMAIN.to_vec();
+        bytes.extend(Self::message_bytes(sender, epoch, message)?);
+        Ok(bytes)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::message::{errors::MessageError, PVSSMessage, SignedMessage};
+    use crate::modified_scrape::{certificate::Certificate, slashing::SignedDealing, tests::{setup_nodes, SSIG}};
+    use crate::signature::eddsa::{EdDSASignature, SecretKey, Signature};
+
+    use ark_bls12_381::{Bls12_381 as E};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_signed_messages() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, sks) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[0].aggregator.receive_share(rng, share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        let dealing = SignedDealing::sign(rng, &nodes[0].aggregator.scheme_sig, &sks[0], shares[0].clone()).unwrap();
+
+        let sk = SecretKey::generate(rng);
+        let messages = vec![
+            PVSSMessage::<E, SSIG>::Share(shares[0].clone()),
+            PVSSMessage::AggregatedShare(transcript.clone()),
+            PVSSMessage::DecryptedShare(nodes[0].decrypt(rng, &transcript).unwrap()),
+            PVSSMessage::Complaint(nodes[1].complain(rng, dealing).unwrap()),
+            PVSSMessage::Certificate(Certificate::<EdDSASignature>::new(7, &transcript)),
+            PVSSMessage::BeaconShare(nodes[0].epoch_contribution(&transcript, 7).unwrap()),
+        ];
+
+        // Messages round-trip, and are authenticated by their sender's signature.
+        for message in messages.into_iter() {
+            let signed = SignedMessage::sign(2, 7, message, &sk).unwrap();
+            let bytes = signed.encode().unwrap();
+            let decoded = SignedMessage::<E, SSIG>::decode(&bytes).unwrap();
+            assert_eq!((decoded.sender, decoded.epoch), (2, 7));
+            assert_eq!(decoded.message.message_type(), signed.message.message_type());
+            assert_eq!(decoded.encode().unwrap(), bytes);
+            decoded.verify(&sk.public_key()).unwrap();
+
+            let mut impersonated = decoded.clone();
+            impersonated.sender = 3;
+            assert!(impersonated.verify(&sk.public_key()).is_err());
+            assert!(decoded.verify(&SecretKey::generate(rng).public_key()).is_err());
+
+            let mut extended = bytes.clone();
+            extended.push(0);
+            assert!(matches!(SignedMessage::<E, SSIG>::decode(&extended), Err(MessageError::TrailingBytes(1))));
+        }
+
+        let mut bytes = SignedMessage::sign(2, 7, PVSSMessage::<E, SSIG>::Share(shares[0].clone()), &sk).unwrap().encode().unwrap();
+        bytes[16] = 9;
+        assert!(matches!(SignedMessage::<E, SSIG>::decode(&bytes), Err(MessageError::UnknownMessageType(9))));
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        let share = nodes[0].share(rng).unwrap();
+        let sk = SecretKey::generate(rng);
+        let signed = SignedMessage::sign(2, 7, PVSSMessage::<E, SSIG>::Share(share), &sk).unwrap();
+        signed.verify(&sk.public_key()).unwrap();
+
+        // Signatures on the same message under another tag, or none, are rejected.
+        let message = SignedMessage::message_bytes(signed.sender, signed.epoch, &signed.message).unwrap();
+        let mut other_version = b"OPTRAND-PVSS-MESSAGE-V2".to_vec();
+        other_version.extend(message.iter());
+        for bytes in [other_version, message].iter() {
+            let mut forged = signed.clone();
+            forged.signature = Signature::sign_bytes(bytes, &sk);
+            assert!(forged.verify(&sk.public_key()).is_err());
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use super::{errors::PVSSError, share::PVSSTranscript};
+use crate::nizk::utils::hash::hash_to_field;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Digest, Hash, Scalar};
+
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_serialize::*;
+
+use rand::{CryptoRng, Rng};
+use std::ops::Neg;
+
+const PERSONALIZATION: &[u8] = b"OPTRACCU";   // persona for mapping contributions to accumulator elements
+
+/* Bilinear accumulator (Nguyen) over the contributions of a transcript. Each
+   contribution (id, gs) is mapped to a scalar x, and the accumulator of a set X is
+   acc = g_1^{prod_{x in X}(tau + x)}. The membership witness for x is the same
+   product with x left out, w = g_1^{prod_{y != x}(tau + y)}, and is checked with a
+   single pairing equation: e(w, g_2^tau * g_2^x) == e(acc, g_2). Both the accumulator
+   and its witnesses are a single G_1 element, independently of the committee size.
+
+   The accumulator is bound to the digest of the finalized transcript, so that a
+   dealer can prove that its contribution is included in that specific transcript.
+   Computing it requires powers of a secret tau, which must come from a trusted setup.
+*/
+
+// Struct AccumulatorSRS holds the powers of tau needed to accumulate up to max_size elements.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AccumulatorSRS<E: PairingEngine> {
+    pub powers_g1: Vec<E::G1Affine>,   // g_1^{tau^k}, for k in {0, ..., max_size}
+    pub g2: E::G2Affine,               // g_2
+    pub tau_g2: E::G2Affine,           // g_2^tau
+}
+
+// Struct Accumulator models an accumulator over the contributions of a transcript.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Accumulator<E: PairingEngine> {
+    pub value: E::G1Affine,            // the accumulated value
+    pub transcript_digest: Digest,     // digest of the transcript the accumulator refers to
+}
+
+
+impl<E: PairingEngine> AccumulatorSRS<E> {
+
+    // Function for generating an accumulator SRS using a specified RNG. Whoever runs
+    // this learns tau and could forge witnesses; in deployments, the SRS should be
+    // the output of a ceremony.
+    pub fn setup<R: Rng + CryptoRng>(rng: &mut R, max_size: usize) -> Result<Self, PVSSError<E>> {
+        let tau = Scalar::<E>::rand(rng);
+        let g1 = E::G1Projective::rand(rng);
+        let g2 = E::G2Projective::rand(rng);
+
+        let mut powers_g1 = vec![];
+        let mut power = Scalar::<E>::one();
+        for _ in 0..max_size+1 {
+            powers_g1.push(g1.mul(power.into_repr()));
+            power *= &tau;
+        }
+
+        Ok(Self {
+            powers_g1: E::G1Projective::batch_normalization_into_affine(&powers_g1),
+            g2: g2.into_affine(),
+            tau_g2: g2.mul(tau.into_repr()).into_affine(),
+        })
+    }
+
+    // Method returning the maximum number of elements that can be accumulated.
+    pub fn max_size(&self) -> usize {
+        self.powers_g1.len() - 1
+    }
+}
+
+
+// Function for mapping a contribution (dealer id and commitment gs) to an accumulator element.
+pub fn element<E: PairingEngine>(id: usize, gs: &E::G2Affine) -> Result<Scalar<E>, PVSSError<E>> {
+    let mut bytes = vec![];
+    (id as u64).serialize(&mut bytes)?;
+    gs.serialize(&mut bytes)?;
+
+    hash_to_field::<Scalar<E>>(PERSONALIZATION, &bytes).map_err(|_| PVSSError::AccumulatorMembershipError(id))
+}
+
+// Utility function for committing to prod_{x in elements}(tau + x), using the SRS.
+fn commit_product<E: PairingEngine>(
+    srs: &AccumulatorSRS<E>,
+    elements: &[Scalar<E>],
+) -> Result<E::G1Affine, PVSSError<E>> {
+    if elements.len() > srs.max_size() {
+        return Err(PVSSError::AccumulatorSizeError(elements.len(), srs.max_size()));
+    }
+
+    // Expand the product into its coefficients, one linear factor at a time.
+    let mut coeffs = vec![Scalar::<E>::one()];
+    for x in elements.iter() {
+        let mut next = vec![Scalar::<E>::zero(); coeffs.len() + 1];
+        for (k, c) in coeffs.iter().enumerate() {
+            next[k] += *c * x;
+            next[k + 1] += c;
+        }
+        coeffs = next;
+    }
+
+    let scalars = coeffs.iter().map(|c| c.into_repr()).collect::<Vec<_>>();
+    Ok(VariableBaseMSM::multi_scalar_mul(&srs.powers_g1[..scalars.len()], &scalars).into_affine())
+}
+
+// Utility function for computing the accumulator elements of a transcript's contributions.
+fn elements_of<E, SSIG>(transcript: &PVSSTranscript<E, SSIG>) -> Result<Vec<(usize, Scalar<E>)>, PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    transcript
+        .contributions
+        .iter()
+        .map(|(id, c)| Ok((*id, element::<E>(*id, &c.decomp_proof.gs())?)))
+        .collect()
+}
+
+
+impl<E: PairingEngine> Accumulator<E> {
+
+    // Function for computing the accumulator over the contributions of a transcript.
+    pub fn new<SSIG>(srs: &AccumulatorSRS<E>, transcript: &PVSSTranscript<E, SSIG>) -> Result<Self, PVSSError<E>>
+    where
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        let elements = elements_of(transcript)?.into_iter().map(|(_, x)| x).collect::<Vec<_>>();
+
+        Ok(Self {
+            value: commit_product(srs, &elements)?,
+            transcript_digest: transcript.digest(),
+        })
+    }
+
+    // Function for computing the membership witness of dealer id's contribution to a transcript.
+    pub fn witness<SSIG>(
+        srs: &AccumulatorSRS<E>,
+        transcript: &PVSSTranscript<E, SSIG>,
+        id: usize,
+    ) -> Result<E::G1Affine, PVSSError<E>>
+    where
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        if !transcript.contributions.contains_key(&id) {
+            return Err(PVSSError::AccumulatorMembershipError(id));
+        }
+
+        let others = elements_of(transcript)?
+            .into_iter()
+            .filter(|(j, _)| *j != id)
+            .map(|(_, x)| x)
+            .collect::<Vec<_>>();
+
+        commit_product(srs, &others)
+    }
+
+    // Method for verifying that dealer id, with commitment gs, is included in the
+    // transcript with the given digest: e(w, g_2^tau * g_2^x) == e(acc, g_2).
+    pub fn verify_membership(
+        &self,
+        srs: &AccumulatorSRS<E>,
+        transcript_digest: &Digest,
+        id: usize,
+        gs: &E::G2Affine,
+        witness: &E::G1Affine,
+    ) -> Result<(), PVSSError<E>> {
+        if self.transcript_digest != *transcript_digest {
+            return Err(PVSSError::AccumulatorMembershipError(id));
+        }
+
+        let x = element::<E>(id, gs)?;
+        let shifted = srs.tau_g2.into_projective() + srs.g2.mul(x.into_repr());
+
+        let pairs = [
+            ((*witness).into(), shifted.into_affine().into()),
+            (self.value.into(), srs.g2.neg().into()),
+        ];
+
+        if !E::product_of_pairings(pairs.iter()).is_one() {
+            return Err(PVSSError::AccumulatorMembershipError(id));
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{
+        accumulator::{Accumulator, AccumulatorSRS},
+        decomp::DecompProof,
+        pvss::PVSSShare,
+        share::{PVSSTranscript, PVSSTranscriptParticipant},
+        srs::SRS,
+        tests::SSIG,
+    };
+    use crate::{Digest, Hash, Scalar};
+
+    use rand::thread_rng;
+
+    // Builds a transcript with random (but well-formed) contributions from ids.
+    fn transcript(ids: &[usize]) -> PVSSTranscript<E, SSIG> {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+
+        let contributions = ids
+            .iter()
+            .map(|id| {
+                let s = Scalar::<E>::rand(rng);
+                let decomp_proof = DecompProof::<E>::new((srs.g2, s, s), srs.g2.mul(s.into_repr()).into_affine(), Digest::default());
+                let signature_on_decomp = (srs.g1, s);
+                (*id, PVSSTranscriptParticipant { decomp_proof, signature_on_decomp, weight: 1 })
+            })
+            .collect();
+
+        PVSSTranscript { degree: 1, num_participants: 4, contributions, pvss_share: PVSSShare::empty(1, 4) }
+    }
+
+    #[test]
+    fn test_membership() {
+        let rng = &mut thread_rng();
+        let srs = AccumulatorSRS::<E>::setup(rng, 4).unwrap();
+        let tx = transcript(&[0, 2, 3]);
+
+        let acc = Accumulator::new(&srs, &tx).unwrap();
+        let witness = Accumulator::witness(&srs, &tx, 2).unwrap();
+
+        acc.verify_membership(&srs, &tx.digest(), 2, &tx.contributions[&2].decomp_proof.gs(), &witness).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_membership_wrong_id() {
+        let rng = &mut thread_rng();
+        let srs = AccumulatorSRS::<E>::setup(rng, 4).unwrap();
+        let tx = transcript(&[0, 2, 3]);
+
+        let acc = Accumulator::new(&srs, &tx).unwrap();
+        let witness = Accumulator::witness(&srs, &tx, 2).unwrap();
+
+        acc.verify_membership(&srs, &tx.digest(), 1, &tx.contributions[&2].decomp_proof.gs(), &witness).unwrap();
+    }
+}
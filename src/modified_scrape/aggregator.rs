@@ -1,256 +1,2371 @@
-use crate::modified_scrape::poly::{ensure_degree, lagrange_interpolation_simple};   // poly::Polynomial, lagrange_interpolation
+use crate::modified_scrape::poly::{ensure_degree_at_points, ensure_degree_at_points_fiat_shamir, lagrange_interpolation};
 use crate::modified_scrape::errors::PVSSError;
-use crate::modified_scrape::pvss::PVSSShare;
-use crate::modified_scrape::share::{PVSSTranscript, PVSSTranscriptParticipant, PVSSAugmentedShare};
-use crate::modified_scrape::participant::Participant;
+use crate::modified_scrape::kernel;
+use crate::modified_scrape::pvss::{PVSSShare, SparsePVSSShare};
+use crate::modified_scrape::share::{contribution_digest, PVSSTranscript, PVSSAugmentedShare};
+use crate::modified_scrape::participant::{Committee, Participant, ParticipantId};
+use crate::modified_scrape::epoch_keys::EpochKey;
 use crate::signature::scheme::BatchVerifiableSignatureScheme;
-use crate::modified_scrape::decomp::{DecompProof, message_from_pi_i};
+use crate::modified_scrape::decomp::{commitments_binding, DecompProof, message_from_pi_i};
+use crate::modified_scrape::memory::{BudgetPolicy, MemoryAccountant};
+use crate::modified_scrape::cache::VerificationCache;
+use crate::modified_scrape::seen::SeenSet;
+use crate::modified_scrape::metadata::ContributionInfo;
+use crate::modified_scrape::metrics::{count_msms, count_pairings, measure, MetricsHook, Operation, OperationCost};
+use crate::modified_scrape::outcome::{VerificationOutcome, Warning, NEAR_THRESHOLD_MARGIN};
+use crate::modified_scrape::trace::VerificationTrace;
+use crate::modified_scrape::stats::{RoundStats, MIN_BATCH_THRESHOLD};
 
 //use crate::modified_scrape::decomp::ProofGroup;
 
-use super::config::Config;
-use crate::Scalar;
+use super::config::{Config, PreparedConfig, ShareLayout};
+use crate::{canonical_digest, digest_bytes, Digest, Hash, Scalar};
 
-use ark_ec::{PairingEngine, ProjectiveCurve};   // msm::VariableBaseMSM, AffineCurve
-use ark_std::collections::BTreeMap;
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_serialize::CanonicalSerialize;
+use ark_std::collections::{BTreeMap, BTreeSet};
 
-//use ark_ff::{One, PrimeField, UniformRand, Zero};
-use ark_ff::{One, Zero};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
 
-use rand::Rng;
-use std::ops::Neg;
+use rand::{seq::index::sample, CryptoRng, Rng};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "parallel")]
+use crate::modified_scrape::metrics::{count_cost, isolate};
+#[cfg(feature = "parallel")]
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rand_chacha::ChaChaRng;
 
 
+// Enumeration of the stages of verifying an augmented PVSS share. The participant's
+// identity is always checked first; the remaining stages run in the order given by
+// the aggregator's stage_order (cheapest first by default), so that garbage traffic
+// is rejected before the expensive checks are reached.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerificationStage {
+    Signature,    // signature on the decomposition proof (cheap)
+    Encryption,   // pairing check of the dealer's own encryption (2 pairings)
+    Core,         // coding check, interpolation and decomposition proof (O(n))
+}
+
+// The default, fail-fast order of the verification stages.
+pub const DEFAULT_STAGE_ORDER: [VerificationStage; 3] = [
+    VerificationStage::Signature,
+    VerificationStage::Encryption,
+    VerificationStage::Core,
+];
+
+// Trait for being notified when an aggregator's transcript first contains the
+// contributions of t + 1 distinct dealers, i.e., when the sharing phase can be
+// finalized. Implemented by closures taking the number of contributors.
+pub trait QuorumHook: Send + Sync {
+    fn on_quorum_reached(&self, contributors: usize);
+}
+
+impl<F: Fn(usize) + Send + Sync> QuorumHook for F {
+    fn on_quorum_reached(&self, contributors: usize) {
+        self(contributors)
+    }
+}
+
+// Enumeration of the ways an aggregator handles shares or transcripts that would
+// push the weight of some contribution above its cap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightPolicy {
+    Reject,     // fail with WeightOverflow
+    Saturate,   // leave the transcript as is, i.e., weights stay at the cap
+}
+
+// Struct WeightLimit caps the weight of each contribution in an aggregator's transcript.
+// A capped weight cannot be represented by partially adding a share, since the core
+// share is a sum over all dealings; saturation thus skips the offending input as a whole.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeightLimit {
+    pub max_weight: u64,        // the largest weight a contribution may reach
+    pub policy: WeightPolicy,   // what to do with inputs exceeding it
+}
+
+// By default, weights are only limited by their representation.
+impl Default for WeightLimit {
+    fn default() -> Self {
+        Self { max_weight: u64::MAX, policy: WeightPolicy::Reject }
+    }
+}
+
+// Enumeration of the ways an aggregator handles re-received copies of a dealing
+// that is already part of its transcript. By default, re-received dealings are ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuplicatePolicy {
+    #[default]
+    RejectDuplicates,   // ignore them, so that every dealing is counted once
+    AllowWeighted,      // aggregate them again, raising the weight of the contribution
+}
+
+// Enumeration of the verification strictness levels of an aggregator, trading
+// performance for assurance. Every level runs the checks required for soundness;
+// higher levels add redundant checks guarding against implementation bugs.
+// The default level is Standard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strictness {
+    Light,      // only the checks required for soundness
+    #[default]
+    Standard,   // also subgroup checks on all points and audits of contribution weights
+    Paranoid,   // also per-recipient encryption pairings and a second interpolation
+}
+
+// Enumeration of the ways the coding (low-degree) check on commitments can be run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DegreeCheckMode {
+    Interactive,   // the dual codeword is sampled by the verifier
+    FiatShamir,    // the dual codeword is derived from the commitments (see ensure_degree_fiat_shamir)
+}
+
 
 pub struct PVSSAggregator<
     E: PairingEngine,
     // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 > {
     pub config: Config<E>,
     // pub scheme_pok: SPOK,   // might be redundant
     pub scheme_sig: SSIG,
     pub participants: BTreeMap<usize, Participant<E, SSIG>>,   // maps ids to Participant instances
 
+    pub committee: Option<Committee>,          // the participants' ids, if not their indices 0..n-1
+
+    pub epoch_keys: BTreeMap<(usize, u64), E::G1Affine>,   // registered encryption keys by (participant, epoch)
+
     pub transcript: PVSSTranscript<E, SSIG>,   // <E, SPOK, SSIG>
+
+    pub memory: Option<MemoryAccountant>,      // optional budget for the stored transcript
+
+    pub cache: VerificationCache,              // digests of shares and transcripts already verified in this round
+
+    pub seen: Option<SeenSet>,                 // optional on-disk record of shares already processed
+
+    pub stage_order: Vec<VerificationStage>,   // order in which share verification stages run
+
+    pub degree_check: DegreeCheckMode,         // how the coding check on commitments is run
+
+    pub weight_limit: WeightLimit,             // cap on the weight of each contribution
+
+    pub duplicates: DuplicatePolicy,           // how re-received copies of aggregated dealings are handled
+
+    pub strictness: Strictness,                // which optional checks verification runs
+
+    pub metadata: BTreeMap<usize, ContributionInfo>,   // bookkeeping on the contributions in transcript
+
+    pub excluded: BTreeSet<usize>,             // dealers excluded after a valid complaint against them
+
+    pub metrics: Option<Arc<dyn MetricsHook>>,   // optional sink for the cost of verifications
+
+    pub quorum_hook: Option<Arc<dyn QuorumHook>>,   // optional callback for when the quorum is reached
+
+    pub tracing: bool,                           // whether verification calls collect a trace
+    trace: Mutex<Option<VerificationTrace>>,     // trace of the latest verification call
+
+    pub batch_threshold: usize,                  // fewest transcripts verify_many checks in a batch
+    stats: Mutex<RoundStats>,                    // verification statistics of the current round
+
+    prepared: Mutex<Option<Arc<PreparedConfig<E>>>>,   // pairing inputs prepared for our SRS and keys (lazily)
 }
 
 
 impl<
         E: PairingEngine,
         // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
-        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,   // NOTE: might want to switch to projective coordinates
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,   // NOTE: might want to switch to projective coordinates
     > PVSSAggregator<E, SSIG>   // <E, SPOK, SSIG>
 {
 
+    // Function for creating an aggregator with an empty transcript, no memory budget
+    // or seen-set, and the default verification settings.
+    pub fn new(
+        config: Config<E>,
+        scheme_sig: SSIG,
+        participants: BTreeMap<usize, Participant<E, SSIG>>,
+    ) -> Self {
+        let transcript = PVSSTranscript::empty(config.degree, participants.len());
+        let cache = VerificationCache::new(0, config.digest(), roster_digest(&participants));
+        Self {
+            config,
+            scheme_sig,
+            participants,
+            committee: None,
+            epoch_keys: BTreeMap::new(),
+            transcript,
+            memory: None,
+            cache,
+            seen: None,
+            stage_order: DEFAULT_STAGE_ORDER.to_vec(),
+            degree_check: DegreeCheckMode::Interactive,
+            weight_limit: WeightLimit::default(),
+            duplicates: DuplicatePolicy::default(),
+            strictness: Strictness::default(),
+            metadata: BTreeMap::new(),
+            excluded: BTreeSet::new(),
+            metrics: None,
+            quorum_hook: None,
+            tracing: false,
+            trace: Mutex::new(None),
+            batch_threshold: MIN_BATCH_THRESHOLD,
+            stats: Mutex::new(RoundStats::new(0)),
+            prepared: Mutex::new(None),
+        }
+    }
+
+
+    // Method for reporting the cost of a completed operation to the metrics hook.
+    pub fn report(&self, operation: Operation, cost: OperationCost) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(operation, cost);
+        }
+    }
+
+
+    // Method returning the trace of the latest share_verify or transcript_verify
+    // call, if tracing was enabled at the time.
+    pub fn last_trace(&self) -> Option<VerificationTrace> {
+        self.trace.lock().unwrap().clone()
+    }
+
+
+    // Method returning the verification statistics of the current round so far.
+    pub fn round_stats(&self) -> RoundStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+
+    // Utility method starting a fresh trace for a verification call, if tracing.
+    fn begin_trace(&self, operation: Operation) {
+        if self.tracing {
+            *self.trace.lock().unwrap() = Some(VerificationTrace::new(operation.name()));
+        }
+    }
+
+
+    // Utility method running a single check, recording it in the current trace (if
+    // any) along with the digests of its inputs. The inputs are only digested when
+    // tracing, so that untraced verification pays nothing.
+    fn traced<T, I, F>(&self, name: &'static str, inputs: I, check: F) -> Result<T, PVSSError<E>>
+    where
+        I: FnOnce() -> Vec<Digest>,
+        F: FnOnce() -> Result<T, PVSSError<E>>,
+    {
+        if !self.tracing {
+            return check();
+        }
+
+        let start = Instant::now();
+        let result = check();
+        self.record_trace(name, inputs, start.elapsed(), &result);
+        result
+    }
+
+
+    // Utility method adding the outcome of a check that was timed elsewhere to the
+    // current trace, if tracing is enabled.
+    fn record_trace<T, I>(&self, name: &'static str, inputs: I, duration: Duration, result: &Result<T, PVSSError<E>>)
+    where
+        I: FnOnce() -> Vec<Digest>,
+    {
+        if !self.tracing {
+            return;
+        }
+
+        if let Some(trace) = self.trace.lock().unwrap().as_mut() {
+            trace.push(name, inputs(), duration, result);
+        }
+    }
+
+
+    // Method for moving the aggregator to a new round (epoch). Shares memoized as
+    // verified in a previous round (or under a different config or roster) are
+    // forgotten, and the batching threshold is tuned according to the previous
+    // round's statistics. Returns true if the verification cache was invalidated.
+    pub fn start_epoch(&mut self, epoch: u64) -> Result<bool, PVSSError<E>> {
+        if let Some(seen) = self.seen.as_mut() {
+            seen.start_epoch(epoch)?;
+        }
+        let stats = self.stats.get_mut().unwrap();
+        if stats.epoch != epoch {
+            self.batch_threshold = stats.recommended_batch_threshold(self.batch_threshold);
+            *stats = RoundStats::new(epoch);
+        }
+        self.epoch_keys.retain(|(_, key_epoch), _| *key_epoch >= epoch);
+        Ok(self.cache.refresh(epoch, self.config.digest(), roster_digest(&self.participants)))
+    }
+
+
+    // Method for registering a participant's encryption key for some epoch, after
+    // checking its certification by the participant's identity key.
+    pub fn register_epoch_key(&mut self, key: &EpochKey<E, SSIG>) -> Result<(), PVSSError<E>> {
+        key.verify(&self.scheme_sig, &self.participants)?;
+
+        match self.epoch_keys.get(&(key.participant_id, key.epoch)) {
+            Some(registered) if *registered != key.public_key =>
+                Err(PVSSError::EpochKeyConflict(key.participant_id, key.epoch)),
+            _ => {
+                self.epoch_keys.insert((key.participant_id, key.epoch), key.public_key);
+                Ok(())
+            }
+        }
+    }
+
+
+    // Method returning the key under which participant id's shares are encrypted in
+    // the current epoch: its registered epoch key if any, or else its identity key.
+    pub fn encryption_key(&self, id: usize) -> Result<E::G1Affine, PVSSError<E>> {
+        if let Some(key) = self.epoch_keys.get(&(id, self.cache.epoch())) {
+            return Ok(*key);
+        }
+
+        self.participants
+            .get(&id)
+            .map(|participant| participant.public_key_sig)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(id))
+    }
+
+
+    // Method returning the pairing inputs prepared for our SRS and the current
+    // encryption keys of all participants. They are prepared on first use, and
+    // again whenever the SRS or a key (e.g., on a new epoch) has changed.
+    pub fn prepared(&self) -> Result<Arc<PreparedConfig<E>>, PVSSError<E>> {
+        let keys = self
+            .participants
+            .keys()
+            .map(|id| Ok((*id, self.encryption_key(*id)?)))
+            .collect::<Result<BTreeMap<_, _>, PVSSError<E>>>()?;
+
+        let mut prepared = self.prepared.lock().unwrap();
+        match prepared.as_ref() {
+            Some(current) if current.is_current(&self.config.srs, &keys) => Ok(current.clone()),
+            _ => {
+                let fresh = Arc::new(PreparedConfig::new(&self.config, &keys));
+                *prepared = Some(fresh.clone());
+                Ok(fresh)
+            },
+        }
+    }
+
+
+    // Method for recording that a share is known to be valid without verifying it
+    // locally, e.g., because a quorum certificate attests to it.
+    pub fn mark_share_verified(&mut self, share: &PVSSAugmentedShare<E, SSIG>) {
+        self.cache.refresh(self.cache.epoch(), self.config.digest(), roster_digest(&self.participants));
+        self.cache.mark_verified(share.digest());
+        self.cache.mark_verified(contribution_digest::<E, SSIG>(share.participant_id, &share.decomp_proof, &share.signature_on_decomp));
+    }
+
+
+    // Method returning the bookkeeping information on a dealer's contribution, if
+    // it is part of our transcript.
+    pub fn contribution_info(&self, id: usize) -> Option<&ContributionInfo> {
+        self.metadata.get(&id)
+    }
+
+
+    // Method returning the protocol index of the participant with the given id: its
+    // index in our committee if we have one, or else the id itself.
+    pub fn index_of(&self, id: ParticipantId) -> Result<usize, PVSSError<E>> {
+        match &self.committee {
+            Some(committee) => {
+                if committee.len() != self.config.num_participants {
+                    return Err(PVSSError::CommitteeSizeError(committee.len(), self.config.num_participants));
+                }
+                committee.index_of(id)
+            },
+            None => self
+                .participants
+                .keys()
+                .copied()
+                .find(|index| *index as u64 == id.0)
+                .ok_or(PVSSError::UnknownParticipantIdError(id.0)),
+        }
+    }
+
+
+    // Method returning the id of the participant with the given protocol index.
+    pub fn id_of(&self, index: usize) -> Result<ParticipantId, PVSSError<E>> {
+        match &self.committee {
+            Some(committee) => committee.id_of(index),
+            None if self.participants.contains_key(&index) => Ok(ParticipantId(index as u64)),
+            None => Err(PVSSError::InvalidParticipantId(index)),
+        }
+    }
+
+
+    // Method returning the ids of the dealers contributing to our transcript.
+    pub fn contributor_ids(&self) -> Result<Vec<ParticipantId>, PVSSError<E>> {
+        self.transcript.contributions.keys().map(|index| self.id_of(*index)).collect()
+    }
+
+
+    // Method returning the number of distinct dealers contributing to our transcript.
+    pub fn contributors(&self) -> usize {
+        self.transcript.contributions.len()
+    }
+
+
+    // Method returning whether our transcript contains the contributions of at
+    // least t + 1 distinct dealers, i.e., whether the quorum has been reached.
+    pub fn is_ready(&self) -> bool {
+        self.contributors() > self.config.degree
+    }
+
+
+    // Utility method calling the quorum hook (if any) if the quorum has just been
+    // reached, given the number of contributors before the latest aggregation.
+    fn notify_quorum(&self, contributors_before: usize) {
+        if contributors_before <= self.config.degree && self.is_ready() {
+            if let Some(hook) = &self.quorum_hook {
+                hook.on_quorum_reached(self.contributors());
+            }
+        }
+    }
+
+
+    // Method for finalizing the sharing phase: returns our aggregated transcript,
+    // provided its contributions cover a total weight above the threshold.
+    pub fn finalize(&self) -> Result<&PVSSTranscript<E, SSIG>, PVSSError<E>> {
+        self.transcript.check_weight_threshold()?;
+        Ok(&self.transcript)
+    }
+
+
     // Method for handling a received augmented PVSS share instance.
-    pub fn receive_share<R: Rng>(
+    pub fn receive_share<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        share: &PVSSAugmentedShare<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+        self.receive_share_from(rng, share, None)
+    }
+
+
+    // Method for handling an augmented PVSS share instance received from a given peer.
+    pub fn receive_share_from<R: Rng + CryptoRng>(
         &mut self,
         rng: &mut R,
         share: &PVSSAugmentedShare<E, SSIG>,
+        source: Option<usize>,
     ) -> Result<(), PVSSError<E>> {
+        let received_at = SystemTime::now();
+
+	// Dealers excluded after a complaint may not contribute again, even with a
+	// share we processed before their exclusion.
+        if self.excluded.contains(&share.participant_id) {
+            return Err(PVSSError::ExcludedDealerError(share.participant_id));
+        }
+
+	// Shares processed before a restart are already part of our (persisted)
+	// transcript, so they are neither re-verified nor re-aggregated.
+        let digest = share.digest();
+        if self.seen.as_ref().is_some_and(|seen| seen.contains(&digest)) {
+            return Ok(());
+        }
+
+	// Copies of a dealing already in our transcript are ignored, rather than
+	// counted again, unless our policy allows it.
+        let aggregated = self
+            .transcript
+            .contributions
+            .get(&share.participant_id)
+            .is_some_and(|contribution| contribution.decomp_proof == share.decomp_proof);
+        if aggregated && self.duplicates == DuplicatePolicy::RejectDuplicates {
+            return Ok(());
+        }
+
 	// Verify augmented PVSS share.
-        self.share_verify(rng, share)?;
+        let verification_start = Instant::now();
+        let verified = self.share_verify(rng, share);
+        let verification_time = verification_start.elapsed();
+        self.stats.get_mut().unwrap().record_share(verified.is_ok(), verification_time);
+        verified.map_err(|e| PVSSError::InvalidShareError(share.participant_id, e.to_string()))?;
 
 	// Q: What if we receive the same PVSS share instance twice in a row?
 	// Does its "weight" somehow factor in?
 
 	// Create a PVSS transcript from the info included in the augmented share.
-        let transcript = PVSSTranscript {
-            degree: self.config.degree,
-            num_participants: self.participants.len(),
-            contributions: vec![(
-                share.participant_id,
-                PVSSTranscriptParticipant {
-                    decomp_proof: share.decomp_proof.clone(),
-    		    signature_on_decomp: share.signature_on_decomp.clone(),   
-                },
-            )]
-            .into_iter()
-            .collect(),
-            pvss_share: share.pvss_share.clone(),
-        };
+        let transcript = PVSSTranscript::from_share(self.config.degree, self.participants.len(), share);
 
 	// Aggregate the newly generated transcript to the current aggregate.
-        self.transcript = self.transcript.aggregate(&transcript)?;
+        let contributors_before = self.contributors();
+        if !self.aggregate_received(&transcript)? {
+            return Ok(());
+        }
+
+        self.record_contributions(&transcript, ContributionInfo { received_at, source, verification_time });
+        self.notify_quorum(contributors_before);
+
+        if let Some(seen) = self.seen.as_mut() {
+            seen.insert(digest)?;
+        }
 
         Ok(())
     }
 
 
-    // Method for handling a received PVSS transcript instance.
-    pub fn receive_transcript<R: Rng>(
+    // Method for handling a burst of received augmented PVSS shares (e.g., the
+    // dealings at the start of a round). The shares are first verified together
+    // (see shares_verify_batch), and those that pass are marked as verified and
+    // aggregated one by one, with no further verification. If the batch fails, every
+    // share is verified on its own instead, which pins the failures to the shares.
+    // Returns one result per share, in order.
+    pub fn receive_shares<R: Rng + CryptoRng>(
         &mut self,
         rng: &mut R,
-        transcript: &PVSSTranscript<E, SSIG>,
-    ) -> Result<(), PVSSError<E>> {
-
-	// Perform checks on the transcript analogous to Context::verify_aggregation
-
-	if transcript.pvss_share.encs.len() != self.config.num_participants || 
-            transcript.pvss_share.comms.len() != self.config.num_participants ||
-            transcript.contributions.len() < self.config.degree {   // maybe break down into individual checks for better control
-            return Err(PVSSError::LengthMismatchError);
-    	}
-
-    	// Coding check for the commitments to ensure that they represent a
-	// commitment to a degree t polynomial.
-	if ensure_degree::<E, _>(rng, &transcript.pvss_share.comms, self.config.degree as u64).is_err() {
-            return Err(PVSSError::DualCodeError);
-    	}
-
-	// Pairing check
+        shares: &[PVSSAugmentedShare<E, SSIG>],
+    ) -> Vec<Result<(), PVSSError<E>>> {
+        if shares.len() > 1 {
+            let batch_start = Instant::now();
+            let verified = self.shares_verify_batch(rng, shares);
+            self.stats.get_mut().unwrap().record_batch(shares.len(), verified.is_ok(), batch_start.elapsed());
+            if verified.is_ok() {
+                for share in shares.iter() {
+                    self.mark_share_verified(share);
+                }
+            }
+        }
 
-	// ...
+        shares.iter().map(|share| self.receive_share_from(rng, share, None)).collect()
+    }
 
-	// Decomposition proof check
-	
-	// ...
 
-	// other...
+    // Method for verifying many augmented PVSS shares at once. Length, subgroup and
+    // binding checks are done per share, as they are cheap, while the rest is batched:
+    // - the coding and interpolation checks are run once, on a random linear
+    //   combination of the shares' commitment vectors (against the same combination
+    //   of their gs), since a combination of codewords is a codeword, while any
+    //   non-codeword survives the combination with probability at most 1/|Fr|;
+    // - the dealers' encryptions are checked with a single product of m+1 pairings;
+    // - the decomposition proofs and the signatures on them are batch verified.
+    pub fn shares_verify_batch<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        shares: &[PVSSAugmentedShare<E, SSIG>],
+    ) -> Result<(), PVSSError<E>> {
+        let n = self.config.num_participants;
+        let prepared = self.prepared()?;
 
-        let mut c = E::G1Projective::zero();
+        let mut entries = vec![];
         let mut public_keys_sig = vec![];
         let mut messages_sig = vec![];
         let mut signatures_sig = vec![];
+        let mut decomp_proofs = vec![];
 
-        let mut public_keys_pok = vec![];
-        let mut messages_pok = vec![];
-        let mut signatures_pok = vec![];
+        for share in shares.iter() {
+            let id = share.participant_id;
+            let participant = self.participants.get(&id).ok_or(PVSSError::<E>::InvalidParticipantId(id))?;
+            let core = &share.pvss_share;
 
-        for (participant_id, contribution) in transcript.contributions.iter() {
-	    // Retrieve participant's profile.
-            let participant = self
-                .participants
-                .get(participant_id)
-                .ok_or(PVSSError::<E>::InvalidParticipantId(*participant_id))?;
+            if core.encs.len() != n || core.comms.len() != n {
+                return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(core.encs.len(), core.comms.len(), n));
+            }
+            if self.strictness >= Strictness::Standard {
+                kernel::subgroup_check::<E>(&core.encs, &core.comms)?;
+            }
+            if share.decomp_proof.binding() != commitments_binding::<E>(&core.comms) {
+                return Err(PVSSError::DecompBindingError);
+            }
 
-	    // serialize decomposition proof into an array of bytes.
-            let message = message_from_pi_i(contribution.decomp_proof)?;
+            let encryption_key = prepared.key(id).ok_or(PVSSError::<E>::InvalidParticipantId(id))?;
+            entries.push((encryption_key.clone(), &core.encs[id], &core.comms[id]));
 
             public_keys_sig.push(&participant.public_key_sig);
-            messages_sig.push(message.clone());
-            signatures_sig.push(&contribution.signature_on_decomp);
+            messages_sig.push(message_from_pi_i(share.decomp_proof)?);
+            signatures_sig.push(&share.signature_on_decomp);
+            decomp_proofs.push(&share.decomp_proof);
+        }
 
-            public_keys_pok.push(&contribution.decomp_proof);
-            messages_pok.push(message);
-            signatures_pok.push(&contribution.c_i_pok);
+        // Combine the commitment vectors and the gs with random coefficients.
+        let rhos = shares.iter().map(|_| Scalar::<E>::rand(rng).into_repr()).collect::<Vec<_>>();
+        let combined = (0..n)
+            .map(|i| {
+                let column = shares.iter().map(|share| share.pvss_share.comms[i]).collect::<Vec<_>>();
+                VariableBaseMSM::multi_scalar_mul(&E::G2Projective::batch_normalization_into_affine(&column), &rhos)
+            })
+            .collect::<Vec<_>>();
+        let gs = decomp_proofs.iter().map(|proof| proof.gs()).collect::<Vec<_>>();
+        let combined_gs = VariableBaseMSM::multi_scalar_mul(&gs, &rhos);
+        count_msms(n + 1);
 
-            c += &contribution
-                .c_i
-                .mul(<E::Fr as From<u64>>::from(contribution.weight));
+        self.coding_check(rng, &combined, None).map_err(|_| PVSSError::DualCodeError)?;
+        let points = self.config.points();
+        kernel::interpolation_check_at::<E>(&combined, &points, self.config.degree, combined_gs)?;
+        if self.strictness >= Strictness::Paranoid {
+            kernel::reverse_interpolation_check_at::<E>(&combined, &points, self.config.degree, combined_gs)?;
         }
 
-        let sig_timer = start_timer!(|| "Signature batch verification");
+        count_msms(1);
+        count_pairings(entries.len() + 1);
+        kernel::encryptions_check_prepared::<E, R>(rng, &prepared.neg_g2, &entries)?;
+
+        DecompProof::verify_batch(rng, &self.config, &decomp_proofs)?;
+
         self.scheme_sig.batch_verify(
             rng,
             &public_keys_sig,
-            &messages_sig
-                .iter()
-                .map(|v| v.as_slice())
-                .collect::<Vec<_>>(),
+            &messages_sig.iter().map(|v| v.as_slice()).collect::<Vec<_>>(),
             &signatures_sig,
         )?;
-        end_timer!(sig_timer);
 
-        let pok_timer = start_timer!(|| "POK batch verification");
-        self.scheme_pok.batch_verify(
-            rng,
-            &public_keys_pok,
-            &messages_pok
-                .iter()
-                .map(|v| v.as_slice())
-                .collect::<Vec<_>>(),
-            &signatures_pok,
-        )?;
-        end_timer!(pok_timer);
+        Ok(())
+    }
 
-	// Verify PVSS share
-        let pvss_timer = start_timer!(|| "PVSS share verification");
-        self.pvss_share_verify(rng, c.into_affine(), &transcript.pvss_share)?;
-        end_timer!(pvss_timer);
 
-        Ok(())
+    // Method for handling a received PVSS transcript instance.
+    pub fn receive_transcript<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+        self.receive_transcript_from(rng, transcript, None)
     }
 
 
-    // Method for verifying individual "core" PVSS shares against a commitment to some secret.
-    pub fn pvss_share_verify<R: Rng>(
-        &self,
+    // Method for handling a PVSS transcript instance received from a given peer.
+    pub fn receive_transcript_from<R: Rng + CryptoRng>(
+        &mut self,
         rng: &mut R,
-	decomp_proof: &DecompProof<E>,   // need to pass on separately since PVSSShares don't have decomps attached
-        share: &PVSSShare<E>,
+        transcript: &PVSSTranscript<E, SSIG>,
+        source: Option<usize>,
     ) -> Result<(), PVSSError<E>> {
-	// Check that the sizes of commitments and encryptions are correct.
-	if share.encs.len() != self.config.num_participants ||
-           share.comms.len() != self.config.num_participants {
-	    return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(share.encs.len(),
-			share.comms.len(), self.config.num_participants));
-	}
+        let received_at = SystemTime::now();
 
-	// Coding check for the commitments to ensure that they represent a
-	// commitment to a degree t polynomial.
-	if ensure_degree::<E, _>(rng, &share.comms, self.config.degree as u64).is_err() {
-            return Err(PVSSError::DualCodeError);
+	// Refuse transcripts that could never fit in our memory budget before
+	// spending any time on verifying them.
+        if let Some(accountant) = &self.memory {
+            let size = transcript.serialized_size();
+            if !accountant.fits(size) {
+                return Err(PVSSError::MemoryBudgetExceeded(size, accountant.budget));
+            }
         }
 
-	// Check pairing condition for correctness of encryption is: e(pk_i, v_i) = e(enc_i, g_2).
-	// NOTE: However, we do not have access to the sender's identity at this point (and by
-	// extension, its public key). Hence, this check is done in share_verify.
+	// Refuse transcripts still counting the contribution of an excluded dealer.
+        if let Some(id) = transcript.contributions.keys().find(|id| self.excluded.contains(id)) {
+            return Err(PVSSError::ExcludedDealerError(*id));
+        }
 
-        // Check decomposition proof.
-	let point = lagrange_interpolation_simple::<E>(&share.comms, self.config.degree as u64).unwrap();   // E::G2Projective
+	// Verify the received transcript, unless a copy of it (i.e., a transcript with
+	// the same digest) was already verified in this round.
+        self.cache.refresh(self.cache.epoch(), self.config.digest(), roster_digest(&self.participants));
+        let digest = transcript.digest();
+        let verification_start = Instant::now();
+        if !self.cache.contains(&digest) {
+            let verified = self.transcript_verify(rng, transcript);
+            self.stats.get_mut().unwrap().record_verification(verification_start.elapsed());
+            verified?;
+            self.cache.mark_verified(digest);
+            for (id, contribution) in transcript.contributions.iter() {
+                self.cache.mark_verified(contribution_digest::<E, SSIG>(*id, &contribution.decomp_proof, &contribution.signature_on_decomp));
+            }
+        }
+        let verification_time = verification_start.elapsed();
 
-	if point.into_affine() != decomp_proof.gs {
-	    return Err(PVSSError::GSCheckError);
-	}
+	// Aggregate the received transcript to the current aggregate.
+        let contributors_before = self.contributors();
+        if !self.aggregate_received(transcript)? {
+            return Ok(());
+        }
 
-	// Verify decomposition proof against our config.
-        if decomp_proof.verify(&self.config).is_err() {
-	    return Err(PVSSError::DecompProofVerificationError);
-	}
+        self.record_contributions(transcript, ContributionInfo { received_at, source, verification_time });
+        self.notify_quorum(contributors_before);
 
         Ok(())
     }
 
 
-    // Method for verifying a received PVSSAugmentedShare instance.
-    pub fn share_verify<R: Rng>(
+    // Method for aggregating a received transcript into the current aggregate in place,
+    // subject to our weight limit and memory budget (if any). Returns false if the
+    // received transcript was skipped. On error, the aggregate is left unchanged.
+    fn aggregate_received(&mut self, received: &PVSSTranscript<E, SSIG>) -> Result<bool, PVSSError<E>> {
+	// Under a memory budget, the aggregate is only known to fit once computed, so
+	// the current one is kept aside in case it has to be restored.
+        let previous = self.memory.as_ref().map(|_| self.transcript.clone());
+
+        match self.transcript.aggregate_assign_capped(received, self.weight_limit.max_weight) {
+            Err(PVSSError::WeightOverflow(_)) if self.weight_limit.policy == WeightPolicy::Saturate => return Ok(false),
+            result => result?,
+        }
+
+        match previous {
+            Some(previous) => self.fit_in_budget(previous, received).map(|_| true),
+            None => Ok(true),
+        }
+    }
+
+
+    // Method for recording bookkeeping information on the contributions of a received
+    // transcript. Contributions we already knew of keep their original information, and
+    // those no longer part of our transcript (e.g., after an eviction) are forgotten.
+    fn record_contributions(&mut self, received: &PVSSTranscript<E, SSIG>, info: ContributionInfo) {
+        for id in received.contributions.keys() {
+            self.metadata.entry(*id).or_insert_with(|| info.clone());
+        }
+
+        let contributions = &self.transcript.contributions;
+        self.metadata.retain(|id, _| contributions.contains_key(id));
+    }
+
+
+    // Method for keeping the freshly aggregated transcript within the memory budget
+    // (if any), restoring the previous aggregate if it does not fit.
+    fn fit_in_budget(
         &mut self,
+        previous: PVSSTranscript<E, SSIG>,
+        received: &PVSSTranscript<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+        let accountant = match self.memory.as_mut() {
+            Some(accountant) => accountant,
+            None => return Ok(()),
+        };
+
+        let size = self.transcript.serialized_size();
+        if accountant.fits(size) {
+            accountant.set_used(size);
+            return Ok(());
+        }
+
+        // The aggregate cannot be partially evicted, since its core share is a sum over
+        // all dealers. Under the Evict policy, we keep the received transcript instead of
+        // ours if it covers more dealers and fits on its own.
+        if accountant.policy == BudgetPolicy::Evict {
+            let received_size = received.serialized_size();
+            if received.contributions.len() > previous.contributions.len() && accountant.fits(received_size) {
+                accountant.set_used(received_size);
+                self.transcript = received.clone();
+                return Ok(());
+            }
+        }
+
+        self.transcript = previous;
+        Err(PVSSError::MemoryBudgetExceeded(size, accountant.budget))
+    }
+
+
+    // Method for verifying an aggregated PVSS transcript against our config and
+    // the registered participants.
+    pub fn transcript_verify<R: Rng + CryptoRng>(
+        &self,
         rng: &mut R,
-        share: &PVSSAugmentedShare<E, SSIG>,
+        transcript: &PVSSTranscript<E, SSIG>,
     ) -> Result<(), PVSSError<E>> {
+        self.transcript_verify_seeded(rng, transcript, None)
+    }
 
-        // Retrieve the Participant instance using the id within the augmented share.
-	let participant_id = share.participant_id;
-        let participant = self
-            .participants
-            .get(&participant_id)
-            .ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
 
-	// Verify correctness of encryption:
-	// e(participant.public_key_sig, share.comms[i]) == e(share.enc[i], self.config.srs.g2)
+    // Method for verifying an aggregated PVSS transcript like transcript_verify, with
+    // the coding check optionally derived from a seed shared among verifiers (e.g.,
+    // the block hash in consensus integrations) instead of our degree check mode, so
+    // that every verifier performs the identical coding check and its outcome can be
+    // replayed later.
+    pub fn transcript_verify_seeded<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+        seed: Option<&[u8]>,
+    ) -> Result<(), PVSSError<E>> {
+        self.begin_trace(Operation::TranscriptVerify);
+        let (result, cost) = measure(|| -> Result<(), PVSSError<E>> {
+            self.transcript_verify_structure(rng, transcript, seed)?;
+
+	    // Verify correctness of all encryptions
+            let pairing_timer = start_timer!(|| "Encryption correctness verification");
+            let encryptions_start = Instant::now();
+            self.traced("encryptions", || vec![transcript.pvss_share.digest()],
+                || self.encryptions_verify(rng, &transcript.pvss_share))?;
+            self.stats.lock().unwrap().record_individual(encryptions_start.elapsed());
+            if self.strictness >= Strictness::Paranoid {
+                self.traced("encryptions_each", || vec![transcript.pvss_share.digest()],
+                    || self.encryptions_verify_each(&transcript.pvss_share))?;
+            }
+            end_timer!(pairing_timer);
 
-	let pairs = [
-            (participant.public_key_sig.into(), share.pvss_share.comms[participant_id].into()),
-            (share.pvss_share.enc[participant_id].into(), self.config.srs.g2.neg().into()),
-        ];
+            Ok(())
+        });
+        self.report(Operation::TranscriptVerify, cost);
 
-        if !E::product_of_pairings(pairs.iter()).is_one() {
-            return Err(PVSSError::EncryptionCorrectnessError);
+        result
+    }
+
+
+    // Method for verifying an aggregated PVSS transcript like transcript_verify,
+    // additionally reporting repeated contributions and contributor counts at or
+    // near the threshold.
+    pub fn transcript_verify_with_report<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+    ) -> VerificationOutcome {
+        let mut warnings = vec![];
+
+        for (dealer, contribution) in transcript.contributions.iter() {
+            if contribution.weight > 1 {
+                warnings.push(Warning::RepeatedContribution { dealer: *dealer, weight: contribution.weight });
+            }
+        }
+
+        let contributors = transcript.contributions.len();
+        let required = self.config.degree + 1;
+        if contributors < required {
+            warnings.push(Warning::BelowThreshold { contributors, required });
+        } else if contributors <= required + NEAR_THRESHOLD_MARGIN {
+            warnings.push(Warning::NearThreshold { contributors, required });
         }
 
-	// Verify the "core" PVSS share against the provided decomposition proof.
-	self.pvss_share_verify(rng, &share.decomp_proof, &share.pvss_share)?;
+        let result = self.transcript_verify(rng, transcript);
+        VerificationOutcome { ok: result.is_ok(), warnings, error: result.err().map(|e| e.to_string()) }
+    }
 
-        // Verify signature on decomposition proof against participant i's public key.
-        self.scheme_sig.verify(
-            &participant.public_key_sig,
-            &message_from_pi_i(share.decomp_proof)?,
-            &share.signature_on_decomp,
-        )?;
 
-        Ok(())
+    // Method for verifying many independent transcripts (e.g., those of consecutive
+    // epochs) at once. Structural checks run per transcript, while the encryption
+    // checks of all transcripts are combined into a single product of n+1 pairings,
+    // with the commitments and encryptions folded by multi-scalar multiplication.
+    // Failures are isolated: if the combined check fails, it is bisected until the
+    // offending transcripts are found. Fewer transcripts than our batching threshold
+    // are checked individually instead. Returns one result per transcript, in order.
+    pub fn verify_many<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcripts: &[PVSSTranscript<E, SSIG>],
+    ) -> Vec<Result<(), PVSSError<E>>> {
+        let mut results = transcripts
+            .iter()
+            .map(|transcript| self.transcript_verify_structure(rng, transcript, None))
+            .collect::<Vec<_>>();
+
+        let pending = results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>();
+
+        if pending.len() < self.batch_threshold {
+            for k in pending {
+                let encryptions_start = Instant::now();
+                if let Err(e) = self.encryptions_verify(rng, &transcripts[k].pvss_share) {
+                    results[k] = Err(e);
+                }
+                self.stats.lock().unwrap().record_individual(encryptions_start.elapsed());
+            }
+            return results;
+        }
+
+        let pairing_timer = start_timer!(|| "Batched encryption correctness verification");
+        let batch_start = Instant::now();
+        let bad = self.isolate_bad_encryptions(rng, transcripts, &pending);
+        self.stats.lock().unwrap().record_batch(pending.len(), bad.is_empty(), batch_start.elapsed());
+        for k in bad {
+            results[k] = Err(PVSSError::EncryptionCorrectnessError);
+        }
+        end_timer!(pairing_timer);
+
+        results
     }
 
+
+    // Utility method returning the indices (among the given ones) of the transcripts
+    // whose encryptions are incorrect, by bisecting the combined check.
+    fn isolate_bad_encryptions<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcripts: &[PVSSTranscript<E, SSIG>],
+        indices: &[usize],
+    ) -> Vec<usize> {
+        if indices.is_empty() || self.encryptions_verify_many(rng, transcripts, indices).is_ok() {
+            return vec![];
+        }
+        if indices.len() == 1 {
+            return indices.to_vec();
+        }
+
+        let (left, right) = indices.split_at(indices.len() / 2);
+        let mut bad = self.isolate_bad_encryptions(rng, transcripts, left);
+        bad.extend(self.isolate_bad_encryptions(rng, transcripts, right));
+        bad
+    }
+
+
+    // Utility method for checking the encryptions of several transcripts at once:
+    // prod_i e(pk_i, sum_k r_{k,i} * comm_{k,i}) * e(-sum_{k,i} r_{k,i} * enc_{k,i}, g_2) == 1
+    fn encryptions_verify_many<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcripts: &[PVSSTranscript<E, SSIG>],
+        indices: &[usize],
+    ) -> Result<(), PVSSError<E>> {
+        let prepared = self.prepared()?;
+        let mut pairs = vec![];
+        let mut enc_bases = vec![];
+        let mut enc_scalars = vec![];
+
+        for id in self.participants.keys() {
+            let encryption_key = prepared.key(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+            let mut comm_bases = vec![];
+            let mut comm_scalars = vec![];
+
+            for k in indices.iter() {
+                let share = &transcripts[*k].pvss_share;
+                let comm = share.comms.get(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+                let enc = share.encs.get(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+
+                let r = Scalar::<E>::rand(rng).into_repr();
+                comm_bases.push(*comm);
+                comm_scalars.push(r);
+                enc_bases.push(*enc);
+                enc_scalars.push(r);
+            }
+
+            let comm_bases = E::G2Projective::batch_normalization_into_affine(&comm_bases);
+            let comm_sum = VariableBaseMSM::multi_scalar_mul(&comm_bases, &comm_scalars);
+            count_msms(1);
+            pairs.push((encryption_key.clone(), comm_sum.into_affine().into()));
+        }
+
+        let enc_bases = E::G1Projective::batch_normalization_into_affine(&enc_bases);
+        let enc_sum = VariableBaseMSM::multi_scalar_mul(&enc_bases, &enc_scalars);
+        count_msms(1);
+        pairs.push((enc_sum.into_affine().into(), prepared.neg_g2.clone()));
+
+        count_pairings(pairs.len());
+        if !E::product_of_pairings(pairs.iter()).is_one() {
+            return Err(PVSSError::EncryptionCorrectnessError);
+        }
+
+        Ok(())
+    }
+
+
+    // Method for probabilistically verifying an aggregated PVSS transcript: it performs
+    // all checks of transcript_verify (coding check, decomposition proofs, signatures
+    // and the gs check), but only checks the encryptions of k participants, sampled
+    // uniformly at random without replacement.
+    //
+    // Soundness: if b of the n encryptions are incorrect, the spot check misses all of
+    // them with probability C(n-b, k) / C(n, k) <= (1 - b/n)^k (see
+    // spot_check_escape_probability). In particular, a transcript with a single bad
+    // encryption escapes with probability 1 - k/n, and k >= ln(1/delta) * n/b samples
+    // catch b bad encryptions except with probability delta. All other checks retain
+    // the soundness of transcript_verify.
+    pub fn spot_check<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+        k: usize,
+    ) -> Result<(), PVSSError<E>> {
+        self.transcript_verify_structure(rng, transcript, None)?;
+
+        let ids = self.participants.keys().cloned().collect::<Vec<_>>();
+        let sampled = sample(rng, ids.len(), k.min(ids.len()))
+            .into_iter()
+            .map(|i| ids[i])
+            .collect::<Vec<_>>();
+
+        let pairing_timer = start_timer!(|| "Encryption correctness spot check");
+        self.encryptions_verify_subset(rng, &transcript.pvss_share, &sampled)?;
+        end_timer!(pairing_timer);
+
+        Ok(())
+    }
+
+
+    // Method for checking everything in an aggregated PVSS transcript except for the
+    // correctness of the encryptions.
+    fn transcript_verify_structure<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+        seed: Option<&[u8]>,
+    ) -> Result<(), PVSSError<E>> {
+
+	// Ensure that the transcript is w.r.t. our configuration.
+        self.traced("config", || vec![self.config.digest()], || {
+            if transcript.degree != self.config.degree || transcript.num_participants != self.config.num_participants {
+                return Err(PVSSError::TranscriptDifferentConfig(
+                    self.config.degree,
+                    transcript.degree,
+                    self.config.num_participants,
+                    transcript.num_participants,
+                ));
+            }
+
+	    // Perform checks on the transcript analogous to Context::verify_aggregation
+	    if transcript.pvss_share.encs.len() != self.config.num_participants ||
+                transcript.pvss_share.comms.len() != self.config.num_participants ||
+                transcript.contributions.len() < self.config.degree {   // maybe break down into individual checks for better control
+                return Err(PVSSError::LengthMismatchError);
+    	    }
+            Ok(())
+        })?;
+
+        if self.strictness >= Strictness::Standard {
+            self.traced("subgroup", || vec![transcript.pvss_share.digest()],
+                || kernel::subgroup_check::<E>(&transcript.pvss_share.encs, &transcript.pvss_share.comms))?;
+            self.traced("weights", || vec![transcript.digest()], || self.audit_weights(transcript))?;
+        }
+
+        let comms = &transcript.pvss_share.comms;
+        let mut gs_total = E::G2Projective::zero();
+        let mut public_keys_sig = vec![];
+        let mut messages_sig = vec![];
+        let mut signatures_sig = vec![];
+        let mut decomp_proofs = vec![];
+
+	// Contributions already verified in this round (as part of a share or of an
+	// earlier transcript) need not have their proofs and signatures checked again.
+	// The cache is only consulted if it is bound to our current config and roster.
+        let cached = self.cache.is_bound_to(&self.config.digest(), &roster_digest(&self.participants));
+
+        for (participant_id, contribution) in transcript.contributions.iter() {
+	    // Retrieve participant's profile.
+            let participant = self
+                .participants
+                .get(participant_id)
+                .ok_or(PVSSError::<E>::InvalidParticipantId(*participant_id))?;
+
+            let digest = contribution_digest::<E, SSIG>(*participant_id, &contribution.decomp_proof, &contribution.signature_on_decomp);
+            if !(cached && self.cache.contains(&digest)) {
+	        // serialize decomposition proof into an array of bytes.
+                let message = message_from_pi_i(contribution.decomp_proof)?;
+
+                public_keys_sig.push(&participant.public_key_sig);
+                messages_sig.push(message);
+                signatures_sig.push(&contribution.signature_on_decomp);
+
+                decomp_proofs.push(&contribution.decomp_proof);
+            }
+
+	    // Accumulate the contributors' commitments to their secrets, each counted
+	    // according to its weight. Contributions of weight zero are meaningless.
+            if contribution.weight == 0 {
+                return Err(PVSSError::InsufficientWeightError(0, 1));
+            }
+            gs_total += contribution.decomp_proof.gs().mul(Scalar::<E>::from(contribution.weight).into_repr());
+        }
+
+    	// Coding check for the commitments to ensure that they represent a
+	// commitment to a degree t polynomial, and verification of all contributors'
+	// decomposition proofs against our config at once.
+        self.coding_and_decomposition_check(rng, comms, seed, &decomp_proofs)?;
+
+        let sig_timer = start_timer!(|| "Signature batch verification");
+        self.traced("signatures", || messages_sig.iter().map(|m| digest_bytes(m)).collect(), || {
+            if messages_sig.is_empty() {
+                return Ok(());
+            }
+            self.scheme_sig.batch_verify(
+                rng,
+                &public_keys_sig,
+                &messages_sig
+                    .iter()
+                    .map(|v| v.as_slice())
+                    .collect::<Vec<_>>(),
+                &signatures_sig,
+            ).map_err(PVSSError::from)
+        })?;
+        end_timer!(sig_timer);
+
+	// The aggregated commitments must interpolate to the combined commitment
+	// to the secrets of all contributors.
+        let points = self.config.points();
+	self.traced("interpolation", || vec![canonical_digest(comms)],
+            || kernel::interpolation_check_at::<E>(comms, &points, self.config.degree, gs_total))?;
+
+        if self.strictness >= Strictness::Paranoid {
+            self.traced("reverse_interpolation", || vec![canonical_digest(comms)],
+                || kernel::reverse_interpolation_check_at::<E>(comms, &points, self.config.degree, gs_total))?;
+        }
+
+        Ok(())
+    }
+
+
+    // Utility method auditing the weights of a transcript's contributions: none may
+    // exceed our weight cap, and no dealing may be counted under two dealers' ids
+    // (which would give its secret the weight of both).
+    fn audit_weights(&self, transcript: &PVSSTranscript<E, SSIG>) -> Result<(), PVSSError<E>> {
+        let mut dealings = BTreeMap::new();
+        for (id, contribution) in transcript.contributions.iter() {
+            if contribution.weight > self.weight_limit.max_weight {
+                return Err(PVSSError::WeightOverflow(contribution.weight));
+            }
+            if let Some(other) = dealings.insert(contribution.decomp_proof.digest(), *id) {
+                return Err(PVSSError::DuplicateContributionError(other, *id));
+            }
+        }
+
+        Ok(())
+    }
+
+
+    // Utility method checking the correctness of every encryption in a core PVSS share
+    // individually, i.e., with 2 pairings per participant instead of a batched check.
+    fn encryptions_verify_each(&self, share: &PVSSShare<E>) -> Result<(), PVSSError<E>> {
+        let prepared = self.prepared()?;
+        for id in self.participants.keys() {
+            let enc = share.encs.get(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+            let comm = share.comms.get(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+            let encryption_key = prepared.key(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+
+            count_pairings(2);
+            kernel::encryption_check_prepared::<E>(&prepared.neg_g2, encryption_key, enc, comm)?;
+        }
+
+        Ok(())
+    }
+
+
+    // Method for checking correctness of all encryptions in a core PVSS share, i.e.,
+    // that e(pk_i, comm_i) == e(enc_i, g_2) holds for every participant i. The n
+    // conditions are combined using independent random coefficients r_i, so that
+    // only n+1 pairings need to be computed:
+    // prod_i e(pk_i, r_i * comm_i) * e(-sum_i r_i * enc_i, g_2) == 1
+    pub fn encryptions_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        share: &PVSSShare<E>,
+    ) -> Result<(), PVSSError<E>> {
+        let ids = self.participants.keys().cloned().collect::<Vec<_>>();
+        self.encryptions_verify_subset(rng, share, &ids)
+    }
+
+
+    // Method for checking correctness of the encryptions of the given participants only.
+    fn encryptions_verify_subset<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        share: &PVSSShare<E>,
+        ids: &[usize],
+    ) -> Result<(), PVSSError<E>> {
+        let entries = ids
+            .iter()
+            .map(|id| match (share.encs.get(*id), share.comms.get(*id)) {
+                (Some(enc), Some(comm)) => Ok((*id, enc, comm)),
+                _ => Err(PVSSError::<E>::InvalidParticipantId(*id)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.encryptions_verify_entries(rng, &entries)
+    }
+
+
+    // Utility method batching the encryption correctness checks of the given
+    // (recipient id, encryption, commitment) triples into a single pairing product.
+    fn encryptions_verify_entries<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        entries: &[(usize, &E::G1Projective, &E::G2Projective)],
+    ) -> Result<(), PVSSError<E>> {
+        let prepared = self.prepared()?;
+        let keyed = entries
+            .iter()
+            .map(|(id, enc, comm)| match prepared.key(*id) {
+                Some(key) => Ok((key.clone(), *enc, *comm)),
+                None => Err(PVSSError::<E>::InvalidParticipantId(*id)),
+            })
+            .collect::<Result<Vec<_>, PVSSError<E>>>()?;
+
+        count_msms(1);
+        count_pairings(keyed.len() + 1);
+        kernel::encryptions_check_prepared::<E, R>(rng, &prepared.neg_g2, &keyed)
+    }
+
+
+    // Utility method running the coding check on a commitment vector in the
+    // configured mode, or derived from the given seed if there is one.
+    fn coding_check<R: Rng + CryptoRng>(&self, rng: &mut R, comms: &[E::G2Projective], seed: Option<&[u8]>) -> Result<(), PVSSError<E>> {
+        coding_check_in_mode::<E, R>(rng, self.degree_check, comms, &self.config, seed)
+    }
+
+
+    // Utility method running the coding check on the commitments of a transcript and
+    // the batched verification of its contributors' decomposition proofs.
+    #[cfg(not(feature = "parallel"))]
+    fn coding_and_decomposition_check<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        comms: &Vec<E::G2Projective>,
+        seed: Option<&[u8]>,
+        decomp_proofs: &[&DecompProof<E>],
+    ) -> Result<(), PVSSError<E>> {
+	self.traced("coding", || vec![canonical_digest(comms)],
+            || self.coding_check(rng, comms, seed).map_err(|_| PVSSError::DualCodeError))?;
+        self.traced("decomposition", || decomp_proofs.iter().map(|proof| proof.digest()).collect(),
+            || DecompProof::verify_batch(rng, &self.config, decomp_proofs))
+    }
+
+
+    // Utility method as above, running the two (independent) checks concurrently.
+    // Each gets its own generator, seeded from rng, and the pairings and MSMs they
+    // perform are counted on the calling thread.
+    #[cfg(feature = "parallel")]
+    fn coding_and_decomposition_check<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        comms: &Vec<E::G2Projective>,
+        seed: Option<&[u8]>,
+        decomp_proofs: &[&DecompProof<E>],
+    ) -> Result<(), PVSSError<E>> {
+        let mut coding_rng = ChaChaRng::from_seed(rng.gen());
+        let mut decomposition_rng = ChaChaRng::from_seed(rng.gen());
+        let (config, mode) = (&self.config, self.degree_check);
+
+        let timed = |check: &dyn Fn(&mut ChaChaRng) -> Result<(), PVSSError<E>>, rng: &mut ChaChaRng| {
+            let start = Instant::now();
+            let (result, cost) = isolate(|| check(rng));
+            (result, cost, start.elapsed())
+        };
+        let ((coding, coding_cost, coding_time), (decomposition, decomposition_cost, decomposition_time)) = rayon::join(
+            || timed(&|rng| coding_check_in_mode::<E, _>(rng, mode, comms, config, seed)
+                .map_err(|_| PVSSError::DualCodeError), &mut coding_rng),
+            || timed(&|rng| DecompProof::verify_batch(rng, config, decomp_proofs), &mut decomposition_rng),
+        );
+        count_cost(coding_cost);
+        count_cost(decomposition_cost);
+
+        self.record_trace("coding", || vec![canonical_digest(comms)], coding_time, &coding);
+        coding?;
+        self.record_trace("decomposition", || decomp_proofs.iter().map(|proof| proof.digest()).collect(),
+            decomposition_time, &decomposition);
+        decomposition
+    }
+
+
+    // Method for verifying individual "core" PVSS shares against a commitment to some secret.
+    pub fn pvss_share_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+	decomp_proof: &DecompProof<E>,   // need to pass on separately since PVSSShares don't have decomps attached
+        share: &PVSSShare<E>,
+    ) -> Result<(), PVSSError<E>> {
+	// Check that the sizes of commitments and encryptions are correct.
+	if share.encs.len() != self.config.num_participants ||
+           share.comms.len() != self.config.num_participants {
+	    return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(share.encs.len(),
+			share.comms.len(), self.config.num_participants));
+	}
+
+	if self.strictness >= Strictness::Standard {
+	    self.traced("subgroup", || vec![share.digest()],
+		|| kernel::subgroup_check::<E>(&share.encs, &share.comms))?;
+	}
+
+	// Coding check for the commitments to ensure that they represent a
+	// commitment to a degree t polynomial.
+	self.traced("coding", || vec![canonical_digest(&share.comms)],
+            || self.coding_check(rng, &share.comms, None).map_err(|_| PVSSError::DualCodeError))?;
+
+	// Check pairing condition for correctness of encryption is: e(pk_i, v_i) = e(enc_i, g_2).
+	// NOTE: However, we do not have access to the sender's identity at this point (and by
+	// extension, its public key). Hence, this check is done in share_verify.
+
+        // Check decomposition proof against the commitments and our config.
+	self.traced("decomposition", || vec![decomp_proof.digest(), canonical_digest(&share.comms)],
+            || decomp_proof.verify_against_core(&self.config, share))?;
+
+	if self.strictness >= Strictness::Paranoid {
+	    self.traced("reverse_interpolation", || vec![canonical_digest(&share.comms)],
+		|| kernel::reverse_interpolation_check::<E>(&self.config, &share.comms, decomp_proof.gs().into_projective()))?;
+	}
+
+	Ok(())
+    }
+
+
+    // Method for verifying a sparse core PVSS share dealt along with the given
+    // decomposition proof, including the correctness of all of its encryptions.
+    // Requires a config with the sparse share layout.
+    pub fn sparse_share_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        decomp_proof: &DecompProof<E>,
+        share: &SparsePVSSShare<E>,
+    ) -> Result<(), PVSSError<E>> {
+        self.sparse_core_verify(rng, share, decomp_proof.gs().into_projective())?;
+        decomp_proof.verify(&self.config)
+    }
+
+
+    // Method for verifying an aggregation of sparse core PVSS shares, given the
+    // decomposition proofs of all the dealers aggregated.
+    pub fn sparse_aggregate_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        decomp_proofs: &[DecompProof<E>],
+        share: &SparsePVSSShare<E>,
+    ) -> Result<(), PVSSError<E>> {
+        DecompProof::verify_batch(rng, &self.config, &decomp_proofs.iter().collect::<Vec<_>>())?;
+
+        let mut gs_total = E::G2Projective::zero();
+        for decomp_proof in decomp_proofs.iter() {
+            gs_total += decomp_proof.gs().into_projective();
+        }
+
+        self.sparse_core_verify(rng, share, gs_total)
+    }
+
+
+    // Utility method checking that a sparse share commits to a degree t polynomial
+    // whose free term is committed to by gs, and that its encryptions are correct.
+    fn sparse_core_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        share: &SparsePVSSShare<E>,
+        gs: E::G2Projective,
+    ) -> Result<(), PVSSError<E>> {
+        if self.config.layout != ShareLayout::Sparse {
+            return Err(PVSSError::ShareLayoutError);
+        }
+
+        // All recipients must be within our committee.
+        let n = self.config.num_participants;
+        if share.num_participants != n || share.entries.keys().any(|id| *id >= n) {
+            return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(share.entries.len(),
+                        share.entries.len(), n));
+        }
+
+        // Coding check over the recipients' evaluation points.
+        let (comms, points) = (share.comms(), share.points(&self.config));
+        let degree = self.config.degree as u64;
+        let coding = match self.degree_check {
+            DegreeCheckMode::Interactive => ensure_degree_at_points::<E, _>(rng, &comms, &points, degree),
+            DegreeCheckMode::FiatShamir => ensure_degree_at_points_fiat_shamir::<E>(&comms, &points, degree),
+        };
+        match coding {
+            Err(PVSSError::InsufficientEvaluationsError) => return Err(PVSSError::InsufficientEvaluationsError),
+            Err(_) => return Err(PVSSError::DualCodeError),
+            Ok(()) => {},
+        }
+
+        // The commitments must interpolate to the commitment to the secret.
+        if lagrange_interpolation::<E>(&comms, &points, degree)? != gs {
+            return Err(PVSSError::GSCheckError);
+        }
+
+        let entries = share
+            .entries
+            .iter()
+            .map(|(id, (enc, comm))| (*id, enc, comm))
+            .collect::<Vec<_>>();
+        self.encryptions_verify_entries(rng, &entries)
+    }
+
+
+    // Method for verifying a received PVSSAugmentedShare instance.
+    pub fn share_verify<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        share: &PVSSAugmentedShare<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+        self.begin_trace(Operation::ShareVerify);
+        let (result, cost) = measure(|| self.share_verify_stages(rng, share));
+        self.report(Operation::ShareVerify, cost);
+        result
+    }
+
+
+    // Method for verifying a received PVSSAugmentedShare like share_verify,
+    // additionally reporting dealers whose contribution we already aggregated.
+    pub fn share_verify_with_report<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        share: &PVSSAugmentedShare<E, SSIG>,
+    ) -> VerificationOutcome {
+        let mut warnings = vec![];
+
+        if self.transcript.contributions.contains_key(&share.participant_id) {
+            warnings.push(Warning::AlreadyAggregated { dealer: share.participant_id });
+        }
+
+        let result = self.share_verify(rng, share);
+        VerificationOutcome { ok: result.is_ok(), warnings, error: result.err().map(|e| e.to_string()) }
+    }
+
+
+    // Utility method performing the actual verification of a PVSSAugmentedShare.
+    fn share_verify_stages<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        share: &PVSSAugmentedShare<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+
+	// Shares already fully verified in this round need not be verified again.
+	// Invalidate the cache first in case our config or roster has changed in the meantime.
+        self.cache.refresh(self.cache.epoch(), self.config.digest(), roster_digest(&self.participants));
+        let digest = share.digest();
+        if self.cache.contains(&digest) {
+            return Ok(());
+        }
+
+        // Retrieve the Participant instance using the id within the augmented share.
+	let participant_id = share.participant_id;
+        let participant = self
+            .participants
+            .get(&participant_id)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
+
+	// Run the stages in the configured order. Stages missing from the order are
+	// still run (last), so that a misconfiguration cannot skip a check.
+	let mut stages = self.stage_order.clone();
+	stages.extend(DEFAULT_STAGE_ORDER.iter().filter(|stage| !self.stage_order.contains(*stage)));
+
+	for stage in stages {
+	    match stage {
+		VerificationStage::Signature => {
+		    // Verify signature on decomposition proof against participant i's public key.
+		    self.traced("signature", || vec![share.decomp_proof.digest()], || kernel::decomp_signature_check(
+			&self.scheme_sig,
+			&participant.public_key_sig,
+			&share.decomp_proof,
+			&share.signature_on_decomp,
+		    ))?;
+		}
+		VerificationStage::Encryption => {
+		    // Verify correctness of encryption under participant i's current encryption key:
+		    // e(pk_i, share.comms[i]) == e(share.enc[i], self.config.srs.g2)
+		    let prepared = self.prepared()?;
+		    let encryption_key = prepared.key(participant_id).ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
+		    let comm = share.pvss_share.comms.get(participant_id).ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
+		    let enc = share.pvss_share.encs.get(participant_id).ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
+
+		    count_pairings(2);
+		    self.traced("encryption", || vec![canonical_digest(enc), canonical_digest(comm)],
+			|| kernel::encryption_check_prepared::<E>(&prepared.neg_g2, encryption_key, enc, comm))?;
+		}
+		VerificationStage::Core => {
+		    // Verify the "core" PVSS share against the provided decomposition proof.
+		    self.pvss_share_verify(rng, &share.decomp_proof, &share.pvss_share)?;
+		}
+	    }
+	}
+
+        self.cache.mark_verified(digest);
+        self.cache.mark_verified(contribution_digest::<E, SSIG>(participant_id, &share.decomp_proof, &share.signature_on_decomp));
+
+        Ok(())
+    }
+
+}
+
+
+// Function hashing the roster, i.e., the ids and signature keys of the participants,
+// to which the verification cache is bound.
+fn roster_digest<E: PairingEngine, SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>>(
+    participants: &BTreeMap<usize, Participant<E, SSIG>>,
+) -> Digest {
+    let mut bytes = vec![];
+    // serializing into a Vec cannot fail
+    (participants.len() as u64).serialize(&mut bytes).unwrap();
+    for (id, participant) in participants.iter() {
+        (*id as u64).serialize(&mut bytes).unwrap();
+        participant.public_key_sig.serialize(&mut bytes).unwrap();
+    }
+    digest_bytes(&bytes)
+}
+
+
+// Function returning the probability that a spot check of k out of n encryptions
+// misses all of b incorrect ones, i.e., C(n-b, k) / C(n, k).
+pub fn spot_check_escape_probability(n: usize, b: usize, k: usize) -> f64 {
+    if b > n || k > n {
+        return 0.0;
+    }
+
+    (0..k).fold(1.0, |p, i| {
+        if i + b >= n {
+            0.0
+        } else {
+            p * (n - b - i) as f64 / (n - i) as f64
+        }
+    })
+}
+
+
+// Function running the coding check on a commitment vector against the degree and
+// evaluation points of config, in the given mode, or derived from the given seed if
+// there is one.
+fn coding_check_in_mode<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    mode: DegreeCheckMode,
+    comms: &[E::G2Projective],
+    config: &Config<E>,
+    seed: Option<&[u8]>,
+) -> Result<(), PVSSError<E>> {
+    let degree = config.degree;
+    if let Some(domain) = config.domain() {
+        return match (seed, mode) {
+            (Some(seed), _) => kernel::coding_check_in_domain_with_seed::<E>(seed, comms, degree, &domain),
+            (None, DegreeCheckMode::Interactive) => kernel::coding_check_in_domain::<E, R>(rng, comms, degree, &domain),
+            (None, DegreeCheckMode::FiatShamir) => kernel::coding_check_in_domain_fiat_shamir::<E>(comms, degree, &domain),
+        };
+    }
+
+    if let Some(seed) = seed {
+        return kernel::coding_check_with_seed::<E>(seed, comms, degree);
+    }
+
+    match mode {
+        DegreeCheckMode::Interactive => kernel::coding_check::<E, R>(rng, comms, degree),
+        DegreeCheckMode::FiatShamir => kernel::coding_check_fiat_shamir::<E>(comms, degree),
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{PairingEngine, ProjectiveCurve};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{
+        aggregator::{spot_check_escape_probability, DuplicatePolicy, Strictness, VerificationStage, WeightLimit, WeightPolicy},
+        config::{Config, ShareLayout},
+        decomp::{Decomp, DecompProof},
+        errors::PVSSError,
+        memory::{BudgetPolicy, MemoryAccountant},
+        outcome::Warning,
+        participant::{Committee, ParticipantId},
+        share::PVSSTranscript,
+        stats::RoundStats,
+        tests::setup_nodes,
+        trace::VerificationTrace,
+    };
+    use crate::{canonical_digest, Hash};
+
+    use rand::thread_rng;
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_quorum() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+
+        let reached = Arc::new(AtomicUsize::new(0));
+        let counter = reached.clone();
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.quorum_hook = Some(Arc::new(move |contributors: usize| {
+            counter.fetch_add(contributors, Ordering::SeqCst);
+        }));
+
+        // Repeated shares do not raise the number of contributors.
+        for share in [&shares[0], &shares[1], &shares[1]].iter() {
+            aggregator.receive_share(rng, share).unwrap();
+            assert!(!aggregator.is_ready());
+        }
+        assert_eq!(aggregator.contributors(), 2);
+        assert_eq!(reached.load(Ordering::SeqCst), 0);
+
+        // The hook fires once, when the t + 1-th dealer is aggregated.
+        aggregator.receive_share(rng, &shares[2]).unwrap();
+        assert!(aggregator.is_ready());
+        aggregator.receive_share(rng, &shares[3]).unwrap();
+        assert_eq!(reached.load(Ordering::SeqCst), t + 1);
+    }
+
+    #[test]
+    fn test_spot_check() {
+        let rng = &mut thread_rng();
+        let (n, t) = (8, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.transcript.clone();
+        nodes[1].aggregator.spot_check(rng, &transcript, 3).unwrap();
+
+        // a bad encryption is always caught when all encryptions are sampled
+        let mut bad: PVSSTranscript<E, _> = transcript.clone();
+        bad.pvss_share.encs[5] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(matches!(nodes[1].aggregator.spot_check(rng, &bad, n), Err(PVSSError::EncryptionCorrectnessError)));
+
+        // while the other checks run in full, even when no encryption is sampled
+        let mut bad: PVSSTranscript<E, _> = transcript.clone();
+        bad.pvss_share.comms[5] = <E as PairingEngine>::G2Projective::rand(rng);
+        assert!(matches!(nodes[1].aggregator.spot_check(rng, &bad, 0), Err(PVSSError::DualCodeError)));
+    }
+
+    #[test]
+    fn test_verify_many() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        // One transcript per "epoch", each from a fresh pair of dealings.
+        let mut transcripts: Vec<PVSSTranscript<E, _>> = vec![];
+        for k in 0..5 {
+            let first = nodes[k % n].share(rng).unwrap();
+            let second = nodes[(k + 1) % n].share(rng).unwrap();
+            transcripts.push(PVSSTranscript::from_share(t, n, &first)
+                .aggregate(&PVSSTranscript::from_share(t, n, &second))
+                .unwrap());
+        }
+
+        let verifier = &nodes[0].aggregator;
+        assert!(verifier.verify_many(rng, &transcripts).iter().all(|r| r.is_ok()));
+
+        // Bad encryptions and bad structure are both pinned to their transcripts.
+        transcripts[1].pvss_share.encs[2] = <E as PairingEngine>::G1Projective::rand(rng);
+        transcripts[4].pvss_share.encs[0] = <E as PairingEngine>::G1Projective::rand(rng);
+        transcripts[3].contributions.clear();
+        let results = verifier.verify_many(rng, &transcripts);
+        assert!(results[0].is_ok() && results[2].is_ok());
+        assert!(matches!(results[1], Err(PVSSError::EncryptionCorrectnessError)));
+        assert!(matches!(results[4], Err(PVSSError::EncryptionCorrectnessError)));
+        assert!(results[3].is_err());
+    }
+
+    #[test]
+    fn test_receive_shares() {
+        let rng = &mut thread_rng();
+        let (n, t) = (5, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let mut shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+
+        // An honest burst passes as a batch, and is aggregated as if received one by one.
+        nodes[0].aggregator.shares_verify_batch(rng, &shares).unwrap();
+        let results = nodes[0].aggregator.receive_shares(rng, &shares[..3]);
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(nodes[0].aggregator.contributors(), 3);
+        assert_eq!(nodes[0].aggregator.round_stats().batches_passed, 1);
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        nodes[1].aggregator.transcript_verify(rng, &transcript).unwrap();
+
+        // A bad dealing fails the batch, and is then pinned down on its own.
+        shares[3].pvss_share.encs[3] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(nodes[1].aggregator.shares_verify_batch(rng, &shares).is_err());
+        let results = nodes[1].aggregator.receive_shares(rng, &shares);
+        assert!(matches!(results[3], Err(PVSSError::InvalidShareError(3, _))));
+        assert!(results.iter().enumerate().all(|(k, result)| k == 3 || result.is_ok()));
+        assert_eq!(nodes[1].aggregator.contributors(), n - 1);
+    }
+
+    #[test]
+    fn test_prepared_config() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+
+        // Pairing inputs are prepared once, and reused across verifications.
+        let aggregator = &mut nodes[0].aggregator;
+        let prepared = aggregator.prepared().unwrap();
+        aggregator.share_verify(rng, &share).unwrap();
+        assert!(Arc::ptr_eq(&prepared, &aggregator.prepared().unwrap()));
+        assert_eq!(prepared.keys.len(), n);
+
+        // A new encryption key invalidates them, and encryptions under the old key no
+        // longer verify.
+        let key = <E as PairingEngine>::G1Projective::rand(rng).into_affine();
+        aggregator.epoch_keys.insert((1, 0), key);
+        let fresh = aggregator.prepared().unwrap();
+        assert!(!Arc::ptr_eq(&prepared, &fresh));
+        assert_eq!(fresh.keys[&1].0, key);
+        assert!(aggregator.encryptions_verify(rng, &share.pvss_share).is_err());
+    }
+
+    #[test]
+    fn test_encryption_randomizers() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let transcript: PVSSTranscript<E, _> = nodes[0].aggregator.transcript.clone();
+        let verifier = &mut nodes[1].aggregator;
+
+        // A single corrupted encryption is caught, wherever it is.
+        for i in 0..n {
+            let mut bad = transcript.clone();
+            bad.pvss_share.encs[i] += <E as PairingEngine>::G1Projective::rand(rng);
+            assert!(matches!(verifier.transcript_verify(rng, &bad), Err(PVSSError::EncryptionCorrectnessError)));
+        }
+
+        // So are corruptions that cancel out in the sum of the encryptions, which
+        // would pass a check with a common coefficient, also in batches.
+        let offset = <E as PairingEngine>::G1Projective::rand(rng);
+        let mut bad = transcript.clone();
+        bad.pvss_share.encs[0] += offset;
+        bad.pvss_share.encs[3] -= offset;
+        assert!(matches!(verifier.transcript_verify(rng, &bad), Err(PVSSError::EncryptionCorrectnessError)));
+
+        verifier.batch_threshold = 1;
+        let results = verifier.verify_many(rng, &[transcript.clone(), bad, transcript]);
+        assert!(results[0].is_ok() && results[2].is_ok());
+        assert!(matches!(results[1], Err(PVSSError::EncryptionCorrectnessError)));
+    }
+
+    #[test]
+    fn test_malformed_shares() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let other = nodes[2].share(rng).unwrap();
+
+        let mut truncated = share.clone();
+        truncated.pvss_share.encs.pop();
+        let mut empty = share.clone();
+        empty.pvss_share.comms.clear();
+        empty.pvss_share.encs.clear();
+        let mut unknown = share.clone();
+        unknown.participant_id = n + 5;
+        let mut forged = share.clone();
+        forged.signature_on_decomp = other.signature_on_decomp;
+        let mut swapped = share.clone();
+        swapped.decomp_proof = other.decomp_proof;
+
+        // Malformed shares are rejected with the offending participant's id, and
+        // leave the transcript untouched.
+        let aggregator = &mut nodes[0].aggregator;
+        for (bad, id) in [(truncated, 1), (empty, 1), (unknown, n + 5), (forged, 1), (swapped, 1)].iter() {
+            match aggregator.receive_share(rng, bad) {
+                Err(PVSSError::InvalidShareError(offender, _)) => assert_eq!(offender, *id),
+                _ => panic!("malformed share of participant {} accepted", id),
+            }
+        }
+        assert!(aggregator.transcript.contributions.is_empty());
+
+        // Malformed transcripts are rejected as well.
+        let mut transcript = PVSSTranscript::from_share(t, n, &share);
+        transcript.pvss_share.comms.truncate(1);
+        assert!(matches!(aggregator.receive_transcript(rng, &transcript), Err(PVSSError::LengthMismatchError)));
+        let mut transcript = PVSSTranscript::from_share(t, n, &share);
+        transcript.pvss_share.comms.clear();
+        transcript.pvss_share.encs.clear();
+        assert!(aggregator.receive_transcript(rng, &transcript).is_err());
+        assert!(aggregator.transcript.contributions.is_empty());
+
+        aggregator.receive_share(rng, &share).unwrap();
+    }
+
+    #[test]
+    fn test_round_stats() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        nodes[0].aggregator.start_epoch(1).unwrap();
+        let mut bad = nodes[1].share(rng).unwrap();
+        bad.pvss_share.encs[1] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(matches!(nodes[0].aggregator.receive_share(rng, &bad), Err(PVSSError::InvalidShareError(1, _))));
+        for i in 1..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+
+        let transcript = nodes[0].aggregator.transcript.clone();
+        let transcripts = vec![transcript.clone(), transcript.clone(), transcript];
+        let verifier = &mut nodes[0].aggregator;
+        verifier.batch_threshold = 2;
+        assert!(verifier.verify_many(rng, &transcripts).iter().all(|r| r.is_ok()));
+        assert!(verifier.verify_many(rng, &transcripts[..1]).iter().all(|r| r.is_ok()));
+
+        let stats = verifier.round_stats();
+        assert_eq!(stats.epoch, 1);
+        assert_eq!((stats.shares_received, stats.shares_accepted), (n, n - 1));
+        assert_eq!(stats.verifications, n);
+        assert!(stats.average_verification_time().is_some());
+        assert_eq!((stats.batches, stats.batched_items, stats.individual_items), (1, 3, 1));
+        assert_eq!(stats.batch_success_ratio(), Some(1.0));
+        assert!(stats.batching_benefit().is_some());
+
+        // A new round tunes the threshold from these statistics and starts afresh.
+        verifier.start_epoch(2).unwrap();
+        assert_eq!(verifier.batch_threshold, stats.recommended_batch_threshold(2));
+        assert_eq!(verifier.round_stats(), RoundStats::new(2));
+    }
+
+    #[test]
+    fn test_skewed_weights() {
+        let rng = &mut thread_rng();
+        let (n, t) = (5, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        // A single dealer counted twice does not reach weight t+1.
+        let heavy = nodes[1].share(rng).unwrap();
+        nodes[0].aggregator.duplicates = DuplicatePolicy::AllowWeighted;
+        nodes[0].aggregator.receive_share(rng, &heavy).unwrap();
+        nodes[0].aggregator.receive_share(rng, &heavy).unwrap();
+        assert_eq!(nodes[0].aggregator.transcript.total_weight(), 2);
+        assert!(matches!(nodes[0].aggregator.finalize(), Err(PVSSError::InsufficientWeightError(2, 3))));
+
+        // One more dealer tips the balance, even though only two dealers contributed.
+        let light = nodes[2].share(rng).unwrap();
+        nodes[0].aggregator.receive_share(rng, &light).unwrap();
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        assert_eq!(transcript.contributions.len(), 2);
+        assert_eq!(transcript.describe().weights[&1], 2);
+
+        // The weighted transcript verifies elsewhere, but not with its weights tampered.
+        nodes[3].aggregator.transcript_verify(rng, &transcript).unwrap();
+        let mut tampered = transcript.clone();
+        tampered.contributions.get_mut(&1).unwrap().weight = 1;
+        assert!(nodes[3].aggregator.transcript_verify(rng, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let other = nodes[1].share(rng).unwrap();
+
+        // By default, a re-received share is ignored...
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.receive_share(rng, &share).unwrap();
+        aggregator.receive_share(rng, &share).unwrap();
+        assert_eq!(aggregator.transcript.total_weight(), 1);
+
+        // ...while a different dealing by the same dealer is not a copy.
+        assert!(aggregator.receive_share(rng, &other).is_err());
+
+        aggregator.duplicates = DuplicatePolicy::AllowWeighted;
+        aggregator.receive_share(rng, &share).unwrap();
+        assert_eq!(aggregator.transcript.describe().weights[&1], 2);
+    }
+
+    #[test]
+    fn test_transcript_digests() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let first = nodes[1].share(rng).unwrap();
+        let second = nodes[2].share(rng).unwrap();
+        let transcript: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &first)
+            .aggregate(&PVSSTranscript::from_share(t, n, &second))
+            .unwrap();
+
+        // Digests are canonical: equal objects hash equally, and any change shows.
+        assert_eq!(transcript.clone().digest(), transcript.digest());
+        assert_ne!(first.pvss_share.digest(), second.pvss_share.digest());
+        let mut tampered = transcript.clone();
+        tampered.pvss_share.encs[0] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert_ne!(tampered.pvss_share.digest(), transcript.pvss_share.digest());
+        assert_ne!(tampered.digest(), transcript.digest());
+
+        // Verified transcripts are memoized by digest, but tampered copies are not.
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.receive_transcript(rng, &transcript).unwrap();
+        assert!(aggregator.cache.contains(&transcript.digest()));
+        assert!(aggregator.receive_transcript(rng, &tampered).is_err());
+        assert!(!aggregator.cache.contains(&tampered.digest()));
+
+        let mut config = aggregator.config.clone();
+        config.degree += 1;
+        assert_ne!(config.digest(), aggregator.config.digest());
+    }
+
+    // Checks that an object hashes like an equal copy of it, decoded from its
+    // serialization, and like its canonical serialization.
+    fn check_digest<T: Hash + CanonicalSerialize + CanonicalDeserialize>(value: &T) {
+        let mut bytes = vec![];
+        value.serialize(&mut bytes).unwrap();
+        let copy = T::deserialize(&bytes[..]).unwrap();
+        assert_eq!(copy.digest(), value.digest());
+        assert_eq!(value.digest(), canonical_digest(value));
+    }
+
+    #[test]
+    fn test_hash_impls() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let transcript: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &share);
+
+        check_digest(&share);
+        check_digest(&share.pvss_share);
+        check_digest(&share.decomp_proof);
+        check_digest(&transcript);
+        assert_ne!(share.digest(), share.pvss_share.digest());
+
+        // Configs hash over their fields, so that equal configs built apart agree.
+        let config = &nodes[0].aggregator.config;
+        let rebuilt = Config {
+            srs: config.srs.clone(),
+            degree: config.degree,
+            num_participants: config.num_participants,
+            layout: config.layout,
+            points: config.points,
+        };
+        assert_eq!(rebuilt.digest(), config.digest());
+        assert_eq!(nodes[1].aggregator.config.digest(), config.digest());
+        let mut other_layout = rebuilt.clone();
+        other_layout.layout = ShareLayout::Sparse;
+        assert_ne!(other_layout.digest(), config.digest());
+    }
+
+    #[test]
+    fn test_aggregate_assign() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let first: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &nodes[1].share(rng).unwrap());
+        let second = PVSSTranscript::from_share(t, n, &nodes[2].share(rng).unwrap());
+
+        // In-place aggregation agrees with aggregate, also when a dealing is counted twice.
+        let mut assigned = first.clone();
+        assigned.aggregate_assign(&second).unwrap();
+        let aggregated = first.aggregate(&second).unwrap();
+        assert_eq!(assigned.digest(), aggregated.digest());
+        assigned.aggregate_assign(&first).unwrap();
+        assert_eq!(assigned.digest(), aggregated.aggregate(&first).unwrap().digest());
+        assert_eq!(assigned.describe().weights[&1], 2);
+
+        // Failed aggregations leave the transcript unchanged.
+        let digest = assigned.digest();
+        assert!(matches!(assigned.aggregate_assign_capped(&first, 2), Err(PVSSError::WeightOverflow(2))));
+        let mut forged = second.clone();
+        let proof = forged.contributions[&2].decomp_proof;
+        forged.contributions.get_mut(&2).unwrap().decomp_proof = DecompProof::new(proof.proof(), first.contributions[&1].decomp_proof.gs(), proof.binding());
+        assert!(matches!(assigned.aggregate_assign(&forged), Err(PVSSError::TranscriptDifferentCommitments)));
+        assert_eq!(assigned.digest(), digest);
+    }
+
+    #[test]
+    fn test_cached_contributions() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (1..4).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[3].aggregator.receive_share(rng, share).unwrap();
+        }
+        let transcript = nodes[3].aggregator.finalize().unwrap().clone();
+        let decomposition = |trace: VerificationTrace| {
+            trace.checks.into_iter().find(|check| check.name == "decomposition").unwrap().inputs
+        };
+
+        // Only the contribution not received as a share has its proof checked.
+        let verifier = &mut nodes[0].aggregator;
+        verifier.tracing = true;
+        for share in shares[..2].iter() {
+            verifier.receive_share(rng, share).unwrap();
+        }
+        verifier.transcript_verify(rng, &transcript).unwrap();
+        assert_eq!(decomposition(verifier.last_trace().unwrap()), vec![shares[2].decomp_proof.digest()]);
+
+        // A cached proof under another signature is no longer the cached contribution.
+        let mut forged = transcript.clone();
+        let signature = forged.contributions[&2].signature_on_decomp;
+        forged.contributions.get_mut(&1).unwrap().signature_on_decomp = signature;
+        assert!(verifier.transcript_verify(rng, &forged).is_err());
+
+        // Once the transcript is received, all of its contributions are known.
+        verifier.receive_transcript(rng, &transcript).unwrap();
+        verifier.transcript_verify(rng, &transcript).unwrap();
+        assert!(decomposition(verifier.last_trace().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_verify_contributions() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        for i in 0..3 {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let aggregator = &nodes[0].aggregator;
+        let (config, scheme_sig, participants) = (&aggregator.config, &aggregator.scheme_sig, &aggregator.participants);
+        let transcript = aggregator.transcript.clone();
+        transcript.verify_contributions(rng, config, scheme_sig, participants).unwrap();
+        for (id, contribution) in transcript.contributions.iter() {
+            contribution.verify(config, scheme_sig, &participants[id].public_key_sig).unwrap();
+        }
+
+        // Bad signatures and bad proofs are told apart.
+        let mut forged = transcript.clone();
+        let signature = forged.contributions[&2].signature_on_decomp;
+        forged.contributions.get_mut(&1).unwrap().signature_on_decomp = signature;
+        assert!(matches!(forged.verify_contributions(rng, config, scheme_sig, participants), Err(PVSSError::SignatureError(_))));
+        assert!(matches!(forged.contributions[&1].verify(config, scheme_sig, &participants[&1].public_key_sig), Err(PVSSError::SignatureError(_))));
+
+        let mut forged = transcript.clone();
+        let proof = forged.contributions[&1].decomp_proof;
+        let gs = transcript.contributions[&2].decomp_proof.gs();
+        forged.contributions.get_mut(&1).unwrap().decomp_proof = DecompProof::new(proof.proof(), gs, proof.binding());
+        assert!(matches!(forged.verify_contributions(rng, config, scheme_sig, participants), Err(PVSSError::DecompProofVerificationError)));
+        assert!(matches!(forged.contributions[&1].verify(config, scheme_sig, &participants[&1].public_key_sig), Err(PVSSError::DecompProofVerificationError)));
+    }
+
+    #[test]
+    fn test_transcript_checks() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        for i in 0..3 {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.transcript.clone();
+
+        // A fresh verifier has no contribution cached, so that every check runs.
+        let verifier = &nodes[1].aggregator;
+        verifier.transcript_verify(rng, &transcript).unwrap();
+
+        let mut other_config = transcript.clone();
+        other_config.degree = t + 1;
+        assert!(matches!(verifier.transcript_verify(rng, &other_config), Err(PVSSError::TranscriptDifferentConfig(..))));
+
+        // A decomposition proof which does not prove knowledge of its gs.
+        let mut bad_proof = transcript.clone();
+        let proof = bad_proof.contributions[&1].decomp_proof;
+        let gs = transcript.contributions[&2].decomp_proof.gs();
+        bad_proof.contributions.get_mut(&1).unwrap().decomp_proof = DecompProof::new(proof.proof(), gs, proof.binding());
+        assert!(matches!(verifier.transcript_verify(rng, &bad_proof), Err(PVSSError::DecompProofVerificationError)));
+
+        // Commitments still of degree t, but no longer interpolating to the sum of
+        // the contributors' gs.
+        let mut bad_gs = transcript.clone();
+        let shift = <E as PairingEngine>::G2Projective::prime_subgroup_generator();
+        for comm in bad_gs.pvss_share.comms.iter_mut() {
+            *comm += &shift;
+        }
+        assert!(matches!(verifier.transcript_verify(rng, &bad_gs), Err(PVSSError::GSCheckError)));
+
+        // An encryption which does not match its commitment.
+        let mut bad_encryption = transcript.clone();
+        bad_encryption.pvss_share.encs[2] += &<E as PairingEngine>::G1Projective::prime_subgroup_generator();
+        assert!(matches!(verifier.transcript_verify(rng, &bad_encryption), Err(PVSSError::EncryptionCorrectnessError)));
+    }
+
+    #[test]
+    fn test_weight_limit() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+
+        // Merging a transcript into itself doubles its weights, until they overflow.
+        let mut doubled: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &share);
+        for _ in 0..63 {
+            doubled = doubled.aggregate(&doubled).unwrap();
+        }
+        assert_eq!(doubled.total_weight(), 1 << 63);
+        assert!(matches!(doubled.aggregate(&doubled), Err(PVSSError::WeightOverflow(u64::MAX))));
+
+        // Thousands of merges stay exact up to the cap, and fail beyond it.
+        let single = PVSSTranscript::from_share(t, n, &share);
+        let mut merged = single.clone();
+        for _ in 1..5000 {
+            merged = merged.aggregate_capped(&single, 5000).unwrap();
+        }
+        assert_eq!(merged.total_weight(), 5000);
+        assert!(matches!(merged.aggregate_capped(&single, 5000), Err(PVSSError::WeightOverflow(5000))));
+
+        // Aggregators either reject or skip shares beyond their cap.
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.weight_limit = WeightLimit { max_weight: 2, policy: WeightPolicy::Reject };
+        aggregator.duplicates = DuplicatePolicy::AllowWeighted;
+        aggregator.receive_share(rng, &share).unwrap();
+        aggregator.receive_share(rng, &share).unwrap();
+        assert!(matches!(aggregator.receive_share(rng, &share), Err(PVSSError::WeightOverflow(2))));
+
+        aggregator.weight_limit.policy = WeightPolicy::Saturate;
+        aggregator.receive_share(rng, &share).unwrap();
+        assert_eq!(aggregator.transcript.total_weight(), 2);
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+
+        // A transcript of dealer 3 only, and one of dealers 0, 1 and 2.
+        let small: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &shares[3]);
+        let mut large = PVSSTranscript::from_share(t, n, &shares[0]);
+        for share in &shares[1..3] {
+            large = large.aggregate(&PVSSTranscript::from_share(t, n, share)).unwrap();
+        }
+        let budget = large.serialized_size();
+
+        // Transcripts that could never fit are refused before being verified.
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.memory = Some(MemoryAccountant::new(budget - 1, BudgetPolicy::Reject));
+        assert!(matches!(aggregator.receive_transcript(rng, &large), Err(PVSSError::MemoryBudgetExceeded(size, b)) if size == budget && b == budget - 1));
+        assert_eq!(aggregator.contributors(), 0);
+
+        // Under Reject, an aggregate beyond the budget leaves the current one untouched.
+        let aggregator = &mut nodes[1].aggregator;
+        aggregator.memory = Some(MemoryAccountant::new(budget, BudgetPolicy::Reject));
+        aggregator.receive_transcript(rng, &small).unwrap();
+        assert_eq!(aggregator.memory.as_ref().unwrap().used(), small.serialized_size());
+        assert!(matches!(aggregator.receive_transcript(rng, &large), Err(PVSSError::MemoryBudgetExceeded(size, b)) if size > budget && b == budget));
+        assert_eq!(aggregator.transcript.contributions.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(aggregator.metadata.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(aggregator.memory.as_ref().unwrap().used(), aggregator.transcript.serialized_size());
+
+        // Under Evict, the received transcript replaces ours as it covers more dealers,
+        // and the aggregator's bookkeeping follows.
+        let aggregator = &mut nodes[2].aggregator;
+        aggregator.memory = Some(MemoryAccountant::new(budget, BudgetPolicy::Evict));
+        aggregator.receive_transcript(rng, &small).unwrap();
+        aggregator.receive_transcript(rng, &large).unwrap();
+        assert_eq!(aggregator.transcript.contributions.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(aggregator.metadata.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(aggregator.memory.as_ref().unwrap().used(), aggregator.transcript.serialized_size());
+        assert!(aggregator.memory.as_ref().unwrap().used() <= budget);
+        let transcript = aggregator.transcript.clone();
+        nodes[3].aggregator.transcript_verify(rng, &transcript).unwrap();
+    }
+
+    #[test]
+    fn test_contribution_info() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+
+        let before = SystemTime::now();
+        let aggregator = &mut nodes[0].aggregator;
+        aggregator.receive_share_from(rng, &shares[1], Some(3)).unwrap();
+        aggregator.receive_share(rng, &shares[2]).unwrap();
+        let after = SystemTime::now();
+
+        let info = aggregator.contribution_info(1).unwrap().clone();
+        assert_eq!(info.source, Some(3));
+        assert!(before <= info.received_at && info.received_at <= after);
+        assert!(info.verification_time > Duration::from_secs(0));
+        assert_eq!(aggregator.contribution_info(2).unwrap().source, None);
+
+        // Dealers we have no contribution of have no information either, including
+        // those whose share we rejected.
+        assert!(aggregator.contribution_info(0).is_none());
+        assert!(aggregator.contribution_info(n).is_none());
+        let mut bad = shares[3].clone();
+        bad.signature_on_decomp = shares[0].signature_on_decomp;
+        assert!(aggregator.receive_share_from(rng, &bad, Some(2)).is_err());
+        assert!(aggregator.contribution_info(3).is_none());
+
+        // Contributions already known keep their original information when they
+        // arrive again within a transcript.
+        let transcript: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &shares[1])
+            .aggregate(&PVSSTranscript::from_share(t, n, &shares[3]))
+            .unwrap();
+        aggregator.receive_transcript_from(rng, &transcript, Some(2)).unwrap();
+        assert_eq!(aggregator.contribution_info(1), Some(&info));
+        assert_eq!(aggregator.contribution_info(3).unwrap().source, Some(2));
+        assert_eq!(aggregator.metadata.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_committee_ids() {
+        let rng = &mut thread_rng();
+        let (n, t) = (3, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+
+        // Without a committee, ids are the protocol indices.
+        let aggregator = &mut nodes[0].aggregator;
+        assert_eq!(aggregator.index_of(ParticipantId(2)).unwrap(), 2);
+        assert!(matches!(aggregator.index_of(ParticipantId(3)), Err(PVSSError::UnknownParticipantIdError(3))));
+
+        // With one, contributions are reported under the dealers' ids.
+        let committee = Committee::new::<E>(&[ParticipantId(42), ParticipantId(7), ParticipantId(1000)]).unwrap();
+        aggregator.committee = Some(committee.clone());
+        aggregator.receive_share(rng, &shares[0]).unwrap();
+        aggregator.receive_share(rng, &shares[2]).unwrap();
+        assert_eq!(aggregator.index_of(ParticipantId(42)).unwrap(), 1);
+        assert_eq!(aggregator.contributor_ids().unwrap(), vec![ParticipantId(7), ParticipantId(1000)]);
+        assert_eq!(aggregator.transcript.contributor_ids(&committee).unwrap(), aggregator.contributor_ids().unwrap());
+        assert!(matches!(aggregator.index_of(ParticipantId(2)), Err(PVSSError::UnknownParticipantIdError(2))));
+
+        // A committee of the wrong size is refused.
+        aggregator.committee = Some(Committee::new::<E>(&[ParticipantId(7)]).unwrap());
+        assert!(matches!(aggregator.index_of(ParticipantId(7)), Err(PVSSError::CommitteeSizeError(1, 3))));
+    }
+
+    #[test]
+    fn test_verification_trace() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+
+        // Nothing is collected unless tracing is enabled.
+        nodes[0].aggregator.share_verify(rng, &share).unwrap();
+        assert!(nodes[0].aggregator.last_trace().is_none());
+
+        let verifier = &mut nodes[2].aggregator;
+        verifier.tracing = true;
+        verifier.share_verify(rng, &share).unwrap();
+        let trace = verifier.last_trace().unwrap();
+        assert_eq!(trace.operation, "share_verify");
+        assert_eq!(trace.names(), vec!["signature", "encryption", "subgroup", "coding", "decomposition"]);
+        assert!(trace.passed());
+        assert_eq!(trace.checks[4].inputs[0], share.decomp_proof.digest());
+
+        // Failing checks are recorded, and end the trace.
+        let transcript = PVSSTranscript::from_share(t, n, &share);
+        let mut bad: PVSSTranscript<E, _> = transcript.clone();
+        bad.pvss_share.encs[0] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(verifier.transcript_verify(rng, &bad).is_err());
+        let trace = verifier.last_trace().unwrap();
+        assert_eq!(trace.names(), vec!["config", "subgroup", "weights", "coding", "decomposition", "signatures", "interpolation", "encryptions"]);
+        assert!(!trace.checks.last().unwrap().passed());
+        assert!(trace.checks[..7].iter().all(|check| check.passed()));
+    }
+
+    #[test]
+    fn test_stage_order() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let other = nodes[2].share(rng).unwrap();
+
+        // A share failing the encryption stage only, and one failing the signature stage too.
+        let mut bad_encryption = share.clone();
+        bad_encryption.pvss_share.encs[1] += &<E as PairingEngine>::G1Projective::prime_subgroup_generator();
+        let mut bad_both = bad_encryption.clone();
+        bad_both.signature_on_decomp = other.signature_on_decomp;
+
+        // The first failing stage in the configured order ends the verification.
+        let verifier = &mut nodes[0].aggregator;
+        verifier.tracing = true;
+        verifier.stage_order = vec![VerificationStage::Encryption, VerificationStage::Signature];
+        assert!(matches!(verifier.share_verify(rng, &bad_both), Err(PVSSError::EncryptionCorrectnessError)));
+        assert_eq!(verifier.last_trace().unwrap().names(), vec!["encryption"]);
+
+        verifier.stage_order = vec![VerificationStage::Signature, VerificationStage::Encryption];
+        assert!(matches!(verifier.share_verify(rng, &bad_both), Err(PVSSError::SignatureError(_))));
+        assert_eq!(verifier.last_trace().unwrap().names(), vec!["signature"]);
+
+        // Stages missing from the order still run, after the configured ones.
+        verifier.stage_order = vec![VerificationStage::Core];
+        assert!(matches!(verifier.share_verify(rng, &bad_encryption), Err(PVSSError::EncryptionCorrectnessError)));
+        assert_eq!(verifier.last_trace().unwrap().names(), vec!["subgroup", "coding", "decomposition", "signature", "encryption"]);
+
+        verifier.stage_order = vec![];
+        assert!(matches!(verifier.share_verify(rng, &bad_encryption), Err(PVSSError::EncryptionCorrectnessError)));
+        assert_eq!(verifier.last_trace().unwrap().names(), vec!["signature", "encryption"]);
+
+        verifier.stage_order = vec![VerificationStage::Core];
+        verifier.share_verify(rng, &share).unwrap();
+        assert_eq!(verifier.last_trace().unwrap().names(), vec!["subgroup", "coding", "decomposition", "signature", "encryption"]);
+        assert!(verifier.last_trace().unwrap().passed());
+    }
+
+    #[test]
+    fn test_strictness() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let transcript = PVSSTranscript::from_share(t, n, &share);
+
+        // Each level runs the checks of the levels below it.
+        let verifier = &mut nodes[2].aggregator;
+        verifier.tracing = true;
+        let mut counts = vec![];
+        for strictness in [Strictness::Light, Strictness::Standard, Strictness::Paranoid].iter() {
+            verifier.strictness = *strictness;
+            verifier.transcript_verify(rng, &transcript).unwrap();
+            counts.push(verifier.last_trace().unwrap().names().len());
+        }
+        assert_eq!(counts, vec![6, 8, 10]);
+
+        // The weight audit rejects a dealing counted under two dealers' ids.
+        let mut duplicated = transcript.clone();
+        let contribution = duplicated.contributions.get(&1).unwrap().clone();
+        duplicated.contributions.insert(3, contribution);
+        verifier.strictness = Strictness::Standard;
+        assert!(matches!(verifier.transcript_verify(rng, &duplicated), Err(PVSSError::DuplicateContributionError(1, 3))));
+    }
+
+    #[test]
+    fn test_verify_with_report() {
+        let rng = &mut thread_rng();
+        let (n, t) = (5, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        // Two dealers, one of them counted twice: valid, but with warnings.
+        let first = nodes[1].share(rng).unwrap();
+        let second = nodes[2].share(rng).unwrap();
+        nodes[0].aggregator.duplicates = DuplicatePolicy::AllowWeighted;
+        nodes[0].aggregator.receive_share(rng, &first).unwrap();
+        nodes[0].aggregator.receive_share(rng, &first).unwrap();
+        nodes[0].aggregator.receive_share(rng, &second).unwrap();
+        let transcript = nodes[0].aggregator.transcript.clone();
+
+        let outcome = nodes[3].aggregator.transcript_verify_with_report(rng, &transcript);
+        assert!(outcome.ok && outcome.error.is_none());
+        assert!(outcome.warnings.contains(&Warning::RepeatedContribution { dealer: 1, weight: 2 }));
+        assert!(outcome.warnings.contains(&Warning::NearThreshold { contributors: 2, required: 2 }));
+
+        // Hard failures are still reported as such.
+        let mut bad: PVSSTranscript<E, _> = transcript.clone();
+        bad.pvss_share.encs[0] = <E as PairingEngine>::G1Projective::rand(rng);
+        let outcome = nodes[3].aggregator.transcript_verify_with_report(rng, &bad);
+        assert!(!outcome.ok && outcome.error.is_some());
+
+        let outcome = nodes[0].aggregator.share_verify_with_report(rng, &second);
+        assert!(outcome.ok);
+        assert_eq!(outcome.warnings, vec![Warning::AlreadyAggregated { dealer: 2 }]);
+    }
+
+    #[test]
+    fn test_sparse_shares() {
+        let rng = &mut thread_rng();
+        let (n, t) = (8, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        for node in nodes.iter_mut() {
+            node.aggregator.config.layout = ShareLayout::Sparse;
+        }
+        let recipients = [1, 3, 4, 6];
+
+        // Two dealers deal to the same shard.
+        let mut shares = vec![];
+        let mut proofs = vec![];
+        for dealer in 0..2 {
+            let (share, secrets) = nodes[dealer].share_pvss_sparse(rng, &recipients).unwrap();
+            let proof = Decomp::<E>::generate(rng, &nodes[dealer].aggregator.config, &secrets.p_0).unwrap();
+            nodes[5].aggregator.sparse_share_verify(rng, &proof, &share).unwrap();
+            shares.push(share);
+            proofs.push(proof);
+        }
+        assert_eq!(shares[0].recipients(), recipients.to_vec());
+
+        let aggregated = shares[0].aggregate(&shares[1]).unwrap();
+        nodes[5].aggregator.sparse_aggregate_verify(rng, &proofs, &aggregated).unwrap();
+        assert!(nodes[5].aggregator.sparse_aggregate_verify(rng, &proofs[..1], &aggregated).is_err());
+
+        // Tampered commitments fail the coding check; tampered encryptions, the pairing check.
+        let mut bad = shares[0].clone();
+        bad.entries.get_mut(&3).unwrap().1 = <E as PairingEngine>::G2Projective::rand(rng);
+        assert!(matches!(nodes[5].aggregator.sparse_share_verify(rng, &proofs[0], &bad), Err(PVSSError::DualCodeError)));
+        let mut bad = shares[0].clone();
+        bad.entries.get_mut(&3).unwrap().0 = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(matches!(nodes[5].aggregator.sparse_share_verify(rng, &proofs[0], &bad), Err(PVSSError::EncryptionCorrectnessError)));
+
+        // Shares to different shards do not aggregate, nor do dense configs accept sparse shares.
+        let (other, _) = nodes[2].share_pvss_sparse(rng, &[0, 2, 5]).unwrap();
+        assert!(matches!(shares[0].aggregate(&other), Err(PVSSError::MismatchedRecipientsError)));
+        nodes[5].aggregator.config.layout = ShareLayout::Dense;
+        assert!(matches!(nodes[5].aggregator.sparse_share_verify(rng, &proofs[0], &shares[0]), Err(PVSSError::ShareLayoutError)));
+    }
+
+    #[test]
+    fn test_spot_check_escape_probability() {
+        assert_eq!(spot_check_escape_probability(10, 1, 10), 0.0);
+        assert_eq!(spot_check_escape_probability(10, 0, 5), 1.0);
+        assert!((spot_check_escape_probability(10, 1, 3) - 0.7).abs() < 1e-9);
+    }
 }
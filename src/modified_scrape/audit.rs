@@ -0,0 +1,169 @@
+use super::{
+    aggregator::PVSSAggregator,
+    config::Config,
+    dealing::encrypt_evals,
+    decomp::{Decomp, DealingArtifacts},
+    errors::PVSSError,
+    poly::Polynomial,
+    share::PVSSAugmentedShare,
+};
+use crate::secrets::SecretPolynomial;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{digest_bytes, Digest, Hash, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_poly::UVPolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use rand::{CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use zeroize::Zeroize;
+
+/* Auditable dealings. A dealer may commit to a random seed ahead of an epoch and
+   derive all of its dealing randomness (the polynomial's coefficients and the
+   decomposition proof's nonce) deterministically from it. Once the epoch is over
+   (and its secret public anyway), the dealer can open the seed to an auditor, who
+   re-derives the dealing and checks it matches the share that was broadcast,
+   i.e., that the dealing was generated honestly rather than chosen adversarially.
+   Opening a seed reveals the dealt secret, so seeds must never be opened before
+   the epoch's beacon has been released, nor reused across epochs.
+*/
+
+const SEED_COMMITMENT_DOMAIN: &[u8] = b"OPTRAND-SEED-COMMIT";
+const SEED_STREAM_DOMAIN: &[u8] = b"OPTRAND-SEED-STREAM";
+
+// A dealing derived from a seed: the secret polynomial and the artifacts derived from it.
+pub type SeededDealing<E> = (SecretPolynomial<Scalar<E>>, DealingArtifacts<E>);
+
+// Struct SeedCommitment models the binding commitment to a dealer's seed for an epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeedCommitment(pub Digest);
+
+// Struct DealingSeed models the opening of a seed commitment.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DealingSeed {
+    pub dealer: usize,    // id of the dealer the seed belongs to
+    pub epoch: u64,       // epoch the seed is used in
+    pub seed: Digest,     // the secret seed itself
+}
+
+// Seeds are wiped from memory when dropped.
+impl Drop for DealingSeed {
+    fn drop(&mut self) {
+        self.seed.0.zeroize();
+    }
+}
+
+impl DealingSeed {
+
+    // Function for sampling a fresh seed for the given dealer and epoch.
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R, dealer: usize, epoch: u64) -> Self {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        Self { dealer, epoch, seed: Digest(seed) }
+    }
+
+    // Utility method for hashing the seed, bound to its dealer and epoch, under a domain.
+    fn hash(&self, domain: &[u8]) -> Digest {
+        let mut bytes = domain.to_vec();
+        bytes.extend_from_slice(&(self.dealer as u64).to_be_bytes());
+        bytes.extend_from_slice(&self.epoch.to_be_bytes());
+        bytes.extend_from_slice(&self.seed.0);
+        digest_bytes(&bytes)
+    }
+
+    // Method returning the commitment to the seed.
+    pub fn commitment(&self) -> SeedCommitment {
+        SeedCommitment(self.hash(SEED_COMMITMENT_DOMAIN))
+    }
+
+    // Method returning the randomness stream all dealing randomness is drawn from.
+    pub fn rng(&self) -> ChaChaRng {
+        ChaChaRng::from_seed(self.hash(SEED_STREAM_DOMAIN).0)
+    }
+
+    // Method for deterministically deriving the dealing's polynomial and artifacts
+    // from the seed, exactly as Node::share does when a seed has been committed.
+    pub fn deal<E: PairingEngine>(
+        &self,
+        config: &Config<E>,
+    ) -> Result<SeededDealing<E>, PVSSError<E>> {
+        let rng = &mut self.rng();
+        let poly = SecretPolynomial::new(Polynomial::<E>::rand(config.degree, rng));
+        let artifacts = Decomp::<E>::from_polynomial(rng, config, poly.expose())?;
+        Ok((poly, artifacts))
+    }
+
+    // Method for auditing a share against the opening of the dealer's seed commitment.
+    // The seed must open the commitment, and re-deriving the dealing from it must
+    // reproduce the share's commitments, encryptions and decomposition proof.
+    pub fn audit<E, SSIG>(
+        &self,
+        aggregator: &PVSSAggregator<E, SSIG>,
+        commitment: &SeedCommitment,
+        share: &PVSSAugmentedShare<E, SSIG>,
+    ) -> Result<(), PVSSError<E>>
+    where
+        E: PairingEngine,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        if self.commitment() != *commitment {
+            return Err(PVSSError::SeedCommitmentError(self.dealer));
+        }
+        if share.participant_id != self.dealer {
+            return Err(PVSSError::SeedAuditError(share.participant_id));
+        }
+
+        let config = &aggregator.config;
+        let (_, artifacts) = self.deal(config)?;
+        let pks = (0..config.num_participants)
+            .map(|j| aggregator.encryption_key(j))
+            .collect::<Result<Vec<_>, _>>()?;
+        let encs = encrypt_evals::<E>(&pks, &artifacts.evals)?;
+
+        if artifacts.comms != share.pvss_share.comms
+            || encs != share.pvss_share.encs
+            || artifacts.decomp_proof.digest() != share.decomp_proof.digest()
+        {
+            return Err(PVSSError::SeedAuditError(self.dealer));
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{errors::PVSSError, tests::setup_nodes};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_seed_audit() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let commitment = nodes[0].dealer.commit_seed(rng, 7);
+        let share = nodes[0].share(rng).unwrap();
+        nodes[1].aggregator.share_verify(rng, &share).unwrap();
+
+        // The opened seed reproduces the dealing.
+        let seed = nodes[0].dealer.open_seed().unwrap();
+        seed.audit(&nodes[2].aggregator, &commitment, &share).unwrap();
+
+        // A dealing not derived from the seed is caught.
+        let other = nodes[1].share(rng).unwrap();
+        let mut forged = other.clone();
+        forged.participant_id = 0;
+        assert!(matches!(seed.audit(&nodes[2].aggregator, &commitment, &forged), Err(PVSSError::SeedAuditError(0))));
+
+        // So is a seed that does not open the commitment.
+        let fresh = nodes[0].dealer.commit_seed(rng, 7);
+        let reopened = nodes[0].dealer.open_seed().unwrap();
+        assert!(matches!(reopened.audit(&nodes[2].aggregator, &commitment, &share), Err(PVSSError::SeedCommitmentError(0))));
+        assert_ne!(fresh, commitment);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::Digest;
+
+use ark_std::collections::BTreeSet;
+
+/* Struct VerificationCache memoizes the digests of shares (and transcripts) that
+   were already fully verified within the current round (i.e., epoch), either by
+   this node or because a quorum certificate attests to them. Re-received copies of
+   such shares skip the expensive checks. The cache is bound to an epoch, to a digest
+   of the config and to a digest of the roster (i.e., of the participants and their
+   keys), and is invalidated whenever any of them changes.
+*/
+
+#[derive(Clone, Debug, Default)]
+pub struct VerificationCache {
+    epoch: u64,                    // the round the cached digests belong to
+    config_digest: Digest,         // digest of the config the digests were verified against
+    roster_digest: Digest,         // digest of the roster the digests were verified against
+    verified: BTreeSet<Digest>,    // digests of fully verified shares
+}
+
+impl VerificationCache {
+
+    // Function for creating an empty cache for the given epoch, config digest and
+    // roster digest.
+    pub fn new(epoch: u64, config_digest: Digest, roster_digest: Digest) -> Self {
+        Self {
+            epoch,
+            config_digest,
+            roster_digest,
+            verified: BTreeSet::new(),
+        }
+    }
+
+    // Method returning the epoch the cache currently refers to.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    // Method returning the number of memoized digests.
+    pub fn len(&self) -> usize {
+        self.verified.len()
+    }
+
+    // Method checking whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.verified.is_empty()
+    }
+
+    // Method checking whether a digest was already verified in this round.
+    pub fn contains(&self, digest: &Digest) -> bool {
+        self.verified.contains(digest)
+    }
+
+    // Method checking whether the cache was built for the config and roster with the
+    // given digests, i.e., whether its contents may be trusted without refreshing it first.
+    pub fn is_bound_to(&self, config_digest: &Digest, roster_digest: &Digest) -> bool {
+        self.config_digest == *config_digest && self.roster_digest == *roster_digest
+    }
+
+    // Method for recording that the share with the given digest has been fully
+    // verified, either locally or as attested by a quorum certificate.
+    pub fn mark_verified(&mut self, digest: Digest) {
+        self.verified.insert(digest);
+    }
+
+    // Method for moving the cache to a new epoch, config and/or roster. Any memoized
+    // digests are dropped if any of them differs from what the cache was built for.
+    // Returns true if the cache was invalidated.
+    pub fn refresh(&mut self, epoch: u64, config_digest: Digest, roster_digest: Digest) -> bool {
+        if self.epoch == epoch && self.is_bound_to(&config_digest, &roster_digest) {
+            return false;
+        }
+
+        self.epoch = epoch;
+        self.config_digest = config_digest;
+        self.roster_digest = roster_digest;
+        self.verified.clear();
+        true
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::cache::VerificationCache;
+    use crate::Digest;
+
+    #[test]
+    fn test_refresh_invalidates() {
+        let mut cache = VerificationCache::new(0, Digest([0u8; 32]), Digest([0u8; 32]));
+        cache.mark_verified(Digest([1u8; 32]));
+
+        // same epoch, config and roster: digests are kept
+        assert!(!cache.refresh(0, Digest([0u8; 32]), Digest([0u8; 32])));
+        assert!(cache.contains(&Digest([1u8; 32])));
+
+        // new epoch: digests are dropped
+        assert!(cache.refresh(1, Digest([0u8; 32]), Digest([0u8; 32])));
+        assert!(cache.is_empty());
+
+        // new config: digests are dropped
+        cache.mark_verified(Digest([1u8; 32]));
+        assert!(cache.refresh(1, Digest([2u8; 32]), Digest([0u8; 32])));
+        assert!(!cache.contains(&Digest([1u8; 32])));
+
+        // new roster: digests are dropped
+        cache.mark_verified(Digest([1u8; 32]));
+        assert!(!cache.is_bound_to(&Digest([2u8; 32]), &Digest([3u8; 32])));
+        assert!(cache.refresh(1, Digest([2u8; 32]), Digest([3u8; 32])));
+        assert!(!cache.contains(&Digest([1u8; 32])));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::modified_scrape::config::Config;
+use crate::modified_scrape::errors::PVSSError;
+use crate::modified_scrape::finalized::FinalizedTranscript;
+use crate::modified_scrape::share::PVSSTranscript;
+use crate::signature::{scheme::BatchVerifiableSignatureScheme, utils::errors::SignatureError};
+use crate::{Digest, Hash, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+
+/* Struct Certificate is a compact, detached proof that a transcript was accepted:
+   the signatures of t+1 signers on the epoch and the transcript's digest, without
+   the transcript itself. Downstream consensus can carry certificates in place of
+   FinalizedTranscripts, and check that one certifies a given transcript by its
+   digest alone.
+
+   The signature scheme is independent of the one dealers sign with, so that
+   certificates may be signed with the keys the consensus layer already holds
+   (typically Ed25519, see EdDSASignature), whose signatures batch verify cheaply.
+*/
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct Certificate<S: BatchVerifiableSignatureScheme> {
+    pub epoch: u64,                                   // the epoch of the certified transcript
+    pub digest: Digest,                               // the digest of the certified transcript
+    pub signatures: BTreeMap<usize, S::Signature>,    // signers' signatures on message()
+}
+
+impl<S: BatchVerifiableSignatureScheme> Certificate<S> {
+
+    // Function for creating a (yet unsigned) certificate for the given transcript.
+    pub fn new<E, SSIG>(epoch: u64, transcript: &PVSSTranscript<E, SSIG>) -> Self
+    where
+        E: PairingEngine,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        Self {
+            epoch,
+            digest: transcript.digest(),
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    // Method returning the message signers sign: the epoch, followed by the digest
+    // of the transcript (as in FinalizedTranscript::message).
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = self.epoch.to_be_bytes().to_vec();
+        message.extend_from_slice(&self.digest.0);
+        message
+    }
+
+    // Method returning whether this is a certificate for the given transcript.
+    pub fn certifies<E, SSIG>(&self, transcript: &PVSSTranscript<E, SSIG>) -> bool
+    where
+        E: PairingEngine,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        self.digest == transcript.digest()
+    }
+
+    // Method for signing the certificate as signer id.
+    pub fn sign<R: Rng + CryptoRng>(&mut self, rng: &mut R, scheme: &S, id: usize, sk: &S::Secret) -> Result<(), SignatureError> {
+        let signature = scheme.sign(rng, sk, &self.message())?;
+        self.signatures.insert(id, signature);
+        Ok(())
+    }
+
+    // Method for adding a signature received from signer id.
+    pub fn add_signature(&mut self, id: usize, signature: S::Signature) {
+        self.signatures.insert(id, signature);
+    }
+
+    // Method for verifying the certificate: there must be signatures by t+1 of the
+    // given signers (t being the degree of config), all of which are valid.
+    pub fn verify<E: PairingEngine, R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        config: &Config<E>,
+        scheme: &S,
+        public_keys: &BTreeMap<usize, S::PublicKey>,
+    ) -> Result<(), PVSSError<E>> {
+        let quorum = config.degree + 1;
+        if self.signatures.len() < quorum {
+            return Err(PVSSError::InsufficientSignaturesError(self.signatures.len(), quorum));
+        }
+
+        let keys = self
+            .signatures
+            .keys()
+            .map(|id| public_keys.get(id).ok_or(PVSSError::<E>::InvalidParticipantId(*id)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let message = self.message();
+        scheme.batch_verify(
+            rng,
+            &keys,
+            &vec![message.as_slice(); keys.len()],
+            &self.signatures.values().collect::<Vec<_>>(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl<E, SSIG> FinalizedTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method detaching the certification of the transcript from the transcript itself.
+    pub fn certificate(&self) -> Certificate<SSIG> {
+        Certificate {
+            epoch: self.epoch,
+            digest: self.transcript.digest(),
+            signatures: self.signatures.clone(),
+        }
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::modified_scrape::{
+        certificate::Certificate,
+        errors::PVSSError,
+        finalized::FinalizedTranscript,
+        tests::{setup_nodes, SSIG},
+    };
+
+    use ark_std::collections::BTreeMap;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_certificate() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, sks) = setup_nodes(n, t);
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        let config = &nodes[0].aggregator.config;
+        let scheme_sig = &nodes[0].aggregator.scheme_sig;
+        let dealer_keys = nodes[0].aggregator.participants.iter().map(|(i, p)| (*i, p.public_key_sig)).collect::<BTreeMap<_, _>>();
+
+        // t+1 signers certify the transcript.
+        let mut certificate = Certificate::<SSIG>::new(7, &transcript);
+        certificate.sign(rng, scheme_sig, 3, &sks[3]).unwrap();
+        assert!(matches!(
+            certificate.verify(rng, config, scheme_sig, &dealer_keys),
+            Err(PVSSError::<E>::InsufficientSignaturesError(1, 2))
+        ));
+        certificate.sign(rng, scheme_sig, 1, &sks[1]).unwrap();
+        certificate.verify(rng, config, scheme_sig, &dealer_keys).unwrap();
+        assert!(certificate.certifies(&transcript));
+
+        // A signature under another signer's id is rejected.
+        let mut forged = certificate.clone();
+        forged.add_signature(0, certificate.signatures[&1]);
+        assert!(forged.verify(rng, config, scheme_sig, &dealer_keys).is_err());
+
+        // Finalized transcripts detach into certificates under the dealers' keys.
+        let mut finalized = FinalizedTranscript::new(7, transcript.clone());
+        for i in 0..2 {
+            finalized.sign(rng, &nodes[i].aggregator.scheme_sig, i, &sks[i]).unwrap();
+        }
+        let detached = finalized.certificate();
+        detached.verify(rng, config, scheme_sig, &dealer_keys).unwrap();
+        assert!(detached.certifies(&transcript));
+    }
+
+    #[cfg(feature = "eddsa")]
+    #[test]
+    fn test_certificate_eddsa() {
+        use crate::signature::{eddsa::EdDSASignature, scheme::SignatureScheme};
+
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        let config = &nodes[0].aggregator.config;
+
+        // Consensus keys, independent of the dealers' ones, certify the transcript.
+        let eddsa = EdDSASignature;
+        let keys = (0..n).map(|i| (i, eddsa.generate_keypair(rng).unwrap())).collect::<BTreeMap<_, _>>();
+        let public_keys = keys.iter().map(|(i, (_, pk))| (*i, *pk)).collect::<BTreeMap<_, _>>();
+        let mut certificate = Certificate::<EdDSASignature>::new(7, &transcript);
+        for i in [0, 2].iter() {
+            certificate.sign(rng, &eddsa, *i, &keys[i].0).unwrap();
+        }
+        certificate.verify::<E, _>(rng, config, &eddsa, &public_keys).unwrap();
+
+        // Only the consensus roster decides who may sign.
+        let mut roster = public_keys.clone();
+        roster.remove(&2);
+        assert!(matches!(
+            certificate.verify::<E, _>(rng, config, &eddsa, &roster),
+            Err(PVSSError::InvalidParticipantId(2))
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::modified_scrape::{
+    aggregator::PVSSAggregator,
+    decryption::DecryptedShare,
+    errors::PVSSError,
+    node::Node,
+    slashing::SignedDealing,
+};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField};
+use ark_serialize::*;
+
+use rand::{CryptoRng, Rng};
+use std::ops::Neg;
+
+/* Complaints against dealers whose encryption for some recipient does not match the
+   corresponding commitment. Verification of a received share only checks the
+   encryption for its dealer, so such shares are aggregated, and only caught once the
+   transcript's encryptions are checked (or never, if shares were marked as verified
+   on the strength of a quorum certificate and the transcript is only spot checked).
+
+   The recipient decrypts its share and proves, with the DLEQ proof of a decryption,
+   that it did so with the key the share was encrypted under; the decryption then
+   contradicts the commitment. Since a dealer only signs its decomposition proof, and
+   not its encryptions, the complaint carries the dealing signed in full (see
+   slashing.rs), so that honest dealers cannot be framed with forged encryptions.
+*/
+
+// Struct Complaint models a recipient's accusation against a dealing.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Complaint<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub dealing: SignedDealing<E, SSIG>,   // the accused dealing, signed in full by its dealer
+    pub decryption: DecryptedShare<E>,     // the complainant's decryption of its share, and proof thereof
+}
+
+impl<E, SSIG> Node<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method for filing a complaint against a dealing, by decrypting our share of it.
+    pub fn complain<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        dealing: SignedDealing<E, SSIG>,
+    ) -> Result<Complaint<E, SSIG>, PVSSError<E>> {
+        let my_id = self.dealer.participant.id;
+        let enc = dealing
+            .share
+            .pvss_share
+            .encs
+            .get(my_id)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(my_id))?
+            .into_affine();
+
+        let decryption = DecryptedShare::generate(rng, &self.aggregator.config, &enc, &self.decryption_key(), my_id)?;
+        Ok(Complaint { dealing, decryption })
+    }
+}
+
+impl<E, SSIG> PVSSAggregator<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method for verifying a complaint. Returns Ok if the accused dealing provably
+    // encrypts a share that does not match its commitment, and an error otherwise.
+    pub fn verify_complaint(&self, complaint: &Complaint<E, SSIG>) -> Result<(), PVSSError<E>> {
+        complaint.dealing.verify(&self.scheme_sig, &self.participants)?;
+
+        let index = complaint.decryption.origin;
+        let share = &complaint.dealing.share.pvss_share;
+        let enc = share.encs.get(index).ok_or(PVSSError::<E>::InvalidParticipantId(index))?;
+        let comm = share.comms.get(index).ok_or(PVSSError::<E>::InvalidParticipantId(index))?;
+
+        // The decryption must be honest, under the key the share was encrypted for...
+        complaint.decryption.verify(&self.config, &enc.into_affine(), &self.encryption_key(index)?)?;
+
+        // ...and must not match the commitment: e(dec, g_2) != e(g_1, comm).
+        let pairs = [
+            (complaint.decryption.dec.into(), self.config.srs.g2.into()),
+            (self.config.srs.g1.into(), comm.into_affine().neg().into()),
+        ];
+        if E::product_of_pairings(pairs.iter()).is_one() {
+            return Err(PVSSError::InvalidComplaint("encryption matches commitment"));
+        }
+
+        Ok(())
+    }
+
+    // Method for excluding the accused dealer's contribution from our transcript on a
+    // valid complaint. The dealing must be the one aggregated into the transcript; it
+    // is subtracted as many times as it was counted, and the dealer may not contribute
+    // again.
+    pub fn exclude_dealer(&mut self, complaint: &Complaint<E, SSIG>) -> Result<(), PVSSError<E>> {
+        self.verify_complaint(complaint)?;
+
+        let share = &complaint.dealing.share;
+        let dealer = share.participant_id;
+        let contribution = self
+            .transcript
+            .contributions
+            .get(&dealer)
+            .ok_or(PVSSError::InvalidComplaint("dealer has no contribution in the transcript"))?;
+        if contribution.decomp_proof != share.decomp_proof {
+            return Err(PVSSError::InvalidComplaint("dealing is not the one aggregated"));
+        }
+
+        let n = self.transcript.num_participants;
+        let (comms, encs) = (&share.pvss_share.comms, &share.pvss_share.encs);
+        if comms.len() != n || encs.len() != n {
+            return Err(PVSSError::LengthMismatchError);
+        }
+
+        let weight = Scalar::<E>::from(contribution.weight).into_repr();
+        for i in 0..n {
+            self.transcript.pvss_share.comms[i] -= comms[i].into_affine().mul(weight);
+            self.transcript.pvss_share.encs[i] -= encs[i].into_affine().mul(weight);
+        }
+        self.transcript.contributions.remove(&dealer);
+        self.metadata.remove(&dealer);
+        self.excluded.insert(dealer);
+
+        if let Some(accountant) = self.memory.as_mut() {
+            accountant.set_used(self.transcript.serialized_size());
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::PairingEngine;
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{
+        errors::PVSSError,
+        seen::SeenSet,
+        slashing::SignedDealing,
+        tests::setup_nodes,
+    };
+
+    use rand::thread_rng;
+    use std::{env, fs};
+
+    #[test]
+    fn test_complaint() {
+        let rng = &mut thread_rng();
+        let (mut nodes, sks) = setup_nodes(4, 1);
+        let path = env::temp_dir().join(format!("optrand-complaint-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        nodes[2].aggregator.seen = Some(SeenSet::open(&path, 2, 16).unwrap());
+
+        // Dealer 0 corrupts the encryption for participant 2, which goes unnoticed
+        // until the transcript is verified.
+        let mut bad = nodes[0].share(rng).unwrap();
+        bad.pvss_share.encs[2] = <E as PairingEngine>::G1Projective::rand(rng);
+        nodes[2].aggregator.receive_share(rng, &bad).unwrap();
+        let shares = (1..4).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[2].aggregator.receive_share(rng, share).unwrap();
+        }
+        assert!(nodes[2].aggregator.transcript_verify(rng, &nodes[2].aggregator.transcript.clone()).is_err());
+
+        // A complaint against an honest dealing is rejected.
+        let scheme_sig = nodes[0].aggregator.scheme_sig.clone();
+        let honest = SignedDealing::sign(rng, &scheme_sig, &sks[1], shares[0].clone()).unwrap();
+        let complaint = nodes[2].complain(rng, honest).unwrap();
+        assert!(matches!(nodes[2].aggregator.verify_complaint(&complaint), Err(PVSSError::InvalidComplaint(_))));
+
+        // The complaint against dealer 0 holds, and excluding it repairs the transcript.
+        let dealing = SignedDealing::sign(rng, &scheme_sig, &sks[0], bad.clone()).unwrap();
+        let complaint = nodes[2].complain(rng, dealing).unwrap();
+        nodes[1].aggregator.verify_complaint(&complaint).unwrap();
+        nodes[2].aggregator.exclude_dealer(&complaint).unwrap();
+
+        let aggregator = &nodes[2].aggregator;
+        let transcript = aggregator.finalize().unwrap().clone();
+        assert!(!transcript.contributions.contains_key(&0));
+        aggregator.transcript_verify(rng, &transcript).unwrap();
+
+        // The excluded dealer may not contribute again, not even by re-sending the
+        // share we had already processed.
+        let again = nodes[0].share(rng).unwrap();
+        assert!(matches!(nodes[2].aggregator.receive_share(rng, &again), Err(PVSSError::ExcludedDealerError(0))));
+        assert!(matches!(nodes[2].aggregator.receive_share(rng, &bad), Err(PVSSError::ExcludedDealerError(0))));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,5 +1,12 @@
+use super::errors::PVSSError;
+use super::participant::{Committee, ParticipantId};
 use super::srs::SRS;
+use crate::{digest_bytes, Digest, Hash, Scalar};
 use ark_ec::PairingEngine;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_serialize::CanonicalSerialize;
+use ark_std::collections::BTreeMap;
+use std::ops::Neg;
 
 /* Struct config models the system-wide public parameters that each party
    in the network needs to know in order to generate/verify a PVSS sharing.
@@ -10,4 +17,140 @@ pub struct Config<E: PairingEngine> {
     pub srs: SRS<E>,               // the associated SRS
     pub degree: usize,             // polynomial degree (t)
     pub num_participants: usize,   // the total number of participants in the protocol
+    pub layout: ShareLayout,       // representation of the core PVSS shares dealt
+    pub points: EvaluationPoints,  // the points the dealt polynomials are evaluated at
+}
+
+
+// Enumeration of the representations of core PVSS shares. In hierarchical or sharded
+// deployments a dealer is only responsible for a subset of the recipients, in which
+// case sparse shares (see SparsePVSSShare) avoid carrying n - |subset| placeholders.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShareLayout {
+    #[default]
+    Dense,    // an encryption and a commitment for each of the n participants
+    Sparse,   // encryptions and commitments only for the dealer's recipients
+}
+
+
+// Enumeration of the points at which dealers evaluate their polynomials, participant
+// i receiving p(x_i). By default x_i = i + 1. For large committees, the points may
+// instead be the first n elements of a radix-2 evaluation domain, x_i = w^i, so that
+// dealing and the coding check run by FFT in O(n log n) rather than O(n t). All
+// parties must agree on the points, which are thus part of the config.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EvaluationPoints {
+    #[default]
+    Integers,   // x_i = i + 1
+    Domain,     // x_i = w^i, for w generating the smallest radix-2 domain of size at least n
+}
+
+
+impl<E: PairingEngine> Config<E> {
+
+    // Method returning the evaluation domain the points lie in, if any. Committees
+    // too large for a radix-2 domain of the scalar field fall back to the integers.
+    pub fn domain(&self) -> Option<Radix2EvaluationDomain<Scalar<E>>> {
+        match self.points {
+            EvaluationPoints::Integers => None,
+            EvaluationPoints::Domain => Radix2EvaluationDomain::new(self.num_participants),
+        }
+    }
+
+    // Method returning the point x_i at which participant i's share is evaluated.
+    pub fn point(&self, id: usize) -> Scalar<E> {
+        Self::point_in(self.domain().as_ref(), id)
+    }
+
+    // Function returning the point x_i of participant i for the given domain (see
+    // domain): the i-th element of the domain, or i + 1 outside of one. This is
+    // the only place the mapping from participants to points is defined.
+    pub fn point_in(domain: Option<&Radix2EvaluationDomain<Scalar<E>>>, id: usize) -> Scalar<E> {
+        match domain {
+            Some(domain) => domain.element(id),
+            None => Scalar::<E>::from((id + 1) as u64),
+        }
+    }
+
+    // Method returning the point at which the share of the committee member with the
+    // given id is evaluated, i.e., the point of its index in the committee.
+    pub fn point_of(&self, committee: &Committee, id: ParticipantId) -> Result<Scalar<E>, PVSSError<E>> {
+        if committee.len() != self.num_participants {
+            return Err(PVSSError::CommitteeSizeError(committee.len(), self.num_participants));
+        }
+        Ok(self.point(committee.index_of::<E>(id)?))
+    }
+
+    // Method returning the points x_0, ..., x_{n-1} of all participants.
+    pub fn points(&self) -> Vec<Scalar<E>> {
+        match self.domain() {
+            Some(domain) => domain.elements().take(self.num_participants).collect(),
+            None => (0..self.num_participants).map(|id| Self::point_in(None, id)).collect(),
+        }
+    }
+}
+
+
+// Config implements the Hash trait. The digest binds the SRS generators,
+// the degree, the number of participants, the layout and the evaluation points.
+impl<E: PairingEngine> Hash for Config<E> {
+    fn digest(&self) -> Digest {
+        let mut bytes = vec![];
+        // serializing into a Vec cannot fail
+        self.srs.g1.serialize(&mut bytes).unwrap();
+        self.srs.g2.serialize(&mut bytes).unwrap();
+        self.srs.g2_prime.serialize(&mut bytes).unwrap();
+        (self.degree as u64).serialize(&mut bytes).unwrap();
+        (self.num_participants as u64).serialize(&mut bytes).unwrap();
+        bytes.push(self.layout as u8);
+        bytes.push(self.points as u8);
+        digest_bytes(&bytes)
+    }
+}
+
+
+/* Struct PreparedConfig caches the pairing inputs that verifiers use over and over,
+   in prepared form: the SRS generators (and -g_2, against which encryptions are
+   paired) and the recipients' encryption keys. Preparing a G2 element computes the
+   line coefficients of its Miller loop, which is a sizeable part of a pairing.
+   The affine values are kept along, so that a stale cache can be detected.
+*/
+
+#[derive(Clone)]
+pub struct PreparedConfig<E: PairingEngine> {
+    pub srs: SRS<E>,                                            // the generators prepared below
+    pub g1: E::G1Prepared,                                      // g_1, prepared
+    pub g2: E::G2Prepared,                                      // g_2, prepared
+    pub neg_g2: E::G2Prepared,                                  // -g_2, prepared
+    pub keys: BTreeMap<usize, (E::G1Affine, E::G1Prepared)>,   // encryption keys by recipient id, as given and prepared
+}
+
+impl<E: PairingEngine> PreparedConfig<E> {
+
+    // Function for preparing the generators of config and the given encryption keys.
+    pub fn new(config: &Config<E>, keys: &BTreeMap<usize, E::G1Affine>) -> Self {
+        let srs = config.srs.clone();
+        Self {
+            g1: srs.g1.into(),
+            g2: srs.g2.into(),
+            neg_g2: srs.g2.neg().into(),
+            keys: keys.iter().map(|(id, key)| (*id, (*key, (*key).into()))).collect(),
+            srs,
+        }
+    }
+
+    // Method returning whether the cache was prepared for the given SRS and keys.
+    pub fn is_current(&self, srs: &SRS<E>, keys: &BTreeMap<usize, E::G1Affine>) -> bool {
+        self.srs.g1 == srs.g1
+            && self.srs.g2 == srs.g2
+            && self.keys.len() == keys.len()
+            && self.keys.iter().zip(keys.iter()).all(|((id, (key, _)), (other_id, other))| id == other_id && key == other)
+    }
+
+    // Method returning the prepared encryption key of recipient id, if any.
+    pub fn key(&self, id: usize) -> Option<&E::G1Prepared> {
+        self.keys.get(&id).map(|(_, prepared)| prepared)
+    }
 }
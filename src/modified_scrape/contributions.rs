@@ -0,0 +1,437 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::{btree_map, BTreeMap};
+
+use std::iter::FromIterator;
+use std::ops::Index;
+
+/* Struct Contributions maps dealer ids to their contributions to a transcript, with
+   the subset of the BTreeMap API the protocol needs. For large committees, the
+   per-lookup pointer chasing of a BTreeMap dominates the hot aggregation loops, so
+   the map switches to a dense layout: a Vec<Option<T>> indexed by id, along with a
+   bitset of occupied slots so that iteration skips empty ones a word at a time.
+   The layout is chosen automatically from the committee size and is invisible to
+   callers, including in the serialized form (which is that of a BTreeMap).
+*/
+
+// Committee size from which contributions are stored densely.
+pub const DENSE_LAYOUT_MIN_PARTICIPANTS: usize = 128;
+
+// Enumeration of the internal layouts of Contributions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContributionsLayout {
+    Sparse,   // ordered map from ids to contributions
+    Dense,    // slots indexed by id, plus a bitset of occupied slots
+}
+
+impl ContributionsLayout {
+
+    // Function returning the layout to use for a committee of n participants.
+    pub fn for_participants(n: usize) -> Self {
+        if n >= DENSE_LAYOUT_MIN_PARTICIPANTS {
+            ContributionsLayout::Dense
+        } else {
+            ContributionsLayout::Sparse
+        }
+    }
+}
+
+
+#[derive(Clone)]
+pub struct Contributions<T> {
+    inner: Inner<T>,
+}
+
+#[derive(Clone)]
+enum Inner<T> {
+    Sparse(BTreeMap<usize, T>),
+    Dense {
+        ids: Vec<usize>,            // ids[i] = i, so that iterators can hand out &usize keys
+        slots: Vec<Option<T>>,      // contribution of dealer i, if any
+        occupied: Vec<u64>,         // bitset of the occupied slots
+        len: usize,                 // number of occupied slots
+    },
+}
+
+impl<T> Contributions<T> {
+
+    // Function for creating an empty map with the given layout.
+    pub fn with_layout(layout: ContributionsLayout) -> Self {
+        let inner = match layout {
+            ContributionsLayout::Sparse => Inner::Sparse(BTreeMap::new()),
+            ContributionsLayout::Dense => Inner::Dense { ids: vec![], slots: vec![], occupied: vec![], len: 0 },
+        };
+        Self { inner }
+    }
+
+    // Function for creating an empty map suited to a committee of n participants.
+    pub fn for_participants(n: usize) -> Self {
+        let mut contributions = Self::with_layout(ContributionsLayout::for_participants(n));
+        contributions.reserve(n);
+        contributions
+    }
+
+    // Method returning the current layout.
+    pub fn layout(&self) -> ContributionsLayout {
+        match self.inner {
+            Inner::Sparse(_) => ContributionsLayout::Sparse,
+            Inner::Dense { .. } => ContributionsLayout::Dense,
+        }
+    }
+
+    // Method for growing a dense map to hold ids up to n - 1. It never shrinks it.
+    fn reserve(&mut self, n: usize) {
+        if let Inner::Dense { ids, slots, occupied, .. } = &mut self.inner {
+            while ids.len() < n {
+                ids.push(ids.len());
+                slots.push(None);
+            }
+            let words = n.div_ceil(64);
+            if occupied.len() < words {
+                occupied.resize(words, 0);
+            }
+        }
+    }
+
+    // Method returning the number of contributions.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Sparse(map) => map.len(),
+            Inner::Dense { len, .. } => *len,
+        }
+    }
+
+    // Method checking whether there are no contributions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Method returning the contribution of the given dealer, if any.
+    pub fn get(&self, id: &usize) -> Option<&T> {
+        match &self.inner {
+            Inner::Sparse(map) => map.get(id),
+            Inner::Dense { slots, .. } => slots.get(*id).and_then(|slot| slot.as_ref()),
+        }
+    }
+
+    // Method returning a mutable reference to the contribution of the given dealer, if any.
+    pub fn get_mut(&mut self, id: &usize) -> Option<&mut T> {
+        match &mut self.inner {
+            Inner::Sparse(map) => map.get_mut(id),
+            Inner::Dense { slots, .. } => slots.get_mut(*id).and_then(|slot| slot.as_mut()),
+        }
+    }
+
+    // Method checking whether the given dealer contributed.
+    pub fn contains_key(&self, id: &usize) -> bool {
+        self.get(id).is_some()
+    }
+
+    // Method for setting the contribution of a dealer, returning the previous one.
+    pub fn insert(&mut self, id: usize, contribution: T) -> Option<T> {
+        if let Inner::Sparse(map) = &mut self.inner {
+            return map.insert(id, contribution);
+        }
+
+        self.reserve(id + 1);
+        match &mut self.inner {
+            Inner::Dense { slots, occupied, len, .. } => {
+                let previous = slots[id].replace(contribution);
+                if previous.is_none() {
+                    occupied[id / 64] |= 1 << (id % 64);
+                    *len += 1;
+                }
+                previous
+            }
+            Inner::Sparse(_) => unreachable!(),
+        }
+    }
+
+    // Method for removing the contribution of a dealer, returning it.
+    pub fn remove(&mut self, id: &usize) -> Option<T> {
+        match &mut self.inner {
+            Inner::Sparse(map) => map.remove(id),
+            Inner::Dense { slots, occupied, len, .. } => {
+                let previous = slots.get_mut(*id).and_then(|slot| slot.take());
+                if previous.is_some() {
+                    occupied[*id / 64] &= !(1 << (*id % 64));
+                    *len -= 1;
+                }
+                previous
+            }
+        }
+    }
+
+    // Method for removing all contributions.
+    pub fn clear(&mut self) {
+        match &mut self.inner {
+            Inner::Sparse(map) => map.clear(),
+            Inner::Dense { slots, occupied, len, .. } => {
+                slots.iter_mut().for_each(|slot| *slot = None);
+                occupied.iter_mut().for_each(|word| *word = 0);
+                *len = 0;
+            }
+        }
+    }
+
+    // Method returning an iterator over (id, contribution) pairs, in ascending id order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &self.inner {
+            Inner::Sparse(map) => Iter::Sparse(map.iter()),
+            Inner::Dense { ids, slots, occupied, .. } => Iter::Dense { ids, slots, occupied, word: 0, bits: occupied.first().cloned().unwrap_or(0) },
+        }
+    }
+
+    // Method returning an iterator over the ids of the dealers, in ascending order.
+    pub fn keys(&self) -> impl Iterator<Item = &usize> {
+        self.iter().map(|(id, _)| id)
+    }
+
+    // Method returning an iterator over the contributions, in ascending id order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(_, contribution)| contribution)
+    }
+
+    // Method for merging two maps into one suited to a committee of n participants.
+    // Dealers found in only one of them keep their contribution, while those found
+    // in both are merged with combine. When both maps are dense, the dealers are
+    // found by scanning the bitsets rather than by looking up every id.
+    pub fn union_with<Err, F>(&self, other: &Self, n: usize, mut combine: F) -> Result<Self, Err>
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Result<T, Err>,
+    {
+        let mut result = Self::for_participants(n);
+
+        if let (Inner::Dense { slots: a, occupied: a_bits, .. }, Inner::Dense { slots: b, occupied: b_bits, .. }) =
+            (&self.inner, &other.inner)
+        {
+            for word in 0..a_bits.len().max(b_bits.len()) {
+                let mut bits = a_bits.get(word).cloned().unwrap_or(0) | b_bits.get(word).cloned().unwrap_or(0);
+                while bits != 0 {
+                    let id = word * 64 + bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+
+                    let contribution = match (a.get(id).and_then(|s| s.as_ref()), b.get(id).and_then(|s| s.as_ref())) {
+                        (Some(x), Some(y)) => combine(x, y)?,
+                        (Some(x), None) | (None, Some(x)) => x.clone(),
+                        (None, None) => continue,
+                    };
+                    result.insert(id, contribution);
+                }
+            }
+            return Ok(result);
+        }
+
+        for (id, x) in self.iter() {
+            let contribution = match other.get(id) {
+                Some(y) => combine(x, y)?,
+                None => x.clone(),
+            };
+            result.insert(*id, contribution);
+        }
+        for (id, y) in other.iter() {
+            if !self.contains_key(id) {
+                result.insert(*id, y.clone());
+            }
+        }
+        Ok(result)
+    }
+}
+
+
+// Iterator over the (id, contribution) pairs of Contributions.
+pub enum Iter<'a, T> {
+    Sparse(btree_map::Iter<'a, usize, T>),
+    Dense {
+        ids: &'a [usize],
+        slots: &'a [Option<T>],
+        occupied: &'a [u64],
+        word: usize,   // index of the bitset word being scanned
+        bits: u64,     // bits of that word not yet visited
+    },
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (&'a usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Sparse(iter) => iter.next(),
+            Iter::Dense { ids, slots, occupied, word, bits } => {
+                while *bits == 0 {
+                    *word += 1;
+                    *bits = *occupied.get(*word)?;
+                }
+                let id = *word * 64 + bits.trailing_zeros() as usize;
+                *bits &= *bits - 1;
+                Some((&ids[id], slots[id].as_ref()?))
+            }
+        }
+    }
+}
+
+
+impl<T> Default for Contributions<T> {
+    fn default() -> Self {
+        Self::with_layout(ContributionsLayout::Sparse)
+    }
+}
+
+impl<T> Index<&usize> for Contributions<T> {
+    type Output = T;
+
+    fn index(&self, id: &usize) -> &T {
+        self.get(id).expect("no contribution for the given id")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Contributions<T> {
+    type Item = (&'a usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for Contributions<T> {
+    type Item = (usize, T);
+    type IntoIter = std::vec::IntoIter<(usize, T)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self.inner {
+            Inner::Sparse(map) => map.into_iter().collect::<Vec<_>>().into_iter(),
+            Inner::Dense { slots, .. } => slots
+                .into_iter()
+                .enumerate()
+                .filter_map(|(id, slot)| slot.map(|contribution| (id, contribution)))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }
+    }
+}
+
+// Collected maps, whose committee size is unknown, are laid out according to the
+// largest id they contain.
+impl<T> FromIterator<(usize, T)> for Contributions<T> {
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        BTreeMap::from_iter(iter).into()
+    }
+}
+
+impl<T> From<BTreeMap<usize, T>> for Contributions<T> {
+    fn from(map: BTreeMap<usize, T>) -> Self {
+        let n = map.keys().next_back().map_or(0, |id| id + 1);
+        match ContributionsLayout::for_participants(n) {
+            ContributionsLayout::Sparse => Self { inner: Inner::Sparse(map) },
+            ContributionsLayout::Dense => {
+                let mut contributions = Self::for_participants(n);
+                for (id, contribution) in map {
+                    contributions.insert(id, contribution);
+                }
+                contributions
+            }
+        }
+    }
+}
+
+
+// Contributions are encoded exactly as a BTreeMap<usize, T> would be, i.e., as the
+// number of entries followed by the (id, contribution) pairs in ascending id order.
+impl<T: CanonicalSerialize> CanonicalSerialize for Contributions<T> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        (self.len() as u64).serialize(&mut writer)?;
+        for (id, contribution) in self.iter() {
+            id.serialize(&mut writer)?;
+            contribution.serialize(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        8 + self
+            .iter()
+            .map(|(id, contribution)| id.serialized_size() + contribution.serialized_size())
+            .sum::<usize>()
+    }
+}
+
+impl<T: CanonicalDeserialize> CanonicalDeserialize for Contributions<T> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(BTreeMap::<usize, T>::deserialize(reader)?.into())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::contributions::{Contributions, ContributionsLayout};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::collections::BTreeMap;
+
+    #[test]
+    fn test_layouts_agree() {
+        let ids = [0usize, 3, 63, 64, 65, 130, 199];
+        let map = ids.iter().map(|id| (*id, *id as u64 * 10)).collect::<BTreeMap<_, _>>();
+
+        let sparse = Contributions::from(map.clone());
+        let mut dense = Contributions::for_participants(200);
+        for (id, value) in map.iter() {
+            assert!(dense.insert(*id, *value).is_none());
+        }
+        assert_eq!(sparse.layout(), ContributionsLayout::Dense);   // the largest id is 199
+        assert_eq!(dense.layout(), ContributionsLayout::Dense);
+        let small = ids[..3].iter().map(|id| (*id, 0u64)).collect::<Contributions<_>>();
+        assert_eq!(small.layout(), ContributionsLayout::Sparse);
+
+        // Same contents, same order, same encoding as the BTreeMap.
+        assert!(dense.iter().eq(map.iter()));
+        assert_eq!(dense.len(), ids.len());
+        assert_eq!(dense[&130], 1300);
+        let mut bytes = vec![];
+        dense.serialize(&mut bytes).unwrap();
+        let mut map_bytes = vec![];
+        map.serialize(&mut map_bytes).unwrap();
+        assert_eq!(bytes, map_bytes);
+        assert!(Contributions::<u64>::deserialize(&bytes[..]).unwrap().iter().eq(map.iter()));
+
+        // Unions agree across layouts.
+        let mut other = Contributions::with_layout(ContributionsLayout::Sparse);
+        other.insert(3, 1u64);
+        other.insert(150, 2u64);
+        let sum = |a: &u64, b: &u64| -> Result<u64, ()> { Ok(a + b) };
+        let mut other_dense = Contributions::for_participants(200);
+        other_dense.insert(3, 1u64);
+        other_dense.insert(150, 2u64);
+        let expected = dense.union_with(&other, 200, sum).unwrap();
+        assert!(dense.union_with(&other_dense, 200, sum).unwrap().iter().eq(expected.iter()));
+        assert_eq!(expected[&3], 31);
+        assert_eq!(expected[&150], 2);
+
+        assert_eq!(dense.remove(&64), Some(640));
+        assert!(!dense.contains_key(&64));
+        dense.clear();
+        assert!(dense.is_empty() && dense.iter().next().is_none());
+    }
+
+    #[test]
+    fn test_descending_inserts() {
+        // Inserting a small id after a large one must not drop the large one.
+        let mut dense = Contributions::for_participants(200);
+        dense.insert(150, 1u64);
+        dense.insert(3, 2u64);
+        assert_eq!(dense.len(), 2);
+        assert!(dense.iter().map(|(id, _)| id).eq([3, 150].iter()));
+        assert_eq!(dense.remove(&150), Some(1));
+        assert!(dense.iter().map(|(id, _)| id).eq([3].iter()));
+
+        // Likewise for a map that grew past its initial size.
+        let mut grown = Contributions::with_layout(ContributionsLayout::Dense);
+        grown.insert(130, 1u64);
+        grown.insert(0, 2u64);
+        assert!(grown.iter().map(|(id, _)| id).eq([0, 130].iter()));
+        assert_eq!(grown.remove(&130), Some(1));
+    }
+}
@@ -1,20 +1,188 @@
 use crate::{modified_scrape::participant::Participant, signature::scheme::BatchVerifiableSignatureScheme};
+use crate::secrets::{SecretPolynomial, SecretScalar};
+use super::{
+    audit::{DealingSeed, SeedCommitment},
+    config::Config,
+    dealing::encrypt_evals,
+    decomp::{Decomp, DecompProof},
+    errors::PVSSError,
+    poly::Polynomial,
+    pvss::{PVSSShare, PVSSShareSecrets},
+};
 use ark_ec::PairingEngine;
+use ark_ff::Zero;
+use ark_poly::UVPolynomial;
+use rand::{CryptoRng, Rng};
 
 // Struct Dealer models the aspects of each party in the network, when acting as a dealer
-// in the PVSS scheme.
+// in the PVSS scheme. Outside this crate, its fields are read through getters, and
+// dealers are created with Dealer::new, which checks the signing key against the
+// participant's public key; Dealer::from_raw_parts skips the check, and is only
+// available with the testing feature.
 #[derive(Clone)]
 pub struct Dealer<
     E: PairingEngine,
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = E::Fr>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = E::Fr>,
 > {
-    pub private_key_sig: SSIG::Secret,            // Dealer's secret (signing) key
+    pub(crate) private_key_sig: SecretScalar<E::Fr>,     // Dealer's secret (signing) key, wiped on drop
 
     // MAY BE REDUNDANT
-    pub accumulated_secret: E::G2Affine,     // Dealer's accumulated secret (in G_2)
+    pub(crate) accumulated_secret: E::G1Affine,     // Dealer's accumulated secret (in G_1)
 
     // MAY BE REDUNDANT
-    pub decryptions: Vec<(usize, E::G1Affine)>,   // Dealer's list of accumulated decryptions, along with the respective participant ids
+    pub(crate) decryptions: Vec<(usize, E::G1Affine)>,   // Dealer's list of accumulated decryptions, along with the respective participant ids
 
-    pub participant: Participant<E, SSIG>,        // Dealers have participant characteristics (structural composition)
+    pub(crate) participant: Participant<E, SSIG>,        // Dealers have participant characteristics (structural composition)
+
+    pub(crate) seed: Option<DealingSeed>,                // Committed seed the dealing randomness is derived from, if any
+}
+
+impl<E, SSIG> Dealer<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = E::Fr>,
+{
+    // Function for creating a dealer from its signing key and its participant
+    // profile, which must carry the matching public key.
+    pub fn new(scheme_sig: &SSIG, private_key_sig: SSIG::Secret, participant: Participant<E, SSIG>) -> Result<Self, PVSSError<E>> {
+        let (_, public_key_sig) = scheme_sig.from_sk(&private_key_sig)?;
+        if public_key_sig != participant.public_key_sig {
+            return Err(PVSSError::MismatchedDealerKeyError(participant.id));
+        }
+
+        Ok(Self::assemble(private_key_sig, participant))
+    }
+
+    // Function for creating a dealer without checking its signing key against its
+    // participant profile, e.g., to test how mismatched keys are handled.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_raw_parts(private_key_sig: SSIG::Secret, participant: Participant<E, SSIG>) -> Self {
+        Self::assemble(private_key_sig, participant)
+    }
+
+    // Utility function assembling a dealer which has not dealt yet.
+    fn assemble(private_key_sig: SSIG::Secret, participant: Participant<E, SSIG>) -> Self {
+        Self {
+            private_key_sig: SecretScalar::new(private_key_sig),
+            accumulated_secret: E::G1Affine::zero(),
+            decryptions: vec![],
+            participant,
+            seed: None,
+        }
+    }
+
+    // Getter for the dealer's participant profile.
+    pub fn participant(&self) -> &Participant<E, SSIG> {
+        &self.participant
+    }
+
+    // Getter for the dealer's accumulated secret.
+    pub fn accumulated_secret(&self) -> E::G1Affine {
+        self.accumulated_secret
+    }
+
+    // Getter for the dealer's accumulated decryptions, by participant id.
+    pub fn decryptions(&self) -> &[(usize, E::G1Affine)] {
+        &self.decryptions
+    }
+
+    // Getter for the dealer's committed seed, if any.
+    pub fn seed(&self) -> Option<&DealingSeed> {
+        self.seed.as_ref()
+    }
+
+    // Method for committing to a fresh seed for the given epoch. From then on, our
+    // dealings are derived deterministically from the seed (see audit.rs). The
+    // commitment must be published before dealing.
+    pub fn commit_seed<R: Rng + CryptoRng>(&mut self, rng: &mut R, epoch: u64) -> SeedCommitment {
+        let seed = DealingSeed::generate(rng, self.participant.id, epoch);
+        let commitment = seed.commitment();
+        self.seed = Some(seed);
+        commitment
+    }
+
+    // Method for opening our committed seed to an auditor. Must only be called
+    // once the epoch's beacon has been released, as the seed reveals our secret.
+    pub fn open_seed(&self) -> Result<DealingSeed, PVSSError<E>> {
+        self.seed.clone().ok_or(PVSSError::NoCommittedSeedError)
+    }
+}
+
+
+/* Struct ExternalDealer models a dealer that is not a member of the committee it
+   deals to (e.g., a bootstrapping authority), and thus has no recipient index of
+   its own. Its shares are dealt to the n recipients' keys, and are checked by the
+   committee with PVSSAggregator::pvss_share_verify and encryptions_verify.
+*/
+
+// An external dealing: the core share, its decomposition proof and the share secrets.
+pub type ExternalDealing<E> = (PVSSShare<E>, DecompProof<E>, PVSSShareSecrets<E>);
+
+pub struct ExternalDealer<E: PairingEngine> {
+    pub config: Config<E>,                 // the committee's configuration
+    pub recipient_keys: Vec<E::G1Affine>,  // encryption keys of recipients 0, ..., n-1
+}
+
+impl<E: PairingEngine> ExternalDealer<E> {
+
+    // Method for dealing a fresh secret to the committee. The share secrets
+    // carry no partial secret, since the dealer is not a recipient.
+    pub fn deal<R: Rng + CryptoRng>(&self, rng: &mut R) -> Result<ExternalDealing<E>, PVSSError<E>> {
+        if self.recipient_keys.len() != self.config.num_participants {
+            return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(self.recipient_keys.len(),
+                        self.recipient_keys.len(), self.config.num_participants));
+        }
+
+        let poly = SecretPolynomial::new(Polynomial::<E>::rand(self.config.degree, rng));
+        let artifacts = Decomp::<E>::from_polynomial(rng, &self.config, poly.expose())?;
+
+        let pvss_share = PVSSShare {
+            comms: artifacts.comms,
+            encs: encrypt_evals::<E>(&self.recipient_keys, &artifacts.evals)?,
+        };
+
+        Ok((pvss_share, artifacts.decomp_proof, PVSSShareSecrets { p_0: poly.expose().coeffs[0], my_secret: None }))
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{dealer::{Dealer, ExternalDealer}, errors::PVSSError, tests::setup_nodes};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_external_dealer() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let external = ExternalDealer {
+            config: nodes[0].aggregator.config.clone(),
+            recipient_keys: nodes.iter().map(|node| node.dealer.participant.public_key_sig).collect(),
+        };
+        let (share, decomp_proof, secrets) = external.deal(rng).unwrap();
+        assert!(secrets.my_secret.is_none());
+
+        // Committee members accept the dealing.
+        let aggregator = &nodes[2].aggregator;
+        aggregator.pvss_share_verify(rng, &decomp_proof, &share).unwrap();
+        aggregator.encryptions_verify(rng, &share).unwrap();
+
+        // Members still get their partial secret when dealing themselves.
+        let (_, secrets) = nodes[1].share_pvss(rng).unwrap();
+        assert!(secrets.my_secret.is_some());
+    }
+
+    #[test]
+    fn test_dealer_key_mismatch() {
+        let (nodes, sks) = setup_nodes(3, 1);
+        let scheme = &nodes[0].aggregator.scheme_sig;
+        let participant = nodes[1].dealer.participant.clone();
+
+        Dealer::new(scheme, sks[1], participant.clone()).unwrap();
+        assert!(matches!(Dealer::new(scheme, sks[0], participant), Err(PVSSError::MismatchedDealerKeyError(1))));
+    }
 }
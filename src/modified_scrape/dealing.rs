@@ -0,0 +1,123 @@
+use super::{config::Config, errors::PVSSError, poly::Polynomial};
+use crate::Scalar;
+
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Polynomial as Poly};
+
+/* The arithmetic of dealing a PVSS share, as pure functions of their inputs. Node
+   uses them to deal, and alternative dealers (hardware-backed, multi-secret,
+   FFT-based, ...) can reuse them instead of re-implementing the math. Participant
+   j (for j in 0, ..., n-1) is given the evaluation at j + 1, unless the config
+   evaluates at the points of a radix-2 domain (see EvaluationPoints).
+*/
+
+// Function computing the evaluations p(1), ..., p(n) of a polynomial.
+pub fn compute_evals<E: PairingEngine>(poly: &Polynomial<E>, n: usize) -> Vec<Scalar<E>> {
+    (1..n + 1)
+        .map(|j| poly.evaluate(&Scalar::<E>::from(j as u64)))
+        .collect()
+}
+
+
+// Function computing the evaluations p(x_0), ..., p(x_{n-1}) of a polynomial at the
+// points of config. Over a domain, they are obtained by a single FFT.
+pub fn compute_evals_for<E: PairingEngine>(config: &Config<E>, poly: &Polynomial<E>) -> Vec<Scalar<E>> {
+    match config.domain() {
+        Some(domain) => {
+            let mut evals = domain.fft(&poly.coeffs);
+            evals.truncate(config.num_participants);
+            evals
+        }
+        None => compute_evals::<E>(poly, config.num_participants),
+    }
+}
+
+
+// Function computing the commitments g_2^{p(j)} to the given evaluations.
+pub fn commit_evals<E: PairingEngine>(g2: &E::G2Affine, evals: &[Scalar<E>]) -> Vec<E::G2Projective> {
+    evals
+        .iter()
+        .map(|eval| g2.mul(eval.into_repr()))
+        .collect()
+}
+
+
+// Function computing the encryptions pk_j^{p(j)} of the given evaluations, under
+// the public keys of the respective participants.
+pub fn encrypt_evals<E: PairingEngine>(pks: &[E::G1Affine], evals: &[Scalar<E>]) -> Result<Vec<E::G1Projective>, PVSSError<E>> {
+    if pks.len() != evals.len() {
+        return Err(PVSSError::LengthMismatchError);
+    }
+
+    Ok(pks
+        .iter()
+        .zip(evals.iter())
+        .map(|(pk, eval)| pk.mul(eval.into_repr()))
+        .collect())
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        config::{Config, EvaluationPoints, ShareLayout},
+        dealing::{commit_evals, compute_evals, compute_evals_for, encrypt_evals},
+        poly::Polynomial,
+        srs::SRS,
+    };
+    use crate::Scalar;
+
+    use ark_bls12_381::Bls12_381 as E;
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_poly::{Polynomial as Poly, UVPolynomial};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_dealing_math() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+        let n = 5;
+
+        let poly = Polynomial::<E>::rand(2, rng);
+        let evals = compute_evals::<E>(&poly, n);
+        assert_eq!(evals[2], poly.evaluate(&Scalar::<E>::from(3u64)));
+
+        let sks = (0..n).map(|_| Scalar::<E>::rand(rng)).collect::<Vec<_>>();
+        let pks = sks.iter().map(|sk| srs.g1.mul(sk.into_repr()).into_affine()).collect::<Vec<_>>();
+
+        // e(pk_j, comm_j) = e(enc_j, g_2) for all j.
+        let comms = commit_evals::<E>(&srs.g2, &evals);
+        let encs = encrypt_evals::<E>(&pks, &evals).unwrap();
+        for j in 0..n {
+            assert_eq!(E::pairing(pks[j], comms[j]), E::pairing(encs[j], srs.g2));
+        }
+
+        assert!(encrypt_evals::<E>(&pks[1..], &evals).is_err());
+    }
+
+    #[test]
+    fn test_domain_evals() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+        let poly = Polynomial::<E>::rand(3, rng);
+
+        // Evaluations by FFT match those at each point, for full and partial domains.
+        for n in [6, 8].iter() {
+            let mut config = Config { srs: srs.clone(), degree: 3, num_participants: *n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+            assert_eq!(compute_evals_for::<E>(&config, &poly), compute_evals::<E>(&poly, *n));
+
+            config.points = EvaluationPoints::Domain;
+            let evals = compute_evals_for::<E>(&config, &poly);
+            assert_eq!(evals.len(), *n);
+            for (j, eval) in evals.iter().enumerate() {
+                assert_eq!(*eval, poly.evaluate(&config.point(j)));
+            }
+            assert_eq!(config.points().len(), *n);
+        }
+    }
+}
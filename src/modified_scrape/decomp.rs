@@ -1,15 +1,17 @@
-use super::{config::Config, errors::PVSSError};
+use super::{config::Config, dealing::{commit_evals, compute_evals_for}, errors::PVSSError, kernel, poly::{lagrange_interpolation, Polynomial}, pvss::PVSSShare, srs::PreparedSRS};
 use crate::nizk::{dlk::{DLKProof, srs::SRS as DLKSRS}, scheme::NIZKProof};
-use crate::Scalar;
+use crate::transcript::{Session, Transcript};
+use crate::{canonical_digest, Digest, Hash, Scalar};
 
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::PrimeField;
+use ark_ff::{PrimeField, Zero};
+use ark_poly::Polynomial as Poly;
 use ark_serialize::*;
 use ark_std::fmt::Debug;
 
 use std::io::Cursor;
 use std::marker::PhantomData;
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 
 pub type ProofGroup<E> = <E as PairingEngine>::G2Affine;   // the group over which the proof is computed
 pub type ProofType<E> = DecompProof<E>;   		   // the type of output decomposition proofs
@@ -20,17 +22,127 @@ pub struct Decomp<E: PairingEngine> {
     pairing_engine: PhantomData<E>,   // cache E
 }
 
-// Struct DecompProof models the actual decomposition proof.
-#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+// Struct DecompProof models the actual decomposition proof. Proofs produced for a
+// specific core PVSS share are bound to the digest of its commitment vector (see
+// commitments_binding), which enters the proof's Fiat-Shamir challenge, so that a
+// valid proof cannot be paired with some other commitment vector interpolating to
+// the same gs. Unbound proofs carry the all-zero digest.
+//
+// The proof's own digest is computed once, on construction (or deserialization),
+// and is not serialized. Proofs are therefore built with new(), and their fields
+// are private so that the cached digest cannot go stale.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct DecompProof<E: PairingEngine> {
-    pub proof: <DLKProof<ProofGroup<E>> as NIZKProof>::Proof,   // the proof of knowledge of discrete log
-    pub gs: ProofGroup<E>,                                      // the associated public statement (i.e., commitment to the secret)
+    proof: <DLKProof<ProofGroup<E>> as NIZKProof>::Proof,   // the proof of knowledge of discrete log
+    gs: ProofGroup<E>,                                      // the associated public statement (i.e., commitment to the secret)
+    binding: Digest,                                        // digest of the commitments the proof is bound to
+    digest: Digest,                                         // cached digest of the above (see Hash)
+}
+
+impl<E: PairingEngine> DecompProof<E> {
+
+    // Function for assembling a decomposition proof from its parts.
+    pub fn new(proof: <DLKProof<ProofGroup<E>> as NIZKProof>::Proof, gs: ProofGroup<E>, binding: Digest) -> Self {
+        let mut decomp_proof = Self { proof, gs, binding, digest: Digest::default() };
+        decomp_proof.digest = canonical_digest(&decomp_proof);
+        decomp_proof
+    }
+
+    // Getter for the proof of knowledge of discrete log.
+    pub fn proof(&self) -> <DLKProof<ProofGroup<E>> as NIZKProof>::Proof {
+        self.proof
+    }
+
+    // Getter for the public statement (i.e., commitment to the secret).
+    pub fn gs(&self) -> ProofGroup<E> {
+        self.gs
+    }
+
+    // Getter for the digest of the commitments the proof is bound to.
+    pub fn binding(&self) -> Digest {
+        self.binding
+    }
+}
+
+// DecompProof implements the CanonicalSerialize trait, leaving out the cached digest.
+impl<E: PairingEngine> CanonicalSerialize for DecompProof<E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.proof.serialize(&mut writer)?;
+        self.gs.serialize(&mut writer)?;
+        self.binding.serialize(&mut writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.proof.serialized_size() + self.gs.serialized_size() + self.binding.serialized_size()
+    }
+
+    fn serialize_uncompressed<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.proof.serialize_uncompressed(&mut writer)?;
+        self.gs.serialize_uncompressed(&mut writer)?;
+        self.binding.serialize_uncompressed(&mut writer)
+    }
+
+    fn uncompressed_size(&self) -> usize {
+        self.proof.uncompressed_size() + self.gs.uncompressed_size() + self.binding.uncompressed_size()
+    }
+
+    fn serialize_unchecked<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        self.proof.serialize_unchecked(&mut writer)?;
+        self.gs.serialize_unchecked(&mut writer)?;
+        self.binding.serialize_unchecked(&mut writer)
+    }
+}
+
+// DecompProof implements the CanonicalDeserialize trait, recomputing the digest.
+impl<E: PairingEngine> CanonicalDeserialize for DecompProof<E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::new(
+            CanonicalDeserialize::deserialize(&mut reader)?,
+            ProofGroup::<E>::deserialize(&mut reader)?,
+            Digest::deserialize(&mut reader)?,
+        ))
+    }
+
+    fn deserialize_uncompressed<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::new(
+            CanonicalDeserialize::deserialize_uncompressed(&mut reader)?,
+            ProofGroup::<E>::deserialize_uncompressed(&mut reader)?,
+            Digest::deserialize_uncompressed(&mut reader)?,
+        ))
+    }
+
+    fn deserialize_unchecked<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::new(
+            CanonicalDeserialize::deserialize_unchecked(&mut reader)?,
+            ProofGroup::<E>::deserialize_unchecked(&mut reader)?,
+            Digest::deserialize_unchecked(&mut reader)?,
+        ))
+    }
+}
+
+
+// Utility function starting the transcript of a session-bound decomposition proof.
+fn session_transcript<E: PairingEngine>(config: &Config<E>,
+                                        binding: &Digest,
+                                        epoch: u64,
+                                        participant_id: usize) -> Transcript {
+    let session = Session { config_digest: config.digest(), epoch, participant_id };
+    let mut transcript = Transcript::for_session(b"OPTRAND-DECOMP", &session);
+    transcript.append_message(b"commitments", &binding.0);
+    transcript
+}
+
+// Function computing the digest a decomposition proof for a core PVSS share with
+// the given commitments is bound to. Encryptions need not be covered, since they
+// are tied to the commitments by the encryption correctness check.
+pub fn commitments_binding<E: PairingEngine>(comms: &Vec<E::G2Projective>) -> Digest {
+    canonical_digest(comms)
 }
 
 impl<E: PairingEngine> Decomp<E> {
 
     // Associated function for generating decomposition proofs.
-    pub fn generate<R: Rng>(rng: &mut R,
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R,
                             config: &Config<E>,
 			    p_0: &Scalar<E>) -> Result<ProofType<E>, PVSSError<E>> {
 	let secret = p_0;
@@ -41,23 +153,191 @@ impl<E: PairingEngine> Decomp<E> {
 	let dlk = DLKProof { srs: dlk_srs };   // initialize proof system for DLK NIZKs.
 
 	// Double-check with Adithya's code for Dleq for increased efficiency/security.
-	let proof = dlk.prove(rng, &secret).unwrap();
+	let proof = dlk.prove(rng, secret).unwrap();
+
+	Ok(DecompProof::new(proof, gs, Digest::default()))
+    }
+
+    // Associated function for generating decomposition proofs bound to the given
+    // commitment vector.
+    pub fn generate_bound<R: Rng + CryptoRng>(rng: &mut R,
+                                  config: &Config<E>,
+                                  p_0: &Scalar<E>,
+                                  comms: &Vec<E::G2Projective>) -> Result<ProofType<E>, PVSSError<E>> {
+	let generator = config.srs.g2;
+	let gs = generator.mul(p_0.into_repr()).into_affine();
+	let binding = commitments_binding::<E>(comms);
+
+	let dlk = DLKProof { srs: DLKSRS::<ProofGroup::<E>> { g_public_key: generator } };
+	let proof = dlk
+	    .prove_with_context(rng, p_0, &binding.0)
+	    .map_err(|_| PVSSError::DecompGenerationError)?;
 
-	Ok(DecompProof { proof, gs })
+	Ok(DecompProof::new(proof, gs, binding))
+    }
+
+    // Associated function for generating decomposition proofs bound to the given
+    // commitment vector and to a session, i.e., to our config, the epoch and the
+    // dealer's id, whose challenge is derived from a domain-separated transcript.
+    // Such proofs cannot be replayed by another dealer or in another epoch.
+    pub fn generate_in_session<R: Rng + CryptoRng>(rng: &mut R,
+                                       config: &Config<E>,
+                                       p_0: &Scalar<E>,
+                                       comms: &Vec<E::G2Projective>,
+                                       epoch: u64,
+                                       participant_id: usize) -> Result<ProofType<E>, PVSSError<E>> {
+	let generator = config.srs.g2;
+	let gs = generator.mul(p_0.into_repr()).into_affine();
+	let binding = commitments_binding::<E>(comms);
+
+	let mut transcript = session_transcript(config, &binding, epoch, participant_id);
+	let dlk = DLKProof { srs: DLKSRS::<ProofGroup::<E>> { g_public_key: generator } };
+	let proof = dlk
+	    .prove_in_transcript(rng, &mut transcript, p_0)
+	    .map_err(|_| PVSSError::DecompGenerationError)?;
+
+	Ok(DecompProof::new(proof, gs, binding))
+    }
+
+    // Associated function for deriving everything a dealer publishes about its
+    // polynomial: the evaluations p(x_0), ..., p(x_{n-1}) at the points of config, the commitments to them and the
+    // decomposition proof for p(0). Before returning, the commitments are checked
+    // to interpolate to the gs of the proof, so that a dealer cannot end up with
+    // commitments that do not match its own proof.
+    pub fn from_polynomial<R: Rng + CryptoRng>(rng: &mut R,
+                                   config: &Config<E>,
+                                   poly: &Polynomial<E>) -> Result<DealingArtifacts<E>, PVSSError<E>> {
+	Self::from_polynomial_with(rng, config, poly, |evals| commit_evals::<E>(&config.srs.g2, evals))
+    }
+
+    // Associated function as from_polynomial, computing the commitments with the
+    // fixed-base tables of the (prepared) SRS of config.
+    pub fn from_polynomial_prepared<R: Rng + CryptoRng>(rng: &mut R,
+                                            config: &Config<E>,
+                                            prepared: &PreparedSRS<E>,
+                                            poly: &Polynomial<E>) -> Result<DealingArtifacts<E>, PVSSError<E>> {
+	Self::from_polynomial_with(rng, config, poly, |evals| prepared.mul_g2(evals))
+    }
+
+    // Utility function deriving the dealing artifacts of a polynomial, with the
+    // given function computing the commitments to its evaluations.
+    fn from_polynomial_with<R, F>(rng: &mut R,
+                                  config: &Config<E>,
+                                  poly: &Polynomial<E>,
+                                  commit: F) -> Result<DealingArtifacts<E>, PVSSError<E>>
+    where
+        R: Rng + CryptoRng,
+        F: FnOnce(&[Scalar<E>]) -> Vec<E::G2Projective>,
+    {
+	if poly.degree() > config.degree {
+	    return Err(PVSSError::DualCodeError);
+	}
+
+	let evals = compute_evals_for::<E>(config, poly);
+	let comms = commit(&evals);
+
+	let p_0 = poly.coeffs.first().cloned().unwrap_or_else(Scalar::<E>::zero);
+	let decomp_proof = Self::generate_bound(rng, config, &p_0, &comms)?;
+
+	// Recompute gs from the commitments and compare.
+	let point = lagrange_interpolation::<E>(&comms, &config.points(), config.degree as u64)?;
+	if point.into_affine() != decomp_proof.gs() {
+	    return Err(PVSSError::GSCheckError);
+	}
+
+	Ok(DealingArtifacts { evals, comms, decomp_proof })
     }
 }
 
+
+// Struct DealingArtifacts bundles the public and secret values a dealer derives
+// from its polynomial, as produced by Decomp::from_polynomial.
+#[derive(Clone, Debug)]
+pub struct DealingArtifacts<E: PairingEngine> {
+    pub evals: Vec<Scalar<E>>,             // the evaluations p(x_0), ..., p(x_{n-1}) (secret)
+    pub comms: Vec<E::G2Projective>,       // the commitments to the evaluations
+    pub decomp_proof: DecompProof<E>,      // the decomposition proof for p(0)
+}
+
 impl<E: PairingEngine> DecompProof<E> {
 
-    // Method for verifying decomposition proofs under some configuration.
+    // Method checking whether the proof is bound to some commitment vector.
+    pub fn is_bound(&self) -> bool {
+        self.binding != Digest::default()
+    }
+
+    // Method for verifying decomposition proofs under some configuration. Bound
+    // proofs are verified under their binding, but the binding itself is not
+    // checked against any commitments (see verify_against_core).
     pub fn verify(&self,
                   config: &Config<E>) -> Result<(), PVSSError<E>> {
 	// Create a proof system for proving knowledge of discrete log
 	let dlk = DLKProof { srs: DLKSRS::<ProofGroup::<E>> { g_public_key: config.srs.g2 } };
+	let context: &[u8] = if self.is_bound() { &self.binding.0 } else { &[] };
+
+	dlk
+           .verify_with_context(&self.gs, &self.proof, context)
+           .map_err(|_| PVSSError::DecompProofVerificationError)
+    }
+
+    // Associated function for verifying many decomposition proofs at once (see
+    // DLKProof::verify_batch_with_context), each under its own binding.
+    pub fn verify_batch<R: Rng + CryptoRng>(rng: &mut R,
+                                config: &Config<E>,
+                                proofs: &[&Self]) -> Result<(), PVSSError<E>> {
+	// An empty batch holds trivially.
+	if proofs.is_empty() {
+	    return Ok(());
+	}
+
+	let dlk = DLKProof { srs: DLKSRS::<ProofGroup::<E>> { g_public_key: config.srs.g2 } };
+	let statements = proofs.iter().map(|p| p.gs).collect::<Vec<_>>();
+	let nizk_proofs = proofs.iter().map(|p| p.proof).collect::<Vec<_>>();
+	let contexts = proofs
+	    .iter()
+	    .map(|p| if p.is_bound() { &p.binding.0[..] } else { &[][..] })
+	    .collect::<Vec<_>>();
 
-	Ok(dlk
-           .verify(&self.gs, &self.proof)
-           .unwrap())                            // TODO: what if the dlk produces an error???
+	dlk
+	   .verify_batch_with_context(rng, &statements, &nizk_proofs, &contexts)
+	   .map_err(|_| PVSSError::DecompProofVerificationError)
+    }
+
+    // Method for verifying decomposition proofs generated with generate_in_session,
+    // for the given epoch and dealer.
+    pub fn verify_in_session(&self,
+                             config: &Config<E>,
+                             epoch: u64,
+                             participant_id: usize) -> Result<(), PVSSError<E>> {
+	let mut transcript = session_transcript(config, &self.binding, epoch, participant_id);
+	let dlk = DLKProof { srs: DLKSRS::<ProofGroup::<E>> { g_public_key: config.srs.g2 } };
+
+	dlk
+           .verify_in_transcript(&mut transcript, &self.gs, &self.proof)
+           .map_err(|_| PVSSError::DecompProofVerificationError)
+    }
+
+    // Method for verifying a decomposition proof as the proof for a specific core
+    // PVSS share: besides the DLK proof, the proof must be bound to the share's
+    // commitments, and gs must equal their interpolation (at 0). The coding check
+    // on the commitments is not included.
+    pub fn verify_against_core(&self,
+                               config: &Config<E>,
+                               share: &PVSSShare<E>) -> Result<(), PVSSError<E>> {
+	if self.binding != commitments_binding::<E>(&share.comms) {
+	    return Err(PVSSError::DecompBindingError);
+	}
+
+	kernel::interpolation_check_at::<E>(&share.comms, &config.points(), config.degree, self.gs.into_projective())?;
+
+	self.verify(config)
+    }
+}
+
+// DecompProof implements the Hash trait, returning the digest cached on construction.
+impl<E: PairingEngine> Hash for DecompProof<E> {
+    fn digest(&self) -> Digest {
+        self.digest
     }
 }
 
@@ -76,10 +356,15 @@ pub fn message_from_pi_i<E: PairingEngine>(pi_i: DecompProof<E>) -> Result<Vec<u
 mod test {
 
     use ark_bls12_381::{Bls12_381 as E};   // implements PairingEngine
-    use ark_poly::UVPolynomial;
+    use ark_ec::AffineCurve;
+    use ark_ff::{One, PrimeField, Zero};
+    use ark_poly::{Polynomial as Poly, UVPolynomial};
 
     use crate::signature::{utils::tests::check_serialization};
-    use crate::modified_scrape::{decomp::Decomp, srs::SRS, poly::Polynomial, config::Config};
+    use crate::{canonical_digest, digest_bytes, Hash, Scalar};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use crate::modified_scrape::{decomp::{commitments_binding, Decomp, DecompProof}, srs::SRS, poly::Polynomial, config::{Config, EvaluationPoints, ShareLayout}};
+    use crate::modified_scrape::{errors::PVSSError, tests::setup_nodes};
 
     use rand::thread_rng;
 
@@ -90,7 +375,7 @@ mod test {
 
 	let t = 3;
 	let n = 10;
-	let conf = Config { srs, degree: t, num_participants: n };
+	let conf = Config { srs, degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
 	let poly = Polynomial::<E>::rand(t, rng);
 
 	let dproof = Decomp::<E>::generate(rng, &conf, &poly.coeffs[0]).unwrap();
@@ -105,12 +390,115 @@ mod test {
 
 	let t = 3;
 	let n = 10;
-	let conf = Config { srs, degree: t, num_participants: n };
+	let conf = Config { srs, degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
 	let poly = Polynomial::<E>::rand(t, rng);
 
 	let dproof = Decomp::<E>::generate(rng, &conf, &poly.coeffs[0]).unwrap();
 
-        check_serialization(dproof.clone());
+        check_serialization(dproof);
+
+        // The cached digest is that of the serialized proof, and survives a round trip.
+        assert_eq!(dproof.digest(), canonical_digest(&dproof));
+        let mut bytes = vec![];
+        dproof.serialize(&mut bytes).unwrap();
+        assert_eq!(DecompProof::<E>::deserialize(&bytes[..]).unwrap().digest(), dproof.digest());
+    }
+
+    #[test]
+    fn test_cached_digest() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        let conf = &nodes[0].aggregator.config.clone();
+        let poly = Polynomial::<E>::rand(1, rng);
+        let unbound = Decomp::<E>::generate(rng, conf, &poly.coeffs[0]).unwrap();
+        let bound = nodes[1].share(rng).unwrap().decomp_proof;
+
+        for dproof in [unbound, bound].iter() {
+            // The cached digest is the one computed afresh from the proof's fields
+            // and from its serialization.
+            let mut bytes = vec![];
+            dproof.serialize(&mut bytes).unwrap();
+            assert_eq!(dproof.digest(), canonical_digest(dproof));
+            assert_eq!(dproof.digest(), digest_bytes(&bytes));
+            assert_eq!(dproof.digest(), DecompProof::<E>::new(dproof.proof(), dproof.gs(), dproof.binding()).digest());
+
+            // Deserialized proofs recompute it rather than trusting any stale value.
+            let decoded = DecompProof::<E>::deserialize(&bytes[..]).unwrap();
+            assert_eq!(decoded.digest(), canonical_digest(&decoded));
+            assert_eq!(decoded.digest(), dproof.digest());
+            let mut unchecked = vec![];
+            dproof.serialize_unchecked(&mut unchecked).unwrap();
+            let decoded = DecompProof::<E>::deserialize_unchecked(&unchecked[..]).unwrap();
+            assert_eq!(decoded.digest(), dproof.digest());
+            let mut uncompressed = vec![];
+            dproof.serialize_uncompressed(&mut uncompressed).unwrap();
+            assert_eq!(DecompProof::<E>::deserialize_uncompressed(&uncompressed[..]).unwrap().digest(), dproof.digest());
+        }
+
+        assert_ne!(unbound.digest(), bound.digest());
+        assert_ne!(DecompProof::<E>::new(bound.proof(), unbound.gs(), bound.binding()).digest(), bound.digest());
+    }
+
+    #[test]
+    fn test_from_polynomial() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+
+	let (t, n) = (3, 10);
+	let conf = Config { srs, degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+	let poly = Polynomial::<E>::rand(t, rng);
+
+	let artifacts = Decomp::<E>::from_polynomial(rng, &conf, &poly).unwrap();
+	assert_eq!(artifacts.evals.len(), n);
+	assert_eq!(artifacts.comms.len(), n);
+	artifacts.decomp_proof.verify(&conf).unwrap();
+
+	// session-bound proofs only verify in their own session
+	let comms = artifacts.comms.clone();
+	let bound = Decomp::<E>::generate_in_session(rng, &conf, &poly.coeffs[0], &comms, 5, 1).unwrap();
+	bound.verify_in_session(&conf, 5, 1).unwrap();
+	assert!(bound.verify_in_session(&conf, 6, 1).is_err());
+	assert!(bound.verify_in_session(&conf, 5, 2).is_err());
+	assert!(bound.verify(&conf).is_err());
+
+	// bound and unbound proofs can be verified together
+	let unbound = Decomp::<E>::generate(rng, &conf, &poly.coeffs[0]).unwrap();
+	DecompProof::verify_batch(rng, &conf, &[&artifacts.decomp_proof, &unbound]).unwrap();
+
+	// polynomials of too high a degree are refused
+	let high = Polynomial::<E>::rand(t + 1, rng);
+	assert!(Decomp::<E>::from_polynomial(rng, &conf, &high).is_err());
+    }
+
+    #[test]
+    fn test_decomp_proof_against_core() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let share = nodes[0].share(rng).unwrap();
+        let other = nodes[1].share(rng).unwrap();
+        let conf = &nodes[0].aggregator.config;
+        share.decomp_proof.verify_against_core(conf, &share.pvss_share).unwrap();
+
+        // a valid proof for some other share is rejected
+        other.decomp_proof.verify(conf).unwrap();
+        assert!(matches!(other.decomp_proof.verify_against_core(conf, &share.pvss_share),
+            Err(PVSSError::DecompBindingError)));
+
+        // as is a proof for the same gs, but bound to different commitments (here,
+        // a multiple of the share's commitments by a polynomial vanishing at 0)
+        let mut mixed = share.pvss_share.clone();
+        let shift = Polynomial::<E>::from_coefficients_vec(vec![Scalar::<E>::zero(), Scalar::<E>::one()]);
+        for (j, comm) in mixed.comms.iter_mut().enumerate() {
+            *comm += conf.srs.g2.mul(shift.evaluate(&Scalar::<E>::from((j + 1) as u64)).into_repr());
+        }
+        assert!(matches!(share.decomp_proof.verify_against_core(conf, &mixed),
+            Err(PVSSError::DecompBindingError)));
+
+        // tampering with the binding invalidates the proof itself
+        let proof = share.decomp_proof;
+        let rebound = DecompProof::new(proof.proof, proof.gs, commitments_binding::<E>(&mixed.comms));
+        assert!(rebound.verify(conf).is_err());
     }
 
 }
@@ -1,24 +1,94 @@
+use super::{config::Config, errors::PVSSError};
+use crate::nizk::{dleq::{DLEQProof, srs::SRS as DLEQSRS}, scheme::NIZKProof};
 use crate::Scalar;
 use ark_ff::{Field, PrimeField};
 use ark_ec::{PairingEngine, AffineCurve, ProjectiveCurve};
+use ark_serialize::*;
+use rand::{CryptoRng, Rng};
+
+pub type DecryptionProof<E> = <DLEQProof<<E as PairingEngine>::G1Affine, <E as PairingEngine>::G1Affine> as NIZKProof>::Proof;
 
 // Struct DecryptedShare represents a decrypted share obtained when a node cancels out its secret
-// key from some given encrypted share.
-// NOTE: It should be noted that without the use of DLEQs, it is not possible to define verification
-// of decryptions.
-#[derive(Clone)]
+// key from some given encrypted share. Since enc = pk^{p(i)} = g_1^{sk * p(i)} and dec = g_1^{p(i)},
+// the decryption is correct iff log_{g_1}(pk) == log_{dec}(enc), which the attached DLEQ proof shows.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct DecryptedShare<E: PairingEngine> {
-    dec: E::G1Affine,   // the decrypted share
-    origin: usize,      // index in the pk_map
+    pub dec: E::G1Affine,             // the decrypted share
+    pub origin: usize,                // index in the pk_map
+    pub proof: DecryptionProof<E>,    // proof of correct decryption
+}
+
+// Utility function returning the DLEQ instance for decryptions into dec: it relates
+// g_1 to the decryption key's public key, and dec to the encrypted share.
+fn dleq<E: PairingEngine>(config: &Config<E>, dec: E::G1Affine) -> DLEQProof<E::G1Affine, E::G1Affine> {
+    DLEQProof { srs: DLEQSRS { g_public_key: config.srs.g1, h_public_key: dec } }
 }
 
 impl<E: PairingEngine> DecryptedShare<E> {
 
-    // Associated function for generating a decrypted share from a given encrypted share.
-    fn generate(enc: &E::G1Affine, sk: &Scalar<E>, my_id: usize) -> DecryptedShare<E> {
+    // Associated function for generating a decrypted share from a given encrypted share,
+    // along with a proof that it was decrypted with the secret key sk.
+    pub fn generate<R: Rng + CryptoRng>(
+	rng: &mut R,
+	config: &Config<E>,
+	enc: &E::G1Affine,
+	sk: &Scalar<E>,
+	my_id: usize,
+    ) -> Result<DecryptedShare<E>, PVSSError<E>> {
 	// dec := enc * sk^{-1}
 	let dec = enc.mul(sk.inverse().unwrap().into_repr()).into_affine();
 
-    	DecryptedShare {dec, origin: my_id}
+	let proof = dleq(config, dec)
+	    .prove(rng, sk)
+	    .map_err(|_| PVSSError::DecryptionProofGenerationError)?;
+
+	Ok(DecryptedShare {dec, origin: my_id, proof})
+    }
+
+    // Method for verifying that the decryption of the encrypted share enc was done with
+    // the secret key matching the public key pk the share was encrypted under.
+    pub fn verify(&self, config: &Config<E>, enc: &E::G1Affine, pk: &E::G1Affine) -> Result<(), PVSSError<E>> {
+	dleq(config, self.dec)
+	    .verify(&(*pk, *enc), &self.proof)
+	    .map_err(|_| PVSSError::DecryptionVerificationError(self.origin))
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{config::{Config, EvaluationPoints, ShareLayout}, decryption::DecryptedShare, srs::SRS};
+    use crate::Scalar;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_verifiable_decryption() {
+        let rng = &mut thread_rng();
+        let config = Config { srs: SRS::<E>::setup(rng).unwrap(), degree: 1, num_participants: 3, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+
+        let sk = Scalar::<E>::rand(rng);
+        let pk = config.srs.g1.mul(sk.into_repr()).into_affine();
+        let eval = Scalar::<E>::rand(rng);
+        let enc = pk.mul(eval.into_repr()).into_affine();
+
+        let decrypted = DecryptedShare::generate(rng, &config, &enc, &sk, 2).unwrap();
+        assert_eq!(decrypted.dec, config.srs.g1.mul(eval.into_repr()).into_affine());
+        decrypted.verify(&config, &enc, &pk).unwrap();
+
+        // A wrong decryption, or one checked against another key or share, is rejected.
+        let mut wrong = decrypted.clone();
+        wrong.dec = config.srs.g1.mul(Scalar::<E>::rand(rng).into_repr()).into_affine();
+        assert!(wrong.verify(&config, &enc, &pk).is_err());
+        let other_pk = config.srs.g1.mul(Scalar::<E>::rand(rng).into_repr()).into_affine();
+        assert!(decrypted.verify(&config, &enc, &other_pk).is_err());
+        assert!(decrypted.verify(&config, &other_pk, &pk).is_err());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,127 @@
+use crate::modified_scrape::{config::Config, errors::PVSSError, escrow::committee_public_key, share::PVSSTranscript};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::One;
+use ark_serialize::*;
+use std::ops::Neg;
+use zeroize::Zeroize;
+
+/* DKG mode. An agreed upon aggregated transcript doubles as the outcome of a
+   distributed key generation for the secret s = p(0) of the aggregated polynomial:
+
+       group public key:          g_2^s                (the weighted sum of the contributors' gs)
+       public key share of i:     g_2^{p(i+1)}         (the aggregated commitment comm_i)
+       secret key share of i:     g_1^{p(i+1)}         (the decryption of the aggregated encryption enc_i)
+
+   Secret key shares are group elements rather than scalars, as in the rest of the
+   protocol, which suits pairing-based threshold schemes (e.g., threshold BLS-style
+   signatures or decryption with keys in G1). Any t + 1 shares determine g_1^s.
+*/
+
+// Struct ThresholdPublicKeys models the public part of the outcome of a DKG.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ThresholdPublicKeys<E: PairingEngine> {
+    pub group_public_key: E::G2Affine,          // g_2^s
+    pub public_key_shares: Vec<E::G2Affine>,    // g_2^{p(i+1)} for participants i = 0, ..., n-1
+}
+
+// Struct ThresholdKeyOutput models a participant's outcome of a DKG. Its secret key
+// share is wiped from memory when dropped.
+pub struct ThresholdKeyOutput<E: PairingEngine> {
+    pub id: usize,                              // id of the participant
+    pub threshold: usize,                       // the threshold t (degree of the polynomial)
+    pub group_public_key: E::G2Affine,          // g_2^s
+    pub public_key_shares: Vec<E::G2Affine>,    // g_2^{p(i+1)} for participants i = 0, ..., n-1
+    pub secret_key_share: E::G1Affine,          // our secret key share g_1^{p(id+1)}
+}
+
+impl<E, SSIG> PVSSTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method returning the public outcome of the DKG the transcript stems from. The
+    // transcript must carry enough contribution weight.
+    pub fn threshold_public_keys(&self) -> Result<ThresholdPublicKeys<E>, PVSSError<E>> {
+        self.check_weight_threshold()?;
+        Ok(ThresholdPublicKeys {
+            group_public_key: committee_public_key(self),
+            public_key_shares: E::G2Projective::batch_normalization_into_affine(&self.pvss_share.comms),
+        })
+    }
+}
+
+impl<E: PairingEngine> ThresholdKeyOutput<E> {
+
+    // Method returning the public part of the output.
+    pub fn public_keys(&self) -> ThresholdPublicKeys<E> {
+        ThresholdPublicKeys {
+            group_public_key: self.group_public_key,
+            public_key_shares: self.public_key_shares.clone(),
+        }
+    }
+
+    // Method for checking that our secret key share matches our public key share,
+    // i.e., that e(secret_key_share, g_2) == e(g_1, public_key_share).
+    pub fn verify(&self, config: &Config<E>) -> Result<(), PVSSError<E>> {
+        let public_key_share = self.public_key_shares.get(self.id).ok_or(PVSSError::<E>::InvalidParticipantId(self.id))?;
+        let pairs = [
+            (self.secret_key_share.into(), config.srs.g2.into()),
+            (config.srs.g1.neg().into(), (*public_key_share).into()),
+        ];
+
+        if !E::product_of_pairings(pairs.iter()).is_one() {
+            return Err(PVSSError::ThresholdKeyShareError(self.id));
+        }
+        Ok(())
+    }
+}
+
+impl<E: PairingEngine> Drop for ThresholdKeyOutput<E> {
+    fn drop(&mut self) {
+        self.secret_key_share.zeroize();
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{errors::PVSSError, tests::setup_nodes};
+
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_threshold_keys() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for node in nodes.iter_mut() {
+            for share in shares.iter() {
+                node.aggregator.receive_share(rng, share).unwrap();
+            }
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+
+        // All nodes agree on the public keys, and hold matching secret key shares.
+        let outputs = nodes.iter().map(|node| node.threshold_keys(&transcript).unwrap()).collect::<Vec<_>>();
+        for output in outputs.iter() {
+            assert_eq!(output.public_keys(), transcript.threshold_public_keys().unwrap());
+            assert_eq!(output.public_key_shares.len(), n);
+        }
+
+        // The secret key shares are those released when decrypting the transcript.
+        let decryption = nodes[2].decrypt(rng, &transcript).unwrap();
+        assert_eq!(outputs[2].secret_key_share, decryption.dec);
+
+        // Another participant's share does not match ours.
+        let (config, mut output) = (&nodes[1].aggregator.config, nodes[1].threshold_keys(&transcript).unwrap());
+        output.secret_key_share = (output.secret_key_share.into_projective() + outputs[0].secret_key_share.into_projective()).into_affine();
+        assert!(matches!(output.verify(config), Err(PVSSError::ThresholdKeyShareError(1))));
+    }
+}
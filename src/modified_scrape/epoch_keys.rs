@@ -0,0 +1,126 @@
+use super::{errors::PVSSError, participant::ParticipantSet};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+
+use ark_ec::PairingEngine;
+use ark_serialize::CanonicalSerialize;
+
+use rand::{CryptoRng, Rng};
+
+/* Epoch-scoped encryption keys provide forward secrecy for the encrypted shares.
+   Each participant may register, for every epoch, an ephemeral (Schnorr) key pair
+   whose public key is signed by its long-term identity key. Shares of that epoch
+   are encrypted under the ephemeral key, and the ephemeral secret is erased once
+   the epoch is over, so that a later compromise of the identity key does not
+   expose past epochs' shares. Participants that did not register a key for an
+   epoch fall back to their identity key.
+*/
+
+const EPOCH_KEY_DOMAIN: &[u8] = b"OPTRAND-EPOCH-KEY";
+
+// Struct EpochKey models a participant's signed encryption key for a single epoch.
+#[derive(Clone)]
+pub struct EpochKey<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub participant_id: usize,         // id of the key's owner
+    pub epoch: u64,                    // epoch the key is valid for
+    pub public_key: E::G1Affine,       // the ephemeral encryption key
+    pub signature: SSIG::Signature,    // owner's identity signature on (id, epoch, public_key)
+}
+
+impl<E, SSIG> EpochKey<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for generating a fresh epoch key pair, certified by the identity key.
+    // Returns the ephemeral secret along with the signed public key.
+    pub fn generate<R: Rng + CryptoRng>(
+        rng: &mut R,
+        scheme_sig: &SSIG,
+        identity_sk: &Scalar<E>,
+        participant_id: usize,
+        epoch: u64,
+    ) -> Result<(Scalar<E>, Self), PVSSError<E>> {
+        let (sk, public_key) = scheme_sig.generate_keypair(rng)?;
+        let message = epoch_key_message::<E>(participant_id, epoch, &public_key)?;
+        let signature = scheme_sig.sign(rng, identity_sk, &message)?;
+
+        Ok((sk, Self { participant_id, epoch, public_key, signature }))
+    }
+
+    // Method for verifying the key's certification against the owner's identity key.
+    pub fn verify(&self, scheme_sig: &SSIG, registry: &ParticipantSet<E, SSIG>) -> Result<(), PVSSError<E>> {
+        let owner = registry
+            .get(&self.participant_id)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(self.participant_id))?;
+
+        let message = epoch_key_message::<E>(self.participant_id, self.epoch, &self.public_key)?;
+        scheme_sig.verify(&owner.public_key_sig, &message, &self.signature)?;
+        Ok(())
+    }
+}
+
+
+// Utility function for computing the message signed when certifying an epoch key.
+fn epoch_key_message<E: PairingEngine>(
+    participant_id: usize,
+    epoch: u64,
+    public_key: &E::G1Affine,
+) -> Result<Vec<u8>, PVSSError<E>> {
+    let mut message = EPOCH_KEY_DOMAIN.to_vec();
+    message.extend_from_slice(&(participant_id as u64).to_be_bytes());
+    message.extend_from_slice(&epoch.to_be_bytes());
+    public_key.serialize(&mut message)?;
+    Ok(message)
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        epoch_keys::EpochKey,
+        errors::PVSSError,
+        tests::{setup_nodes, SSIG},
+    };
+
+    use ark_bls12_381::Bls12_381 as E;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_epoch_key_encryption() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        // Everyone registers an epoch-1 key with everyone.
+        let keys = nodes
+            .iter_mut()
+            .map(|node| node.generate_epoch_key(rng, 1).unwrap())
+            .collect::<Vec<_>>();
+        for node in nodes.iter_mut() {
+            node.aggregator.start_epoch(1).unwrap();
+            for key in keys.iter() {
+                node.aggregator.register_epoch_key(key).unwrap();
+            }
+        }
+
+        // Shares are encrypted under, and decrypted with, the epoch keys.
+        let share = nodes[0].share(rng).unwrap();
+        nodes[1].receive_share_and_decrypt(rng, share.clone()).unwrap();
+        nodes[2].aggregator.receive_share(rng, &share).unwrap();
+
+        // Old epoch secrets can be erased.
+        nodes[1].forget_epoch_keys_before(2);
+        assert!(nodes[1].epoch_secrets.is_empty());
+
+        // A key certified by someone else is rejected.
+        let (_, mut forged) = EpochKey::<E, SSIG>::generate(rng, &nodes[0].aggregator.scheme_sig,
+            nodes[0].dealer.private_key_sig.expose(), 0, 1).unwrap();
+        forged.participant_id = 3;
+        assert!(matches!(nodes[2].aggregator.register_epoch_key(&forged), Err(PVSSError::SignatureError(_))));
+    }
+}
@@ -42,8 +42,14 @@ pub enum PVSSError<E: PairingEngine> {
     TranscriptDifferentCommitments,
     #[error("Decomposition proof does not verify")]
     DecompProofVerificationError,
+    #[error("Decomposition proof is not bound to the share's commitments")]
+    DecompBindingError,
     #[error("Insufficient number of decryptions provided for reconstruction Got: {0}, Expected: >= {1}")]
     InsufficientDecryptionsError(usize, usize),
+    #[error("Could not generate proof of correct decryption")]
+    DecryptionProofGenerationError,
+    #[error("Decryption by participant {0} does not verify")]
+    DecryptionVerificationError(usize),
     #[error("Length mismatch")]
     LengthMismatchError,
     #[error("Correctness of encryption check failed")]
@@ -55,10 +61,96 @@ pub enum PVSSError<E: PairingEngine> {
     EvaluationsCheckError(E::G1Affine),
     #[error("Could not generate evaluation domain")]
     EvaluationDomainError,
+    #[error("Invalid Optrand reference encoding: {0}")]
+    ReferenceEncodingError(String),
+    #[error("Memory budget exceeded: {0} bytes needed, budget is {1} bytes")]
+    MemoryBudgetExceeded(usize, usize),
+    #[error("Proof of correct beacon encryption does not verify")]
+    BeaconEncryptionProofError,
+    #[error("Accumulator SRS supports at most {1} elements, {0} given")]
+    AccumulatorSizeError(usize, usize),
+    #[error("Membership of participant {0}'s contribution does not verify")]
+    AccumulatorMembershipError(usize),
+    #[error("Slashing evidence is invalid: {0}")]
+    InvalidSlashingEvidence(&'static str),
+    #[error("Invalid aggregation tree shape: group size {0}, fanout {1}")]
+    InvalidTreeShape(usize, usize),
+    #[error("Epoch {0} is not later than the current epoch {1}")]
+    StaleEpochError(u64, u64),
+    #[error("Beacon value does not match its proof")]
+    BeaconProofError,
+    #[error("Cannot sample a sub-committee of {0} out of {1} members")]
+    SubcommitteeSizeError(usize, usize),
+    #[error("Sub-committee was not derived from the beacon and roster")]
+    SubcommitteeProofError,
+    #[error("Combined beacon does not match the committees' beacons and transcripts")]
+    CombinedBeaconError,
+    #[error("Participant {0} already registered a different key for epoch {1}")]
+    EpochKeyConflict(usize, u64),
+    #[error("Insufficient contribution weight: {0} given, at least {1} required")]
+    InsufficientWeightError(u64, u64),
+    #[error("Contribution weight exceeds the maximum of {0}")]
+    WeightOverflow(u64),
+    #[error("Digest of commitments and beacon does not match")]
+    CommitmentsDigestError,
+    #[error("Public key appears more than once in the roster")]
+    DuplicatePublicKeyError,
+    #[error("Sparse shares have different recipients")]
+    MismatchedRecipientsError,
+    #[error("Share layout does not match the one in our config")]
+    ShareLayoutError,
+    #[error("Insufficient signatures: {0} given, at least {1} required")]
+    InsufficientSignaturesError(usize, usize),
+    #[error("Point outside the prime-order subgroup")]
+    SubgroupCheckError,
+    #[error("Participants {0} and {1} contributed the same dealing")]
+    DuplicateContributionError(usize, usize),
+    #[error("Participant {0} is not the leader of view {1}")]
+    NotLeaderError(usize, u64),
+    #[error("Message is for view {0}, expected view {1}")]
+    StaleViewError(u64, u64),
+    #[error("Signing key of dealer {0} does not match its public key")]
+    MismatchedDealerKeyError(usize),
+    #[error("Seed does not open the commitment of dealer {0}")]
+    SeedCommitmentError(usize),
+    #[error("Dealing of participant {0} was not derived from the opened seed")]
+    SeedAuditError(usize),
+    #[error("No seed has been committed to")]
+    NoCommittedSeedError,
+    #[error("Contribution is for epoch {0}, expected epoch {1}")]
+    EpochMismatchError(u64, u64),
+    #[error("Insufficient epoch contributions: {0} given, at least {1} required")]
+    InsufficientEpochContributionsError(usize, usize),
+    #[error("Epoch contributions do not interpolate consistently")]
+    InconsistentEpochContributionsError,
+    #[error("Epoch contribution of node {0} does not match its commitment")]
+    InvalidEpochContributionError(usize),
+    #[error("Secret key share of participant {0} does not match its public key share")]
+    ThresholdKeyShareError(usize),
+    #[error("Dealer {0} did not reshare the secret of its contribution")]
+    ReshareSecretMismatchError(usize),
+    #[error("No resharing of the contribution of dealer {0}")]
+    MissingReshareError(usize),
+    #[error("Reshared transcript does not encode the group public key")]
+    ResharedKeyMismatchError,
+    #[error("Complaint is invalid: {0}")]
+    InvalidComplaint(&'static str),
+    #[error("Contribution of dealer {0} was excluded after a complaint")]
+    ExcludedDealerError(usize),
+    #[error("Participant id {0} appears more than once in the committee")]
+    DuplicateParticipantIdError(u64),
+    #[error("Participant id {0} is not in the committee")]
+    UnknownParticipantIdError(u64),
+    #[error("Committee has {0} members, config has {1} participants")]
+    CommitteeSizeError(usize, usize),
+    #[error("Share of participant {0} was rejected: {1}")]
+    InvalidShareError(usize, String),
     #[error("Config, dealer and nodes had different SRSes")]
     DifferentSRS,
     #[error("Signature error: {0}")]
     SignatureError(#[from] SignatureError),
     #[error("Serialization error: {0}")]
     SerializationError(#[from] SerializationError),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
 }
@@ -0,0 +1,211 @@
+use super::{config::Config, decryption::DecryptedShare, errors::PVSSError, poly::lagrange_coefficients, share::PVSSTranscript};
+use crate::nizk::utils::hash::hash_to_field;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{GT, Scalar};
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::*;
+
+use rand::{CryptoRng, Rng};
+
+const PERSONALIZATION: &[u8] = b"OPTRESCR";   // persona for the proof of correct encryption
+
+/* Escrow of beacon values. Upon reconstruction, a node may threshold-encrypt the
+   beacon to a separate consumer committee instead of releasing it. The committee's
+   aggregated public key is the combined commitment pk_C = g_2^{s'} to the secret s'
+   of its own PVSS transcript, and the beacon B is encrypted as:
+
+   c1 = g_2^r,    c2 = B * e(g_1, pk_C)^r
+
+   along with a proof of knowledge of r bound to (pk_C, c1, c2). Any t+1 members of
+   the committee can decrypt by publishing e(dec_j, c1), where dec_j = g_1^{p'(j)} is
+   their decrypted share of the committee's transcript; these combine (in the exponent)
+   into the mask e(g_1^{s'}, g_2^r). Both committees must share the SRS.
+*/
+
+// Struct BeaconCiphertext models the encryption of a beacon value to a committee.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaconCiphertext<E: PairingEngine> {
+    pub c1: E::G2Affine,                     // commitment to the encryption randomness
+    pub c2: GT<E>,                           // the masked beacon value
+    pub proof: (E::G2Affine, Scalar<E>),     // proof of correct encryption (commitment to nonce, response)
+}
+
+// Struct BeaconDecryptionShare models a committee member's share of the decryption mask.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeaconDecryptionShare<E: PairingEngine> {
+    pub value: GT<E>,   // e(dec_j, c1)
+    pub origin: usize,  // index of the committee member
+}
+
+
+// Function returning the aggregated public key of a committee from its transcript,
+// i.e., the combined commitment to the secrets of all contributors.
+pub fn committee_public_key<E, SSIG>(transcript: &PVSSTranscript<E, SSIG>) -> E::G2Affine
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    transcript.weighted_gs().into_affine()
+}
+
+
+// Utility function for computing the challenge of the proof of correct encryption.
+fn challenge<E: PairingEngine>(
+    config: &Config<E>,
+    committee_pk: &E::G2Affine,
+    c1: &E::G2Affine,
+    c2: &GT<E>,
+    nonce_comm: &E::G2Affine,
+) -> Result<Scalar<E>, PVSSError<E>> {
+    let mut bytes = vec![];
+    config.srs.g1.serialize(&mut bytes)?;
+    config.srs.g2.serialize(&mut bytes)?;
+    committee_pk.serialize(&mut bytes)?;
+    c1.serialize(&mut bytes)?;
+    c2.serialize(&mut bytes)?;
+    nonce_comm.serialize(&mut bytes)?;
+
+    hash_to_field::<Scalar<E>>(PERSONALIZATION, &bytes).map_err(|_| PVSSError::BeaconEncryptionProofError)
+}
+
+
+impl<E: PairingEngine> BeaconCiphertext<E> {
+
+    // Function for encrypting a beacon value to the committee with the given public key.
+    pub fn encrypt<R: Rng + CryptoRng>(
+        rng: &mut R,
+        config: &Config<E>,
+        committee_pk: &E::G2Affine,
+        beacon: &GT<E>,
+    ) -> Result<Self, PVSSError<E>> {
+        let r = Scalar::<E>::rand(rng);
+
+        let c1 = config.srs.g2.mul(r.into_repr()).into_affine();
+        let mask = E::pairing(config.srs.g1, committee_pk.mul(r.into_repr()));
+        let c2 = *beacon * mask;
+
+        // Prove knowledge of r, binding the proof to the ciphertext.
+        let k = Scalar::<E>::rand(rng);
+        let nonce_comm = config.srs.g2.mul(k.into_repr()).into_affine();
+        let e = challenge(config, committee_pk, &c1, &c2, &nonce_comm)?;
+        let z = k + (e * r);
+
+        Ok(Self { c1, c2, proof: (nonce_comm, z) })
+    }
+
+    // Method for verifying the proof of correct encryption: g_2^z == a * c1^e.
+    pub fn verify(&self, config: &Config<E>, committee_pk: &E::G2Affine) -> Result<(), PVSSError<E>> {
+        let (nonce_comm, z) = self.proof;
+        let e = challenge(config, committee_pk, &self.c1, &self.c2, &nonce_comm)?;
+
+        let lhs = config.srs.g2.mul(z.into_repr());
+        let rhs = nonce_comm.into_projective() + self.c1.mul(e.into_repr());
+
+        if lhs != rhs {
+            return Err(PVSSError::BeaconEncryptionProofError);
+        }
+
+        Ok(())
+    }
+
+    // Method for computing a committee member's decryption share from its decrypted
+    // share of the committee's transcript.
+    pub fn decryption_share(&self, dec: &DecryptedShare<E>) -> BeaconDecryptionShare<E> {
+        BeaconDecryptionShare {
+            value: E::pairing(dec.dec, self.c1),
+            origin: dec.origin,
+        }
+    }
+
+    // Method for recovering the beacon value from t+1 decryption shares.
+    pub fn decrypt(&self, shares: &[BeaconDecryptionShare<E>], config: &Config<E>) -> Result<GT<E>, PVSSError<E>> {
+        let degree = config.degree;
+        if shares.len() <= degree {
+            return Err(PVSSError::InsufficientDecryptionsError(shares.len(), degree + 1));
+        }
+
+        let points = shares
+            .iter()
+            .map(|s| config.point(s.origin))
+            .collect::<Vec<_>>();
+
+        // Lagrange interpolation in the exponent (over GT)
+        let lambdas = lagrange_coefficients::<E>(&points[..degree+1], Scalar::<E>::zero())?;
+        let mut mask = GT::<E>::one();
+
+        for (share, lambda) in shares.iter().zip(lambdas.iter()) {
+            mask *= &share.value.pow(lambda.into_repr());
+        }
+
+        let mask_inv = mask.inverse().ok_or(PVSSError::BeaconEncryptionProofError)?;
+        Ok(self.c2 * mask_inv)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};   // implements PairingEngine
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::{PrimeField, UniformRand};
+    use ark_poly::{Polynomial as Poly, UVPolynomial};
+
+    use crate::modified_scrape::{
+        config::{Config, EvaluationPoints, ShareLayout}, decryption::DecryptedShare, escrow::BeaconCiphertext, poly::Polynomial, srs::SRS,
+    };
+    use crate::{GT, Scalar};
+
+    use rand::thread_rng;
+
+    // Sets up a committee secret p'(0) of degree t, returning its public key and
+    // the members' decrypted shares g_1^{p'(j)}, each decrypted under a fresh key.
+    fn committee(conf: &Config<E>) -> (<E as PairingEngine>::G2Affine, Vec<DecryptedShare<E>>) {
+        let rng = &mut thread_rng();
+        let poly = Polynomial::<E>::rand(conf.degree, rng);
+
+        let pk = conf.srs.g2.mul(poly.coeffs[0].into_repr()).into_affine();
+        let decs = (0..conf.num_participants)
+            .map(|j| {
+                let sk = Scalar::<E>::rand(rng);
+                let eval = poly.evaluate(&conf.point(j)) * sk;
+                let enc = conf.srs.g1.mul(eval.into_repr()).into_affine();
+                DecryptedShare::generate(rng, conf, &enc, &sk, j).unwrap()
+            })
+            .collect();
+
+        (pk, decs)
+    }
+
+    #[test]
+    fn test_escrow_roundtrip() {
+        let rng = &mut thread_rng();
+        let conf = Config { srs: SRS::<E>::setup(rng).unwrap(), degree: 3, num_participants: 10, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+        let (pk, decs) = committee(&conf);
+
+        let beacon = GT::<E>::rand(rng);
+        let ct = BeaconCiphertext::encrypt(rng, &conf, &pk, &beacon).unwrap();
+        ct.verify(&conf, &pk).unwrap();
+
+        // any t+1 members suffice
+        let shares = decs[5..9].iter().map(|d| ct.decryption_share(d)).collect::<Vec<_>>();
+        assert_eq!(ct.decrypt(&shares, &conf).unwrap(), beacon);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_escrow_tampered() {
+        let rng = &mut thread_rng();
+        let conf = Config { srs: SRS::<E>::setup(rng).unwrap(), degree: 3, num_participants: 10, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+        let (pk, _) = committee(&conf);
+
+        let beacon = GT::<E>::rand(rng);
+        let mut ct = BeaconCiphertext::encrypt(rng, &conf, &pk, &beacon).unwrap();
+        ct.c2 *= &GT::<E>::rand(rng);
+
+        ct.verify(&conf, &pk).unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+use crate::modified_scrape::config::Config;
+use crate::modified_scrape::errors::PVSSError;
+use crate::modified_scrape::kernel;
+use crate::modified_scrape::participant::Participant;
+use crate::modified_scrape::share::PVSSTranscript;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{canonical_digest, Digest, Hash, Scalar};
+
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+
+/* Struct FinalizedTranscript models the outcome of an epoch's dealing phase: the
+   aggregated transcript the committee settled on, certified by the signatures of
+   (at least) t+1 committee members on its digest. Since at most t members are
+   corrupt, a certified transcript was verified and accepted by some honest member.
+*/
+
+// Struct Registry holds what is needed to check a transcript besides the config: the
+// signature scheme, the participants of the epoch and, for participants that used
+// epoch keys, the encryption keys they registered (see PVSSAggregator::epoch_keys).
+#[derive(Clone)]
+pub struct Registry<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub scheme: SSIG,                                             // the signature scheme
+    pub participants: BTreeMap<usize, Participant<E, SSIG>>,      // the epoch's participants
+    pub encryption_keys: BTreeMap<usize, E::G1Affine>,            // epoch keys, overriding identity keys
+}
+
+impl<E, SSIG> Registry<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+
+    // Function for creating a registry in which everyone encrypts under their identity key.
+    pub fn new(scheme: SSIG, participants: BTreeMap<usize, Participant<E, SSIG>>) -> Self {
+        Self {
+            scheme,
+            participants,
+            encryption_keys: BTreeMap::new(),
+        }
+    }
+
+    // Method returning the key the shares of participant id were encrypted under.
+    pub fn encryption_key(&self, id: usize) -> Result<E::G1Affine, PVSSError<E>> {
+        if let Some(key) = self.encryption_keys.get(&id) {
+            return Ok(*key);
+        }
+
+        self.participants
+            .get(&id)
+            .map(|participant| participant.public_key_sig)
+            .ok_or(PVSSError::InvalidParticipantId(id))
+    }
+}
+
+// Struct ReverifyOptions selects the checks run when re-verifying a stored transcript.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReverifyOptions {
+    pub check_certificate: bool,   // verify the signatures certifying the transcript
+    pub trust_certificate: bool,   // skip the checks a valid certificate attests to
+}
+
+// By default, everything is checked from scratch.
+impl Default for ReverifyOptions {
+    fn default() -> Self {
+        Self { check_certificate: true, trust_certificate: false }
+    }
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct FinalizedTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub epoch: u64,                                     // the epoch the transcript belongs to
+    pub transcript: PVSSTranscript<E, SSIG>,            // the aggregated transcript
+    pub signatures: BTreeMap<usize, SSIG::Signature>,   // committee members' signatures on message()
+}
+
+impl<E, SSIG> FinalizedTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+
+    // Function for creating a (yet uncertified) finalized transcript.
+    pub fn new(epoch: u64, transcript: PVSSTranscript<E, SSIG>) -> Self {
+        Self {
+            epoch,
+            transcript,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    // Method returning the message committee members sign: the epoch, followed by
+    // the digest of the transcript.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = self.epoch.to_be_bytes().to_vec();
+        message.extend_from_slice(&self.transcript.digest().0);
+        message
+    }
+
+    // Method returning the number of signatures needed for certification, i.e., t+1.
+    pub fn quorum(&self) -> usize {
+        self.transcript.degree + 1
+    }
+
+    // Method for signing the transcript as committee member id.
+    pub fn sign<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        scheme: &SSIG,
+        id: usize,
+        sk: &SSIG::Secret,
+    ) -> Result<(), PVSSError<E>> {
+        let signature = scheme.sign(rng, sk, &self.message())?;
+        self.signatures.insert(id, signature);
+        Ok(())
+    }
+
+    // Method for adding a signature received from committee member id.
+    pub fn add_signature(&mut self, id: usize, signature: SSIG::Signature) {
+        self.signatures.insert(id, signature);
+    }
+
+    // Method for verifying the certification of the transcript: there must be a
+    // quorum of signatures, all by registered participants and all valid. Note that
+    // the transcript itself is not verified here (see PVSSAggregator::transcript_verify).
+    pub fn verify_certificate<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        scheme: &SSIG,
+        participants: &BTreeMap<usize, Participant<E, SSIG>>,
+    ) -> Result<(), PVSSError<E>> {
+        if self.signatures.len() < self.quorum() {
+            return Err(PVSSError::InsufficientSignaturesError(self.signatures.len(), self.quorum()));
+        }
+
+        let public_keys = self
+            .signatures
+            .keys()
+            .map(|id| participants
+                .get(id)
+                .map(|participant| &participant.public_key_sig)
+                .ok_or(PVSSError::<E>::InvalidParticipantId(*id)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let message = self.message();
+        scheme.batch_verify(
+            rng,
+            &public_keys,
+            &vec![message.as_slice(); public_keys.len()],
+            &self.signatures.values().collect::<Vec<_>>(),
+        )?;
+
+        Ok(())
+    }
+
+    // Method for re-verifying a stored transcript at a later date, e.g., by archival
+    // services, relying on no aggregator state but the given config and registry.
+    // Cheap structural checks always run. With trust_certificate set (which requires
+    // check_certificate), the cryptographic checks of the transcript are skipped, since
+    // the t+1 signers include an honest member that ran them; otherwise the coding,
+    // decomposition, signature, interpolation and encryption checks (see kernel) run.
+    pub fn reverify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        config: &Config<E>,
+        registry: &Registry<E, SSIG>,
+        options: ReverifyOptions,
+    ) -> Result<(), PVSSError<E>> {
+        let transcript = &self.transcript;
+        let share = &transcript.pvss_share;
+        let n = config.num_participants;
+
+        if transcript.degree != config.degree || transcript.num_participants != n {
+            return Err(PVSSError::TranscriptDifferentConfig(config.degree, transcript.degree, n, transcript.num_participants));
+        }
+        if share.encs.len() != n || share.comms.len() != n {
+            return Err(PVSSError::LengthMismatchError);
+        }
+        transcript.check_weight_threshold()?;
+
+        if options.check_certificate {
+            self.verify_certificate(rng, &registry.scheme, &registry.participants)?;
+            if options.trust_certificate {
+                return Ok(());
+            }
+        }
+
+        kernel::coding_check_for::<E, R>(rng, config, &share.comms)?;
+
+        let mut gs_total = E::G2Projective::zero();
+        for contribution in transcript.contributions.values() {
+            if contribution.weight == 0 {
+                return Err(PVSSError::InsufficientWeightError(0, 1));
+            }
+            gs_total += contribution.decomp_proof.gs().mul(Scalar::<E>::from(contribution.weight).into_repr());
+        }
+        transcript.verify_contributions(rng, config, &registry.scheme, &registry.participants)?;
+
+        kernel::interpolation_check_at::<E>(&share.comms, &config.points(), config.degree, gs_total)?;
+
+        let entries = (0..n)
+            .map(|id| Ok((registry.encryption_key(id)?, &share.encs[id], &share.comms[id])))
+            .collect::<Result<Vec<_>, PVSSError<E>>>()?;
+        kernel::encryptions_check::<E, R>(rng, config.srs.g2, &entries)
+    }
+}
+
+// FinalizedTranscript implements the Hash trait.
+impl<E, SSIG> Hash for FinalizedTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn digest(&self) -> Digest {
+        canonical_digest(self)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::PairingEngine;
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{
+        errors::PVSSError,
+        finalized::{FinalizedTranscript, Registry, ReverifyOptions},
+        tests::setup_nodes,
+    };
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_certification() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, sks) = setup_nodes(n, t);
+
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let aggregator = &nodes[0].aggregator;
+        let mut finalized = FinalizedTranscript::new(3, aggregator.transcript.clone());
+
+        // t signatures do not suffice, t+1 do.
+        finalized.sign(rng, &aggregator.scheme_sig, 2, &sks[2]).unwrap();
+        assert!(matches!(finalized.verify_certificate(rng, &aggregator.scheme_sig, &aggregator.participants),
+                         Err(PVSSError::InsufficientSignaturesError(1, 2))));
+        finalized.sign(rng, &aggregator.scheme_sig, 3, &sks[3]).unwrap();
+        finalized.verify_certificate(rng, &aggregator.scheme_sig, &aggregator.participants).unwrap();
+
+        // Signatures are bound to the epoch and the transcript, and to their signer.
+        let mut other = finalized.clone();
+        other.epoch = 4;
+        assert!(other.verify_certificate(rng, &aggregator.scheme_sig, &aggregator.participants).is_err());
+        let mut other = finalized.clone();
+        other.transcript.pvss_share.encs[0] = <E as PairingEngine>::G1Projective::rand(rng);
+        assert!(other.verify_certificate(rng, &aggregator.scheme_sig, &aggregator.participants).is_err());
+        let mut other = finalized.clone();
+        let signature = other.signatures.remove(&3).unwrap();
+        other.add_signature(1, signature);
+        assert!(other.verify_certificate(rng, &aggregator.scheme_sig, &aggregator.participants).is_err());
+    }
+
+    #[test]
+    fn test_reverify() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, sks) = setup_nodes(n, t);
+
+        for i in 0..n {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[0].aggregator.receive_share(rng, &share).unwrap();
+        }
+        let config = nodes[0].aggregator.config.clone();
+        let registry = Registry::new(nodes[0].aggregator.scheme_sig.clone(), nodes[0].aggregator.participants.clone());
+
+        let mut finalized = FinalizedTranscript::new(0, nodes[0].aggregator.transcript.clone());
+        for (id, sk) in sks.iter().enumerate().take(t + 1) {
+            finalized.sign(rng, &registry.scheme, id, sk).unwrap();
+        }
+
+        // The stored transcript re-verifies in full, and when trusting its certificate.
+        finalized.reverify(rng, &config, &registry, ReverifyOptions::default()).unwrap();
+        let trusting = ReverifyOptions { check_certificate: true, trust_certificate: true };
+        finalized.reverify(rng, &config, &registry, trusting).unwrap();
+
+        // A certified but corrupted transcript is only caught by the full checks. Since its
+        // digest changed, re-certify it (as t+1 colluding signers could).
+        let mut bad = FinalizedTranscript::new(0, finalized.transcript.clone());
+        bad.transcript.pvss_share.encs[2] = <E as PairingEngine>::G1Projective::rand(rng);
+        for (id, sk) in sks.iter().enumerate().take(t + 1) {
+            bad.sign(rng, &registry.scheme, id, sk).unwrap();
+        }
+        bad.reverify(rng, &config, &registry, trusting).unwrap();
+        assert!(matches!(bad.reverify(rng, &config, &registry, ReverifyOptions::default()),
+                         Err(PVSSError::EncryptionCorrectnessError)));
+
+        // Without enough signatures, the certificate check fails unless skipped.
+        finalized.signatures.remove(&0);
+        assert!(finalized.reverify(rng, &config, &registry, ReverifyOptions::default()).is_err());
+        let uncertified = ReverifyOptions { check_certificate: false, trust_certificate: false };
+        finalized.reverify(rng, &config, &registry, uncertified).unwrap();
+    }
+}
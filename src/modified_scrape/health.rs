@@ -0,0 +1,66 @@
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/* Struct HealthReport is the outcome of a node's self-test (see Node::self_test).
+   Each check is reported separately so that an operator health endpoint can tell
+   a misconfigured roster from, e.g., a broken clock.
+*/
+
+// Enumeration CheckStatus is the outcome of a single health check.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckStatus {
+    Passed,
+    Failed(String),   // reason for the failure
+}
+
+impl CheckStatus {
+
+    // Method checking whether the check passed.
+    pub fn is_passed(&self) -> bool {
+        *self == CheckStatus::Passed
+    }
+}
+
+// CheckStatus implements the Display trait.
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckStatus::Passed => write!(f, "ok"),
+            CheckStatus::Failed(reason) => write!(f, "FAILED ({})", reason),
+        }
+    }
+}
+
+
+#[derive(Clone, Debug)]
+pub struct HealthReport {
+    pub srs: CheckStatus,             // the SRS generators are well-formed
+    pub config: CheckStatus,          // degree, roster and number of participants agree
+    pub keys: CheckStatus,            // our signing key matches our registered public key
+    pub clock: CheckStatus,           // the system clock is sane and agrees with the monotonic clock
+    pub ceremony: CheckStatus,        // a local deal/verify/decrypt/reconstruct run succeeded
+    pub ceremony_time: Duration,      // time taken by the local ceremony
+    pub checked_at: SystemTime,       // when the self-test started
+}
+
+impl HealthReport {
+
+    // Method checking whether every check passed.
+    pub fn is_healthy(&self) -> bool {
+        [&self.srs, &self.config, &self.keys, &self.clock, &self.ceremony]
+            .iter()
+            .all(|check| check.is_passed())
+    }
+}
+
+// HealthReport implements the Display trait.
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Node health: {}", if self.is_healthy() { "healthy" } else { "UNHEALTHY" })?;
+        writeln!(f, "  srs:       {}", self.srs)?;
+        writeln!(f, "  config:    {}", self.config)?;
+        writeln!(f, "  keys:      {}", self.keys)?;
+        writeln!(f, "  clock:     {}", self.clock)?;
+        write!(f, "  ceremony:  {} in {:?}", self.ceremony, self.ceremony_time)
+    }
+}
@@ -0,0 +1,346 @@
+use crate::modified_scrape::config::Config;
+use crate::modified_scrape::decomp::{message_from_pi_i, DecompProof};
+use crate::modified_scrape::errors::PVSSError;
+use crate::modified_scrape::pvss::PVSSShare;
+use crate::modified_scrape::poly::{
+    ensure_degree, ensure_degree_fiat_shamir, ensure_degree_in_domain, ensure_degree_in_domain_fiat_shamir,
+    ensure_degree_in_domain_with_seed, ensure_degree_with_seed, lagrange_interpolation, lagrange_interpolation_simple,
+};
+use crate::signature::scheme::SignatureScheme;
+use crate::Scalar;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{FpParameters, One, PrimeField, UniformRand, Zero};
+use ark_poly::Radix2EvaluationDomain;
+
+use rand::{CryptoRng, Rng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::ops::Neg;
+
+/* Verification kernel. This module collects the equations a verifier of the
+   Modified SCRAPE PVSS ultimately relies on, as free functions over explicit inputs:
+   they perform no I/O, keep no state and never consult a participant roster or
+   cache. Everything else (the aggregator's bookkeeping, caching, metrics, staging,
+   epochs, ...) only decides *which* inputs are fed into these functions, so that
+   audits of the protocol's soundness can be confined to this file and to the
+   primitives it calls (poly.rs for the dual-code and interpolation arithmetic,
+   DecompProof::verify_against_core, the DLK proof system and the signature scheme).
+
+   The checks, for a threshold t, n recipients, generators g_1 in G1 and g_2 in G2:
+   1. encryption: e(pk_i, comm_i) == e(enc_i, g_2), for every recipient i;
+   2. coding: comm_1, ..., comm_n are evaluations of a degree t polynomial in the
+      exponent of g_2;
+   3. decomposition: the commitments interpolate to gs = g_2^s at 0, the proof of
+      knowledge of s verifies and, for bound proofs, the proof is bound to the
+      commitments;
+   4. signature: the dealer's signature on its decomposition proof verifies
+      under the dealer's signing key.
+
+   The subgroup and reverse interpolation checks are redundant given the above (and
+   the deserialization checks on points), and only run at higher strictness levels.
+*/
+
+// Encryption check for a single recipient: e(pk, comm) == e(enc, g_2).
+pub fn encryption_check<E: PairingEngine>(
+    g2: E::G2Affine,
+    pk: E::G1Affine,
+    enc: &E::G1Projective,
+    comm: &E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    encryption_check_prepared::<E>(&g2.neg().into(), &pk.into(), enc, comm)
+}
+
+// Encryption check for a single recipient as above, with -g_2 and pk prepared (see
+// PreparedConfig).
+pub fn encryption_check_prepared<E: PairingEngine>(
+    neg_g2: &E::G2Prepared,
+    pk: &E::G1Prepared,
+    enc: &E::G1Projective,
+    comm: &E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    let pairs = [
+        (pk.clone(), comm.into_affine().into()),
+        (enc.into_affine().into(), neg_g2.clone()),
+    ];
+
+    if !E::product_of_pairings(pairs.iter()).is_one() {
+        return Err(PVSSError::EncryptionCorrectnessError);
+    }
+
+    Ok(())
+}
+
+// Encryption check for many (key, encryption, commitment) triples at once. The
+// conditions are combined using independent random coefficients r_i, so that only
+// k+1 pairings need to be computed for k triples:
+// prod_i e(pk_i, r_i * comm_i) * e(-sum_i r_i * enc_i, g_2) == 1
+// A false triple passes with probability at most 1/|Fr|. The coefficients must be
+// drawn independently: with a common r, the check only constrains the sum of the
+// encryptions, and errors that cancel out in it go unnoticed. With the parallel feature,
+// the terms of the product are computed concurrently.
+pub fn encryptions_check<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    g2: E::G2Affine,
+    entries: &[(E::G1Affine, &E::G1Projective, &E::G2Projective)],
+) -> Result<(), PVSSError<E>> {
+    let prepared = entries
+        .iter()
+        .map(|(pk, enc, comm)| ((*pk).into(), *enc, *comm))
+        .collect::<Vec<_>>();
+    encryptions_check_prepared::<E, R>(rng, &g2.neg().into(), &prepared)
+}
+
+// A pair of prepared inputs to a product of pairings.
+type PreparedPair<E> = (<E as PairingEngine>::G1Prepared, <E as PairingEngine>::G2Prepared);
+
+// Encryption check for many triples as above, with -g_2 and the keys prepared (see
+// PreparedConfig).
+pub fn encryptions_check_prepared<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    neg_g2: &E::G2Prepared,
+    entries: &[(E::G1Prepared, &E::G1Projective, &E::G2Projective)],
+) -> Result<(), PVSSError<E>> {
+    let rs = entries.iter().map(|_| Scalar::<E>::rand(rng).into_repr()).collect::<Vec<_>>();
+
+    #[cfg(not(feature = "parallel"))]
+    let terms = entries.iter().zip(rs.iter());
+    #[cfg(feature = "parallel")]
+    let terms = entries.par_iter().zip(rs.par_iter());
+
+    let (mut pairs, enc_terms): (Vec<PreparedPair<E>>, Vec<E::G1Projective>) = terms
+        .map(|((pk, enc, comm), r)| ((pk.clone(), comm.mul(*r).into_affine().into()), enc.mul(*r)))
+        .unzip();
+    let enc_sum = enc_terms.iter().fold(E::G1Projective::zero(), |sum, term| sum + term);
+
+    pairs.push((enc_sum.into_affine().into(), neg_g2.clone()));
+
+    if !E::product_of_pairings(pairs.iter()).is_one() {
+        return Err(PVSSError::EncryptionCorrectnessError);
+    }
+
+    Ok(())
+}
+
+// Coding check: the commitments are evaluations (at 1, ..., n) of a polynomial of
+// degree at most t in the exponent. The dual-code vector is sampled from rng.
+pub fn coding_check<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    comms: &[E::G2Projective],
+    degree: usize,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree::<E, R>(rng, comms, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+// Coding check as above, with the dual-code vector derived from the commitments
+// themselves (Fiat-Shamir), so that every verifier performs the identical check.
+pub fn coding_check_fiat_shamir<E: PairingEngine>(
+    comms: &[E::G2Projective],
+    degree: usize,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree_fiat_shamir::<E>(comms, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+// Coding check as above, with the dual-code vector derived from the commitments and
+// an externally supplied seed shared by all verifiers (e.g., a block hash).
+pub fn coding_check_with_seed<E: PairingEngine>(
+    seed: &[u8],
+    comms: &[E::G2Projective],
+    degree: usize,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree_with_seed::<E>(seed, comms, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+// Coding checks as the three above, for commitments to the evaluations at the points
+// of a radix-2 domain (see EvaluationPoints).
+pub fn coding_check_in_domain<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    comms: &[E::G2Projective],
+    degree: usize,
+    domain: &Radix2EvaluationDomain<Scalar<E>>,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree_in_domain::<E, R>(rng, comms, domain, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+pub fn coding_check_in_domain_fiat_shamir<E: PairingEngine>(
+    comms: &[E::G2Projective],
+    degree: usize,
+    domain: &Radix2EvaluationDomain<Scalar<E>>,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree_in_domain_fiat_shamir::<E>(comms, domain, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+pub fn coding_check_in_domain_with_seed<E: PairingEngine>(
+    seed: &[u8],
+    comms: &[E::G2Projective],
+    degree: usize,
+    domain: &Radix2EvaluationDomain<Scalar<E>>,
+) -> Result<(), PVSSError<E>> {
+    ensure_degree_in_domain_with_seed::<E>(seed, comms, domain, degree as u64).map_err(|_| PVSSError::DualCodeError)
+}
+
+// Interactive coding check against the degree and evaluation points of config.
+pub fn coding_check_for<E: PairingEngine, R: Rng + CryptoRng>(
+    rng: &mut R,
+    config: &Config<E>,
+    comms: &[E::G2Projective],
+) -> Result<(), PVSSError<E>> {
+    match config.domain() {
+        Some(domain) => coding_check_in_domain::<E, R>(rng, comms, config.degree, &domain),
+        None => coding_check::<E, R>(rng, comms, config.degree),
+    }
+}
+
+// Interpolation check: the commitments interpolate to gs at 0. For aggregated
+// shares, gs is the weighted sum of the contributors' commitments to their secrets.
+pub fn interpolation_check<E: PairingEngine>(
+    comms: &[E::G2Projective],
+    degree: usize,
+    gs: E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    if lagrange_interpolation_simple::<E>(comms, degree as u64)? != gs {
+        return Err(PVSSError::GSCheckError);
+    }
+
+    Ok(())
+}
+
+// Interpolation check as above, for commitments at the given points (one per
+// commitment, e.g., those of a config).
+pub fn interpolation_check_at<E: PairingEngine>(
+    comms: &[E::G2Projective],
+    points: &[Scalar<E>],
+    degree: usize,
+    gs: E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    if lagrange_interpolation::<E>(comms, points, degree as u64)? != gs {
+        return Err(PVSSError::GSCheckError);
+    }
+
+    Ok(())
+}
+
+// Reverse interpolation check: like interpolation_check, but from the last t+1
+// commitments instead of the first ones, at the degree and evaluation points of config.
+pub fn reverse_interpolation_check<E: PairingEngine>(
+    config: &Config<E>,
+    comms: &[E::G2Projective],
+    gs: E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    reverse_interpolation_check_at::<E>(comms, &config.points(), config.degree, gs)
+}
+
+// Reverse interpolation check as above, for commitments at the given points.
+pub fn reverse_interpolation_check_at<E: PairingEngine>(
+    comms: &[E::G2Projective],
+    points: &[Scalar<E>],
+    degree: usize,
+    gs: E::G2Projective,
+) -> Result<(), PVSSError<E>> {
+    if comms.len() <= degree {
+        return Err(PVSSError::InsufficientEvaluationsError);
+    }
+    if comms.len() != points.len() {
+        return Err(PVSSError::DifferentPointsEvalsError);
+    }
+
+    let first = comms.len() - degree - 1;
+    if lagrange_interpolation::<E>(&comms[first..], &points[first..], degree as u64)? != gs {
+        return Err(PVSSError::GSCheckError);
+    }
+
+    Ok(())
+}
+
+// Subgroup check: the encryptions and commitments lie in the prime-order subgroups
+// of G1 and G2, respectively.
+pub fn subgroup_check<E: PairingEngine>(
+    encs: &[E::G1Projective],
+    comms: &[E::G2Projective],
+) -> Result<(), PVSSError<E>> {
+    let modulus = <Scalar<E> as PrimeField>::Params::MODULUS;
+    let encs_ok = E::G1Projective::batch_normalization_into_affine(encs)
+        .iter()
+        .all(|enc| enc.mul(modulus).is_zero());
+    let comms_ok = E::G2Projective::batch_normalization_into_affine(comms)
+        .iter()
+        .all(|comm| comm.mul(modulus).is_zero());
+
+    if !encs_ok || !comms_ok {
+        return Err(PVSSError::SubgroupCheckError);
+    }
+
+    Ok(())
+}
+
+// Decomposition check of a dealer's proof against the commitments of its core share
+// (see DecompProof::verify_against_core).
+pub fn decomposition_check<E: PairingEngine>(
+    config: &Config<E>,
+    proof: &DecompProof<E>,
+    share: &PVSSShare<E>,
+) -> Result<(), PVSSError<E>> {
+    proof.verify_against_core(config, share)
+}
+
+// Signature check: the dealer's signature on the (serialized) decomposition proof.
+pub fn decomp_signature_check<E: PairingEngine, SSIG: SignatureScheme>(
+    scheme: &SSIG,
+    public_key: &SSIG::PublicKey,
+    proof: &DecompProof<E>,
+    signature: &SSIG::Signature,
+) -> Result<(), PVSSError<E>> {
+    scheme.verify(public_key, &message_from_pi_i(*proof)?, signature)?;
+    Ok(())
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, PairingEngine};
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{errors::PVSSError, kernel, tests::setup_nodes};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_kernel() {
+        let rng = &mut thread_rng();
+        let (n, t) = (5, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let share = nodes[1].share(rng).unwrap();
+        let aggregator = &nodes[0].aggregator;
+        let (config, core) = (&aggregator.config, &share.pvss_share);
+
+        // All checks pass on an honestly dealt share.
+        let keys = (0..n).map(|i| aggregator.encryption_key(i).unwrap()).collect::<Vec<_>>();
+        let entries = (0..n).map(|i| (keys[i], &core.encs[i], &core.comms[i])).collect::<Vec<_>>();
+        kernel::encryption_check::<E>(config.srs.g2, keys[3], &core.encs[3], &core.comms[3]).unwrap();
+        kernel::encryptions_check::<E, _>(rng, config.srs.g2, &entries).unwrap();
+        kernel::coding_check::<E, _>(rng, &core.comms, t).unwrap();
+        kernel::coding_check_fiat_shamir::<E>(&core.comms, t).unwrap();
+        kernel::decomposition_check::<E>(config, &share.decomp_proof, core).unwrap();
+        kernel::reverse_interpolation_check::<E>(config, &core.comms, share.decomp_proof.gs().into_projective()).unwrap();
+        let signing_key = aggregator.participants[&1].public_key_sig;
+        kernel::decomp_signature_check(&aggregator.scheme_sig, &signing_key, &share.decomp_proof, &share.signature_on_decomp).unwrap();
+
+        // ... and each of them catches its own kind of tampering.
+        let mut bad = core.clone();
+        bad.encs[3] = <E as PairingEngine>::G1Projective::rand(rng);
+        let entries = (0..n).map(|i| (keys[i], &bad.encs[i], &bad.comms[i])).collect::<Vec<_>>();
+        assert!(matches!(kernel::encryptions_check::<E, _>(rng, config.srs.g2, &entries), Err(PVSSError::EncryptionCorrectnessError)));
+
+        let mut bad = core.clone();
+        bad.comms[0] = <E as PairingEngine>::G2Projective::rand(rng);
+        assert!(matches!(kernel::coding_check::<E, _>(rng, &bad.comms, t), Err(PVSSError::DualCodeError)));
+        assert!(kernel::decomposition_check::<E>(config, &share.decomp_proof, &bad).is_err());
+        assert!(matches!(kernel::reverse_interpolation_check::<E>(config, &core.comms, bad.comms[0]), Err(PVSSError::GSCheckError)));
+
+        let other = nodes[2].share(rng).unwrap();
+        assert!(kernel::decomp_signature_check(&nodes[0].aggregator.scheme_sig, &signing_key, &other.decomp_proof, &share.signature_on_decomp).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use super::{aggregator::PVSSAggregator, errors::PVSSError, participant::Participant, share::PVSSTranscript};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Hash, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/* Leader-based aggregation with view changes. In view v, participant v mod n acts as
+   the leader that collects shares and aggregates them. Nodes that see no progress
+   within their timeout move on to the next view (doubling the timeout on each
+   consecutive view change), so that a crashed or malicious leader is eventually
+   replaced. The leader of the new view then recovers the partial transcripts the
+   others hold (state transfer):
+
+       leader -> all:     AggregateRequest { view }                 (signed by the leader)
+       node -> leader:    AggregateResponse { view, transcript }    (signed by the node)
+
+   and merges every response that verifies into its own aggregate. Transcripts are
+   fully verified before being merged, so a malicious responder cannot corrupt it.
+*/
+
+const REQUEST_DOMAIN: &[u8] = b"OPTRAGRQ";    // domain tag of aggregate-request messages
+const RESPONSE_DOMAIN: &[u8] = b"OPTRAGRS";   // domain tag of aggregate-response messages
+
+const MAX_BACKOFF_SHIFT: u32 = 16;            // the timeout grows at most 2^16-fold
+
+// Function returning the leader of the given view among n participants.
+pub fn leader_of(view: u64, num_participants: usize) -> usize {
+    (view % num_participants as u64) as usize
+}
+
+// Utility function looking up the public key of participant id.
+fn public_key<E, SSIG>(participants: &BTreeMap<usize, Participant<E, SSIG>>, id: usize) -> Result<&E::G1Affine, PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    participants
+        .get(&id)
+        .map(|participant| &participant.public_key_sig)
+        .ok_or(PVSSError::InvalidParticipantId(id))
+}
+
+// Struct ViewTimer tracks the current view and triggers view changes on timeouts.
+#[derive(Clone, Debug)]
+pub struct ViewTimer {
+    pub view: u64,                  // the current view
+    pub num_participants: usize,    // number of participants the leader rotates over
+    pub timeout: Duration,          // base timeout of a view
+    timeouts: u32,                  // consecutive view changes without progress
+    deadline: Instant,              // when the current view times out
+}
+
+impl ViewTimer {
+
+    // Function for creating a timer starting at view 0 at time now.
+    pub fn new(num_participants: usize, timeout: Duration, now: Instant) -> Self {
+        Self { view: 0, num_participants, timeout, timeouts: 0, deadline: now + timeout }
+    }
+
+    // Method returning the leader of the current view.
+    pub fn leader(&self) -> usize {
+        leader_of(self.view, self.num_participants)
+    }
+
+    // Method returning the time the current view times out.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    // Method for recording progress of the current leader, which resets the timeout.
+    pub fn progress(&mut self, now: Instant) {
+        self.timeouts = 0;
+        self.deadline = now + self.timeout;
+    }
+
+    // Method for checking the timeout at time now. If the current view timed out, moves
+    // to the next view and returns it.
+    pub fn poll(&mut self, now: Instant) -> Option<u64> {
+        if now < self.deadline {
+            return None;
+        }
+
+        self.timeouts = (self.timeouts + 1).min(MAX_BACKOFF_SHIFT);
+        self.view += 1;
+        self.deadline = now + self.timeout * 2u32.pow(self.timeouts);
+        Some(self.view)
+    }
+
+    // Method for catching up with a later view (e.g., after a valid request for it).
+    // Returns true if the view changed.
+    pub fn enter_view(&mut self, view: u64, now: Instant) -> bool {
+        if view <= self.view {
+            return false;
+        }
+
+        self.view = view;
+        self.deadline = now + self.timeout * 2u32.pow(self.timeouts);
+        true
+    }
+}
+
+// Struct AggregateRequest represents the leader's request for the partial transcripts
+// of the other participants.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct AggregateRequest<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub view: u64,                    // the view of the request
+    pub leader: usize,                // id of the requesting leader
+    pub signature: SSIG::Signature,   // the leader's signature on the above
+    pairing_type: PhantomData<E>,
+}
+
+impl<E, SSIG> AggregateRequest<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Utility function returning the signed message: the domain tag, the view and the leader.
+    fn message(view: u64, leader: usize) -> Vec<u8> {
+        let mut message = REQUEST_DOMAIN.to_vec();
+        message.extend_from_slice(&view.to_be_bytes());
+        message.extend_from_slice(&(leader as u64).to_be_bytes());
+        message
+    }
+
+    // Function for creating the request of leader for the given view.
+    pub fn new<R: Rng + CryptoRng>(rng: &mut R, scheme: &SSIG, view: u64, leader: usize, sk: &SSIG::Secret) -> Result<Self, PVSSError<E>> {
+        let signature = scheme.sign(rng, sk, &Self::message(view, leader))?;
+        Ok(Self { view, leader, signature, pairing_type: PhantomData })
+    }
+
+    // Method for verifying that the request was signed by the leader of its view.
+    pub fn verify(&self, scheme: &SSIG, participants: &BTreeMap<usize, Participant<E, SSIG>>) -> Result<(), PVSSError<E>> {
+        if self.leader != leader_of(self.view, participants.len()) {
+            return Err(PVSSError::NotLeaderError(self.leader, self.view));
+        }
+
+        let pk = public_key(participants, self.leader)?;
+        scheme.verify(pk, &Self::message(self.view, self.leader), &self.signature)?;
+        Ok(())
+    }
+}
+
+// Struct AggregateResponse represents a participant's answer to an AggregateRequest,
+// carrying its partial transcript.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct AggregateResponse<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub view: u64,                              // the view of the request answered
+    pub responder: usize,                       // id of the responding participant
+    pub transcript: PVSSTranscript<E, SSIG>,    // the responder's partial transcript
+    pub signature: SSIG::Signature,             // the responder's signature on the above
+}
+
+impl<E, SSIG> AggregateResponse<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Utility function returning the signed message: the domain tag, the view, the
+    // responder and the digest of the transcript.
+    fn message(view: u64, responder: usize, transcript: &PVSSTranscript<E, SSIG>) -> Vec<u8> {
+        let mut message = RESPONSE_DOMAIN.to_vec();
+        message.extend_from_slice(&view.to_be_bytes());
+        message.extend_from_slice(&(responder as u64).to_be_bytes());
+        message.extend_from_slice(&transcript.digest().0);
+        message
+    }
+
+    // Function for answering a request with our partial transcript, after checking the
+    // request comes from the leader of its view.
+    pub fn new<R: Rng + CryptoRng>(
+        rng: &mut R,
+        aggregator: &PVSSAggregator<E, SSIG>,
+        request: &AggregateRequest<E, SSIG>,
+        responder: usize,
+        sk: &SSIG::Secret,
+    ) -> Result<Self, PVSSError<E>> {
+        request.verify(&aggregator.scheme_sig, &aggregator.participants)?;
+
+        let transcript = aggregator.transcript.clone();
+        let signature = aggregator.scheme_sig.sign(rng, sk, &Self::message(request.view, responder, &transcript))?;
+        Ok(Self { view: request.view, responder, transcript, signature })
+    }
+
+    // Method for verifying that the response answers the request of the given view and
+    // was signed by its responder. The transcript itself is not verified here.
+    pub fn verify(&self, view: u64, scheme: &SSIG, participants: &BTreeMap<usize, Participant<E, SSIG>>) -> Result<(), PVSSError<E>> {
+        if self.view != view {
+            return Err(PVSSError::StaleViewError(self.view, view));
+        }
+
+        let pk = public_key(participants, self.responder)?;
+        scheme.verify(pk, &Self::message(self.view, self.responder, &self.transcript), &self.signature)?;
+        Ok(())
+    }
+
+    // Method for merging the response's transcript into the aggregator of the leader of
+    // the given view (state transfer). The transcript is fully verified before being
+    // merged, and skipped if it contributes no dealers we do not already cover.
+    // Returns true if the aggregate changed.
+    pub fn apply<R: Rng + CryptoRng>(&self, rng: &mut R, view: u64, aggregator: &mut PVSSAggregator<E, SSIG>) -> Result<bool, PVSSError<E>> {
+        self.verify(view, &aggregator.scheme_sig, &aggregator.participants)?;
+
+        let covered = &aggregator.transcript.contributions;
+        if self.transcript.contributions.keys().all(|id| covered.contains_key(id)) {
+            return Ok(false);
+        }
+
+        aggregator.receive_transcript_from(rng, &self.transcript, Some(self.responder))?;
+        Ok(true)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        errors::PVSSError,
+        leader::{leader_of, AggregateRequest, AggregateResponse, ViewTimer},
+        tests::setup_nodes,
+    };
+
+    use rand::thread_rng;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_view_change_recovery() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, sks) = setup_nodes(n, t);
+
+        // The leader of view 0 stalls, so the timer moves to view 1, with a doubled timeout.
+        let timeout = Duration::from_millis(100);
+        let start = Instant::now();
+        let mut timer = ViewTimer::new(n, timeout, start);
+        assert_eq!(timer.poll(start), None);
+        assert_eq!(timer.poll(start + timeout), Some(1));
+        assert_eq!(timer.deadline(), start + timeout + 2 * timeout);
+        assert_eq!(timer.leader(), 1);
+
+        // Before the stall, node 1 aggregated the shares of nodes 0 and 1, and node 2 that of node 2.
+        let shares = (0..3).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        nodes[1].aggregator.receive_share(rng, &shares[0]).unwrap();
+        nodes[1].aggregator.receive_share(rng, &shares[1]).unwrap();
+        nodes[2].aggregator.receive_share(rng, &shares[2]).unwrap();
+
+        // Only the leader of a view may request transcripts.
+        let forged = AggregateRequest::new(rng, &nodes[2].aggregator.scheme_sig, 1, 2, &sks[2]).unwrap();
+        assert!(matches!(
+            AggregateResponse::new(rng, &nodes[3].aggregator, &forged, 3, &sks[3]),
+            Err(PVSSError::NotLeaderError(2, 1))
+        ));
+
+        // The new leader recovers node 2's partial transcript.
+        assert_eq!(leader_of(1, n), 1);
+        let request = AggregateRequest::new(rng, &nodes[1].aggregator.scheme_sig, 1, 1, &sks[1]).unwrap();
+        let response = AggregateResponse::new(rng, &nodes[2].aggregator, &request, 2, &sks[2]).unwrap();
+        assert!(matches!(
+            response.apply(rng, 2, &mut nodes[1].aggregator),
+            Err(PVSSError::StaleViewError(1, 2))
+        ));
+        assert!(response.apply(rng, 1, &mut nodes[1].aggregator).unwrap());
+        assert!(!response.apply(rng, 1, &mut nodes[1].aggregator).unwrap());
+
+        let transcript = nodes[1].aggregator.finalize().unwrap();
+        assert_eq!(transcript.contributions.len(), 3);
+        nodes[3].aggregator.transcript_verify(rng, transcript).unwrap();
+    }
+}
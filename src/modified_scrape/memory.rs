@@ -0,0 +1,52 @@
+/* Memory accounting for aggregation. An aggregator may be given a budget (in bytes)
+   for the data it stores, i.e., its aggregated transcript: the core PVSS share and
+   the per-dealer contributions. Data that would push the stored size beyond the
+   budget is handled according to the configured BudgetPolicy.
+*/
+
+// Enumeration of the policies applied when new data would exceed the budget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BudgetPolicy {
+    Reject,   // refuse the new data and keep the current aggregate
+    Evict,    // keep whichever of the current and the received aggregate covers more dealers
+}
+
+// Struct MemoryAccountant tracks the number of bytes held by an aggregator.
+#[derive(Clone, Debug)]
+pub struct MemoryAccountant {
+    pub budget: usize,          // maximum number of bytes that may be stored
+    pub policy: BudgetPolicy,   // what to do when the budget would be exceeded
+    used: usize,                // number of bytes currently stored
+}
+
+impl MemoryAccountant {
+
+    // Function for creating a new accountant with nothing charged against it.
+    pub fn new(budget: usize, policy: BudgetPolicy) -> Self {
+        Self {
+            budget,
+            policy,
+            used: 0,
+        }
+    }
+
+    // Method returning the number of bytes currently stored.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    // Method returning the number of bytes that may still be stored.
+    pub fn remaining(&self) -> usize {
+        self.budget.saturating_sub(self.used)
+    }
+
+    // Method checking whether data of the given total size fits in the budget.
+    pub fn fits(&self, size: usize) -> bool {
+        size <= self.budget
+    }
+
+    // Method for recording that the stored data now occupies size bytes.
+    pub fn set_used(&mut self, size: usize) {
+        self.used = size;
+    }
+}
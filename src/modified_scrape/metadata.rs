@@ -0,0 +1,14 @@
+use std::time::{Duration, SystemTime};
+
+/* Operational bookkeeping an aggregator keeps about each contribution it has
+   accepted. None of this is part of the cryptographic transcript; it only
+   serves to analyze slow or late dealers.
+*/
+
+// Struct ContributionInfo records how and when a dealer's contribution reached us.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContributionInfo {
+    pub received_at: SystemTime,          // when the contribution was first received
+    pub source: Option<usize>,            // id of the peer we received it from, if known
+    pub verification_time: Duration,      // time spent verifying the share/transcript that carried it
+}
@@ -0,0 +1,222 @@
+use ark_std::collections::BTreeMap;
+use std::cell::Cell;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/* Cost metrics for the expensive operations. The code paths that compute pairings
+   or multi-scalar multiplications (MSMs) report how many they actually performed
+   (one per Miller loop, i.e., per pair in a product of pairings), so the counts
+   reflect whichever verification strategy and batching is in effect. The counts of
+   each top-level operation are handed to the aggregator's MetricsHook, if any;
+   PairingHistogram is a hook that renders them in the Prometheus text format.
+*/
+
+// Enumeration of the operations whose cost is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Operation {
+    ShareVerify,
+    TranscriptVerify,
+    Reconstruct,
+}
+
+impl Operation {
+
+    // Method returning the label used for the operation in exported metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::ShareVerify => "share_verify",
+            Operation::TranscriptVerify => "transcript_verify",
+            Operation::Reconstruct => "reconstruct",
+        }
+    }
+}
+
+// Struct OperationCost counts the expensive primitives performed by an operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OperationCost {
+    pub pairings: u64,   // number of Miller loops
+    pub msms: u64,       // number of multi-scalar multiplications
+}
+
+// Trait for receiving the cost of every completed operation.
+pub trait MetricsHook: Send + Sync {
+    fn record(&self, operation: Operation, cost: OperationCost);
+}
+
+
+thread_local! {
+    static COST: Cell<OperationCost> = Cell::new(OperationCost::default());
+}
+
+// Function for recording that some pairings were computed on this thread.
+pub fn count_pairings(n: usize) {
+    COST.with(|cost| {
+        let mut c = cost.get();
+        c.pairings += n as u64;
+        cost.set(c);
+    });
+}
+
+// Function for recording that some MSMs were computed on this thread.
+pub fn count_msms(n: usize) {
+    COST.with(|cost| {
+        let mut c = cost.get();
+        c.msms += n as u64;
+        cost.set(c);
+    });
+}
+
+// Function for running f and returning the pairings and MSMs it performed. Nested
+// measurements are included in the enclosing one.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, OperationCost) {
+    let outer = COST.with(|cost| cost.replace(OperationCost::default()));
+    let result = f();
+    let inner = COST.with(|cost| cost.get());
+    COST.with(|cost| cost.set(OperationCost {
+        pairings: outer.pairings + inner.pairings,
+        msms: outer.msms + inner.msms,
+    }));
+    (result, inner)
+}
+
+// Function for running f on some other thread (e.g., as a rayon task) and returning
+// the pairings and MSMs it performed, which are not left on that thread's counters.
+// The caller adds them to its own with count_cost.
+pub fn isolate<T, F: FnOnce() -> T>(f: F) -> (T, OperationCost) {
+    let outer = COST.with(|cost| cost.replace(OperationCost::default()));
+    let result = f();
+    let inner = COST.with(|cost| cost.replace(outer));
+    (result, inner)
+}
+
+// Function for recording a cost measured with isolate on this thread.
+pub fn count_cost(other: OperationCost) {
+    COST.with(|cost| {
+        let c = cost.get();
+        cost.set(OperationCost { pairings: c.pairings + other.pairings, msms: c.msms + other.msms });
+    });
+}
+
+
+// Upper bounds of the histogram buckets (besides +Inf).
+pub const HISTOGRAM_BUCKETS: [u64; 12] = [0, 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024];
+
+// Struct Histogram models a cumulative histogram in the Prometheus sense.
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    buckets: [u64; 12],   // number of observations <= each bound
+    count: u64,           // total number of observations
+    sum: u64,             // sum of all observations
+}
+
+impl Histogram {
+    fn observe(&mut self, value: u64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(HISTOGRAM_BUCKETS.iter()) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+
+    fn render(&self, out: &mut String, metric: &str, operation: &str) {
+        for (bucket, bound) in self.buckets.iter().zip(HISTOGRAM_BUCKETS.iter()) {
+            let _ = writeln!(out, "{}_bucket{{operation=\"{}\",le=\"{}\"}} {}", metric, operation, bound, bucket);
+        }
+        let _ = writeln!(out, "{}_bucket{{operation=\"{}\",le=\"+Inf\"}} {}", metric, operation, self.count);
+        let _ = writeln!(out, "{}_sum{{operation=\"{}\"}} {}", metric, operation, self.sum);
+        let _ = writeln!(out, "{}_count{{operation=\"{}\"}} {}", metric, operation, self.count);
+    }
+}
+
+// Struct PairingHistogram is a MetricsHook keeping histograms of the pairings and
+// MSMs per operation.
+#[derive(Debug, Default)]
+pub struct PairingHistogram {
+    histograms: Mutex<BTreeMap<Operation, (Histogram, Histogram)>>,   // (pairings, MSMs)
+}
+
+impl PairingHistogram {
+
+    // Function for creating an empty set of histograms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Method returning the histograms in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let histograms = self.histograms.lock().unwrap();
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP optrand_pairings Pairings (Miller loops) computed per operation.");
+        let _ = writeln!(out, "# TYPE optrand_pairings histogram");
+        for (operation, (pairings, _)) in histograms.iter() {
+            pairings.render(&mut out, "optrand_pairings", operation.name());
+        }
+
+        let _ = writeln!(out, "# HELP optrand_msms Multi-scalar multiplications computed per operation.");
+        let _ = writeln!(out, "# TYPE optrand_msms histogram");
+        for (operation, (_, msms)) in histograms.iter() {
+            msms.render(&mut out, "optrand_msms", operation.name());
+        }
+
+        out
+    }
+}
+
+impl MetricsHook for PairingHistogram {
+    fn record(&self, operation: Operation, cost: OperationCost) {
+        let mut histograms = self.histograms.lock().unwrap();
+        let (pairings, msms) = histograms.entry(operation).or_default();
+        pairings.observe(cost.pairings);
+        msms.observe(cost.msms);
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        metrics::{count_cost, count_pairings, isolate, measure, MetricsHook, Operation, PairingHistogram},
+        tests::setup_nodes,
+    };
+
+    use rand::thread_rng;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_share_verify_pairings() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let histogram = Arc::new(PairingHistogram::new());
+        nodes[1].aggregator.metrics = Some(histogram.clone() as Arc<dyn MetricsHook>);
+
+        let share = nodes[0].share(rng).unwrap();
+        nodes[1].aggregator.share_verify(rng, &share).unwrap();
+        let transcript = nodes[0].aggregator.transcript.clone();
+        let _ = nodes[1].aggregator.transcript_verify(rng, &transcript);
+
+        let exported = histogram.render_prometheus();
+        // share verification checks the dealer's own encryption with 2 pairings
+        assert!(exported.contains("optrand_pairings_bucket{operation=\"share_verify\",le=\"1\"} 0"));
+        assert!(exported.contains("optrand_pairings_bucket{operation=\"share_verify\",le=\"2\"} 1"));
+        assert!(exported.contains("optrand_pairings_sum{operation=\"share_verify\"} 2"));
+        assert!(exported.contains(&format!("optrand_msms_count{{operation=\"{}\"}} 1", Operation::TranscriptVerify.name())));
+    }
+
+    #[test]
+    fn test_isolated_cost() {
+        // Work done on another thread is counted on ours, and only once.
+        let (_, cost) = measure(|| {
+            let (_, other) = std::thread::spawn(|| isolate(|| count_pairings(3))).join().unwrap();
+            let (_, local) = isolate(|| count_pairings(2));
+            count_cost(other);
+            count_cost(local);
+        });
+        assert_eq!(cost.pairings, 5);
+    }
+}
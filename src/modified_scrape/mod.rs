@@ -3,12 +3,43 @@ pub mod srs;
 pub mod poly;
 pub mod config;
 pub mod decomp;
+pub mod dealing;
 pub mod decryption;
+pub mod escrow;
+pub mod audit;
+pub mod kernel;
+pub mod verifier;
 
 pub mod pvss;
 pub mod share;
+pub mod contributions;
+pub mod summary;
+pub mod finalized;
+pub mod certificate;
+pub mod dkg;
+pub mod resharing;
 
 pub mod participant;
+pub mod epoch_keys;
 pub mod dealer;
 pub mod aggregator;
-//pub mod node;
+pub mod tree;
+pub mod leader;
+pub mod accumulator;
+pub mod memory;
+pub mod cache;
+pub mod seen;
+pub mod metadata;
+pub mod metrics;
+pub mod outcome;
+pub mod trace;
+pub mod stats;
+pub mod health;
+pub mod node;
+pub mod slashing;
+pub mod complaint;
+
+pub use verifier::verify_aggregated_transcript;
+
+#[cfg(test)]
+pub mod tests;
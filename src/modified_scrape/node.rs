@@ -1,25 +1,43 @@
 use crate::{
     modified_scrape::{
         aggregator::PVSSAggregator,
-        config::Config,
+        config::{Config, EvaluationPoints, ShareLayout},
         dealer::Dealer,
+        epoch_keys::EpochKey,
         errors::PVSSError,
+        health::{CheckStatus, HealthReport},
+        metrics::{count_pairings, measure, Operation},
         participant::{Participant, ParticipantState},
-        pvss::{PVSSShare, PVSSShareSecrets},
-	decomp::{Decomp, DecompProof, message_from_pi_i},
+        pvss::{PVSSShare, PVSSShareSecrets, SparsePVSSShare},
+        srs::PreparedSRS,
+	decomp::{Decomp, DealingArtifacts, message_from_pi_i},
+        dealing::{compute_evals_for, encrypt_evals},
     },
     signature::scheme::BatchVerifiableSignatureScheme,
 };
-use crate::modified_scrape::share::{PVSSTranscript, PVSSAugmentedShare};
-use super::poly::{Polynomial, lagrange_interpolation, lagrange_interpolation_simple, ensure_degree};
+use crate::modified_scrape::share::{PVSSAugmentedShare, PVSSTranscript};
+use super::poly::{interpolate_at_zero, sample_with_free_term, Polynomial};
 use super::decryption::DecryptedShare;
+use super::escrow::BeaconCiphertext;
+use crate::beacon::epoch::EpochContribution;
+use crate::modified_scrape::dkg::ThresholdKeyOutput;
+use crate::secrets::{SecretPolynomial, SecretScalar};
 use crate::{GT, Scalar};
 
 use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
-use ark_ff::{Field, PrimeField, UniformRand};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_poly::{Polynomial as Poly, UVPolynomial};
 
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 use std::collections::BTreeMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Earliest wall-clock time (2021-01-01 UTC) a correctly set clock may report.
+const MIN_SANE_UNIX_TIME: u64 = 1_609_459_200;
+
+// Largest tolerated disagreement between the wall clock and the monotonic clock
+// over the course of a self-test.
+const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(1);
 
 
 /* Struct Node models the individual nodes participating in the PVSS sharing
@@ -29,78 +47,75 @@ use std::collections::BTreeMap;
 
 pub struct Node<
     E: PairingEngine,
-    SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 > {
-    pub aggregator: DKGAggregator<E, SPOK, SSIG>,     // the aggregator aspect of the node
-    pub dealer: Dealer<E, SSIG>,                      // the dealer aspect of the node
+    pub aggregator: PVSSAggregator<E, SSIG>,     // the aggregator aspect of the node
+    pub dealer: Dealer<E, SSIG>,                 // the dealer aspect of the node
+    pub epoch_secrets: BTreeMap<u64, SecretScalar<Scalar<E>>>,   // our ephemeral decryption keys, by epoch
+    pub prepared_srs: PreparedSRS<E>,            // fixed-base tables of the SRS, for dealing
 }
 
 impl<
         E: PairingEngine,
-        SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
-        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
-    > Node<E, SPOK, SSIG>
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    > Node<E, SSIG>
 {
 
-    // Function for creating a new node in the PVSS sharing protocol.
+    // Function for creating a new node in the PVSS sharing protocol. The fixed-base
+    // tables of the SRS are precomputed here, once, for dealings to n participants.
     pub fn new(
         config: Config<E>,
-        scheme_pok: SPOK,   // might be redundant
         scheme_sig: SSIG,
         dealer: Dealer<E, SSIG>,
         participants: BTreeMap<usize, Participant<E, SSIG>>,
     ) -> Result<Self, PVSSError<E>> {
-        let degree = config.degree;
-        let num_participants = participants.len();
+        let prepared_srs = config.srs.prepare(config.num_participants);
         let node = Node {
-            aggregator: PVSSAggregator {
-                config,
-                scheme_pok,   // might be redundant
-                scheme_sig,
-                participants,
-                transcript: PVSSTranscript::empty(degree, num_participants),
-            },
+            aggregator: PVSSAggregator::new(config, scheme_sig, participants),
             dealer,
+            epoch_secrets: BTreeMap::new(),
+            prepared_srs,
         };
         Ok(node)
     }
 
 
     // Method for generating a core PVSS share.
-    pub fn share_pvss<R: Rng>(
+    pub fn share_pvss<R: Rng + CryptoRng>(
         &mut self,
         rng: &mut R,
     ) -> Result<(PVSSShare<E>, PVSSShareSecrets<E>), PVSSError<E>> {
-	let t = self.aggregator.config.degree;
-	let n = self.aggregator.config.num_participants;
+	// Sample a random degree t polynomial (wiped once we are done with it)
+	let poly = SecretPolynomial::new(Polynomial::<E>::rand(self.aggregator.config.degree, rng));
+	self.share_pvss_from_polynomial(&poly)
+    }
+
 
-	// Sample a random degree t polynomial
-	let poly = Polynomial::<E>::rand(t, rng);
+    // Method for generating a core PVSS share of the given secret s0, i.e., sharing
+    // it with a random degree t polynomial whose free term is s0.
+    pub fn share_pvss_with_secret<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        s0: Scalar<E>,
+    ) -> Result<(PVSSShare<E>, PVSSShareSecrets<E>), PVSSError<E>> {
+	let poly = SecretPolynomial::new(sample_with_free_term::<E, R>(self.aggregator.config.degree, s0, rng));
+	self.share_pvss_from_polynomial(&poly)
+    }
 
-	// Evaluate poly(j) for all j in {1, ..., n}
-	let mut evals = (1..n+1)
-	    .map(|j| poly.evaluate(&Scalar::<E>::from(j as u64)))
-	    .collect::<Vec<_>>();
+
+    // Utility method deriving a core PVSS share from the given sharing polynomial.
+    fn share_pvss_from_polynomial(
+        &self,
+        poly: &SecretPolynomial<Scalar<E>>,
+    ) -> Result<(PVSSShare<E>, PVSSShareSecrets<E>), PVSSError<E>> {
+	// Evaluate poly at the points of all nodes in {0, ..., n-1}
+	let evals = compute_evals_for::<E>(&self.aggregator.config, poly.expose());
 
 	// Compute commitments for all nodes in {0, ..., n-1}
-	let mut comms = (0..n)
-	    .map(|j| config.srs.g2.mul(evals[j].into_repr()))
-	    .collect::<Vec<_>>();
-
-	// Compute encryptions for all nodes in {0, ..., n-1}
-	let mut encs = (0..n)
-	    .map::<Result<E::G2Affine, PVSSError<E>>, _>(|j| {
-                Ok(self
-                    .aggregator
-                    .participants
-                    .get(&j)
-                    .ok_or(PVSSError::<E>::InvalidParticipantId(j))?
-                    .public_key_sig
-                    .mul(evals[j].into_repr())
-                    .into_affine())
-            })
-            .collect::<Result<_, _>>()?;
+	let comms = self.prepared_srs.mul_g2(&evals);
+
+	// Compute encryptions for all nodes in {0, ..., n-1}, under their current encryption keys
+	let encs = self.encrypt_evals(&evals)?;
 
 	// Compose PVSS share
 	let pvss_share = PVSSShare {
@@ -110,18 +125,14 @@ impl<
 	    // sig_of_knowledge
         };
 
-	// Generate my_secret
-        let my_secret = self
-            .aggregator
-            .config
-            .srs
-            .g1
-            .mul(evals[self.dealer.participant.id].into_repr())
-            .into_affine();
+	// Generate my_secret, unless we are not among the recipients
+        let my_secret = evals
+            .get(self.dealer.participant.id)
+            .map(|eval| self.prepared_srs.mul_g1(&[*eval])[0].into_affine());
 
 	// Create PVSSShareSecrets
         let pvss_share_secrets = PVSSShareSecrets {
-            p_0: poly.coeffs[0],
+            p_0: poly.expose().coeffs[0],
             my_secret,
         };
 
@@ -130,26 +141,116 @@ impl<
     }
 
 
+    // Utility method encrypting the evaluations p(1), ..., p(n) for nodes
+    // 0, ..., n-1 respectively, under their current encryption keys.
+    fn encrypt_evals(&self, evals: &[Scalar<E>]) -> Result<Vec<E::G1Projective>, PVSSError<E>> {
+        let pks = (0..evals.len())
+            .map(|j| self.aggregator.encryption_key(j))
+            .collect::<Result<Vec<_>, _>>()?;
+        encrypt_evals::<E>(&pks, evals)
+    }
+
+
+    // Method for dealing a sparse core PVSS share to the given recipients only, as
+    // the dealers of hierarchical or sharded deployments do. Requires a config with
+    // the sparse share layout.
+    pub fn share_pvss_sparse<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        recipients: &[usize],
+    ) -> Result<(SparsePVSSShare<E>, PVSSShareSecrets<E>), PVSSError<E>> {
+        let config = &self.aggregator.config;
+        if config.layout != ShareLayout::Sparse {
+            return Err(PVSSError::ShareLayoutError);
+        }
+
+	let poly = SecretPolynomial::new(Polynomial::<E>::rand(config.degree, rng));
+
+	// Evaluate, commit to and encrypt poly(x_j) for each recipient j only.
+	let entries = recipients
+	    .iter()
+	    .map(|j| {
+                if *j >= config.num_participants {
+                    return Err(PVSSError::InvalidParticipantId(*j));
+                }
+                let eval = poly.expose().evaluate(&config.point(*j)).into_repr();
+                let enc = self.aggregator.encryption_key(*j)?.mul(eval);
+                Ok((*j, (enc, config.srs.g2.mul(eval))))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let id = self.dealer.participant.id;
+        let my_secret = if recipients.contains(&id) {
+            Some(config.srs.g1.mul(poly.expose().evaluate(&config.point(id)).into_repr()).into_affine())
+        } else {
+            None
+        };
+
+        let sparse_share = SparsePVSSShare {
+            num_participants: config.num_participants,
+            entries,
+        };
+
+        Ok((sparse_share, PVSSShareSecrets { p_0: poly.expose().coeffs[0], my_secret }))
+    }
+
+
     // Method for generating a PVSSAugmentedShare instance for secret sharing.
-    pub fn share<R: Rng>(&mut self, rng: &mut R) -> Result<PVSSAugmentedShare<E, SSIG>, PVSSError<E>> {
-	// Create the core PVSSShare first.
-	let (pvss_share, pvss_share_secrets) = self.share_pvss(rng)?;
+    pub fn share<R: Rng + CryptoRng>(&mut self, rng: &mut R) -> Result<PVSSAugmentedShare<E, SSIG>, PVSSError<E>> {
+	// Sample a random degree t polynomial, and derive the commitments and the
+	// decomposition proof from it (checked to be consistent with each other).
+	// If we committed to a seed, all of this randomness is derived from it.
+	let artifacts = match &self.dealer.seed {
+	    Some(seed) => seed.deal(&self.aggregator.config)?.1,
+	    None => {
+	        let poly = SecretPolynomial::new(Polynomial::<E>::rand(self.aggregator.config.degree, rng));
+	        Decomp::<E>::from_polynomial_prepared(rng, &self.aggregator.config, &self.prepared_srs, poly.expose())?
+	    },
+	};
+	self.share_from_artifacts(rng, artifacts)
+    }
+
 
-	// Generate decomposition proof.
-	let decomp_proof = Decomp::<E>::generate(rng, &aggregator.config, &pvss_share_secrets.p_0).unwrap();
+    // Method for generating a PVSSAugmentedShare instance for secret sharing the given
+    // secret s0. Any committed seed is not used, as it determines the secret itself.
+    pub fn share_with_secret<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        s0: Scalar<E>,
+    ) -> Result<PVSSAugmentedShare<E, SSIG>, PVSSError<E>> {
+	let poly = SecretPolynomial::new(sample_with_free_term::<E, R>(self.aggregator.config.degree, s0, rng));
+	let artifacts = Decomp::<E>::from_polynomial_prepared(rng, &self.aggregator.config, &self.prepared_srs, poly.expose())?;
+	self.share_from_artifacts(rng, artifacts)
+    }
+
+
+    // Utility method encrypting and signing the given dealing artifacts into a
+    // PVSSAugmentedShare instance.
+    fn share_from_artifacts<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        artifacts: DealingArtifacts<E>,
+    ) -> Result<PVSSAugmentedShare<E, SSIG>, PVSSError<E>> {
+	let decomp_proof = artifacts.decomp_proof;
+
+	// Create the core PVSSShare.
+	let pvss_share = PVSSShare {
+	    comms: artifacts.comms,
+	    encs: self.encrypt_evals(&artifacts.evals)?,
+	};
 
 	// Use the (private) signing key contained in the dealer instance to also compute
 	// the public key w.r.t. the signature scheme indicated by the aggregator instance.
 	let signature_keypair = self
             .aggregator
             .scheme_sig
-            .from_sk(&(self.dealer.private_key_sig))?;
+            .from_sk(self.dealer.private_key_sig.expose())?;
 
 	// Sign the decomposition proof.
-	let signature_on_decomp =
-            Some(self.aggregator
-                .scheme_sig
-                .sign(rng, &signature_keypair.0, &message_from_pi_i(decomp_proof)?)?);
+	let signature_on_decomp = self
+            .aggregator
+            .scheme_sig
+            .sign(rng, &signature_keypair.0, &message_from_pi_i(decomp_proof)?)?;
 
 	// Create the augmented PVSS share.
 	let share = PVSSAugmentedShare {
@@ -166,44 +267,135 @@ impl<
     }
 
 
+    // Method for generating our encryption key for the given epoch. The ephemeral
+    // secret is kept until erased by forget_epoch_keys_before, and the public key is
+    // registered with our own aggregator; it must be sent to the other participants.
+    pub fn generate_epoch_key<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+        epoch: u64,
+    ) -> Result<EpochKey<E, SSIG>, PVSSError<E>> {
+        let (sk, key) = EpochKey::generate(rng, &self.aggregator.scheme_sig, self.dealer.private_key_sig.expose(),
+            self.dealer.participant.id, epoch)?;
+
+        self.aggregator.register_epoch_key(&key)?;
+        self.epoch_secrets.insert(epoch, SecretScalar::new(sk));
+
+        Ok(key)
+    }
+
+
+    // Method for erasing the ephemeral secrets of all epochs before the given one.
+    pub fn forget_epoch_keys_before(&mut self, epoch: u64) {
+        self.epoch_secrets = self.epoch_secrets.split_off(&epoch);
+    }
+
+
+    // Method returning the key with which shares of the current epoch are decrypted:
+    // our ephemeral secret for the epoch, if our aggregator uses an epoch key for us,
+    // or else our identity key.
+    pub fn decryption_key(&self) -> Scalar<E> {
+        let epoch = self.aggregator.cache.epoch();
+        match self.epoch_secrets.get(&epoch) {
+            Some(sk) if self.aggregator.epoch_keys.contains_key(&(self.dealer.participant.id, epoch)) => *sk.expose(),
+            _ => *self.dealer.private_key_sig.expose(),
+        }
+    }
+
+
+    // Method for decrypting our encrypted share of the given transcript, along with a
+    // proof of correct decryption that other nodes can check via verify_decryption.
+    pub fn decrypt<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &PVSSTranscript<E, SSIG>,
+    ) -> Result<DecryptedShare<E>, PVSSError<E>> {
+        let my_id = self.dealer.participant.id;
+        let enc = transcript.pvss_share.encs.get(my_id).ok_or(PVSSError::<E>::InvalidParticipantId(my_id))?;
+        DecryptedShare::generate(rng, &self.aggregator.config, &enc.into_affine(), &self.decryption_key(), my_id)
+    }
+
+
+    // Method for verifying a decryption released by some participant against its
+    // encrypted share in the given transcript and its current encryption key.
+    pub fn verify_decryption(
+        &self,
+        transcript: &PVSSTranscript<E, SSIG>,
+        decryption: &DecryptedShare<E>,
+    ) -> Result<(), PVSSError<E>> {
+        let origin = decryption.origin;
+        let enc = transcript.pvss_share.encs.get(origin).ok_or(PVSSError::<E>::InvalidParticipantId(origin))?;
+        decryption.verify(&self.aggregator.config, &enc.into_affine(), &self.aggregator.encryption_key(origin)?)
+    }
+
+
+    // Utility method decrypting our share g_1^{p(id+1)} of the secret of a transcript.
+    fn secret_share(&self, transcript: &PVSSTranscript<E, SSIG>) -> Result<E::G1Affine, PVSSError<E>> {
+        let my_id = self.dealer.participant.id;
+        let enc = transcript.pvss_share.encs.get(my_id).ok_or(PVSSError::<E>::InvalidParticipantId(my_id))?;
+        let sk_inverse = SecretScalar::new(self.decryption_key().inverse().ok_or(PVSSError::<E>::InvalidParticipantId(my_id))?);
+        Ok(enc.into_affine().mul(sk_inverse.expose().into_repr()).into_affine())
+    }
+
+
+    // Method for computing our contribution to the beacon of an epoch, from our share
+    // of the secret of the given (agreed upon) transcript. The share itself is never
+    // released, so the same transcript can serve many epochs.
+    pub fn epoch_contribution(
+        &self,
+        transcript: &PVSSTranscript<E, SSIG>,
+        epoch: u64,
+    ) -> Result<EpochContribution<E>, PVSSError<E>> {
+        EpochContribution::new(&self.aggregator.config, epoch, self.dealer.participant.id, &self.secret_share(transcript)?)
+    }
+
+
+    // Method for using the given (agreed upon) transcript as the outcome of a DKG:
+    // returns the group public key, the public key shares of all participants and
+    // our secret key share, checked against our public key share.
+    pub fn threshold_keys(&self, transcript: &PVSSTranscript<E, SSIG>) -> Result<ThresholdKeyOutput<E>, PVSSError<E>> {
+        let public_keys = transcript.threshold_public_keys()?;
+        let output = ThresholdKeyOutput {
+            id: self.dealer.participant.id,
+            threshold: transcript.degree,
+            group_public_key: public_keys.group_public_key,
+            public_key_shares: public_keys.public_key_shares,
+            secret_key_share: self.secret_share(transcript)?,
+        };
+        output.verify(&self.aggregator.config)?;
+        Ok(output)
+    }
+
+
     // Assumes that the participant id has been authenticated.
-    pub fn receive_share_and_decrypt<R: Rng>(
+    pub fn receive_share_and_decrypt<R: Rng + CryptoRng>(
         &mut self,
         rng: &mut R,
         share: PVSSAugmentedShare<E, SSIG>,
     ) -> Result<(), PVSSError<E>> {
 	// Retrieve participant's id from the share
 	let participant_id = share.participant_id;
+	let my_id = self.dealer.participant.id;
 
-	// Anonymous function for performing the decryption
-	match (|| -> Result<DecryptedShare<E>, PVSSError<E>> {   // Result<E::G2Affine, PVSSError<E>>
-            self.aggregator.receive_share(rng, &share)?;   // ................
-	    
-	    /*
-	    // decryption occurs here
-            let secret = share.pvss_share.encs[self.dealer.participant.id]
-                .mul(self.dealer.private_key_sig.inverse().unwrap().into_repr())
-                .into_affine();
-	    */
-
-	    // decrypt share
-	    let secret = DecryptedShare::generate(share.pvss_share.encs[self.dealer.participant.id],
-		self.dealer.private_key_sig,
-		self.dealer.participant.id);
-
-            Ok(secret)
-        })() {
-            Ok(secret) => {
-                self.dealer.accumulated_secret = self.dealer.accumulated_secret + secret;   // ?????
-                let participant = self
-                    .aggregator
-                    .participants
-                    .get_mut(&participant_id)
-                    .ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
-                participant.state = ParticipantState::Verified;
-            }
-            Err(_) => {}
-        };
+	// Verify the share and aggregate it to our transcript
+	self.aggregator.receive_share(rng, &share)?;
+
+	// decrypt share
+	let secret = DecryptedShare::<E>::generate(rng,
+	    &self.aggregator.config,
+	    &share.pvss_share.encs[my_id].into_affine(),
+	    &self.decryption_key(),
+	    my_id)?;
+
+	// Accumulate the decryption (in G_1)
+	self.dealer.accumulated_secret = (self.dealer.accumulated_secret.into_projective() + secret.dec.into_projective()).into_affine();
+
+	let participant = self
+            .aggregator
+            .participants
+            .get_mut(&participant_id)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(participant_id))?;
+	participant.state = ParticipantState::Verified;
 
 	Ok(())
     }
@@ -211,7 +403,7 @@ impl<
 
 /*
     // Assumes that the participant id has been authenticated.
-    pub fn receive_transcript_and_decrypt<R: Rng>(
+    pub fn receive_transcript_and_decrypt<R: Rng + CryptoRng>(
         &mut self,
         rng: &mut R,
         transcript: DKGTranscript<E, SPOK, SSIG>,
@@ -239,51 +431,264 @@ impl<
 
     // Method for reconstructing the shared secret and beacon value.
     pub fn reconstruct(
-	&mut self,
-	decryptions: &Vec<DecryptedShare<E>>
+	&self,
+	decryptions: &[DecryptedShare<E>]
+	) -> Result<(E::G1Affine, GT<E>), PVSSError<E>> {
+	let (result, cost) = measure(|| self.interpolate_beacon(decryptions));
+	self.aggregator.report(Operation::Reconstruct, cost);
+	result
+    }
+
+
+    // Utility method performing the actual reconstruction.
+    fn interpolate_beacon(
+	&self,
+	decryptions: &[DecryptedShare<E>]
 	) -> Result<(E::G1Affine, GT<E>), PVSSError<E>> {
 
-	let degree = self.aggregator.config.degree as u64;
+	let degree = self.aggregator.config.degree;
+
+	// The transcript the decryptions stem from must carry enough contribution weight.
+	self.aggregator.transcript.check_weight_threshold()?;
 
 	if decryptions.len() <= degree {
-	    return Err(PVSSError::InsufficientDecryptionsError(decryptions.size(), self.aggregator.config.degree));
+	    return Err(PVSSError::InsufficientDecryptionsError(decryptions.len(), degree + 1));
 	}
 
-	// NOTE: Mind the +1 when extracting the origin
-	let (points, evals): (Vec<_>, Vec<_>) = (0..decryptions.len())
-	    .map(|i| (decryptions[i].origin + 1, decryptions[i].dec))
+	let (points, evals): (Vec<_>, Vec<_>) = decryptions
+	    .iter()
+	    .map(|d| (self.aggregator.config.point(d.origin), d.dec))
 	    .unzip();
 
 	// Lagrange interpolation over group G_1
-	match (|| -> Result<E::G1Projective, PVSSError<E>> {
-            let mut sum = E::G1Projective::zero();
-
-    	    for j in 0..degree+1 {
-                let x_j = points[j as usize];
-	        let mut prod = Scalar::<E>::one();
-	        for k in 0..degree+1 {
-	            if j != k {
-	                let x_k = points[k as usize];
-	                prod *= x_k * (x_k - x_j).inverse().unwrap();
-	            }
-	        }
-
-	        // Recovery formula
-	        sum += evals[j as usize].mul(prod.into_repr());
-            }
-
-            Ok(sum)
-        })() {
-            Ok(sum) => {
-                let point = sum.into_affine();
-            }
-            Err(_) => {}
-        };
+	let point = interpolate_at_zero::<E, E::G1Affine>(&evals[..degree+1], &points[..degree+1])?.into_affine();
 
 	// Compute the "beacon value"
-	let S = E::pairing(point, self.aggregator.config.g2_prime);   // in <E as PairingEngine>::Fqk
+	count_pairings(1);
+	let beacon = E::pairing(point, self.aggregator.config.srs.g2_prime);   // in <E as PairingEngine>::Fqk
+
+	Ok((point, beacon))
+    }
 
-	Ok((point, S))
+
+    // Method for reconstructing the beacon value and immediately encrypting it to a
+    // consumer committee with the given aggregated public key (see escrow), so that
+    // the beacon itself is never released by this node.
+    pub fn reconstruct_and_escrow<R: Rng + CryptoRng>(
+	&self,
+	rng: &mut R,
+	decryptions: &[DecryptedShare<E>],
+	committee_pk: &E::G2Affine,
+	) -> Result<BeaconCiphertext<E>, PVSSError<E>> {
+	let (_, beacon) = self.reconstruct(decryptions)?;
+
+	BeaconCiphertext::encrypt(rng, &self.aggregator.config, committee_pk, &beacon)
     }
 
+
+    // Method for checking that the node is fit to take part in the protocol; meant to
+    // run at startup and from an operator health endpoint. Besides static checks on
+    // the SRS, config and keys, it runs a mini-ceremony in which the node deals to
+    // itself, verifies, decrypts and reconstructs.
+    pub fn self_test<R: Rng + CryptoRng>(&self, rng: &mut R) -> HealthReport {
+        let checked_at = SystemTime::now();
+        let start = Instant::now();
+
+        let config = &self.aggregator.config;
+        let srs = &config.srs;
+
+        let srs_check = if srs.g1.is_zero() || srs.g2.is_zero() || srs.g2_prime.is_zero() {
+            CheckStatus::Failed("SRS contains the identity element".to_string())
+        } else if srs.g2 == srs.g2_prime {
+            CheckStatus::Failed("g2 and g2_prime coincide".to_string())
+        } else {
+            CheckStatus::Passed
+        };
+
+        let config_check = if config.degree >= config.num_participants {
+            CheckStatus::Failed(format!("degree {} is not below the number of participants {}",
+                config.degree, config.num_participants))
+        } else if self.aggregator.participants.len() != config.num_participants {
+            CheckStatus::Failed(format!("{} participants registered, config expects {}",
+                self.aggregator.participants.len(), config.num_participants))
+        } else {
+            CheckStatus::Passed
+        };
+
+        let id = self.dealer.participant.id;
+        let keys_check = match self.aggregator.scheme_sig.from_sk(self.dealer.private_key_sig.expose()) {
+            Err(e) => CheckStatus::Failed(format!("signing key is unusable: {}", e)),
+            Ok((_, pk)) if pk != self.dealer.participant.public_key_sig =>
+                CheckStatus::Failed("signing key does not match our public key".to_string()),
+            Ok((_, pk)) => match self.aggregator.participants.get(&id) {
+                None => CheckStatus::Failed(format!("id {} is not in the roster", id)),
+                Some(p) if p.public_key_sig != pk =>
+                    CheckStatus::Failed(format!("roster holds a different public key for id {}", id)),
+                Some(_) => CheckStatus::Passed,
+            },
+        };
+
+        let ceremony_start = Instant::now();
+        let ceremony = self.self_test_ceremony(rng);
+        let ceremony_time = ceremony_start.elapsed();
+
+        let clock = match checked_at.duration_since(UNIX_EPOCH) {
+            Err(_) => CheckStatus::Failed("system clock is before the Unix epoch".to_string()),
+            Ok(now) if now.as_secs() < MIN_SANE_UNIX_TIME =>
+                CheckStatus::Failed(format!("system clock reports {}s since the Unix epoch", now.as_secs())),
+            Ok(_) => match SystemTime::now().duration_since(checked_at) {
+                Err(_) => CheckStatus::Failed("system clock went backwards".to_string()),
+                Ok(wall) => {
+                    let monotonic = start.elapsed();
+                    let drift = wall.abs_diff(monotonic);
+                    if drift > MAX_CLOCK_DRIFT {
+                        CheckStatus::Failed(format!("wall clock drifted {:?} from the monotonic clock", drift))
+                    } else {
+                        CheckStatus::Passed
+                    }
+                }
+            },
+        };
+
+        HealthReport {
+            srs: srs_check,
+            config: config_check,
+            keys: keys_check,
+            clock,
+            ceremony,
+            ceremony_time,
+            checked_at,
+        }
+    }
+
+
+    // Utility method running a local instance of the protocol with t = 0 and n = 2
+    // (the smallest parameters the degree check accepts), in which every participant
+    // is this node, over our own SRS and keys.
+    fn self_test_ceremony<R: Rng + CryptoRng>(&self, rng: &mut R) -> CheckStatus {
+        let srs = self.aggregator.config.srs.clone();
+        let (g1, g2) = (srs.g1, srs.g2);
+        let config = Config { srs, degree: 0, num_participants: 2, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+
+        let participants = (0..2)
+            .map(|j| {
+                let mut participant = self.dealer.participant.clone();
+                participant.id = j;
+                participant.state = ParticipantState::Initial;
+                (j, participant)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let dealer = Dealer {
+            private_key_sig: self.dealer.private_key_sig.clone(),
+            accumulated_secret: E::G1Affine::zero(),
+            decryptions: vec![],
+            participant: participants[&0].clone(),
+            seed: None,
+        };
+
+        let result = (|| -> Result<bool, PVSSError<E>> {
+            let mut node = Node::new(config, self.aggregator.scheme_sig.clone(), dealer, participants)?;
+
+            let share = node.share(rng)?;
+            node.aggregator.receive_share(rng, &share)?;
+
+            // Every encryption is under our key, so we can decrypt all of them.
+            let transcript = node.aggregator.finalize()?;
+            let decryptions = (0..2)
+                .map(|j| DecryptedShare::<E>::generate(rng, &node.aggregator.config,
+                    &transcript.pvss_share.encs[j].into_affine(), self.dealer.private_key_sig.expose(), j))
+                .collect::<Result<Vec<_>, _>>()?;
+            let gs = transcript.weighted_gs();
+            let (point, _) = node.reconstruct(&decryptions)?;
+
+            // The reconstructed secret g1^{s} must match the transcript's g2^{s}.
+            Ok(E::pairing(point, g2) == E::pairing(g1, gs))
+        })();
+
+        match result {
+            Ok(true) => CheckStatus::Passed,
+            Ok(false) => CheckStatus::Failed("reconstructed secret does not match the dealt one".to_string()),
+            Err(e) => CheckStatus::Failed(e.to_string()),
+        }
+    }
+
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        config::EvaluationPoints,
+        dealer::Dealer,
+        health::CheckStatus,
+        tests::setup_nodes,
+    };
+
+    use ark_bls12_381::Fr;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{PrimeField, UniformRand};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_self_test() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let report = nodes[0].self_test(rng);
+        assert!(report.is_healthy(), "{}", report);
+
+        // a signing key that does not match the roster is reported
+        nodes[1].dealer = Dealer::from_raw_parts(Fr::rand(rng), nodes[1].dealer.participant().clone());
+        let report = nodes[1].self_test(rng);
+        assert!(!report.is_healthy());
+        assert!(matches!(report.keys, CheckStatus::Failed(_)));
+        assert_eq!(report.srs, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn test_share_with_secret() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        let s0 = Fr::rand(rng);
+
+        let (_, secrets) = nodes[0].share_pvss_with_secret(rng, s0).unwrap();
+        assert_eq!(secrets.p_0, s0);
+
+        // The augmented share commits to s0, and is accepted as any other share.
+        let share = nodes[1].share_with_secret(rng, s0).unwrap();
+        let g2 = nodes[1].aggregator.config.srs.g2;
+        assert_eq!(share.decomp_proof.gs(), g2.mul(s0.into_repr()).into_affine());
+        nodes[2].aggregator.receive_share(rng, &share).unwrap();
+    }
+
+    #[test]
+    fn test_evaluation_domain() {
+        let rng = &mut thread_rng();
+        let (n, t) = (8, 3);
+        let (mut nodes, _) = setup_nodes(n, t);
+        for node in nodes.iter_mut() {
+            node.aggregator.config.points = EvaluationPoints::Domain;
+        }
+
+        // Shares dealt over the domain are verified, aggregated and decrypted as usual.
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[0].aggregator.receive_share(rng, share).unwrap();
+        }
+        let transcript = nodes[0].aggregator.finalize().unwrap().clone();
+        nodes[1].aggregator.transcript_verify(rng, &transcript).unwrap();
+
+        // Any t + 1 decryptions reconstruct the same secret.
+        let decryptions = nodes.iter().map(|node| node.decrypt(rng, &transcript).unwrap()).collect::<Vec<_>>();
+        let (first, _) = nodes[0].reconstruct(&decryptions[..t + 1]).unwrap();
+        let (last, _) = nodes[0].reconstruct(&decryptions[n - t - 1..]).unwrap();
+        assert_eq!(first, last);
+
+        // Verifiers evaluating at the integers reject them.
+        nodes[2].aggregator.config.points = EvaluationPoints::Integers;
+        assert!(nodes[2].aggregator.receive_share(rng, &shares[1]).is_err());
+    }
 }
@@ -0,0 +1,57 @@
+use std::fmt;
+
+/* Two-tier verification results. Some checks succeed but still point at anomalies
+   an operator may want to know about; the verify_with_report family of methods of
+   PVSSAggregator returns them as warnings next to the pass/fail verdict, while the
+   Result-returning methods keep failing hard and stay silent about warnings.
+*/
+
+// Number of contributors above the threshold under which a transcript is reported
+// as being near the threshold.
+pub const NEAR_THRESHOLD_MARGIN: usize = 1;
+
+// Enumeration of the anomalies reported by verifications that otherwise succeed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    // The dealer's contribution is counted more than once.
+    RepeatedContribution { dealer: usize, weight: u64 },
+    // The number of distinct contributors is at most NEAR_THRESHOLD_MARGIN above
+    // the t+1 needed, so that few faulty dealers suffice to stall reconstruction.
+    NearThreshold { contributors: usize, required: usize },
+    // The number of distinct contributors is below t+1.
+    BelowThreshold { contributors: usize, required: usize },
+    // The dealer's contribution is already part of our aggregated transcript.
+    AlreadyAggregated { dealer: usize },
+}
+
+// Warning implements the Display trait.
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::RepeatedContribution { dealer, weight } =>
+                write!(f, "contribution of dealer {} is counted {} times", dealer, weight),
+            Warning::NearThreshold { contributors, required } =>
+                write!(f, "only {} contributors, {} required", contributors, required),
+            Warning::BelowThreshold { contributors, required } =>
+                write!(f, "{} contributors are fewer than the {} required", contributors, required),
+            Warning::AlreadyAggregated { dealer } =>
+                write!(f, "dealer {} is already part of our transcript", dealer),
+        }
+    }
+}
+
+// Struct VerificationOutcome is the result of a verification with warnings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VerificationOutcome {
+    pub ok: bool,                  // whether all (hard) checks passed
+    pub warnings: Vec<Warning>,    // anomalies found along the way
+    pub error: Option<String>,     // the reason for failing, if !ok
+}
+
+impl VerificationOutcome {
+
+    // Method checking whether the verification passed without any warning.
+    pub fn is_clean(&self) -> bool {
+        self.ok && self.warnings.is_empty()
+    }
+}
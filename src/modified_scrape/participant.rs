@@ -1,11 +1,16 @@
 use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::signature::schnorr::SchnorrSignature;
+use super::errors::PVSSError;
 use ark_ec::PairingEngine;
+use ark_serialize::*;
 use std::marker::PhantomData;
+use ark_std::collections::BTreeMap;
 use crate::Scalar;
 
 // Struct ParticipantState models the states that each participant in the PVSS
 // scheme goes through.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ParticipantState {
     Dealer,
     DealerShared,
@@ -14,13 +19,277 @@ pub enum ParticipantState {
 }
 
 // Struct Participant models each individual party participating in the PVSS scheme.
+// Outside this crate, its fields are read through getters, and participants are
+// created with Participant::new (or one of the other constructors) in their initial
+// state, which with_state changes. Participant::from_raw_parts is only available
+// with the testing feature.
 #[derive(Clone)]
 pub struct Participant<
     E: PairingEngine,
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 > {
-    pub pairing_type: PhantomData<E>,
-    pub id: usize,                         // participant id
-    pub public_key_sig: SSIG::PublicKey,   // participant public key
-    pub state: ParticipantState,           // participant current state
+    pub(crate) pairing_type: PhantomData<E>,
+    pub(crate) id: usize,                         // participant id
+    pub(crate) public_key_sig: SSIG::PublicKey,   // participant public key
+    pub(crate) state: ParticipantState,           // participant current state
+}
+
+impl<
+        E: PairingEngine,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    > Participant<E, SSIG>
+{
+
+    // Function for creating a participant in its initial state.
+    pub fn new(id: usize, public_key_sig: SSIG::PublicKey) -> Self {
+        Self {
+            pairing_type: PhantomData,
+            id,
+            public_key_sig,
+            state: ParticipantState::Initial,
+        }
+    }
+
+    // Function for creating a participant in any state.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_raw_parts(id: usize, public_key_sig: SSIG::PublicKey, state: ParticipantState) -> Self {
+        Self::new(id, public_key_sig).with_state(state)
+    }
+
+    // Method returning the participant, moved to the given state.
+    pub fn with_state(mut self, state: ParticipantState) -> Self {
+        self.state = state;
+        self
+    }
+
+    // Getter for the participant id.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    // Getter for the participant public key.
+    pub fn public_key_sig(&self) -> &SSIG::PublicKey {
+        &self.public_key_sig
+    }
+
+    // Getter for the participant current state.
+    pub fn state(&self) -> &ParticipantState {
+        &self.state
+    }
+
+    // Function for creating a participant from externally generated key material:
+    // its id in a roster of num_participants, and its public key in the standard
+    // encoding (see SchnorrSignature::public_key_from_bytes), which is fully validated.
+    pub fn from_raw_keys(id: usize, num_participants: usize, public_key: &[u8]) -> Result<Self, PVSSError<E>> {
+        if id >= num_participants {
+            return Err(PVSSError::InvalidParticipantId(id));
+        }
+
+        Ok(Self::new(id, SchnorrSignature::<E::G1Affine>::public_key_from_bytes(public_key)?))
+    }
+}
+
+
+// ParticipantSet models the roster of all participants, keyed by their ids.
+pub type ParticipantSet<E, SSIG> = BTreeMap<usize, Participant<E, SSIG>>;
+
+
+/* Committees with arbitrary ids. Within the protocol, participants are identified
+   by their index 0..n-1, which is their position in comms and encs and determines
+   their evaluation point (see Config::point). Committees whose members carry ids
+   assigned elsewhere (e.g., validator ids, not necessarily contiguous) map them to
+   indices with a Committee: the ids are sorted, and each one's index is its rank.
+*/
+
+// Struct ParticipantId models a participant's id outside the protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ParticipantId(pub u64);
+
+// Struct Committee maps the ids of a committee's members to protocol indices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Committee {
+    ids: Vec<ParticipantId>,   // the members' ids, in increasing order (i.e., by index)
+}
+
+impl Committee {
+
+    // Function for creating a committee from its members' ids, given in any order.
+    pub fn new<E: PairingEngine>(ids: &[ParticipantId]) -> Result<Self, PVSSError<E>> {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        if let Some(pair) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(PVSSError::DuplicateParticipantIdError(pair[0].0));
+        }
+        Ok(Self { ids })
+    }
+
+    // Method returning the number of members.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    // Method returning whether the committee has no members.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    // Method returning the protocol index of the member with the given id.
+    pub fn index_of<E: PairingEngine>(&self, id: ParticipantId) -> Result<usize, PVSSError<E>> {
+        self.ids.binary_search(&id).map_err(|_| PVSSError::UnknownParticipantIdError(id.0))
+    }
+
+    // Method returning the id of the member at the given protocol index.
+    pub fn id_of<E: PairingEngine>(&self, index: usize) -> Result<ParticipantId, PVSSError<E>> {
+        self.ids.get(index).copied().ok_or(PVSSError::InvalidParticipantId(index))
+    }
+
+    // Method returning the members' ids, in index order.
+    pub fn ids(&self) -> &[ParticipantId] {
+        &self.ids
+    }
+
+    // Method for creating the roster of the committee from its members' keys, which
+    // must be given for exactly the members.
+    pub fn participants<E, SSIG>(&self, keys: &BTreeMap<ParticipantId, SSIG::PublicKey>) -> Result<ParticipantSet<E, SSIG>, PVSSError<E>>
+    where
+        E: PairingEngine,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    {
+        if let Some(id) = keys.keys().find(|id| self.ids.binary_search(id).is_err()) {
+            return Err(PVSSError::UnknownParticipantIdError(id.0));
+        }
+
+        self.ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                let key = keys.get(id).ok_or(PVSSError::<E>::UnknownParticipantIdError(id.0))?;
+                Ok((index, Participant::new(index, *key)))
+            })
+            .collect()
+    }
+}
+
+
+/* Deterministic index assignment. Nodes that build the roster independently from
+   the same (e.g., on-chain) list of identity keys must agree on every participant's
+   id, since ids determine evaluation points and hence shares. FromUnordered assigns
+   ids 0..n by sorting the keys by their compressed canonical serialization, compared
+   lexicographically as byte strings. The order the keys are given in is irrelevant,
+   and this rule is part of the protocol: it must not change between versions.
+*/
+
+pub trait FromUnordered<K>: Sized {
+    type Error;
+
+    // Function for creating a roster from identity keys given in any order.
+    fn from_unordered(keys: &[K]) -> Result<Self, Self::Error>;
+}
+
+impl<E, SSIG> FromUnordered<E::G1Affine> for ParticipantSet<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    type Error = PVSSError<E>;
+
+    fn from_unordered(keys: &[E::G1Affine]) -> Result<Self, PVSSError<E>> {
+        let mut encoded = keys
+            .iter()
+            .map(|key| {
+                let mut bytes = vec![];
+                key.serialize(&mut bytes)?;
+                Ok((bytes, *key))
+            })
+            .collect::<Result<Vec<_>, PVSSError<E>>>()?;
+        encoded.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Equal keys would leave their relative order, and thus the ids, undefined.
+        if encoded.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(PVSSError::DuplicatePublicKeyError);
+        }
+
+        Ok(encoded
+            .into_iter()
+            .enumerate()
+            .map(|(id, (_, public_key_sig))| (id, Participant::new(id, public_key_sig)))
+            .collect())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        errors::PVSSError,
+        participant::{Committee, FromUnordered, ParticipantId, ParticipantSet},
+        tests::{setup_nodes, SSIG},
+    };
+    use crate::signature::{schnorr::{srs::SRS as SchnorrSRS, SchnorrVersion}, scheme::SignatureScheme};
+
+    use ark_bls12_381::{Bls12_381 as E, G1Affine};
+    use ark_ec::AffineCurve;
+    use ark_std::collections::BTreeMap;
+    use rand::{seq::SliceRandom, thread_rng};
+
+    #[test]
+    fn test_from_unordered() {
+        let rng = &mut thread_rng();
+        let schnorr = SSIG { srs: SchnorrSRS { g_public_key: G1Affine::prime_subgroup_generator() }, version: SchnorrVersion::KeyBound };
+        let mut keys = (0..6)
+            .map(|_| schnorr.generate_keypair(rng).unwrap().1)
+            .collect::<Vec<_>>();
+
+        // Any permutation of the keys yields the same roster.
+        let roster = ParticipantSet::<E, SSIG>::from_unordered(&keys).unwrap();
+        keys.shuffle(rng);
+        let shuffled = ParticipantSet::<E, SSIG>::from_unordered(&keys).unwrap();
+        assert_eq!(roster.len(), 6);
+        for (id, participant) in roster.iter() {
+            assert_eq!(participant.id, *id);
+            assert_eq!(participant.public_key_sig, shuffled[id].public_key_sig);
+        }
+
+        keys.push(keys[0]);
+        assert!(matches!(ParticipantSet::<E, SSIG>::from_unordered(&keys), Err(PVSSError::DuplicatePublicKeyError)));
+    }
+
+    #[test]
+    fn test_committee() {
+        let rng = &mut thread_rng();
+        let schnorr = SSIG { srs: SchnorrSRS { g_public_key: G1Affine::prime_subgroup_generator() }, version: SchnorrVersion::KeyBound };
+        let ids = [ParticipantId(42), ParticipantId(7), ParticipantId(1000)];
+
+        // Members are indexed by the rank of their ids.
+        let committee = Committee::new::<E>(&ids).unwrap();
+        assert_eq!(committee.index_of::<E>(ParticipantId(7)).unwrap(), 0);
+        assert_eq!(committee.index_of::<E>(ParticipantId(1000)).unwrap(), 2);
+        assert_eq!(committee.id_of::<E>(1).unwrap(), ParticipantId(42));
+        assert!(matches!(committee.index_of::<E>(ParticipantId(8)), Err(PVSSError::UnknownParticipantIdError(8))));
+        assert!(matches!(Committee::new::<E>(&[ParticipantId(7), ParticipantId(7)]), Err(PVSSError::DuplicateParticipantIdError(7))));
+
+        let mut keys = ids
+            .iter()
+            .map(|id| (*id, schnorr.generate_keypair(rng).unwrap().1))
+            .collect::<BTreeMap<_, _>>();
+        let roster = committee.participants::<E, SSIG>(&keys).unwrap();
+        assert_eq!(roster[&1].public_key_sig, keys[&ParticipantId(42)]);
+
+        keys.insert(ParticipantId(8), keys[&ParticipantId(7)]);
+        assert!(matches!(committee.participants::<E, SSIG>(&keys), Err(PVSSError::UnknownParticipantIdError(8))));
+
+        // A member without a key is reported by its id.
+        keys.remove(&ParticipantId(8));
+        keys.remove(&ParticipantId(1000));
+        assert!(matches!(committee.participants::<E, SSIG>(&keys), Err(PVSSError::UnknownParticipantIdError(1000))));
+
+        // Evaluation points follow the indices.
+        let (nodes, _) = setup_nodes(3, 1);
+        let config = &nodes[0].aggregator.config;
+        assert_eq!(config.point_of(&committee, ParticipantId(42)).unwrap(), config.point(1));
+        let (nodes, _) = setup_nodes(4, 1);
+        assert!(matches!(nodes[0].aggregator.config.point_of(&committee, ParticipantId(42)), Err(PVSSError::CommitteeSizeError(3, 4))));
+    }
 }
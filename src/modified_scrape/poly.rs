@@ -1,30 +1,49 @@
+use super::config::Config;
 use super::errors::PVSSError;
 
-use ark_ff::{Field, Zero, One};
-use ark_ec::{PairingEngine, ProjectiveCurve};
-use ark_poly::{UVPolynomial, Polynomial as Poly, polynomial::univariate::DensePolynomial};
+use ark_ff::{batch_inversion, Field, Zero, One};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain, UVPolynomial, Polynomial as Poly, polynomial::univariate::DensePolynomial};
 use ark_std::ops::AddAssign;
 use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
 use crate::Scalar;
+use super::metrics::count_msms;
 
 // use ark_std::ops::{Add, Mul};
 
-use rand::Rng;
+use blake2s_simd::Params;
+use rand::{CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+const DEGREE_CHECK_PERSONALIZATION: &[u8] = b"OPTRDEGC";   // persona for deriving the coding-check polynomial
 
 // A polynomial with the various coefficients in the Scalar Group
 pub type Polynomial<E> = DensePolynomial<Scalar<E>>;
 
 
+// Function for sampling a random polynomial of the given degree with a fixed free
+// term, i.e., a random sharing of a given secret.
+pub fn sample_with_free_term<E: PairingEngine, R: Rng + CryptoRng>(degree: usize, free_term: Scalar<E>, rng: &mut R) -> Polynomial<E> {
+    let mut poly = Polynomial::<E>::rand(degree, rng);
+    match poly.coeffs.first_mut() {
+        Some(coeff) => *coeff = free_term,
+        None => poly.coeffs.push(free_term),
+    }
+    poly
+}
+
+
 
 // Function for ensuring that the commitment vector evals is
 // also a commitment to a polynomial of specified degree.
 pub fn ensure_degree<E, R>(rng: &mut R,
-                           evaluations: &Vec<E::G2Projective>,
+                           evaluations: &[E::G2Projective],
                            degree: u64) -> Result<(), PVSSError<E>>
 where
 	E: PairingEngine,
 	E::G2Projective: AddAssign,
-	R: Rng
+	R: Rng + CryptoRng
 	//Scalar<E>: AsRef<[u64]>,
 	//Scalar<E>: AddAssign<<E as PairingEngine>::G2Affine>,
 	//Scalar<E>: From<u64>,
@@ -37,24 +56,21 @@ where
         return Err(PVSSError::InsufficientEvaluationsError);
     }
 
+    // the check is a single linear combination of the evaluations
+    count_msms(1);
+
     // sample a random polynomial of appropriate degree
     let poly = Polynomial::<E>::rand((num-degree-2) as usize, rng);
 
-    let mut v = E::G2Projective::zero();
-
-    for i in 1..num+1 {
-        let scalar_i = Scalar::<E>::from(i);
-	let mut cperp = poly.evaluate(&scalar_i);
-	for j in 1..num+1 {
-            let scalar_j = Scalar::<E>::from(j);
-            if i != j {
-                cperp *= (scalar_i - scalar_j).inverse().unwrap();
-            }
-        }
-	v += evaluations[(i-1) as usize].mul(cperp.into_repr());   // .into_affine();
-    }
+    // the dual codeword: cperp_i = poly(x_i) * v_i, at the integer points x_i of Config
+    let scalars = dual_code_coefficients::<E>(num as usize)
+        .iter()
+        .enumerate()
+        .map(|(i, v_i)| (poly.evaluate(&Config::<E>::point_in(None, i)) * v_i).into_repr())
+        .collect::<Vec<_>>();
+    let bases = E::G2Projective::batch_normalization_into_affine(evaluations);
 
-    if v.into_affine() != E::G2Affine::zero() {
+    if !VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
 	return Err(PVSSError::DualCodeError);
     }
 
@@ -63,9 +79,76 @@ where
 }
 
 
+// Utility function computing the coefficients v_i = prod_{j != i} 1/(i - j) of the
+// dual code for the points i = 1, ..., num (those of Config outside of a domain). The
+// denominators have the closed form prod_{j != i} (i - j) = (-1)^{num-i} (i-1)! (num-i)!,
+// and are inverted in a batch.
+fn dual_code_coefficients<E: PairingEngine>(num: usize) -> Vec<Scalar<E>> {
+    let mut factorials = vec![Scalar::<E>::one(); num.max(1)];
+    for k in 1..num {
+        factorials[k] = factorials[k - 1] * Scalar::<E>::from(k as u64);
+    }
+
+    let mut coefficients = (1..num + 1)
+        .map(|i| {
+            let denominator = factorials[i - 1] * factorials[num - i];
+            if (num - i) % 2 == 1 { -denominator } else { denominator }
+        })
+        .collect::<Vec<_>>();
+
+    // the points are distinct and below the characteristic, so nothing is zero
+    batch_inversion(&mut coefficients);
+    coefficients
+}
+
+
+
+// Utility function computing the Lagrange coefficients
+// lambda_j = prod_{k != j} (x - x_k) / (x_j - x_k) of the given points at x. The
+// numerators are formed from prefix and suffix products, and the denominators are
+// inverted in a single batch. The points must be distinct.
+pub fn lagrange_coefficients<E: PairingEngine>(points: &[Scalar<E>],
+                                               x: Scalar<E>) -> Result<Vec<Scalar<E>>, PVSSError<E>> {
+    let num = points.len();
+
+    // suffixes[j] = prod_{k >= j} (x - x_k)
+    let mut suffixes = vec![Scalar::<E>::one(); num + 1];
+    for j in (0..num).rev() {
+        suffixes[j] = suffixes[j + 1] * (x - points[j]);
+    }
+
+    let mut numerators = Vec::with_capacity(num);
+    let mut prefix = Scalar::<E>::one();
+    for j in 0..num {
+        numerators.push(prefix * suffixes[j + 1]);
+        prefix *= x - points[j];
+    }
+
+    let mut denominators = points
+        .iter()
+        .enumerate()
+        .map(|(j, x_j)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| *k != j)
+                .fold(Scalar::<E>::one(), |acc, (_, x_k)| acc * (*x_j - x_k))
+        })
+        .collect::<Vec<_>>();
+
+    // batch_inversion leaves zeros as they are, so repeated points must be caught here
+    if denominators.iter().any(|d| d.is_zero()) {
+        return Err(PVSSError::DifferentPointsEvalsError);
+    }
+    batch_inversion(&mut denominators);
+
+    Ok(numerators.iter().zip(denominators.iter()).map(|(n, d)| *n * d).collect())
+}
+
+
 
 // Utility function for Lagrange interpolation from a given list of evaluations.
-pub fn lagrange_interpolation_simple<E>(evals: &Vec<E::G2Projective>,
+pub fn lagrange_interpolation_simple<E>(evals: &[E::G2Projective],
 					degree: u64) -> Result<E::G2Projective, PVSSError<E>> 
 where
 	E: PairingEngine,
@@ -76,31 +159,220 @@ where
         return Err(PVSSError::InsufficientEvaluationsError);
     }
 
-    let mut sum = E::G2Projective::zero();
-    
-    for j in 0..degree+1 {
-        let x_j = Scalar::<E>::from(j + 1);
-	let mut prod = Scalar::<E>::one();
-	for k in 0..degree+1 {
-	    if j != k {
-	        let x_k = Scalar::<E>::from(k + 1);
-	        prod *= x_k * (x_k - x_j).inverse().unwrap();
-	    }
-	}
+    let points = (0..(degree + 1) as usize).map(|i| Config::<E>::point_in(None, i)).collect::<Vec<_>>();
+    let bases = E::G2Projective::batch_normalization_into_affine(&evals[..points.len()]);
+    interpolate_at_zero::<E, E::G2Affine>(&bases, &points)
+}
+
+
 
-	// Recovery formula
-	sum += evals[j as usize].mul(prod.into_repr());
+// Non-interactive variant of ensure_degree: the polynomial defining the dual
+// codeword is derived by Fiat-Shamir from the commitment vector and the degree,
+// rather than sampled by the verifier. The outcome is thus a deterministic function
+// of the commitments, so that a party's claim that they pass the check is
+// transferable and can be re-checked by anyone at the same cost.
+pub fn ensure_degree_fiat_shamir<E>(evaluations: &[E::G2Projective],
+                                    degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+	E::G2Projective: AddAssign,
+{
+    let mut bytes = degree.to_be_bytes().to_vec();
+    evaluations.serialize(&mut bytes)?;
+
+    ensure_degree::<E, _>(&mut degree_check_rng(&bytes), evaluations, degree)
+}
+
+
+// Variant of ensure_degree_fiat_shamir with the polynomial defining the dual codeword
+// additionally derived from an externally supplied seed (e.g., a block hash), so that
+// all verifiers sharing the seed perform the identical, replayable check. The seed is
+// hashed along with the commitments, so a dealer that knows it in advance still cannot
+// tailor commitments to the resulting codeword.
+pub fn ensure_degree_with_seed<E>(seed: &[u8],
+                                  evaluations: &[E::G2Projective],
+                                  degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+	E::G2Projective: AddAssign,
+{
+    let mut bytes = (seed.len() as u64).to_be_bytes().to_vec();
+    bytes.extend_from_slice(seed);
+    bytes.extend_from_slice(&degree.to_be_bytes());
+    evaluations.serialize(&mut bytes)?;
+
+    ensure_degree::<E, _>(&mut degree_check_rng(&bytes), evaluations, degree)
+}
+
+
+// Variant of ensure_degree for evaluations at arbitrary (distinct) points, as found
+// in sparse shares. With exactly degree+1 evaluations the check is vacuous, since
+// any degree+1 values are interpolated by a polynomial of the specified degree.
+pub fn ensure_degree_at_points<E, R>(rng: &mut R,
+                                     evaluations: &[E::G2Projective],
+                                     points: &[Scalar<E>],
+                                     degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+	R: Rng + CryptoRng
+{
+    let num = evaluations.len() as u64;
+
+    if evaluations.len() != points.len() {
+	return Err(PVSSError::DifferentPointsEvalsError);
+    }
+
+    if num < degree + 1 {
+        return Err(PVSSError::InsufficientEvaluationsError);
+    }
+
+    if num == degree + 1 {
+        return Ok(());
+    }
+
+    count_msms(1);
+
+    // sample a random polynomial of appropriate degree
+    let poly = Polynomial::<E>::rand((num-degree-2) as usize, rng);
+
+    let mut v = E::G2Projective::zero();
+
+    for (i, x_i) in points.iter().enumerate() {
+	let mut cperp = poly.evaluate(x_i);
+	for (j, x_j) in points.iter().enumerate() {
+            if i != j {
+                cperp *= (*x_i - *x_j).inverse().ok_or(PVSSError::DifferentPointsEvalsError)?;
+            }
+        }
+	v += evaluations[i].mul(cperp.into_repr());
     }
 
-    Ok(sum)
+    if !v.is_zero() {
+	return Err(PVSSError::DualCodeError);
+    }
+
+    Ok(())
 }
 
 
+// Variant of ensure_degree for evaluations at the first elements of a radix-2
+// evaluation domain. When they cover the whole domain, the dual codeword is
+// v_i = w^i / N (for N the size of the domain) times the evaluations of the random
+// polynomial, which an FFT yields at once: the check costs O(n log n) field
+// operations and one MSM. Otherwise it is run as for arbitrary points.
+pub fn ensure_degree_in_domain<E, R>(rng: &mut R,
+                                     evaluations: &[E::G2Projective],
+                                     domain: &Radix2EvaluationDomain<Scalar<E>>,
+                                     degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+	R: Rng + CryptoRng
+{
+    let num = evaluations.len() as u64;
+
+    if evaluations.len() != domain.size() {
+	let points = domain.elements().take(evaluations.len()).collect::<Vec<_>>();
+	return ensure_degree_at_points::<E, R>(rng, evaluations, &points, degree);
+    }
+
+    if num < degree + 1 {
+        return Err(PVSSError::InsufficientEvaluationsError);
+    }
+
+    if num == degree + 1 {
+        return Ok(());
+    }
+
+    count_msms(1);
+
+    // sample a random polynomial of appropriate degree, and evaluate it over the domain
+    let poly = Polynomial::<E>::rand((num-degree-2) as usize, rng);
+    let scalars = domain
+        .fft(&poly.coeffs)
+        .iter()
+        .zip(domain.elements())
+        .map(|(m_i, x_i)| (*m_i * x_i * domain.size_inv).into_repr())
+        .collect::<Vec<_>>();
+    let bases = E::G2Projective::batch_normalization_into_affine(evaluations);
+
+    if !VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
+	return Err(PVSSError::DualCodeError);
+    }
+
+    Ok(())
+}
+
+
+// Non-interactive variant of ensure_degree_in_domain, binding the derived
+// polynomial to the size of the domain as well as to the evaluations.
+pub fn ensure_degree_in_domain_fiat_shamir<E>(evaluations: &[E::G2Projective],
+                                              domain: &Radix2EvaluationDomain<Scalar<E>>,
+                                              degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+{
+    let mut bytes = degree.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&domain.size.to_be_bytes());
+    evaluations.serialize(&mut bytes)?;
+
+    ensure_degree_in_domain::<E, _>(&mut degree_check_rng(&bytes), evaluations, domain, degree)
+}
+
+
+// Variant of ensure_degree_with_seed for evaluations over a radix-2 domain.
+pub fn ensure_degree_in_domain_with_seed<E>(seed: &[u8],
+                                            evaluations: &[E::G2Projective],
+                                            domain: &Radix2EvaluationDomain<Scalar<E>>,
+                                            degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+{
+    let mut bytes = (seed.len() as u64).to_be_bytes().to_vec();
+    bytes.extend_from_slice(seed);
+    bytes.extend_from_slice(&degree.to_be_bytes());
+    bytes.extend_from_slice(&domain.size.to_be_bytes());
+    evaluations.serialize(&mut bytes)?;
+
+    ensure_degree_in_domain::<E, _>(&mut degree_check_rng(&bytes), evaluations, domain, degree)
+}
+
+
+// Non-interactive variant of ensure_degree_at_points, binding the derived
+// polynomial to the points as well as to the evaluations.
+pub fn ensure_degree_at_points_fiat_shamir<E>(evaluations: &[E::G2Projective],
+                                              points: &[Scalar<E>],
+                                              degree: u64) -> Result<(), PVSSError<E>>
+where
+	E: PairingEngine,
+{
+    let mut bytes = degree.to_be_bytes().to_vec();
+    evaluations.serialize(&mut bytes)?;
+    points.serialize(&mut bytes)?;
+
+    ensure_degree_at_points::<E, _>(&mut degree_check_rng(&bytes), evaluations, points, degree)
+}
+
+
+// Utility function deriving the randomness of a non-interactive degree check
+// from the bytes it must be bound to.
+fn degree_check_rng(bytes: &[u8]) -> ChaChaRng {
+    let hash = Params::new()
+        .hash_length(32)
+        .personal(DEGREE_CHECK_PERSONALIZATION)
+        .to_state()
+        .update(bytes)
+        .finalize();
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(hash.as_bytes());
+
+    ChaChaRng::from_seed(seed)
+}
+
 
 // Utility function for Lagrange interpolation from a given list of points
 // and evaluations.
-pub fn lagrange_interpolation<E>(evals: &Vec<E::G2Projective>,
-				 points: &Vec<Scalar<E>>,
+pub fn lagrange_interpolation<E>(evals: &[E::G2Projective],
+				 points: &[Scalar<E>],
 				 degree: u64) -> Result<E::G2Projective, PVSSError<E>> 
 where
 	E: PairingEngine,
@@ -114,23 +386,26 @@ where
 	return Err(PVSSError::DifferentPointsEvalsError);
     }
 
-    let mut sum = E::G2Projective::zero();
+    let num = (degree + 1) as usize;
+    let bases = E::G2Projective::batch_normalization_into_affine(&evals[..num]);
+    interpolate_at_zero::<E, E::G2Affine>(&bases, &points[..num])
+}
 
-    for j in 0..degree+1 {
-        let x_j = points[j as usize];
-	let mut prod = Scalar::<E>::one();
-	for k in 0..degree+1 {
-	    if j != k {
-	        let x_k = points[k as usize];
-	        prod *= x_k * (x_k - x_j).inverse().unwrap();
-	    }
-	}
 
-	// Recovery formula
-	sum += evals[j as usize].mul(prod.into_repr());
-    }
+// Utility function interpolating the given evaluations (in any group) at the given
+// points, and evaluating the result at 0 in the exponent, as a single MSM.
+pub fn interpolate_at_zero<E, G>(evals: &[G], points: &[Scalar<E>]) -> Result<G::Projective, PVSSError<E>>
+where
+	E: PairingEngine,
+	G: AffineCurve<ScalarField = Scalar<E>>,
+{
+    let scalars = lagrange_coefficients::<E>(points, Scalar::<E>::zero())?
+        .iter()
+        .map(|lambda| lambda.into_repr())
+        .collect::<Vec<_>>();
 
-    Ok(sum)
+    count_msms(1);
+    Ok(VariableBaseMSM::multi_scalar_mul(evals, &scalars))
 }
 
 
@@ -142,13 +417,13 @@ where
 mod test {
     use rand::{Rng, thread_rng};
     use crate::ark_std::UniformRand;
-    use ark_ff::PrimeField;
-    use ark_poly::{UVPolynomial, Polynomial as Poly};
-    use ark_ec::{PairingEngine, ProjectiveCurve, AffineCurve};
+    use ark_ff::{Field, One, PrimeField};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain, UVPolynomial, Polynomial as Poly};
+    use ark_ec::{PairingEngine, AffineCurve};
     use ark_bls12_381::{Bls12_381 as E};   // implements PairingEngine
 
 
-    use crate::modified_scrape::{poly::{Polynomial, ensure_degree, lagrange_interpolation_simple, lagrange_interpolation}};
+    use crate::modified_scrape::{poly::{Polynomial, dual_code_coefficients, lagrange_coefficients, ensure_degree, ensure_degree_fiat_shamir, ensure_degree_in_domain, ensure_degree_in_domain_fiat_shamir, ensure_degree_with_seed, lagrange_interpolation_simple, lagrange_interpolation}};
     use crate::modified_scrape::{srs::SRS};
     use crate::Scalar;
 
@@ -190,6 +465,119 @@ mod test {
     }
 
 
+    #[test]
+    fn test_dual_code_coefficients() {
+        let num = 9;
+        let coefficients = dual_code_coefficients::<E>(num);
+
+        // The batched closed form agrees with the definition.
+        for i in 1..num + 1 {
+            let mut v_i = Scalar::<E>::one();
+            for j in 1..num + 1 {
+                if i != j {
+                    v_i *= (Scalar::<E>::from(i as u64) - Scalar::<E>::from(j as u64)).inverse().unwrap();
+                }
+            }
+            assert_eq!(coefficients[i - 1], v_i);
+        }
+    }
+
+    #[test]
+    fn test_lagrange_coefficients() {
+        let rng = &mut thread_rng();
+        let points = (0..5).map(|_| Scalar::<E>::rand(rng)).collect::<Vec<_>>();
+        let x = Scalar::<E>::rand(rng);
+        let coefficients = lagrange_coefficients::<E>(&points, x).unwrap();
+
+        // The batched coefficients agree with the definition.
+        for (j, x_j) in points.iter().enumerate() {
+            let mut lambda = Scalar::<E>::one();
+            for (k, x_k) in points.iter().enumerate() {
+                if j != k {
+                    lambda *= (x - x_k) * (*x_j - x_k).inverse().unwrap();
+                }
+            }
+            assert_eq!(coefficients[j], lambda);
+        }
+
+        // Repeated points are rejected.
+        let repeated = vec![points[0], points[1], points[0]];
+        assert!(lagrange_coefficients::<E>(&repeated, x).is_err());
+    }
+
+    #[test]
+    fn test_ensure_degree_fiat_shamir() {
+	let rng = &mut thread_rng();
+        let deg = rng.gen_range(MIN_DEGREE, MAX_DEGREE) as u64;
+	let generator = SRS::<E>::setup(rng).unwrap().g2;
+
+	// commitments to a polynomial of the right degree pass
+	let p = Polynomial::<E>::rand(deg as usize, rng);
+	let evals = (1..(deg+5))
+		.map(|x| generator.mul(p.evaluate(&Scalar::<E>::from(x)).into_repr()))
+		.collect::<Vec<_>>();
+	ensure_degree_fiat_shamir::<E>(&evals, deg).unwrap();
+
+	// while commitments to a polynomial of higher degree do not
+	let q = Polynomial::<E>::rand((deg+1) as usize, rng);
+	let evals = (1..(deg+5))
+		.map(|x| generator.mul(q.evaluate(&Scalar::<E>::from(x)).into_repr()))
+		.collect::<Vec<_>>();
+	assert!(ensure_degree_fiat_shamir::<E>(&evals, deg).is_err());
+    }
+
+
+    #[test]
+    fn test_ensure_degree_in_domain() {
+	let rng = &mut thread_rng();
+	let deg = 5;
+	let generator = SRS::<E>::setup(rng).unwrap().g2;
+	let p = Polynomial::<E>::rand(deg, rng);
+	let q = Polynomial::<E>::rand(deg + 1, rng);
+
+	// over the whole domain (by FFT) and over a part of it (point by point)
+	for num in [16, 11].iter() {
+	    let domain = Radix2EvaluationDomain::<Scalar<E>>::new(*num).unwrap();
+	    let commit = |poly: &Polynomial<E>| domain
+		.elements()
+		.take(*num)
+		.map(|x| generator.mul(poly.evaluate(&x).into_repr()))
+		.collect::<Vec<_>>();
+
+	    // commitments to a polynomial of the right degree pass
+	    let evals = commit(&p);
+	    ensure_degree_in_domain::<E, _>(rng, &evals, &domain, deg as u64).unwrap();
+	    ensure_degree_in_domain_fiat_shamir::<E>(&evals, &domain, deg as u64).unwrap();
+
+	    // while commitments to a polynomial of higher degree do not
+	    let evals = commit(&q);
+	    assert!(ensure_degree_in_domain::<E, _>(rng, &evals, &domain, deg as u64).is_err());
+	    assert!(ensure_degree_in_domain_fiat_shamir::<E>(&evals, &domain, deg as u64).is_err());
+	}
+    }
+
+
+    #[test]
+    fn test_ensure_degree_with_seed() {
+	let rng = &mut thread_rng();
+        let deg = rng.gen_range(MIN_DEGREE, MAX_DEGREE) as u64;
+	let generator = SRS::<E>::setup(rng).unwrap().g2;
+	let seed = b"block hash";
+
+	let p = Polynomial::<E>::rand(deg as usize, rng);
+	let evals = (1..(deg+5))
+		.map(|x| generator.mul(p.evaluate(&Scalar::<E>::from(x)).into_repr()))
+		.collect::<Vec<_>>();
+	ensure_degree_with_seed::<E>(seed, &evals, deg).unwrap();
+
+	let q = Polynomial::<E>::rand((deg+1) as usize, rng);
+	let evals = (1..(deg+5))
+		.map(|x| generator.mul(q.evaluate(&Scalar::<E>::from(x)).into_repr()))
+		.collect::<Vec<_>>();
+	assert!(ensure_degree_with_seed::<E>(seed, &evals, deg).is_err());
+    }
+
+
     #[test]
     #[should_panic]
     fn test_ensure_degree_insufficient_evals() {
@@ -228,7 +616,7 @@ mod test {
 	let shared_secret = generator.mul(secret.into_repr());
 
 	let evals = (1..(deg+2))
-		.map(|x| generator.mul(p.evaluate(&Scalar::<E>::from(x as u64)).into_repr()))
+		.map(|x| generator.mul(p.evaluate(&Scalar::<E>::from(x)).into_repr()))
 		.collect::<Vec<_>>();
 
 	let reconstructed_secret = lagrange_interpolation_simple::<E>(&evals, deg).unwrap();   // G2Projective
@@ -279,7 +667,7 @@ mod test {
 	let shared_secret = generator.mul(secret.into_repr());
 
 	let points = (1..(deg+2))
-		.map(|j| Scalar::<E>::from(j as u64))
+		.map(Scalar::<E>::from)
 		.collect::<Vec<_>>();
 	let evals = (1..(deg+2))
 		.map(|j| generator.mul(p.evaluate(&points[(j-1) as usize]).into_repr()))
@@ -1,21 +1,29 @@
 use ark_ec::PairingEngine;
 use ark_ff::Zero;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::collections::BTreeMap;
 
-use crate::Scalar;
+use crate::{canonical_digest, Digest, Hash, Scalar};
+use crate::modified_scrape::config::Config;
 use crate::modified_scrape::errors::PVSSError;
 
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 
 /* Struct PVSSShare models the PVSS sharing generated by the a participant when acting as dealer */
 
+// Outside this crate, the vectors are read through getters, and shares are created
+// with PVSSShare::new, which checks that the vectors have the same length (or are
+// obtained by dealing or deserialization). PVSSShare::from_raw_parts skips the
+// check, and is only available with the testing feature.
 #[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct PVSSShare<E>
 where
     E: PairingEngine,
     // SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>   // Double-check PublicKey (drop constraint if unnecessary)
 {
-    pub comms: Vec<E::G2Projective>,    	     // vector of commitments v
-    pub encs: Vec<E::G1Projective>,     	     // vector of encryptions c
+    pub(crate) comms: Vec<E::G2Projective>,    	     // vector of commitments v
+    pub(crate) encs: Vec<E::G1Projective>,     	     // vector of encryptions c
 
 
     // Moved to PVSSAugmentedShare
@@ -30,6 +38,34 @@ where
     // SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>   // Double-check PublicKey (drop constraint if unnecessary)
 {
 
+    // Create a PVSSShare from a commitment and an encryption per participant.
+    pub fn new(comms: Vec<E::G2Projective>, encs: Vec<E::G1Projective>) -> Result<Self, PVSSError<E>> {
+	if comms.len() != encs.len() {
+	    return Err(PVSSError::MismatchedCommitmentsEncryptionsError(comms.len(), encs.len()));
+	}
+	Ok(PVSSShare { comms, encs })
+    }
+
+
+    // Create a PVSSShare from any vectors, e.g., to test how malformed shares are handled.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn from_raw_parts(comms: Vec<E::G2Projective>, encs: Vec<E::G1Projective>) -> Self {
+	PVSSShare { comms, encs }
+    }
+
+
+    // Getter for the vector of commitments.
+    pub fn comms(&self) -> &[E::G2Projective] {
+	&self.comms
+    }
+
+
+    // Getter for the vector of encryptions.
+    pub fn encs(&self) -> &[E::G1Projective] {
+	&self.encs
+    }
+
+
     // Create a new "empty" PVSSShare, where all fields are set to "zero" values.
     pub fn empty(_degree: usize, num_participants: usize) -> Self {
         PVSSShare {
@@ -41,22 +77,7 @@ where
 
     // Aggregation of PVSSShare instances.
     pub fn aggregate(&self, other: &Self) -> Result<Self, PVSSError<E>> {
-	// Perform some basic checks
-	if self.comms.len() == 0 {
-	    return Err(PVSSError::EmptyEncryptionsVectorError);
-	}
-
-	if self.comms.len() != other.comms.len() {
-	    return Err(PVSSError::MismatchedCommitmentsError(self.comms.len(), other.comms.len()));
-	}
-
-	if self.encs.len() != other.encs.len() {
-	    return Err(PVSSError::MismatchedEncryptionsError(self.encs.len(), other.encs.len()));
-	}
-
-	if self.comms.len() != self.encs.len() {
-	    return Err(PVSSError::MismatchedCommitmentsEncryptionsError(self.comms.len(), other.encs.len()));
-	}
+	self.check_aggregatable(other)?;
 
 	// Aggregate PVSS shares
 	let result = Self {
@@ -79,11 +100,201 @@ where
 	Ok(result)
     }
 
+
+    // In-place aggregation of PVSSShare instances, sparing the allocation of a new
+    // share. On error, self is left unchanged.
+    pub fn aggregate_assign(&mut self, other: &Self) -> Result<(), PVSSError<E>> {
+	self.check_aggregatable(other)?;
+
+	for (c1, c2) in self.comms.iter_mut().zip(other.comms.iter()) {
+	    *c1 += c2;
+	}
+	for (e1, e2) in self.encs.iter_mut().zip(other.encs.iter()) {
+	    *e1 += e2;
+	}
+
+	Ok(())
+    }
+
+
+    // Utility method performing the basic checks preceding an aggregation.
+    fn check_aggregatable(&self, other: &Self) -> Result<(), PVSSError<E>> {
+	if self.comms.is_empty() {
+	    return Err(PVSSError::EmptyEncryptionsVectorError);
+	}
+
+	if self.comms.len() != other.comms.len() {
+	    return Err(PVSSError::MismatchedCommitmentsError(self.comms.len(), other.comms.len()));
+	}
+
+	if self.encs.len() != other.encs.len() {
+	    return Err(PVSSError::MismatchedEncryptionsError(self.encs.len(), other.encs.len()));
+	}
+
+	if self.comms.len() != self.encs.len() {
+	    return Err(PVSSError::MismatchedCommitmentsEncryptionsError(self.comms.len(), other.encs.len()));
+	}
+
+	Ok(())
+    }
+
 }
 
 
-// PVSSShareSecrets models the secret parts underlying each share.
+// PVSSShare implements the Hash trait.
+impl<E: PairingEngine> Hash for PVSSShare<E> {
+    fn digest(&self) -> Digest {
+        canonical_digest(self)
+    }
+}
+
+
+/* Struct SparsePVSSShare models a PVSS sharing that only covers a subset of the
+   participants, as dealt in hierarchical or sharded deployments (see ShareLayout).
+   Only the recipients' encryptions and commitments are kept, keyed by recipient id;
+   recipient j is still given the evaluation of the dealer's polynomial at j + 1.
+*/
+
+#[derive(Clone, Debug)]
+pub struct SparsePVSSShare<E: PairingEngine> {
+    pub num_participants: usize,                                      // size of the whole committee
+    pub entries: BTreeMap<usize, (E::G1Projective, E::G2Projective)>,   // recipient id -> (encryption, commitment)
+}
+
+impl<E: PairingEngine> SparsePVSSShare<E> {
+
+    // Function for restricting a dense PVSSShare to the given recipients.
+    pub fn from_dense(share: &PVSSShare<E>, recipients: &[usize]) -> Result<Self, PVSSError<E>> {
+        let entries = recipients
+            .iter()
+            .map(|id| match (share.encs.get(*id), share.comms.get(*id)) {
+                (Some(enc), Some(comm)) => Ok((*id, (*enc, *comm))),
+                _ => Err(PVSSError::InvalidParticipantId(*id)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            num_participants: share.comms.len(),
+            entries,
+        })
+    }
+
+    // Method returning the ids of the recipients, in ascending order.
+    pub fn recipients(&self) -> Vec<usize> {
+        self.entries.keys().cloned().collect()
+    }
+
+    // Method returning the evaluation points of the recipients under config, in
+    // ascending order.
+    pub fn points(&self, config: &Config<E>) -> Vec<Scalar<E>> {
+        self.entries.keys().map(|id| config.point(*id)).collect()
+    }
+
+    // Method returning the commitments of the recipients, in ascending order.
+    pub fn comms(&self) -> Vec<E::G2Projective> {
+        self.entries.values().map(|(_, comm)| *comm).collect()
+    }
+
+    // Aggregation of SparsePVSSShare instances. Sums of sharings only make sense
+    // at the points both cover, so both must have the same recipients.
+    pub fn aggregate(&self, other: &Self) -> Result<Self, PVSSError<E>> {
+        if self.entries.is_empty() {
+            return Err(PVSSError::EmptyEncryptionsVectorError);
+        }
+
+        if self.num_participants != other.num_participants ||
+           !self.entries.keys().eq(other.entries.keys()) {
+            return Err(PVSSError::MismatchedRecipientsError);
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .zip(other.entries.values())
+            .map(|((id, (e1, c1)), (e2, c2))| (*id, (*e1 + *e2, *c1 + *c2)))
+            .collect();
+
+        Ok(Self {
+            num_participants: self.num_participants,
+            entries,
+        })
+    }
+}
+
+
+// SparsePVSSShare is encoded as the committee size and the number of entries,
+// followed by the (id, encryption, commitment) triples in ascending id order.
+impl<E: PairingEngine> CanonicalSerialize for SparsePVSSShare<E> {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        (self.num_participants as u64).serialize(&mut writer)?;
+        (self.entries.len() as u64).serialize(&mut writer)?;
+        for (id, (enc, comm)) in self.entries.iter() {
+            (*id as u64).serialize(&mut writer)?;
+            enc.serialize(&mut writer)?;
+            comm.serialize(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        16 + self
+            .entries
+            .values()
+            .map(|(enc, comm)| 8 + enc.serialized_size() + comm.serialized_size())
+            .sum::<usize>()
+    }
+}
+
+impl<E: PairingEngine> CanonicalDeserialize for SparsePVSSShare<E> {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let num_participants = u64::deserialize(&mut reader)? as usize;
+        let len = u64::deserialize(&mut reader)?;
+
+        let mut entries = BTreeMap::new();
+        for _ in 0..len {
+            let id = u64::deserialize(&mut reader)? as usize;
+            let enc = E::G1Projective::deserialize(&mut reader)?;
+            let comm = E::G2Projective::deserialize(&mut reader)?;
+
+            // Ids must be in range and strictly ascending for the encoding to be canonical.
+            if id >= num_participants || entries.keys().next_back().is_some_and(|last| *last >= id) {
+                return Err(SerializationError::InvalidData);
+            }
+            entries.insert(id, (enc, comm));
+        }
+
+        Ok(Self { num_participants, entries })
+    }
+}
+
+
+// SparsePVSSShare implements the Hash trait.
+impl<E: PairingEngine> Hash for SparsePVSSShare<E> {
+    fn digest(&self) -> Digest {
+        canonical_digest(self)
+    }
+}
+
+
+// PVSSShareSecrets models the secret parts underlying each share. Dealers that are
+// not themselves recipients of the share (e.g., external dealers) have no partial secret.
 pub struct PVSSShareSecrets<E: PairingEngine> {
-    pub p_0: Scalar<E>,           // secret polynomial free term s s.t.: p_i(0) = s
-    pub my_secret: E::G1Affine,   // partial secret; is this one correct???
+    pub p_0: Scalar<E>,                   // secret polynomial free term s s.t.: p_i(0) = s
+    pub my_secret: Option<E::G1Affine>,   // partial secret g_1^{p(id+1)}, if the dealer is a recipient
 }
+
+// Share secrets are wiped from memory when dropped.
+impl<E: PairingEngine> Zeroize for PVSSShareSecrets<E> {
+    fn zeroize(&mut self) {
+        self.p_0.zeroize();
+        self.my_secret.zeroize();
+    }
+}
+
+impl<E: PairingEngine> Drop for PVSSShareSecrets<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: PairingEngine> ZeroizeOnDrop for PVSSShareSecrets<E> {}
@@ -0,0 +1,312 @@
+use crate::modified_scrape::{
+    config::Config,
+    decomp::{message_from_pi_i, Decomp, DecompProof},
+    dealing::encrypt_evals,
+    errors::PVSSError,
+    escrow::committee_public_key,
+    kernel,
+    node::Node,
+    poly::sample_with_free_term,
+    pvss::PVSSShare,
+    share::PVSSTranscript,
+};
+use crate::secrets::SecretPolynomial;
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+
+/* Proactive resharing of the secret s of an agreed upon transcript to a new
+   committee, with possibly different t and n (but the same SRS).
+
+   Shareholders cannot act as dealers of their decrypted shares: those are group
+   elements g_1^{p(i+1)}, whose discrete logs nobody knows. Instead, each original
+   contributor j, who knows the free term p_j(0) of its own dealing, re-deals it with
+   a fresh polynomial of the new degree toward the new committee's keys:
+
+       s = sum_j w_j * p_j(0)       (w_j the weight of contribution j in the old transcript)
+
+   A resharing dealing is accepted when its decomposition proof commits to the same
+   g_2^{p_j(0)} as the old contribution, and it is signed by the same dealer. The
+   dealings of all old contributors, weighted as before, aggregate into a sharing of
+   the same s, so the reshared transcript encodes the old group public key g_2^s.
+*/
+
+// Struct ReshareDealing models a contributor's resharing of its secret toward the
+// new committee.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct ReshareDealing<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub dealer: usize,                          // id of the dealer in the old committee
+    pub pvss_share: PVSSShare<E>,               // the sharing toward the new committee
+    pub decomp_proof: DecompProof<E>,           // proof of knowledge of the shared secret
+    pub signature_on_decomp: SSIG::Signature,   // signature on the proof under the dealer's old key
+}
+
+// Struct ResharedTranscript models the aggregated sharing held by the new committee.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
+pub struct ResharedTranscript<E: PairingEngine> {
+    pub degree: usize,                    // the threshold of the new committee
+    pub num_participants: usize,          // the size of the new committee
+    pub group_public_key: E::G2Affine,    // g_2^s, unchanged by the resharing
+    pub pvss_share: PVSSShare<E>,         // the aggregated sharing of s
+}
+
+// Struct Resharing gathers what is needed to check the handover of an old transcript's
+// secret to a new committee.
+pub struct Resharing<'a, E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    pub scheme_sig: &'a SSIG,                           // the signature scheme of the old committee
+    pub old_transcript: &'a PVSSTranscript<E, SSIG>,    // the transcript whose secret is reshared
+    pub old_pks: &'a [E::G1Affine],                     // signing keys of the old participants
+    pub new_config: &'a Config<E>,                      // the config of the new committee
+    pub new_pks: &'a [E::G1Affine],                     // encryption keys of the new participants
+}
+
+impl<E, SSIG> Node<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method for resharing the free term of our contribution to an old transcript
+    // toward a new committee, given by its config and encryption keys.
+    pub fn reshare<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        secret: Scalar<E>,
+        new_config: &Config<E>,
+        new_pks: &[E::G1Affine],
+    ) -> Result<ReshareDealing<E, SSIG>, PVSSError<E>> {
+        let srs = &self.aggregator.config.srs;
+        if new_config.srs.g1 != srs.g1 || new_config.srs.g2 != srs.g2 {
+            return Err(PVSSError::DifferentSRS);
+        }
+
+        let poly = SecretPolynomial::new(sample_with_free_term::<E, R>(new_config.degree, secret, rng));
+        let artifacts = Decomp::<E>::from_polynomial(rng, new_config, poly.expose())?;
+        let decomp_proof = artifacts.decomp_proof;
+
+        let pvss_share = PVSSShare {
+            comms: artifacts.comms,
+            encs: encrypt_evals::<E>(new_pks, &artifacts.evals)?,
+        };
+
+        let signature_on_decomp = self
+            .aggregator
+            .scheme_sig
+            .sign(rng, self.dealer.private_key_sig.expose(), &message_from_pi_i(decomp_proof)?)?;
+
+        Ok(ReshareDealing {
+            dealer: self.dealer.participant.id,
+            pvss_share,
+            decomp_proof,
+            signature_on_decomp,
+        })
+    }
+}
+
+impl<'a, E, SSIG> Resharing<'a, E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for setting up the resharing of old_transcript to a new committee.
+    pub fn new(
+        scheme_sig: &'a SSIG,
+        old_transcript: &'a PVSSTranscript<E, SSIG>,
+        old_pks: &'a [E::G1Affine],
+        new_config: &'a Config<E>,
+        new_pks: &'a [E::G1Affine],
+    ) -> Result<Self, PVSSError<E>> {
+        if old_pks.len() != old_transcript.num_participants || new_pks.len() != new_config.num_participants {
+            return Err(PVSSError::LengthMismatchError);
+        }
+        old_transcript.check_weight_threshold()?;
+
+        Ok(Self { scheme_sig, old_transcript, old_pks, new_config, new_pks })
+    }
+
+    // Method for verifying a single resharing dealing.
+    pub fn verify_dealing<R: Rng + CryptoRng>(&self, rng: &mut R, dealing: &ReshareDealing<E, SSIG>) -> Result<(), PVSSError<E>> {
+        let id = dealing.dealer;
+        let contribution = self.old_transcript.contributions.get(&id).ok_or(PVSSError::<E>::InvalidParticipantId(id))?;
+        let public_key = self.old_pks.get(id).ok_or(PVSSError::<E>::InvalidParticipantId(id))?;
+
+        // The dealing must share the secret of the old contribution.
+        if dealing.decomp_proof.gs() != contribution.decomp_proof.gs() {
+            return Err(PVSSError::ReshareSecretMismatchError(id));
+        }
+
+        let (comms, encs) = (&dealing.pvss_share.comms, &dealing.pvss_share.encs);
+        let n = self.new_config.num_participants;
+        if comms.len() != n || encs.len() != n {
+            return Err(PVSSError::MismatchedCommitsEncryptionsParticipantsError(comms.len(), encs.len(), n));
+        }
+
+        kernel::subgroup_check::<E>(encs, comms)?;
+        kernel::coding_check_for::<E, R>(rng, self.new_config, comms)?;
+        kernel::decomposition_check::<E>(self.new_config, &dealing.decomp_proof, &dealing.pvss_share)?;
+        kernel::decomp_signature_check::<E, SSIG>(self.scheme_sig, public_key, &dealing.decomp_proof, &dealing.signature_on_decomp)?;
+
+        let entries = (0..n)
+            .map(|i| (self.new_pks[i], &encs[i], &comms[i]))
+            .collect::<Vec<_>>();
+        kernel::encryptions_check::<E, R>(rng, self.new_config.srs.g2, &entries)
+    }
+
+    // Method for verifying and aggregating the resharing dealings. Every contributor
+    // to the old transcript must have reshared, and its dealing is counted with the
+    // weight of its old contribution.
+    pub fn aggregate<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        dealings: &[ReshareDealing<E, SSIG>],
+    ) -> Result<ResharedTranscript<E>, PVSSError<E>> {
+        let mut by_dealer = BTreeMap::new();
+        for dealing in dealings.iter() {
+            self.verify_dealing(rng, dealing)?;
+            by_dealer.entry(dealing.dealer).or_insert(dealing);
+        }
+
+        let n = self.new_config.num_participants;
+        let mut pvss_share = PVSSShare::<E>::empty(self.new_config.degree, n);
+        for (id, contribution) in self.old_transcript.contributions.iter() {
+            let dealing = by_dealer.get(id).ok_or(PVSSError::<E>::MissingReshareError(*id))?;
+            let weight = Scalar::<E>::from(contribution.weight).into_repr();
+            for i in 0..n {
+                pvss_share.comms[i] += dealing.pvss_share.comms[i].into_affine().mul(weight);
+                pvss_share.encs[i] += dealing.pvss_share.encs[i].into_affine().mul(weight);
+            }
+        }
+
+        let transcript = ResharedTranscript {
+            degree: self.new_config.degree,
+            num_participants: n,
+            group_public_key: committee_public_key(self.old_transcript),
+            pvss_share,
+        };
+        self.verify_reshared(rng, &transcript)?;
+        Ok(transcript)
+    }
+
+    // Method for verifying that a reshared transcript is a valid sharing, toward the
+    // new committee, of the old transcript's secret.
+    pub fn verify_reshared<R: Rng + CryptoRng>(&self, rng: &mut R, transcript: &ResharedTranscript<E>) -> Result<(), PVSSError<E>> {
+        if transcript.group_public_key != committee_public_key(self.old_transcript) {
+            return Err(PVSSError::ResharedKeyMismatchError);
+        }
+        transcript.verify(rng, self.new_config, self.new_pks)
+    }
+}
+
+impl<E: PairingEngine> ResharedTranscript<E> {
+
+    // Method for verifying the transcript against the new committee's config and
+    // encryption keys, and its own group public key.
+    pub fn verify<R: Rng + CryptoRng>(&self, rng: &mut R, config: &Config<E>, pks: &[E::G1Affine]) -> Result<(), PVSSError<E>> {
+        let (t, n) = (config.degree, config.num_participants);
+        if self.degree != t || self.num_participants != n {
+            return Err(PVSSError::TranscriptDifferentConfig(t, self.degree, n, self.num_participants));
+        }
+
+        let (comms, encs) = (&self.pvss_share.comms, &self.pvss_share.encs);
+        if comms.len() != n || encs.len() != n || pks.len() != n {
+            return Err(PVSSError::LengthMismatchError);
+        }
+
+        kernel::subgroup_check::<E>(encs, comms)?;
+        kernel::coding_check_for::<E, R>(rng, config, comms)?;
+        kernel::interpolation_check_at::<E>(comms, &config.points(), t, self.group_public_key.into_projective())
+            .map_err(|_| PVSSError::ResharedKeyMismatchError)?;
+
+        let entries = (0..n)
+            .map(|i| (pks[i], &encs[i], &comms[i]))
+            .collect::<Vec<_>>();
+        kernel::encryptions_check::<E, R>(rng, config.srs.g2, &entries)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{
+        config::{Config, EvaluationPoints, ShareLayout},
+        errors::PVSSError,
+        escrow::committee_public_key,
+        resharing::Resharing,
+        tests::setup_nodes,
+    };
+    use crate::signature::scheme::SignatureScheme;
+
+    use ark_bls12_381::Bls12_381 as E;
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::{Field, One, PrimeField};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_resharing() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        // Deal from committed seeds, so that the contributors can recover their secrets.
+        for node in nodes.iter_mut() {
+            node.dealer.commit_seed(rng, 0);
+        }
+        let shares = (0..3).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[3].aggregator.receive_share(rng, share).unwrap();
+        }
+        let old_transcript = nodes[3].aggregator.finalize().unwrap().clone();
+        let old_config = nodes[3].aggregator.config.clone();
+        let old_pks = nodes[3].aggregator.participants.values().map(|p| p.public_key_sig).collect::<Vec<_>>();
+        let secrets = (0..3)
+            .map(|i| nodes[i].dealer.seed.as_ref().unwrap().deal(&old_config).unwrap().0.expose().coeffs[0])
+            .collect::<Vec<_>>();
+
+        // A new committee of 5 with threshold 2, under the same SRS.
+        let scheme_sig = nodes[0].aggregator.scheme_sig.clone();
+        let new_config = Config { srs: old_config.srs.clone(), degree: 2, num_participants: 5, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+        let new_keys = (0..5).map(|_| scheme_sig.generate_keypair(rng).unwrap()).collect::<Vec<_>>();
+        let new_pks = new_keys.iter().map(|(_, pk)| *pk).collect::<Vec<_>>();
+
+        let dealings = (0..3)
+            .map(|i| nodes[i].reshare(rng, secrets[i], &new_config, &new_pks).unwrap())
+            .collect::<Vec<_>>();
+        let resharing = Resharing::new(&scheme_sig, &old_transcript, &old_pks, &new_config, &new_pks).unwrap();
+        let reshared = resharing.aggregate(rng, &dealings).unwrap();
+        assert_eq!(reshared.group_public_key, committee_public_key(&old_transcript));
+
+        // The new shares decrypt to points matching their commitments.
+        for (i, (sk, _)) in new_keys.iter().enumerate() {
+            let dec = reshared.pvss_share.encs[i].into_affine().mul(sk.inverse().unwrap().into_repr()).into_affine();
+            let pairs = [
+                (dec.into(), new_config.srs.g2.into()),
+                ((-new_config.srs.g1).into(), reshared.pvss_share.comms[i].into_affine().into()),
+            ];
+            assert!(E::product_of_pairings(pairs.iter()).is_one());
+        }
+
+        // A dealing of some other secret, or a missing dealing, is rejected.
+        let bad = nodes[1].reshare(rng, secrets[0], &new_config, &new_pks).unwrap();
+        assert!(matches!(resharing.verify_dealing(rng, &bad), Err(PVSSError::ReshareSecretMismatchError(1))));
+        assert!(matches!(resharing.aggregate(rng, &dealings[..2]), Err(PVSSError::MissingReshareError(2))));
+
+        // A reshared transcript for another group public key is rejected.
+        let mut forged = reshared.clone();
+        forged.group_public_key = new_config.srs.g2;
+        assert!(matches!(resharing.verify_reshared(rng, &forged), Err(PVSSError::ResharedKeyMismatchError)));
+    }
+}
@@ -0,0 +1,226 @@
+use crate::Digest;
+
+use ark_std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+/* Persistent set of the digests of messages (shares, transcripts) a node has already
+   processed, so that after a crash or restart it does not re-verify them. The set is
+   stored as an append-only log of (epoch, digest) records, which is compacted when
+   old epochs fall out of the retention window.
+
+   In memory, each epoch keeps up to exact_capacity digests in an exact set; further
+   digests go to a Bloom filter. A Bloom filter false positive makes the node drop a
+   message it has not actually seen, which costs liveness but never safety, since
+   nothing is accepted on the basis of the set. The log itself always stores every
+   digest, so restarts rebuild the same structure.
+*/
+
+const RECORD_LENGTH: usize = 8 + 32;   // epoch (big-endian u64) || digest
+const BLOOM_BITS: usize = 1 << 16;     // bits per epoch filter
+const BLOOM_HASHES: usize = 4;         // probes per digest
+
+// Struct EpochSeen models the digests seen during a single epoch.
+#[derive(Clone, Debug)]
+struct EpochSeen {
+    exact: BTreeSet<Digest>,   // digests stored exactly
+    bloom: Vec<u64>,           // Bloom filter for the digests beyond exact_capacity
+    overflowed: bool,          // whether the Bloom filter is in use
+}
+
+impl EpochSeen {
+    fn new() -> Self {
+        Self { exact: BTreeSet::new(), bloom: vec![0u64; BLOOM_BITS / 64], overflowed: false }
+    }
+
+    // Digests are uniformly distributed, so their bytes directly serve as probes.
+    fn probes(digest: &Digest) -> impl Iterator<Item = usize> + '_ {
+        (0..BLOOM_HASHES).map(move |i| {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(&digest.0[4 * i..4 * i + 4]);
+            u32::from_be_bytes(bytes) as usize % BLOOM_BITS
+        })
+    }
+
+    fn contains(&self, digest: &Digest) -> bool {
+        self.exact.contains(digest)
+            || (self.overflowed && Self::probes(digest).all(|b| self.bloom[b / 64] & (1 << (b % 64)) != 0))
+    }
+
+    fn insert(&mut self, digest: Digest, exact_capacity: usize) {
+        if self.exact.len() < exact_capacity {
+            self.exact.insert(digest);
+            return;
+        }
+
+        self.overflowed = true;
+        for b in Self::probes(&digest).collect::<Vec<_>>() {
+            self.bloom[b / 64] |= 1 << (b % 64);
+        }
+    }
+}
+
+
+// Struct SeenSet models the persistent set of processed message digests.
+pub struct SeenSet {
+    path: PathBuf,                        // location of the log
+    log: File,                            // the log, opened for appending
+    pub retention: u64,                   // number of epochs (including the current one) kept
+    pub exact_capacity: usize,            // digests per epoch kept exactly before using the filter
+    current_epoch: u64,                   // the latest epoch started
+    epochs: BTreeMap<u64, EpochSeen>,     // digests seen, per epoch
+}
+
+impl SeenSet {
+
+    // Function for opening (or creating) the seen-set stored at path, keeping the
+    // last retention epochs.
+    pub fn open<P: AsRef<Path>>(path: P, retention: u64, exact_capacity: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut records = vec![];
+        if path.exists() {
+            let mut reader = BufReader::new(File::open(&path)?);
+            let mut record = [0u8; RECORD_LENGTH];
+            loop {
+                match reader.read_exact(&mut record) {
+                    Ok(()) => {
+                        let mut epoch = [0u8; 8];
+                        epoch.copy_from_slice(&record[..8]);
+                        let mut digest = [0u8; 32];
+                        digest.copy_from_slice(&record[8..]);
+                        records.push((u64::from_be_bytes(epoch), Digest(digest)));
+                    }
+                    // A torn trailing record (crash during a write) is ignored.
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        let log = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut seen = Self {
+            path,
+            log,
+            retention: retention.max(1),
+            exact_capacity,
+            current_epoch: 0,
+            epochs: BTreeMap::new(),
+        };
+
+        for (epoch, digest) in records.iter() {
+            seen.current_epoch = seen.current_epoch.max(*epoch);
+            seen.epochs.entry(*epoch).or_insert_with(EpochSeen::new).insert(*digest, exact_capacity);
+        }
+        seen.prune()?;
+
+        Ok(seen)
+    }
+
+    // Method checking whether a digest was processed within the retention window.
+    pub fn contains(&self, digest: &Digest) -> bool {
+        self.epochs.values().any(|e| e.contains(digest))
+    }
+
+    // Method for durably recording that a digest was processed in the current epoch.
+    pub fn insert(&mut self, digest: Digest) -> io::Result<()> {
+        let mut record = self.current_epoch.to_be_bytes().to_vec();
+        record.extend_from_slice(&digest.0);
+        self.log.write_all(&record)?;
+        self.log.sync_data()?;
+
+        let exact_capacity = self.exact_capacity;
+        self.epochs.entry(self.current_epoch).or_insert_with(EpochSeen::new).insert(digest, exact_capacity);
+        Ok(())
+    }
+
+    // Method for moving to a new epoch, forgetting epochs outside the retention window.
+    pub fn start_epoch(&mut self, epoch: u64) -> io::Result<()> {
+        if epoch <= self.current_epoch {
+            return Ok(());
+        }
+
+        self.current_epoch = epoch;
+        self.prune()
+    }
+
+    // Method returning the latest epoch started.
+    pub fn epoch(&self) -> u64 {
+        self.current_epoch
+    }
+
+    // Utility method for dropping expired epochs and compacting the log accordingly.
+    fn prune(&mut self) -> io::Result<()> {
+        let oldest = (self.current_epoch + 1).saturating_sub(self.retention);
+        if self.epochs.keys().next().is_none_or(|e| *e >= oldest) {
+            return Ok(());
+        }
+        self.epochs = self.epochs.split_off(&oldest);
+
+        // Rewrite the surviving records and atomically replace the log.
+        let mut kept = vec![];
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut record = [0u8; RECORD_LENGTH];
+        while reader.read_exact(&mut record).is_ok() {
+            let mut epoch = [0u8; 8];
+            epoch.copy_from_slice(&record[..8]);
+            if u64::from_be_bytes(epoch) >= oldest {
+                kept.extend_from_slice(&record);
+            }
+        }
+
+        let tmp = self.path.with_extension("compact");
+        {
+            let mut file = File::create(&tmp)?;
+            file.write_all(&kept)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        self.log = OpenOptions::new().append(true).open(&self.path)?;
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::seen::SeenSet;
+    use crate::Digest;
+
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_seen_set_persistence() {
+        let path = env::temp_dir().join(format!("optrand-seen-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        {
+            let mut seen = SeenSet::open(&path, 2, 1).unwrap();
+            seen.start_epoch(1).unwrap();
+            seen.insert(Digest([1u8; 32])).unwrap();
+            seen.insert(Digest([2u8; 32])).unwrap();   // goes to the Bloom filter
+            seen.start_epoch(2).unwrap();
+            seen.insert(Digest([3u8; 32])).unwrap();
+        }
+
+        // everything survives a restart
+        let mut seen = SeenSet::open(&path, 2, 1).unwrap();
+        assert_eq!(seen.epoch(), 2);
+        assert!(seen.contains(&Digest([1u8; 32])));
+        assert!(seen.contains(&Digest([2u8; 32])));
+        assert!(seen.contains(&Digest([3u8; 32])));
+        assert!(!seen.contains(&Digest([4u8; 32])));
+
+        // epoch 1 falls out of the retention window
+        seen.start_epoch(3).unwrap();
+        assert!(!seen.contains(&Digest([1u8; 32])));
+        assert!(SeenSet::open(&path, 2, 1).unwrap().contains(&Digest([3u8; 32])));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,15 +2,21 @@ use crate::{
     signature::scheme::BatchVerifiableSignatureScheme,
 };
 
+use crate::modified_scrape::config::Config;
 use crate::modified_scrape::errors::PVSSError;
+use crate::modified_scrape::kernel;
+use crate::modified_scrape::participant::{Committee, Participant, ParticipantId};
 use crate::modified_scrape::pvss::PVSSShare;
-use crate::Scalar;
-use crate::modified_scrape::decomp::DecompProof;
+use crate::{canonical_digest, digest_bytes, Digest, Hash, Scalar};
+use crate::modified_scrape::decomp::{message_from_pi_i, DecompProof};
+use crate::modified_scrape::summary::TranscriptSummary;
+use crate::modified_scrape::contributions::Contributions;
 
-use ark_ec::PairingEngine;
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use ark_std::collections::BTreeMap;
-use std::io::Cursor;
+use rand::{CryptoRng, Rng};
 
 
 
@@ -21,7 +27,7 @@ pub struct PVSSAugmentedShare<E, SSIG>
 where
     E: PairingEngine,
     // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,   // might be redundant
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 {
     pub participant_id: usize,
     pub pvss_share: PVSSShare<E>,
@@ -37,13 +43,13 @@ pub struct PVSSTranscript<E, SSIG>
 where
     E: PairingEngine,
     // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,   // might be redundant
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 {
     pub degree: usize,
     pub num_participants: usize,
 
     // "contributions" isn't a very fitting name IMO...
-    pub contributions: BTreeMap<usize, PVSSTranscriptParticipant<E, SSIG>>,   // <E, SPOK, SSIG>
+    pub contributions: Contributions<PVSSTranscriptParticipant<E, SSIG>>,   // <E, SPOK, SSIG>
     pub pvss_share: PVSSShare<E>,
 }
 
@@ -53,26 +59,62 @@ where
 pub struct PVSSTranscriptParticipant<
     E: PairingEngine,
     // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,   // might be redundant
-    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
 > {
     pub decomp_proof: DecompProof<E>,           // contains gs
     pub signature_on_decomp: SSIG::Signature,   
+    pub weight: u64,                            // number of times the contribution is counted in the aggregate
 }
 
 
-// Utility function for buffering a decomposition proof into a buffer and
-// obtaining a reference to said buffer.
-pub fn message_from_pi_i<E: PairingEngine>(pi_i: DecompProof<E>) -> Result<Vec<u8>, PVSSError<E>> {
-    let mut message_writer = Cursor::new(vec![]);
-    pi_i.serialize(&mut message_writer)?;
-    Ok(message_writer.get_ref().to_vec())
+// PVSSAugmentedShare implements the Hash trait.
+impl<E, SSIG> Hash for PVSSAugmentedShare<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn digest(&self) -> Digest {
+        canonical_digest(self)
+    }
+}
+
+
+// PVSSTranscript implements the Hash trait.
+impl<E, SSIG> Hash for PVSSTranscript<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn digest(&self) -> Digest {
+        canonical_digest(self)
+    }
+}
+
+
+// Utility function computing the digest identifying a dealer's contribution, i.e., the
+// dealer's id, its decomposition proof and its signature thereon. The weight is left
+// out, as it plays no part in verifying the contribution.
+pub fn contribution_digest<E, SSIG>(
+    participant_id: usize,
+    decomp_proof: &DecompProof<E>,
+    signature_on_decomp: &SSIG::Signature,
+) -> Digest
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    let mut bytes = (participant_id as u64).to_be_bytes().to_vec();
+    // serializing into a Vec cannot fail
+    decomp_proof.serialize(&mut bytes).unwrap();
+    signature_on_decomp.serialize(&mut bytes).unwrap();
+    digest_bytes(&bytes)
 }
 
 
 impl<
         E: PairingEngine,
         // SPOK: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
-        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G2Affine, Secret = Scalar<E>>,
+        SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
     > PVSSTranscript<E, SSIG>   // 
 {
     // Function for generating a new PVSSTranscript instance.
@@ -80,13 +122,39 @@ impl<
         Self {
             degree,
             num_participants,
-            contributions: BTreeMap::new(),
+            contributions: Contributions::for_participants(num_participants),
             pvss_share: PVSSShare::empty(degree, num_participants),
         }
     }
 
+    // Function for creating a PVSSTranscript from the info included in an augmented share.
+    pub fn from_share(degree: usize, num_participants: usize, share: &PVSSAugmentedShare<E, SSIG>) -> Self {
+        let mut contributions = Contributions::for_participants(num_participants);
+        contributions.insert(
+            share.participant_id,
+            PVSSTranscriptParticipant {
+                decomp_proof: share.decomp_proof,
+                signature_on_decomp: share.signature_on_decomp.clone(),
+                weight: 1,
+            },
+        );
+
+        Self {
+            degree,
+            num_participants,
+            contributions,
+            pvss_share: share.pvss_share.clone(),
+        }
+    }
+
     // Method for aggregating PVSS transcripts.
     pub fn aggregate(&self, other: &Self) -> Result<Self, PVSSError<E>> {
+        self.aggregate_capped(other, u64::MAX)
+    }
+
+    // Method for aggregating PVSS transcripts, failing if the weight of any
+    // contribution in the result would exceed max_weight.
+    pub fn aggregate_capped(&self, other: &Self, max_weight: u64) -> Result<Self, PVSSError<E>> {
 	// Ensure that both PVSS transcripts are w.r.t. a common configuration
         if self.degree != other.degree || self.num_participants != other.num_participants {
             return Err(PVSSError::TranscriptDifferentConfig(
@@ -97,37 +165,187 @@ impl<
             ));
         }
 
-	// 
-        let contributions = (0..self.num_participants)   // this seems to be a bit inefficient...
-            .map(
-                |i| match (self.contributions.get(&i), other.contributions.get(&i)) {
-                    (Some(a), Some(b)) => {
-                        if a.decomp_proof.gs != b.decomp_proof.gs {
-                            return Err(PVSSError::TranscriptDifferentCommitments);
-                        }
-                        let transcript_participant = PVSSTranscriptParticipant {
-                            decomp_proof: a.decomp_proof,
-                            signature_on_decomp: a.signature_on_decomp.clone(),
-                        };
-                        Ok(Some((i, transcript_participant)))
-                    }
-                    (Some(a), None) => Ok(Some((i, a.clone()))),
-                    (None, Some(b)) => Ok(Some((i, b.clone()))),
-                    (None, None) => Ok(None),
-                },
-            )
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .filter_map(|e| e)
-            .collect::<Vec<_>>();
+	// Merge the contributions of both transcripts.
+        let contributions = self.contributions.union_with(
+            &other.contributions,
+            self.num_participants,
+            |a, b| {
+                if a.decomp_proof.gs() != b.decomp_proof.gs() {
+                    return Err(PVSSError::TranscriptDifferentCommitments);
+                }
+                // The same dealing is included in both aggregates, so it is counted twice.
+                Ok(PVSSTranscriptParticipant {
+                    decomp_proof: a.decomp_proof,
+                    signature_on_decomp: a.signature_on_decomp.clone(),
+                    weight: a.weight.checked_add(b.weight).ok_or(PVSSError::WeightOverflow(max_weight))?,
+                })
+            },
+        )?;
+
+        if contributions.values().any(|c| c.weight > max_weight) {
+            return Err(PVSSError::WeightOverflow(max_weight));
+        }
 
         let aggregated_tx = Self {
             degree: self.degree,
             num_participants: self.num_participants,
-            contributions: contributions.into_iter().collect(),
-            pvss_share: self.pvss_share.aggregate(&other.pvss_share).unwrap(),   // aggregate the core PVSS shares
+            contributions,
+            pvss_share: self.pvss_share.aggregate(&other.pvss_share)?,   // aggregate the core PVSS shares
         };
 
         Ok(aggregated_tx)
     }
+
+    // Method for aggregating another PVSS transcript into this one in place.
+    pub fn aggregate_assign(&mut self, other: &Self) -> Result<(), PVSSError<E>> {
+        self.aggregate_assign_capped(other, u64::MAX)
+    }
+
+    // Method for aggregating another PVSS transcript into this one in place, like
+    // aggregate_capped. Only the contributions new to this transcript are cloned, and
+    // on error, the transcript is left unchanged.
+    pub fn aggregate_assign_capped(&mut self, other: &Self, max_weight: u64) -> Result<(), PVSSError<E>> {
+        if self.degree != other.degree || self.num_participants != other.num_participants {
+            return Err(PVSSError::TranscriptDifferentConfig(
+                self.degree,
+                other.degree,
+                self.num_participants,
+                other.num_participants,
+            ));
+        }
+
+	// Check the merged contributions before modifying anything.
+        let mut weights = Vec::with_capacity(other.contributions.len());
+        for (id, b) in other.contributions.iter() {
+            let weight = match self.contributions.get(id) {
+                Some(a) if a.decomp_proof.gs() != b.decomp_proof.gs() => return Err(PVSSError::TranscriptDifferentCommitments),
+                Some(a) => a.weight.checked_add(b.weight).ok_or(PVSSError::WeightOverflow(max_weight))?,
+                None => b.weight,
+            };
+            if weight > max_weight {
+                return Err(PVSSError::WeightOverflow(max_weight));
+            }
+            weights.push(weight);
+        }
+
+        self.aggregate_pvss_share_assign(&other.pvss_share)?;
+
+        for ((id, b), weight) in other.contributions.iter().zip(weights) {
+            match self.contributions.get_mut(id) {
+                Some(a) => a.weight = weight,
+                None => {
+                    self.contributions.insert(*id, b.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Method for adding a core PVSS share into the transcript's own in place. The
+    // contributions are left to the caller.
+    pub fn aggregate_pvss_share_assign(&mut self, pvss_share: &PVSSShare<E>) -> Result<(), PVSSError<E>> {
+        self.pvss_share.aggregate_assign(pvss_share)
+    }
+
+    // Method returning the total weight of the contributions in the transcript
+    // (saturating at u64::MAX).
+    pub fn total_weight(&self) -> u64 {
+        self.contributions.values().fold(0u64, |total, c| total.saturating_add(c.weight))
+    }
+
+    // Method returning the commitment g_2^s to the aggregated secret, where every
+    // contributor's gs is counted according to its weight.
+    pub fn weighted_gs(&self) -> E::G2Projective {
+        self.contributions
+            .values()
+            .map(|c| c.decomp_proof.gs().mul(Scalar::<E>::from(c.weight).into_repr()))
+            .sum()
+    }
+
+    // Method for checking that the contributions cover a total weight above the
+    // threshold (i.e., the degree), as required for the transcript to be finalized.
+    pub fn check_weight_threshold(&self) -> Result<(), PVSSError<E>> {
+        let total_weight = self.total_weight();
+        if total_weight <= self.degree as u64 {
+            return Err(PVSSError::InsufficientWeightError(total_weight, self.degree as u64 + 1));
+        }
+        Ok(())
+    }
+
+    // Method returning the ids of the dealers contributing to the transcript, for a
+    // committee whose members' ids are not their protocol indices.
+    pub fn contributor_ids(&self, committee: &Committee) -> Result<Vec<ParticipantId>, PVSSError<E>> {
+        self.contributions.keys().map(|index| committee.id_of(*index)).collect()
+    }
+
+    // Method for obtaining a structured summary of the transcript, suitable for
+    // inspection and debugging (see TranscriptSummary's Display impl).
+    pub fn describe(&self) -> TranscriptSummary {
+        TranscriptSummary {
+            degree: self.degree,
+            num_participants: self.num_participants,
+            contributors: self.contributions.keys().cloned().collect(),
+            weights: self.contributions.iter().map(|(id, c)| (*id, c.weight)).collect(),
+            digest: self.digest(),
+            pvss_share_digest: self.pvss_share.digest(),
+            decomp_digests: self
+                .contributions
+                .iter()
+                .map(|(id, contribution)| (*id, contribution.decomp_proof.digest()))
+                .collect(),
+            size: self.serialized_size(),
+            pvss_share_size: self.pvss_share.serialized_size(),
+        }
+    }
+
+    // Method for verifying all contributions to the transcript at once: the
+    // decomposition proofs are batch verified against config, and so are the
+    // contributors' signatures on them, under their keys among participants. A bad
+    // proof yields DecompProofVerificationError, a bad signature SignatureError.
+    pub fn verify_contributions<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        config: &Config<E>,
+        scheme_sig: &SSIG,
+        participants: &BTreeMap<usize, Participant<E, SSIG>>,
+    ) -> Result<(), PVSSError<E>> {
+        let mut public_keys = vec![];
+        let mut messages = vec![];
+        let mut signatures = vec![];
+        for (id, contribution) in self.contributions.iter() {
+            let participant = participants.get(id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+            public_keys.push(&participant.public_key_sig);
+            messages.push(message_from_pi_i(contribution.decomp_proof)?);
+            signatures.push(&contribution.signature_on_decomp);
+        }
+
+        let proofs = self.contributions.values().map(|contribution| &contribution.decomp_proof).collect::<Vec<_>>();
+        DecompProof::verify_batch(rng, config, &proofs)?;
+
+        if signatures.is_empty() {
+            return Ok(());
+        }
+        scheme_sig.batch_verify(
+            rng,
+            &public_keys,
+            &messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>(),
+            &signatures,
+        )?;
+        Ok(())
+    }
+}
+
+impl<E, SSIG> PVSSTranscriptParticipant<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Method for verifying a single contribution: its decomposition proof against
+    // config, and its contributor's signature on it under public_key. As for
+    // PVSSTranscript::verify_contributions, the two failures are told apart.
+    pub fn verify(&self, config: &Config<E>, scheme_sig: &SSIG, public_key: &SSIG::PublicKey) -> Result<(), PVSSError<E>> {
+        self.decomp_proof.verify(config)?;
+        kernel::decomp_signature_check::<E, SSIG>(scheme_sig, public_key, &self.decomp_proof, &self.signature_on_decomp)
+    }
 }
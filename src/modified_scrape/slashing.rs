@@ -0,0 +1,285 @@
+use super::{
+    config::Config,
+    decomp::{message_from_pi_i, DecompProof},
+    errors::PVSSError,
+    participant::ParticipantSet,
+    share::PVSSAugmentedShare,
+};
+use crate::nizk::{dleq::{srs::SRS as DLEQSRS, DLEQProof}, scheme::NIZKProof};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Hash, Scalar};
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField};
+use ark_serialize::*;
+
+use rand::{CryptoRng, Rng};
+use std::ops::Neg;
+
+/* Evidence of dealer misbehavior, in a form an external (e.g., staking) system can
+   adjudicate using only this crate: each piece of evidence is self-contained and is
+   checked against the config, the signature scheme and the participant registry.
+
+   A dealer's signature_on_decomp only covers its decomposition proof, not the
+   commitments and encryptions of its share. Evidence about the latter must therefore
+   carry a SignedDealing, i.e., the dealer's signature on the digest of the whole
+   augmented share; deployments that want such faults to be slashable must require
+   dealers to sign their dealings in full.
+*/
+
+pub type DLEQProofType<E> = <DLEQProof<<E as PairingEngine>::G1Affine, <E as PairingEngine>::G1Affine> as NIZKProof>::Proof;
+
+// Struct SignedDealing models an augmented share signed in full by its dealer.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SignedDealing<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub share: PVSSAugmentedShare<E, SSIG>,
+    pub signature_on_share: SSIG::Signature,   // signature on share.digest()
+}
+
+// Enumeration whose variants model the kinds of slashable misbehavior.
+#[derive(Clone)]
+pub enum SlashingEvidence<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    // The dealer signed a decomposition proof that does not verify.
+    InvalidDecompProof {
+        dealer: usize,
+        decomp_proof: DecompProof<E>,
+        signature_on_decomp: SSIG::Signature,
+    },
+    // The dealer's encryption for participant index does not match its commitment.
+    // The complaint consists of index's decryption dec and a DLEQ proof that
+    // log_{g_1}(pk_index) == log_{dec}(enc_index), i.e., that dec was decrypted honestly.
+    BadEncryption {
+        dealing: SignedDealing<E, SSIG>,
+        index: usize,
+        dec: E::G1Affine,
+        proof: DLEQProofType<E>,
+    },
+    // The dealer signed two different decomposition proofs (i.e., dealt twice).
+    Equivocation {
+        dealer: usize,
+        first: (DecompProof<E>, SSIG::Signature),
+        second: (DecompProof<E>, SSIG::Signature),
+    },
+}
+
+
+impl<E, SSIG> SignedDealing<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for signing an augmented share in full.
+    pub fn sign<R: Rng + CryptoRng>(
+        rng: &mut R,
+        scheme_sig: &SSIG,
+        sk: &SSIG::Secret,
+        share: PVSSAugmentedShare<E, SSIG>,
+    ) -> Result<Self, PVSSError<E>> {
+        let signature_on_share = scheme_sig.sign(rng, sk, &share.digest().0)?;
+        Ok(Self { share, signature_on_share })
+    }
+
+    // Method for verifying the dealer's signature on the dealing.
+    pub fn verify(&self, scheme_sig: &SSIG, registry: &ParticipantSet<E, SSIG>) -> Result<(), PVSSError<E>> {
+        let dealer = registry
+            .get(&self.share.participant_id)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(self.share.participant_id))?;
+
+        scheme_sig.verify(&dealer.public_key_sig, &self.share.digest().0, &self.signature_on_share)?;
+        Ok(())
+    }
+}
+
+
+impl<E, SSIG> SlashingEvidence<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for filing a complaint against a dealing whose encryption for us (index)
+    // does not match the corresponding commitment, using our secret key sk.
+    pub fn bad_encryption<R: Rng + CryptoRng>(
+        rng: &mut R,
+        config: &Config<E>,
+        dealing: SignedDealing<E, SSIG>,
+        index: usize,
+        sk: &Scalar<E>,
+    ) -> Result<Self, PVSSError<E>> {
+        let enc = dealing
+            .share
+            .pvss_share
+            .encs
+            .get(index)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(index))?
+            .into_affine();
+
+        // dec := enc * sk^{-1}
+        let sk_inv = sk.inverse().ok_or(PVSSError::InvalidSlashingEvidence("zero secret key"))?;
+        let dec = enc.mul(sk_inv.into_repr()).into_affine();
+
+        let dleq = DLEQProof { srs: DLEQSRS { g_public_key: config.srs.g1, h_public_key: dec } };
+        let proof = dleq.prove(rng, sk).map_err(|_| PVSSError::InvalidSlashingEvidence("could not prove decryption"))?;
+
+        Ok(SlashingEvidence::BadEncryption { dealing, index, dec, proof })
+    }
+
+    // Method returning the id of the accused dealer.
+    pub fn accused(&self) -> usize {
+        match self {
+            SlashingEvidence::InvalidDecompProof { dealer, .. } => *dealer,
+            SlashingEvidence::BadEncryption { dealing, .. } => dealing.share.participant_id,
+            SlashingEvidence::Equivocation { dealer, .. } => *dealer,
+        }
+    }
+
+    // Method for verifying the evidence. Returns Ok if the accused dealer provably
+    // misbehaved, and an error otherwise.
+    pub fn verify(
+        &self,
+        scheme_sig: &SSIG,
+        config: &Config<E>,
+        registry: &ParticipantSet<E, SSIG>,
+    ) -> Result<(), PVSSError<E>> {
+        let accused = self.accused();
+        let dealer = registry
+            .get(&accused)
+            .ok_or(PVSSError::<E>::InvalidParticipantId(accused))?;
+
+        match self {
+            SlashingEvidence::InvalidDecompProof { decomp_proof, signature_on_decomp, .. } => {
+                // The dealer must have signed the proof...
+                scheme_sig.verify(&dealer.public_key_sig, &message_from_pi_i(*decomp_proof)?, signature_on_decomp)?;
+
+                // ...and the proof must not verify.
+                if decomp_proof.verify(config).is_ok() {
+                    return Err(PVSSError::InvalidSlashingEvidence("decomposition proof is valid"));
+                }
+            }
+            SlashingEvidence::BadEncryption { dealing, index, dec, proof } => {
+                dealing.verify(scheme_sig, registry)?;
+
+                let complainant = registry
+                    .get(index)
+                    .ok_or(PVSSError::<E>::InvalidParticipantId(*index))?;
+                let share = &dealing.share.pvss_share;
+                let enc = share.encs.get(*index).ok_or(PVSSError::<E>::InvalidParticipantId(*index))?;
+                let comm = share.comms.get(*index).ok_or(PVSSError::<E>::InvalidParticipantId(*index))?;
+
+                // The decryption must be honest: log_{g_1}(pk) == log_{dec}(enc).
+                let dleq = DLEQProof { srs: DLEQSRS { g_public_key: config.srs.g1, h_public_key: *dec } };
+                dleq.verify(&(complainant.public_key_sig, enc.into_affine()), proof)
+                    .map_err(|_| PVSSError::InvalidSlashingEvidence("decryption proof does not verify"))?;
+
+                // The decryption must not match the commitment: e(dec, g_2) != e(g_1, comm).
+                let pairs = [
+                    ((*dec).into(), config.srs.g2.into()),
+                    (config.srs.g1.into(), comm.into_affine().neg().into()),
+                ];
+                if E::product_of_pairings(pairs.iter()).is_one() {
+                    return Err(PVSSError::InvalidSlashingEvidence("encryption matches commitment"));
+                }
+            }
+            SlashingEvidence::Equivocation { first, second, .. } => {
+                if first.0 == second.0 {
+                    return Err(PVSSError::InvalidSlashingEvidence("decomposition proofs are identical"));
+                }
+
+                scheme_sig.verify(&dealer.public_key_sig, &message_from_pi_i(first.0)?, &first.1)?;
+                scheme_sig.verify(&dealer.public_key_sig, &message_from_pi_i(second.0)?, &second.1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::PairingEngine;
+    use ark_std::UniformRand;
+
+    use crate::modified_scrape::{
+        errors::PVSSError,
+        slashing::{SignedDealing, SlashingEvidence},
+        tests::{setup_nodes, SSIG},
+    };
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_equivocation() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let first = nodes[0].share(rng).unwrap();
+        let second = nodes[0].share(rng).unwrap();
+
+        let evidence = SlashingEvidence::<E, SSIG>::Equivocation {
+            dealer: 0,
+            first: (first.decomp_proof, first.signature_on_decomp),
+            second: (second.decomp_proof, second.signature_on_decomp),
+        };
+
+        let agg = &nodes[1].aggregator;
+        evidence.verify(&agg.scheme_sig, &agg.config, &agg.participants).unwrap();
+
+        // The same proof twice is no evidence, and neither are proofs pinned on another dealer.
+        let repeated = SlashingEvidence::<E, SSIG>::Equivocation {
+            dealer: 0,
+            first: (first.decomp_proof, first.signature_on_decomp),
+            second: (first.decomp_proof, first.signature_on_decomp),
+        };
+        assert!(matches!(
+            repeated.verify(&agg.scheme_sig, &agg.config, &agg.participants),
+            Err(PVSSError::InvalidSlashingEvidence(_))
+        ));
+        let misattributed = SlashingEvidence::<E, SSIG>::Equivocation {
+            dealer: 1,
+            first: (first.decomp_proof, first.signature_on_decomp),
+            second: (second.decomp_proof, second.signature_on_decomp),
+        };
+        assert!(matches!(
+            misattributed.verify(&agg.scheme_sig, &agg.config, &agg.participants),
+            Err(PVSSError::SignatureError(_))
+        ));
+    }
+
+    #[test]
+    fn test_bad_encryption() {
+        let rng = &mut thread_rng();
+        let (mut nodes, sks) = setup_nodes(4, 1);
+
+        // dealer 0 corrupts the encryption for participant 2
+        let mut share = nodes[0].share(rng).unwrap();
+        share.pvss_share.encs[2] = <E as PairingEngine>::G1Projective::rand(rng);
+        let dealing = SignedDealing::sign(rng, &nodes[0].aggregator.scheme_sig, &sks[0], share).unwrap();
+
+        let agg = &nodes[2].aggregator;
+        let evidence = SlashingEvidence::bad_encryption(rng, &agg.config, dealing, 2, &sks[2]).unwrap();
+        evidence.verify(&agg.scheme_sig, &agg.config, &agg.participants).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bad_encryption_honest_dealer() {
+        let rng = &mut thread_rng();
+        let (mut nodes, sks) = setup_nodes(4, 1);
+
+        let share = nodes[0].share(rng).unwrap();
+        let dealing = SignedDealing::sign(rng, &nodes[0].aggregator.scheme_sig, &sks[0], share).unwrap();
+
+        let agg = &nodes[2].aggregator;
+        let evidence = SlashingEvidence::bad_encryption(rng, &agg.config, dealing, 2, &sks[2]).unwrap();
+        evidence.verify(&agg.scheme_sig, &agg.config, &agg.participants).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
 use crate::modified_scrape::errors::PVSSError;
-use ark_ec::{PairingEngine, ProjectiveCurve};
-use ark_ff::UniformRand;
-use rand::Rng;
+use crate::Scalar;
+use ark_ec::{msm::FixedBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use rand::{CryptoRng, Rng};
 
 /* The Structured Reference String (SRS) of the modified SCRAPE PVSS scheme. */
 
@@ -15,11 +16,94 @@ pub struct SRS<E: PairingEngine> {
 impl<E: PairingEngine> SRS<E> {
 
     // Function setup generates an SRS instance using a specified RNG.
-    pub fn setup<R: Rng>(rng: &mut R) -> Result<Self, PVSSError<E>> {
+    pub fn setup<R: Rng + CryptoRng>(rng: &mut R) -> Result<Self, PVSSError<E>> {
         Ok(Self {
             g1: E::G1Projective::rand(rng).into_affine(),
             g2: E::G2Projective::rand(rng).into_affine(),
             g2_prime: E::G2Projective::rand(rng).into_affine(),
         })
     }
+
+    // Method precomputing the window tables of the generators, sized for batches
+    // of (about) num_scalars scalar multiplications.
+    pub fn prepare(&self, num_scalars: usize) -> PreparedSRS<E> {
+        let scalar_size = Scalar::<E>::size_in_bits();
+        let window = FixedBaseMSM::get_mul_window_size(num_scalars.max(1));
+        PreparedSRS {
+            scalar_size,
+            window,
+            g1_table: FixedBaseMSM::get_window_table(scalar_size, window, self.g1.into_projective()),
+            g2_table: FixedBaseMSM::get_window_table(scalar_size, window, self.g2.into_projective()),
+            g2_prime_table: FixedBaseMSM::get_window_table(scalar_size, window, self.g2_prime.into_projective()),
+        }
+    }
+}
+
+
+/* Struct PreparedSRS holds fixed-base window tables for the generators of an SRS,
+   so that batches of multiplications of the same generator (e.g., the n commitments
+   g_2^{p(j)} of a dealing) cost a few table lookups and additions each, rather than
+   a full double-and-add. Encryptions pk_j^{p(j)} have a different base each, and do
+   not benefit.
+*/
+
+#[derive(Clone)]
+pub struct PreparedSRS<E: PairingEngine> {
+    scalar_size: usize,                        // bit size of the scalars multiplied
+    window: usize,                             // window size of the tables
+    g1_table: Vec<Vec<E::G1Affine>>,           // window table of g_1
+    g2_table: Vec<Vec<E::G2Affine>>,           // window table of g_2
+    g2_prime_table: Vec<Vec<E::G2Affine>>,     // window table of g_2_prime
+}
+
+impl<E: PairingEngine> PreparedSRS<E> {
+
+    // Method computing g_1^{s} for each of the given scalars s.
+    pub fn mul_g1(&self, scalars: &[Scalar<E>]) -> Vec<E::G1Projective> {
+        FixedBaseMSM::multi_scalar_mul::<E::G1Projective>(self.scalar_size, self.window, &self.g1_table, scalars)
+    }
+
+    // Method computing g_2^{s} for each of the given scalars s.
+    pub fn mul_g2(&self, scalars: &[Scalar<E>]) -> Vec<E::G2Projective> {
+        FixedBaseMSM::multi_scalar_mul::<E::G2Projective>(self.scalar_size, self.window, &self.g2_table, scalars)
+    }
+
+    // Method computing g_2_prime^{s} for each of the given scalars s.
+    pub fn mul_g2_prime(&self, scalars: &[Scalar<E>]) -> Vec<E::G2Projective> {
+        FixedBaseMSM::multi_scalar_mul::<E::G2Projective>(self.scalar_size, self.window, &self.g2_prime_table, scalars)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::AffineCurve;
+    use ark_ff::{PrimeField, UniformRand};
+
+    use crate::modified_scrape::srs::SRS;
+    use crate::Scalar;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_prepared_srs() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+        let prepared = srs.prepare(8);
+
+        // Fixed-base multiplication agrees with plain multiplication, for any batch size.
+        let scalars = (0..11).map(|_| Scalar::<E>::rand(rng)).collect::<Vec<_>>();
+        let g1s = prepared.mul_g1(&scalars);
+        let g2s = prepared.mul_g2(&scalars);
+        let g2_primes = prepared.mul_g2_prime(&scalars);
+        for (i, s) in scalars.iter().enumerate() {
+            assert_eq!(g1s[i], srs.g1.mul(s.into_repr()));
+            assert_eq!(g2s[i], srs.g2.mul(s.into_repr()));
+            assert_eq!(g2_primes[i], srs.g2_prime.mul(s.into_repr()));
+        }
+        assert!(prepared.mul_g2(&[]).is_empty());
+    }
 }
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+/* Per-round verification statistics, for adaptive tuning. An aggregator counts the
+   shares it receives and accepts, and times its verifications and its encryption
+   checks, distinguishing checks of single transcripts from batched ones (see
+   PVSSAggregator::verify_many). At the start of every round, the statistics of the
+   previous one are used to pick the smallest number of transcripts worth batching:
+   batching pays off only if a batched check costs less per transcript than an
+   individual one, and if batches rarely fail (failing batches are bisected, which
+   costs more than checking their transcripts individually).
+*/
+
+// Smallest and largest batching thresholds the aggregator selects.
+pub const MIN_BATCH_THRESHOLD: usize = 2;
+pub const MAX_BATCH_THRESHOLD: usize = 1 << 10;
+
+// Batch success ratio below which batching is considered harmful.
+const MIN_BATCH_SUCCESS_RATIO: f64 = 0.5;
+
+// Struct RoundStats collects the verification statistics of a single round.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RoundStats {
+    pub epoch: u64,                       // the round the statistics refer to
+    pub shares_received: usize,           // number of shares received (and not seen before)
+    pub shares_accepted: usize,           // number of received shares that passed verification
+    pub verifications: usize,             // number of received shares and transcripts verified
+    pub verification_time: Duration,      // total time spent verifying them
+    pub batches: usize,                   // number of batched encryption checks
+    pub batches_passed: usize,            // number of batched encryption checks that passed
+    pub batched_items: usize,             // number of transcripts checked in batches
+    pub batched_time: Duration,           // total time spent on batched encryption checks
+    pub individual_items: usize,          // number of transcripts whose encryptions were checked alone
+    pub individual_time: Duration,        // total time spent on individual encryption checks
+}
+
+// Utility function returning the average of a total duration over some count.
+fn average(total: Duration, count: usize) -> Option<Duration> {
+    if count == 0 {
+        return None;
+    }
+    Some(total / count as u32)
+}
+
+impl RoundStats {
+
+    // Function for creating empty statistics for the given round.
+    pub fn new(epoch: u64) -> Self {
+        Self { epoch, ..Default::default() }
+    }
+
+    // Method for recording the verification of a received share.
+    pub fn record_share(&mut self, accepted: bool, time: Duration) {
+        self.shares_received += 1;
+        if accepted {
+            self.shares_accepted += 1;
+        }
+        self.record_verification(time);
+    }
+
+    // Method for recording the verification of a received share or transcript.
+    pub fn record_verification(&mut self, time: Duration) {
+        self.verifications += 1;
+        self.verification_time += time;
+    }
+
+    // Method for recording a batched encryption check over the given number of transcripts.
+    pub fn record_batch(&mut self, items: usize, passed: bool, time: Duration) {
+        self.batches += 1;
+        if passed {
+            self.batches_passed += 1;
+        }
+        self.batched_items += items;
+        self.batched_time += time;
+    }
+
+    // Method for recording an encryption check of a single transcript.
+    pub fn record_individual(&mut self, time: Duration) {
+        self.individual_items += 1;
+        self.individual_time += time;
+    }
+
+    // Method returning the fraction of batched checks that passed, if any ran.
+    pub fn batch_success_ratio(&self) -> Option<f64> {
+        if self.batches == 0 {
+            return None;
+        }
+        Some(self.batches_passed as f64 / self.batches as f64)
+    }
+
+    // Method returning the average time spent verifying a received share or transcript.
+    pub fn average_verification_time(&self) -> Option<Duration> {
+        average(self.verification_time, self.verifications)
+    }
+
+    // Method returning the measured speedup of batching, i.e., the average time of
+    // an individual encryption check divided by the average time per transcript of
+    // a batched one. Values above 1 mean batching pays off. Unknown until both
+    // kinds of checks have run.
+    pub fn batching_benefit(&self) -> Option<f64> {
+        let individual = average(self.individual_time, self.individual_items)?;
+        let batched = average(self.batched_time, self.batched_items)?;
+        if batched == Duration::from_secs(0) {
+            return None;
+        }
+        Some(individual.as_secs_f64() / batched.as_secs_f64())
+    }
+
+    // Method for selecting the batching threshold of the next round, given the current
+    // one: batching is made more eager if it paid off and batches mostly passed, and
+    // more reluctant if it did not. Without measurements, the threshold is kept.
+    pub fn recommended_batch_threshold(&self, current: usize) -> usize {
+        let harmful = self.batch_success_ratio().is_some_and(|ratio| ratio < MIN_BATCH_SUCCESS_RATIO)
+            || self.batching_benefit().is_some_and(|benefit| benefit < 1.0);
+        let helpful = self.batching_benefit().is_some_and(|benefit| benefit >= 1.0);
+
+        let next = if harmful {
+            current.saturating_mul(2)
+        } else if helpful {
+            current / 2
+        } else {
+            current
+        };
+        next.clamp(MIN_BATCH_THRESHOLD, MAX_BATCH_THRESHOLD)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::stats::{RoundStats, MIN_BATCH_THRESHOLD};
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_recommended_batch_threshold() {
+        let ms = Duration::from_millis;
+
+        // Nothing measured: the threshold is kept.
+        let mut stats = RoundStats::new(1);
+        assert_eq!(stats.recommended_batch_threshold(8), 8);
+        assert_eq!(stats.batching_benefit(), None);
+
+        stats.record_share(true, ms(4));
+        stats.record_share(false, ms(2));
+        assert_eq!((stats.shares_received, stats.shares_accepted), (2, 1));
+        assert_eq!(stats.average_verification_time(), Some(ms(3)));
+
+        // Batching is four times cheaper per transcript and batches pass.
+        stats.record_individual(ms(8));
+        stats.record_batch(4, true, ms(8));
+        assert!((stats.batching_benefit().unwrap() - 4.0).abs() < 1e-9);
+        assert_eq!(stats.recommended_batch_threshold(8), 4);
+        assert_eq!(stats.recommended_batch_threshold(2), MIN_BATCH_THRESHOLD);
+
+        // Batches that mostly fail make batching more reluctant.
+        stats.record_batch(4, false, ms(8));
+        stats.record_batch(4, false, ms(8));
+        assert_eq!(stats.batch_success_ratio(), Some(1.0 / 3.0));
+        assert_eq!(stats.recommended_batch_threshold(8), 16);
+    }
+}
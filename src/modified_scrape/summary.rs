@@ -0,0 +1,91 @@
+use crate::Digest;
+
+use ark_std::collections::BTreeMap;
+use std::fmt;
+
+/* Struct TranscriptSummary is a structured, human-readable view of an aggregated
+   PVSS transcript (see PVSSTranscript::describe). It contains no curve points,
+   only ids, weights, digests and sizes, so it can be printed or logged freely.
+*/
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranscriptSummary {
+    pub degree: usize,                             // polynomial degree (t)
+    pub num_participants: usize,                   // the total number of participants (n)
+    pub contributors: Vec<usize>,                  // ids of the dealers included, ascending
+    pub weights: BTreeMap<usize, u64>,             // number of times each dealer's share was counted
+    pub digest: Digest,                            // digest of the whole transcript
+    pub pvss_share_digest: Digest,                 // digest of the aggregated core share
+    pub decomp_digests: BTreeMap<usize, Digest>,   // digest of each dealer's decomposition proof
+    pub size: usize,                               // serialized size of the transcript in bytes
+    pub pvss_share_size: usize,                    // serialized size of the core share in bytes
+}
+
+// TranscriptSummary implements the Display trait.
+impl fmt::Display for TranscriptSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "PVSS transcript (t = {}, n = {})", self.degree, self.num_participants)?;
+        writeln!(f, "  digest:        {}", self.digest)?;
+        writeln!(f, "  size:          {} bytes", self.size)?;
+        writeln!(f, "  core share:    {} ({} bytes)", self.pvss_share_digest, self.pvss_share_size)?;
+        write!(f, "  contributors:  {} of {}", self.contributors.len(), self.num_participants)?;
+
+        for id in self.contributors.iter() {
+            write!(f, "\n    [{}] weight {}, decomp proof {}",
+                id,
+                self.weights.get(id).cloned().unwrap_or_default(),
+                self.decomp_digests.get(id).cloned().unwrap_or_default())?;
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_serialize::CanonicalSerialize;
+
+    use crate::modified_scrape::{share::PVSSTranscript, tests::setup_nodes};
+    use crate::{canonical_digest, Hash};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_describe() {
+        let rng = &mut thread_rng();
+        let (n, t) = (4, 1);
+        let (mut nodes, _) = setup_nodes(n, t);
+        let first = nodes[1].share(rng).unwrap();
+        let second = nodes[2].share(rng).unwrap();
+
+        // Dealer 2's share is counted twice.
+        let single: PVSSTranscript<E, _> = PVSSTranscript::from_share(t, n, &second);
+        let transcript = PVSSTranscript::from_share(t, n, &first)
+            .aggregate(&single)
+            .unwrap()
+            .aggregate(&single)
+            .unwrap();
+
+        let summary = transcript.describe();
+        assert_eq!(summary.degree, t);
+        assert_eq!(summary.num_participants, n);
+        assert_eq!(summary.contributors, vec![1, 2]);
+        assert_eq!(summary.weights.iter().map(|(id, w)| (*id, *w)).collect::<Vec<_>>(), vec![(1, 1), (2, 2)]);
+        assert_eq!(summary.digest, canonical_digest(&transcript));
+        assert_eq!(summary.pvss_share_digest, canonical_digest(&transcript.pvss_share));
+        assert_eq!(summary.decomp_digests[&1], first.decomp_proof.digest());
+        assert_eq!(summary.decomp_digests[&2], second.decomp_proof.digest());
+        assert_eq!(summary.decomp_digests.len(), 2);
+        assert_eq!(summary.size, transcript.serialized_size());
+        assert_eq!(summary.pvss_share_size, transcript.pvss_share.serialized_size());
+
+        let printed = summary.to_string();
+        assert!(printed.starts_with("PVSS transcript (t = 1, n = 4)"));
+        assert!(printed.contains("contributors:  2 of 4"));
+        assert!(printed.contains(&format!("[2] weight 2, decomp proof {}", second.decomp_proof.digest())));
+    }
+}
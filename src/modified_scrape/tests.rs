@@ -0,0 +1,42 @@
+use ark_bls12_381::{Bls12_381 as E, G1Affine};
+
+use crate::modified_scrape::{
+    config::{Config, EvaluationPoints, ShareLayout},
+    dealer::Dealer,
+    node::Node,
+    participant::Participant,
+    srs::SRS,
+};
+use crate::signature::{schnorr::{srs::SRS as SchnorrSRS, SchnorrSignature, SchnorrVersion}, scheme::SignatureScheme};
+use crate::Scalar;
+
+use rand::thread_rng;
+use std::collections::BTreeMap;
+
+pub type SSIG = SchnorrSignature<G1Affine>;
+
+// Sets up n nodes sharing a config of degree t, returning them along with their secret keys.
+pub fn setup_nodes(n: usize, t: usize) -> (Vec<Node<E, SSIG>>, Vec<Scalar<E>>) {
+    let rng = &mut thread_rng();
+    let srs = SRS::<E>::setup(rng).unwrap();
+    let config = Config { srs: srs.clone(), degree: t, num_participants: n, layout: ShareLayout::Dense, points: EvaluationPoints::Integers };
+    let schnorr = SSIG { srs: SchnorrSRS { g_public_key: srs.g1 }, version: SchnorrVersion::KeyBound };
+
+    let keypairs = (0..n).map(|_| schnorr.generate_keypair(rng).unwrap()).collect::<Vec<_>>();
+    let participants = keypairs
+        .iter()
+        .enumerate()
+        .map(|(id, (_, pk))| (id, Participant::new(id, *pk)))
+        .collect::<BTreeMap<_, _>>();
+
+    let nodes = keypairs
+        .iter()
+        .enumerate()
+        .map(|(id, (sk, _))| {
+            let dealer = Dealer::new(&schnorr, *sk, participants[&id].clone()).unwrap();
+            Node::new(config.clone(), schnorr.clone(), dealer, participants.clone()).unwrap()
+        })
+        .collect();
+
+    (nodes, keypairs.into_iter().map(|(sk, _)| sk).collect())
+}
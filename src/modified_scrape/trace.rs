@@ -0,0 +1,84 @@
+use crate::Digest;
+
+use std::fmt;
+use std::time::Duration;
+
+/* Verification traces, for audits that require evidence of what was verified. When
+   tracing is enabled on an aggregator, each call to share_verify or transcript_verify
+   collects a trace listing every check executed, in order: its name, the digests of
+   the inputs it was run on, how long it took and whether it passed. Traces describe
+   only the checks that actually ran, so a call failing early has a short trace, and
+   a share skipped thanks to the verification cache has an empty one.
+*/
+
+// Struct CheckRecord describes a single executed check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckRecord {
+    pub name: &'static str,       // name of the check
+    pub inputs: Vec<Digest>,      // digests of the check's inputs
+    pub duration: Duration,       // time taken by the check
+    pub error: Option<String>,    // why the check failed, if it did
+}
+
+impl CheckRecord {
+
+    // Method checking whether the check passed.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// Struct VerificationTrace lists the checks executed by a verification call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerificationTrace {
+    pub operation: &'static str,     // the verification call traced
+    pub checks: Vec<CheckRecord>,    // the executed checks, in order
+}
+
+impl VerificationTrace {
+
+    // Function for creating an empty trace of some verification call.
+    pub fn new(operation: &'static str) -> Self {
+        Self { operation, checks: vec![] }
+    }
+
+    // Method for recording the outcome of an executed check.
+    pub fn push<T, Err: fmt::Display>(
+        &mut self,
+        name: &'static str,
+        inputs: Vec<Digest>,
+        duration: Duration,
+        result: &Result<T, Err>,
+    ) {
+        self.checks.push(CheckRecord {
+            name,
+            inputs,
+            duration,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+    }
+
+    // Method returning the names of the checks executed, in order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.checks.iter().map(|c| c.name).collect()
+    }
+
+    // Method checking whether every executed check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed())
+    }
+}
+
+impl fmt::Display for VerificationTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.operation)?;
+        for check in self.checks.iter() {
+            write!(f, "\n    {} [{:?}] {}", check.name, check.duration,
+                   check.error.as_deref().unwrap_or("ok"))?;
+            for input in check.inputs.iter() {
+                write!(f, "\n        input {}", input)?;
+            }
+        }
+        Ok(())
+    }
+}
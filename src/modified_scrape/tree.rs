@@ -0,0 +1,194 @@
+use super::{errors::PVSSError, share::{PVSSAugmentedShare, PVSSTranscript}};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::Scalar;
+
+use ark_ec::PairingEngine;
+use ark_std::collections::BTreeSet;
+
+/* Hierarchical aggregation. For very large committees, participants are split into
+   sub-committees (the leaves of the tree) of group_size consecutive ids, each of which
+   aggregates the shares of its own members locally. Internal nodes then merge the
+   aggregates of up to fanout children, until a single root aggregate remains. Every
+   node tracks which dealers' contributions its aggregate covers, so that no all-to-all
+   share exchange is needed.
+
+   The tree does not verify anything itself: shares should be verified (e.g., with
+   PVSSAggregator::share_verify) by the sub-committee before they are added, and the
+   root transcript can be checked with PVSSAggregator::transcript_verify.
+*/
+
+// Struct TreeNode models a single (leaf or internal) aggregate of the tree.
+#[derive(Clone)]
+pub struct TreeNode<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub transcript: PVSSTranscript<E, SSIG>,   // the node's aggregate
+    pub covers: BTreeSet<usize>,               // ids of the dealers whose contributions are included
+    pub children: Vec<usize>,                  // indices of the child nodes (empty for leaves)
+    pub parent: Option<usize>,                 // index of the parent node (None for the root)
+}
+
+// Struct AggregationTree models the whole hierarchy. Nodes are stored so that children
+// always precede their parents; the first nodes are the leaves.
+#[derive(Clone)]
+pub struct AggregationTree<
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+> {
+    pub degree: usize,                      // polynomial degree (t)
+    pub num_participants: usize,            // the total number of participants (n)
+    pub group_size: usize,                  // number of participants per sub-committee
+    pub nodes: Vec<TreeNode<E, SSIG>>,      // all nodes of the tree, leaves first
+    pub num_leaves: usize,                  // number of sub-committees
+    pub root: usize,                        // index of the root node
+}
+
+
+impl<E, SSIG> AggregationTree<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    // Function for building a tree for n participants, grouped into sub-committees of
+    // group_size, where internal nodes merge up to fanout children.
+    pub fn new(degree: usize, num_participants: usize, group_size: usize, fanout: usize) -> Result<Self, PVSSError<E>> {
+        if num_participants == 0 || group_size == 0 || fanout < 2 {
+            return Err(PVSSError::InvalidTreeShape(group_size, fanout));
+        }
+
+        let empty_node = |children: Vec<usize>| TreeNode {
+            transcript: PVSSTranscript::empty(degree, num_participants),
+            covers: BTreeSet::new(),
+            children,
+            parent: None,
+        };
+
+        let num_leaves = num_participants.div_ceil(group_size);
+        let mut nodes = (0..num_leaves).map(|_| empty_node(vec![])).collect::<Vec<_>>();
+
+        // Build the tree bottom-up, one level at a time.
+        let mut level = (0..num_leaves).collect::<Vec<_>>();
+        while level.len() > 1 {
+            let mut next = vec![];
+            for chunk in level.chunks(fanout) {
+                let index = nodes.len();
+                for child in chunk.iter() {
+                    nodes[*child].parent = Some(index);
+                }
+                nodes.push(empty_node(chunk.to_vec()));
+                next.push(index);
+            }
+            level = next;
+        }
+
+        Ok(Self {
+            degree,
+            num_participants,
+            group_size,
+            root: nodes.len() - 1,
+            nodes,
+            num_leaves,
+        })
+    }
+
+    // Method returning the index of the leaf (sub-committee) responsible for a dealer.
+    pub fn leaf_of(&self, id: usize) -> Result<usize, PVSSError<E>> {
+        if id >= self.num_participants {
+            return Err(PVSSError::InvalidParticipantId(id));
+        }
+        Ok(id / self.group_size)
+    }
+
+    // Method for adding a (verified) augmented share to its dealer's sub-committee aggregate.
+    // Returns the index of the leaf that now covers the share.
+    pub fn add_share(&mut self, share: &PVSSAugmentedShare<E, SSIG>) -> Result<usize, PVSSError<E>> {
+        let leaf = self.leaf_of(share.participant_id)?;
+        let node = &mut self.nodes[leaf];
+
+        // Counting a dealer twice would double its share in the aggregate.
+        if node.covers.contains(&share.participant_id) {
+            return Ok(leaf);
+        }
+
+        let transcript = PVSSTranscript::from_share(self.degree, self.num_participants, share);
+        node.transcript = node.transcript.aggregate(&transcript)?;
+        node.covers.insert(share.participant_id);
+
+        Ok(leaf)
+    }
+
+    // Method for merging the children's aggregates into every internal node, up to the
+    // root. Returns the root aggregate.
+    pub fn merge(&mut self) -> Result<&PVSSTranscript<E, SSIG>, PVSSError<E>> {
+        for index in self.num_leaves..self.nodes.len() {
+            let mut transcript = PVSSTranscript::empty(self.degree, self.num_participants);
+            let mut covers = BTreeSet::new();
+
+            for child in self.nodes[index].children.iter() {
+                transcript = transcript.aggregate(&self.nodes[*child].transcript)?;
+                covers.extend(self.nodes[*child].covers.iter().cloned());
+            }
+
+            self.nodes[index].transcript = transcript;
+            self.nodes[index].covers = covers;
+        }
+
+        Ok(&self.nodes[self.root].transcript)
+    }
+
+    // Method returning the ids of the dealers covered by a node's aggregate.
+    pub fn covers(&self, index: usize) -> Option<&BTreeSet<usize>> {
+        self.nodes.get(index).map(|node| &node.covers)
+    }
+
+    // Method returning the root aggregate (as of the last merge).
+    pub fn root_transcript(&self) -> &PVSSTranscript<E, SSIG> {
+        &self.nodes[self.root].transcript
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::modified_scrape::{tests::{setup_nodes, SSIG}, tree::AggregationTree};
+    use crate::Hash;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_tree_aggregation() {
+        let rng = &mut thread_rng();
+        let (n, t) = (8, 2);
+        let (mut nodes, _) = setup_nodes(n, t);
+
+        let mut tree = AggregationTree::<E, SSIG>::new(t, n, 2, 2).unwrap();
+        assert_eq!(tree.num_leaves, 4);
+
+        let shares = (0..n).map(|i| nodes[i].share(rng).unwrap()).collect::<Vec<_>>();
+        for share in shares.iter() {
+            nodes[0].aggregator.share_verify(rng, share).unwrap();
+            tree.add_share(share).unwrap();
+        }
+
+        let root = tree.merge().unwrap().clone();
+        assert_eq!(tree.covers(tree.root).unwrap().len(), n);
+
+        // the root aggregate is a valid transcript covering all dealers
+        nodes[0].aggregator.transcript_verify(rng, &root).unwrap();
+
+        // a share added twice is only counted once
+        assert_eq!(tree.add_share(&shares[3]).unwrap(), 1);
+        assert_eq!(tree.merge().unwrap().digest(), root.digest());
+
+        // and the root matches the aggregate of a single, flat aggregator
+        for share in shares.iter() {
+            nodes[1].aggregator.receive_share(rng, share).unwrap();
+        }
+        assert_eq!(nodes[1].aggregator.transcript.digest(), root.digest());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::modified_scrape::{
+    config::Config,
+    decomp::{message_from_pi_i, DecompProof},
+    errors::PVSSError,
+    kernel,
+    share::PVSSTranscript,
+};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Hash, Scalar};
+
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::{PrimeField, Zero};
+use ark_std::collections::BTreeMap;
+
+use rand::{CryptoRng, Rng};
+
+/* Stateless public verification of aggregated transcripts, for light clients and
+   auditors that hold no keys and keep no aggregator state. The checks are those of
+   PVSSAggregator::transcript_verify at the standard strictness level, with the
+   interactive coding check, run directly on the verification kernel: the only
+   inputs are the config, the signature scheme and the participants' public keys.
+*/
+
+// Function for publicly verifying an aggregated transcript. participants_pks holds
+// the public keys of participants 0, ..., n-1, under which the decomposition proofs
+// are signed and the shares are encrypted (transcripts encrypted under epoch keys
+// have to be checked by an aggregator that registered them).
+pub fn verify_aggregated_transcript<E, SSIG, R>(
+    rng: &mut R,
+    config: &Config<E>,
+    scheme_sig: &SSIG,
+    participants_pks: &[E::G1Affine],
+    transcript: &PVSSTranscript<E, SSIG>,
+) -> Result<(), PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    R: Rng + CryptoRng,
+{
+    let (t, n) = (config.degree, config.num_participants);
+    if transcript.degree != t || transcript.num_participants != n {
+        return Err(PVSSError::TranscriptDifferentConfig(t, transcript.degree, n, transcript.num_participants));
+    }
+
+    let (encs, comms) = (&transcript.pvss_share.encs, &transcript.pvss_share.comms);
+    if encs.len() != n || comms.len() != n || participants_pks.len() != n || transcript.contributions.len() < t {
+        return Err(PVSSError::LengthMismatchError);
+    }
+
+    kernel::subgroup_check::<E>(encs, comms)?;
+    kernel::coding_check_for::<E, R>(rng, config, comms)?;
+
+    let mut gs_total = E::G2Projective::zero();
+    let mut dealings = BTreeMap::new();
+    let mut public_keys = vec![];
+    let mut messages = vec![];
+    let mut signatures = vec![];
+    let mut decomp_proofs = vec![];
+
+    for (id, contribution) in transcript.contributions.iter() {
+        let public_key = participants_pks.get(*id).ok_or(PVSSError::<E>::InvalidParticipantId(*id))?;
+
+        // No dealing may be counted twice, nor with weight zero.
+        if let Some(other) = dealings.insert(contribution.decomp_proof.digest(), *id) {
+            return Err(PVSSError::DuplicateContributionError(other, *id));
+        }
+        if contribution.weight == 0 {
+            return Err(PVSSError::InsufficientWeightError(0, 1));
+        }
+
+        public_keys.push(public_key);
+        messages.push(message_from_pi_i(contribution.decomp_proof)?);
+        signatures.push(&contribution.signature_on_decomp);
+        decomp_proofs.push(&contribution.decomp_proof);
+        gs_total += contribution.decomp_proof.gs().mul(Scalar::<E>::from(contribution.weight).into_repr());
+    }
+
+    DecompProof::verify_batch(rng, config, &decomp_proofs)?;
+    scheme_sig.batch_verify(
+        rng,
+        &public_keys,
+        &messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>(),
+        &signatures,
+    )?;
+    kernel::interpolation_check_at::<E>(comms, &config.points(), t, gs_total)?;
+
+    let entries = (0..n)
+        .map(|i| (participants_pks[i], &encs[i], &comms[i]))
+        .collect::<Vec<_>>();
+    kernel::encryptions_check::<E, R>(rng, config.srs.g2, &entries)
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{errors::PVSSError, tests::setup_nodes, verify_aggregated_transcript};
+
+    use ark_ec::ProjectiveCurve;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_verify_aggregated_transcript() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        for i in 0..3 {
+            let share = nodes[i].share(rng).unwrap();
+            nodes[3].aggregator.receive_share(rng, &share).unwrap();
+        }
+
+        // A verifier holding only the public parameters accepts the transcript.
+        let aggregator = &nodes[3].aggregator;
+        let transcript = aggregator.finalize().unwrap().clone();
+        let pks = aggregator.participants.values().map(|p| p.public_key_sig).collect::<Vec<_>>();
+        verify_aggregated_transcript(rng, &aggregator.config, &aggregator.scheme_sig, &pks, &transcript).unwrap();
+
+        // It rejects tampered encryptions, and a roster of the wrong size.
+        let mut tampered = transcript.clone();
+        tampered.pvss_share.encs[0] = tampered.pvss_share.encs[1].double();
+        assert!(verify_aggregated_transcript(rng, &aggregator.config, &aggregator.scheme_sig, &pks, &tampered).is_err());
+        assert!(matches!(
+            verify_aggregated_transcript(rng, &aggregator.config, &aggregator.scheme_sig, &pks[..3], &transcript),
+            Err(PVSSError::LengthMismatchError)
+        ));
+    }
+}
@@ -0,0 +1,18 @@
+use crate::message::errors::MessageError;
+use crate::modified_scrape::errors::PVSSError;
+use ark_ec::PairingEngine;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while driving a node over the network.
+#[derive(Error, Debug)]
+pub enum NetError<E: PairingEngine> {
+    #[error("Transport error: {0}")]
+    TransportError(String),
+    #[error("Transport closed before the epoch completed")]
+    TransportClosed,
+    #[error("Message error: {0}")]
+    MessageError(#[from] MessageError),
+    #[error("PVSS error: {0}")]
+    PVSSError(#[from] PVSSError<E>),
+}
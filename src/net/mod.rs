@@ -0,0 +1,430 @@
+use crate::beacon::epoch::{BeaconOutput, EpochBeacon, EpochContribution};
+use crate::message::{PVSSMessage, SignedMessage};
+use crate::modified_scrape::{
+    leader::leader_of,
+    node::Node,
+    share::{PVSSAugmentedShare, PVSSTranscript},
+};
+use crate::net::errors::NetError;
+use crate::signature::{
+    eddsa::{PublicKey, SecretKey},
+    scheme::BatchVerifiableSignatureScheme,
+};
+use crate::Scalar;
+
+use ark_ec::PairingEngine;
+use ark_std::collections::BTreeMap;
+use futures::future::BoxFuture;
+use rand::{CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+use tokio::sync::watch;
+
+pub mod errors;
+
+/* Network driver. A NodeDriver runs a Node through an epoch over a pluggable,
+   asynchronous transport, exchanging SignedMessages (see message/mod.rs) under the
+   nodes' Ed25519 identity keys:
+
+       every node -> leader:     Share            (its dealing)
+       leader -> all:            AggregatedShare  (once quorum shares are aggregated)
+       every node -> all:        BeaconShare      (once the transcript is accepted)
+
+   where the leader of epoch e is node e mod n (see leader_of). The leader waits
+   for n - t shares by default, so that t silent nodes cannot stall it. Nodes
+   accept the leader's transcript only if it verifies, then release their
+   contribution to the epoch's beacon, whose output they compute from any t + 1
+   contributions. Contributions are verified against the accepted transcript (see
+   beacon/epoch.rs), those received before it are held until then. Messages that
+   fail authentication or verification are dropped and counted.
+
+   The final transcript and the beacon output are published on watch channels, so
+   that other tasks can await them. View changes on a silent leader (see leader.rs)
+   are left to the integrator, who may restart the epoch with another driver.
+*/
+
+// Trait Transport abstracts the network the driver runs over. The transport need
+// not be authenticated, since every message is signed by its sender.
+pub trait Transport: Send {
+    // Method for sending a message to node to.
+    fn send(&mut self, to: usize, message: Vec<u8>) -> BoxFuture<'_, Result<(), String>>;
+
+    // Method for sending a message to every node but ourselves.
+    fn broadcast(&mut self, message: Vec<u8>) -> BoxFuture<'_, Result<(), String>>;
+
+    // Method for receiving the next message, or None once the transport is closed.
+    fn recv(&mut self) -> BoxFuture<'_, Option<Vec<u8>>>;
+}
+
+// Struct NodeDriver drives a node through an epoch over a transport.
+pub struct NodeDriver<E, SSIG, T>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    T: Transport,
+{
+    pub node: Node<E, SSIG>,                                               // the driven node
+    pub epoch: u64,                                                        // the epoch being run
+    pub quorum: usize,                                                     // shares the leader aggregates before finalizing
+    pub rejected: usize,                                                   // messages dropped so far
+    identity: SecretKey,                                                   // our identity key
+    identities: BTreeMap<usize, PublicKey>,                                // the nodes' identity keys, by id
+    transport: T,                                                          // the network
+    rng: ChaChaRng,                                                        // source of our randomness
+    transcript: Option<PVSSTranscript<E, SSIG>>,                           // the accepted transcript, if any
+    beacon: EpochBeacon<E>,                                                // verified contributions to the epoch's beacon
+    pending: BTreeMap<usize, EpochContribution<E>>,                        // contributions received before the transcript, by sender
+    output: Option<BeaconOutput>,                                          // the epoch's beacon output, if any
+    transcript_tx: watch::Sender<Option<PVSSTranscript<E, SSIG>>>,
+    transcript_rx: watch::Receiver<Option<PVSSTranscript<E, SSIG>>>,
+    output_tx: watch::Sender<Option<BeaconOutput>>,
+    output_rx: watch::Receiver<Option<BeaconOutput>>,
+}
+
+impl<E, SSIG, T> NodeDriver<E, SSIG, T>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+    T: Transport,
+{
+    // Function for creating a driver of node in the given epoch, with our identity
+    // key and those of all nodes. The leader waits for the shares of n - t nodes
+    // unless quorum is changed. The driver's randomness is seeded from rng.
+    pub fn new<R: Rng + CryptoRng>(
+        rng: &mut R,
+        node: Node<E, SSIG>,
+        epoch: u64,
+        identity: SecretKey,
+        identities: BTreeMap<usize, PublicKey>,
+        transport: T,
+    ) -> Self {
+        let quorum = node.aggregator.config.num_participants - node.aggregator.config.degree;
+        let beacon = EpochBeacon::for_config(epoch, &node.aggregator.config);
+        let (transcript_tx, transcript_rx) = watch::channel(None);
+        let (output_tx, output_rx) = watch::channel(None);
+
+        Self {
+            node,
+            epoch,
+            quorum,
+            rejected: 0,
+            identity,
+            identities,
+            transport,
+            rng: ChaChaRng::from_seed(rng.gen()),
+            transcript: None,
+            beacon,
+            pending: BTreeMap::new(),
+            output: None,
+            transcript_tx,
+            transcript_rx,
+            output_tx,
+            output_rx,
+        }
+    }
+
+    // Method returning our node id.
+    pub fn id(&self) -> usize {
+        self.node.dealer.participant.id
+    }
+
+    // Method returning the leader of the epoch.
+    pub fn leader(&self) -> usize {
+        leader_of(self.epoch, self.node.aggregator.config.num_participants)
+    }
+
+    // Method returning a channel on which the accepted transcript is published.
+    pub fn watch_transcript(&self) -> watch::Receiver<Option<PVSSTranscript<E, SSIG>>> {
+        self.transcript_rx.clone()
+    }
+
+    // Method returning a channel on which the epoch's beacon output is published.
+    pub fn watch_beacon(&self) -> watch::Receiver<Option<BeaconOutput>> {
+        self.output_rx.clone()
+    }
+
+    // Method for running the epoch: we deal, then handle incoming messages until
+    // the beacon output is known, which is returned.
+    pub async fn run(&mut self) -> Result<BeaconOutput, NetError<E>> {
+        self.deal().await?;
+        loop {
+            if let Some(output) = self.output {
+                return Ok(output);
+            }
+            let message = self.transport.recv().await.ok_or(NetError::TransportClosed)?;
+            self.handle(&message).await?;
+        }
+    }
+
+    // Method for dealing our share and sending it to the leader.
+    pub async fn deal(&mut self) -> Result<(), NetError<E>> {
+        let share = self.node.share(&mut self.rng)?;
+        let (id, leader) = (self.id(), self.leader());
+        if id == leader {
+            self.on_share(id, &share).await
+        } else {
+            self.send_to(leader, PVSSMessage::Share(share)).await
+        }
+    }
+
+    // Method for handling a message received from the transport. Only errors of the
+    // transport or of our own node are returned.
+    pub async fn handle(&mut self, message: &[u8]) -> Result<(), NetError<E>> {
+        let signed = match SignedMessage::<E, SSIG>::decode(message) {
+            Ok(signed) => signed,
+            Err(_) => {
+                self.rejected += 1;
+                return Ok(());
+            },
+        };
+
+        let authentic = signed.epoch == self.epoch
+            && self.identities.get(&signed.sender).is_some_and(|pk| signed.verify(pk).is_ok());
+        if !authentic {
+            self.rejected += 1;
+            return Ok(());
+        }
+
+        match signed.message {
+            PVSSMessage::Share(share) => self.on_share(signed.sender, &share).await,
+            PVSSMessage::AggregatedShare(transcript) => self.on_transcript(signed.sender, transcript).await,
+            PVSSMessage::BeaconShare(contribution) => self.on_beacon_share(signed.sender, &contribution),
+            // decryptions, complaints and certificates are left to the integrator
+            _ => Ok(()),
+        }
+    }
+
+    // Utility method handling a share received by the leader. Once quorum shares
+    // are aggregated, the transcript is sent to all and accepted.
+    async fn on_share(&mut self, sender: usize, share: &PVSSAugmentedShare<E, SSIG>) -> Result<(), NetError<E>> {
+        if self.id() != self.leader() || self.transcript.is_some() {
+            return Ok(());
+        }
+        if share.participant_id != sender || self.node.aggregator.receive_share_from(&mut self.rng, share, Some(sender)).is_err() {
+            self.rejected += 1;
+            return Ok(());
+        }
+
+        if self.node.aggregator.contributors() >= self.quorum {
+            let transcript = self.node.aggregator.finalize()?.clone();
+            self.broadcast(PVSSMessage::AggregatedShare(transcript.clone())).await?;
+            self.accept(transcript).await?;
+        }
+        Ok(())
+    }
+
+    // Utility method handling the leader's transcript, which is accepted if it verifies.
+    async fn on_transcript(&mut self, sender: usize, transcript: PVSSTranscript<E, SSIG>) -> Result<(), NetError<E>> {
+        if sender != self.leader() || self.transcript.is_some() {
+            return Ok(());
+        }
+        let valid = transcript.check_weight_threshold().is_ok()
+            && self.node.aggregator.transcript_verify(&mut self.rng, &transcript).is_ok();
+        if !valid {
+            self.rejected += 1;
+            return Ok(());
+        }
+        self.accept(transcript).await
+    }
+
+    // Utility method accepting a transcript: it is published, and our contribution
+    // to the epoch's beacon is released.
+    async fn accept(&mut self, transcript: PVSSTranscript<E, SSIG>) -> Result<(), NetError<E>> {
+        let contribution = self.node.epoch_contribution(&transcript, self.epoch)?;
+        self.transcript = Some(transcript.clone());
+        // we hold a receiver, so that publishing cannot fail
+        let _ = self.transcript_tx.send(Some(transcript));
+
+        self.broadcast(PVSSMessage::BeaconShare(contribution.clone())).await?;
+        let id = self.id();
+        self.on_beacon_share(id, &contribution)?;
+
+        let pending = std::mem::take(&mut self.pending);
+        for (sender, contribution) in pending.iter() {
+            self.on_beacon_share(*sender, contribution)?;
+        }
+        Ok(())
+    }
+
+    // Utility method collecting a contribution to the epoch's beacon, and computing
+    // the output once enough have been collected. Before our transcript is accepted,
+    // the first contribution of each sender is held, to be verified then.
+    fn on_beacon_share(&mut self, sender: usize, contribution: &EpochContribution<E>) -> Result<(), NetError<E>> {
+        if contribution.origin != sender {
+            self.rejected += 1;
+            return Ok(());
+        }
+        let transcript = match self.transcript.as_ref() {
+            Some(transcript) => transcript,
+            None => {
+                self.pending.entry(sender).or_insert_with(|| contribution.clone());
+                return Ok(());
+            },
+        };
+
+        let valid = contribution.verify(&self.node.aggregator.config, &transcript.pvss_share.comms).is_ok()
+            && self.beacon.add(contribution).is_ok();
+        if !valid {
+            self.rejected += 1;
+            return Ok(());
+        }
+        if self.output.is_some() || !self.beacon.is_ready() {
+            return Ok(());
+        }
+
+        let output = self.beacon.output()?;
+        self.output = Some(output);
+        let _ = self.output_tx.send(Some(output));
+        Ok(())
+    }
+
+    // Utility method signing a message and sending it to node to.
+    async fn send_to(&mut self, to: usize, message: PVSSMessage<E, SSIG>) -> Result<(), NetError<E>> {
+        let bytes = SignedMessage::sign(self.id(), self.epoch, message, &self.identity)?.encode()?;
+        self.transport.send(to, bytes).await.map_err(NetError::TransportError)
+    }
+
+    // Utility method signing a message and sending it to all other nodes.
+    async fn broadcast(&mut self, message: PVSSMessage<E, SSIG>) -> Result<(), NetError<E>> {
+        let bytes = SignedMessage::sign(self.id(), self.epoch, message, &self.identity)?.encode()?;
+        self.transport.broadcast(bytes).await.map_err(NetError::TransportError)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::beacon::epoch::EpochContribution;
+    use crate::message::{PVSSMessage, SignedMessage};
+    use crate::modified_scrape::tests::{setup_nodes, SSIG};
+    use crate::net::{NodeDriver, Transport};
+    use crate::signature::eddsa::SecretKey;
+    use crate::{Hash, Scalar, GT};
+
+    use ark_ec::{AffineCurve, PairingEngine};
+    use ark_std::{collections::BTreeMap, UniformRand};
+    use futures::{
+        channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        executor::block_on,
+        future::{join_all, ready, BoxFuture},
+        StreamExt,
+    };
+    use rand::thread_rng;
+
+    // An in-memory transport over unbounded channels.
+    struct ChannelTransport {
+        id: usize,
+        peers: Vec<UnboundedSender<Vec<u8>>>,
+        inbox: UnboundedReceiver<Vec<u8>>,
+    }
+
+    impl Transport for ChannelTransport {
+        fn send(&mut self, to: usize, message: Vec<u8>) -> BoxFuture<'_, Result<(), String>> {
+            Box::pin(ready(self.peers[to].unbounded_send(message).map_err(|e| e.to_string())))
+        }
+
+        fn broadcast(&mut self, message: Vec<u8>) -> BoxFuture<'_, Result<(), String>> {
+            let id = self.id;
+            let result = self
+                .peers
+                .iter()
+                .enumerate()
+                .filter(|(to, _)| *to != id)
+                .try_for_each(|(_, peer)| peer.unbounded_send(message.clone()).map_err(|e| e.to_string()));
+            Box::pin(ready(result))
+        }
+
+        fn recv(&mut self) -> BoxFuture<'_, Option<Vec<u8>>> {
+            Box::pin(self.inbox.next())
+        }
+    }
+
+    // The drivers of a test committee, with their identity keys and a sender to
+    // each driver's inbox.
+    type Drivers = (Vec<NodeDriver<E, SSIG, ChannelTransport>>, Vec<SecretKey>, Vec<UnboundedSender<Vec<u8>>>);
+
+    // Sets up the drivers of n nodes in epoch 6, connected by channel transports.
+    // Returns the drivers, along with the nodes' identity keys and a sender to
+    // each node's inbox.
+    fn setup_drivers(n: usize, t: usize) -> Drivers {
+        let rng = &mut thread_rng();
+        let (nodes, _) = setup_nodes(n, t);
+        let identities = (0..n).map(|_| SecretKey::generate(rng)).collect::<Vec<_>>();
+        let public_keys = identities.iter().enumerate().map(|(id, sk)| (id, sk.public_key())).collect::<BTreeMap<_, _>>();
+        let (peers, inboxes): (Vec<_>, Vec<_>) = (0..n).map(|_| unbounded()).unzip();
+
+        let drivers = nodes
+            .into_iter()
+            .zip(identities.iter().cloned().zip(inboxes))
+            .enumerate()
+            .map(|(id, (node, (identity, inbox)))| {
+                let transport = ChannelTransport { id, peers: peers.clone(), inbox };
+                NodeDriver::new(rng, node, 6, identity, public_keys.clone(), transport)
+            })
+            .collect::<Vec<_>>();
+        (drivers, identities, peers)
+    }
+
+    #[test]
+    fn test_node_driver() {
+        let (mut drivers, _, _) = setup_drivers(4, 1);
+        let transcripts = drivers.iter().map(|driver| driver.watch_transcript()).collect::<Vec<_>>();
+        let beacons = drivers.iter().map(|driver| driver.watch_beacon()).collect::<Vec<_>>();
+
+        // All nodes end the epoch with the same output, on the leader's transcript.
+        let outputs = block_on(join_all(drivers.iter_mut().map(|driver| driver.run())))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(outputs[0].epoch, 6);
+        assert!(outputs.iter().all(|output| *output == outputs[0]));
+        assert!(beacons.iter().all(|beacon| *beacon.borrow() == Some(outputs[0])));
+
+        let leader = drivers[0].leader();
+        let digest = drivers[leader].node.aggregator.transcript.digest();
+        assert!(transcripts.iter().all(|transcript| transcript.borrow().as_ref().unwrap().digest() == digest));
+        assert!(drivers.iter().all(|driver| driver.rejected == 0));
+    }
+
+    #[test]
+    fn test_forged_beacon_share() {
+        let rng = &mut thread_rng();
+        let (mut drivers, identities, peers) = setup_drivers(4, 1);
+
+        // Nodes 1 and 3 send node 0 forged contributions ahead of the transcript.
+        for sender in [1, 3].iter() {
+            let proof = (Scalar::<E>::rand(rng), <E as PairingEngine>::G1Affine::prime_subgroup_generator());
+            let contribution = EpochContribution::<E> { epoch: 6, origin: *sender, sigma: GT::<E>::rand(rng), proof };
+            let message = SignedMessage::<E, SSIG>::sign(*sender, 6, PVSSMessage::BeaconShare(contribution), &identities[*sender]).unwrap();
+            peers[0].unbounded_send(message.encode().unwrap()).unwrap();
+        }
+
+        // Node 0 drops them once it can verify them, and still agrees with the others.
+        let outputs = block_on(join_all(drivers.iter_mut().map(|driver| driver.run())))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(outputs.iter().all(|output| *output == outputs[0]));
+        assert_eq!(drivers[0].rejected, 2);
+    }
+
+    #[test]
+    fn test_silent_node() {
+        let (mut drivers, _, _) = setup_drivers(4, 1);
+
+        // The leader does not wait for the share of a node which never deals.
+        // (its driver is kept, so that messages to it can still be sent)
+        let silent = (drivers[0].leader() + 1) % 4;
+        let _silent = drivers.remove(silent);
+        let outputs = block_on(join_all(drivers.iter_mut().map(|driver| driver.run())))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(outputs.iter().all(|output| *output == outputs[0]));
+        let leader = drivers.iter().find(|driver| driver.id() == driver.leader()).unwrap();
+        assert_eq!(leader.node.aggregator.contributors(), 3);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::nizk::utils::{errors::NIZKError, hash::hash_to_group};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2s_simd::Params;
+
+use rand::{CryptoRng, Rng};
+use zeroize::Zeroizing;
+
+const PERSONALIZATION: &[u8] = b"XGRPDLEQ";        // persona for the Fiat-Shamir challenge
+const BASE_PERSONALIZATION: &[u8] = b"XGRPBASE";   // persona for deriving the Pedersen bases
+
+/* Cross-group discrete log equality. Given generators g_1 and g_2 of groups of
+   different prime orders q_1 and q_2 (e.g., Curve25519 hosting an Ed25519 identity,
+   and G1 of BLS12-381 hosting an encryption key), a proof shows that the public keys
+   X_1 = x * g_1 and X_2 = x * g_2 share the same secret x, seen as an integer.
+
+   Since the scalar fields differ, x is decomposed into b = min(|q_1|, |q_2|) - 1
+   bits, each committed to in both groups with Pedersen commitments
+       C_i = b_i * g_1 + r_i * h_1,    D_i = b_i * g_2 + s_i * h_2
+   where the randomness is chosen so that sum_i 2^i r_i = sum_i 2^i s_i = 0. The
+   verifier checks sum_i 2^i C_i == X_1 and sum_i 2^i D_i == X_2, and an OR proof
+   (Cramer-Damgard-Schoenmakers) per bit shows that either both C_i, D_i open to 0
+   or both open to 1. Branch challenges are 128-bit integers, split by XOR, so that
+   they embed identically into both scalar fields.
+
+   Security model:
+   - Soundness relies on the discrete log assumption in both groups, with h_1 and h_2
+     derived by hashing to the groups so that nobody knows their discrete logs to
+     g_1 and g_2; the challenge is a random oracle (BLAKE2s), giving a soundness error
+     of about 2^-128 per proof. It then follows that log_{g_1} X_1 and log_{g_2} X_2
+     are the same integer below 2^b.
+   - Zero-knowledge holds in the random oracle model, and reveals only that x < 2^b.
+     Secrets of b bits or more (e.g., unreduced clamped Ed25519 scalars) must first be
+     reduced modulo the order of the identity group.
+   - The proof makes no claim about how x was generated, and binds to no context
+     beyond the two public keys; callers should sign or hash it along with their
+     own context to prevent replays.
+*/
+
+// Struct CrossGroupDLEQ holds the generators the keys are over and the Pedersen bases.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossGroupDLEQ<C1: AffineCurve, C2: AffineCurve> {
+    pub g1: C1,   // generator of the first group
+    pub h1: C1,   // Pedersen base of the first group
+    pub g2: C2,   // generator of the second group
+    pub h2: C2,   // Pedersen base of the second group
+}
+
+// Struct BitProof models the commitments to a single bit of the secret and the OR
+// proof that both commitments open to the same bit.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BitProof<C1: AffineCurve, C2: AffineCurve> {
+    pub commitments: (C1, C2),                               // C_i, D_i
+    pub nonces: ((C1, C2), (C1, C2)),                        // nonce commitments of both branches
+    pub challenge: (u64, u64),                               // 128-bit challenge of branch 0 (high, low)
+    pub responses1: (C1::ScalarField, C1::ScalarField),      // responses of both branches in the first group
+    pub responses2: (C2::ScalarField, C2::ScalarField),      // responses of both branches in the second group
+}
+
+// Struct CrossGroupProof models a whole proof, with one BitProof per bit of the secret.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CrossGroupProof<C1: AffineCurve, C2: AffineCurve> {
+    pub bits: Vec<BitProof<C1, C2>>,
+}
+
+// The prover's first move for a bit: its commitments and the nonce commitments of both branches.
+type FirstMove<C1, C2> = ((C1, C2), ((C1, C2), (C1, C2)));
+
+// Utility functions converting 128-bit challenges to and from their stored form.
+fn split(c: u128) -> (u64, u64) {
+    ((c >> 64) as u64, c as u64)
+}
+
+fn join(c: (u64, u64)) -> u128 {
+    ((c.0 as u128) << 64) | c.1 as u128
+}
+
+impl<C1: AffineCurve, C2: AffineCurve> CrossGroupDLEQ<C1, C2> {
+
+    // Function for setting up a proof system for keys over generators g1 and g2. The
+    // Pedersen bases are derived by hashing the generators to the respective groups.
+    pub fn new(g1: C1, g2: C2) -> Result<Self, NIZKError> {
+        let mut bytes = vec![];
+        g1.serialize(&mut bytes)?;
+        g2.serialize(&mut bytes)?;
+
+        let h1 = hash_to_group::<C1>(BASE_PERSONALIZATION, &[&bytes[..], b"1"].concat())?.into_affine();
+        let h2 = hash_to_group::<C2>(BASE_PERSONALIZATION, &[&bytes[..], b"2"].concat())?.into_affine();
+
+        Ok(Self { g1, h1, g2, h2 })
+    }
+
+    // Function returning the number of bits secrets are decomposed into.
+    pub fn bits() -> usize {
+        C1::ScalarField::size_in_bits().min(C2::ScalarField::size_in_bits()) - 1
+    }
+
+    // Method computing the public keys of a secret in both groups.
+    pub fn statement(&self, x: &C1::ScalarField) -> (C1, C2) {
+        let x2 = C2::ScalarField::from_le_bytes_mod_order(&x.into_repr().to_bytes_le());
+        (self.g1.mul(x.into_repr()).into_affine(), self.g2.mul(x2.into_repr()).into_affine())
+    }
+
+    // Utility method computing the Fiat-Shamir challenge over the public keys, the
+    // bit commitments and the nonce commitments.
+    fn challenge(
+        &self,
+        statement: &(C1, C2),
+        bits: &[FirstMove<C1, C2>],
+    ) -> Result<u128, NIZKError> {
+        let mut bytes = vec![];
+        self.g1.serialize(&mut bytes)?;
+        self.h1.serialize(&mut bytes)?;
+        self.g2.serialize(&mut bytes)?;
+        self.h2.serialize(&mut bytes)?;
+        statement.serialize(&mut bytes)?;
+        for bit in bits.iter() {
+            bit.serialize(&mut bytes)?;
+        }
+
+        let hash = Params::new()
+            .hash_length(16)
+            .personal(PERSONALIZATION)
+            .to_state()
+            .update(&bytes)
+            .finalize();
+        let mut challenge = [0u8; 16];
+        challenge.copy_from_slice(hash.as_bytes());
+        Ok(u128::from_be_bytes(challenge))
+    }
+
+    // Function for proving that the public keys of x in both groups share the secret.
+    // The secret must be below 2^bits().
+    pub fn prove<R: Rng + CryptoRng>(&self, rng: &mut R, x: &C1::ScalarField) -> Result<CrossGroupProof<C1, C2>, NIZKError> {
+        let n = Self::bits();
+        let repr = x.into_repr();
+        if repr.num_bits() as usize > n {
+            return Err(NIZKError::WitnessTooLarge(n));
+        }
+        let statement = self.statement(x);
+
+        // Sample the randomness of all but the last bit, and choose that of the last bit
+        // so that the weighted sums vanish.
+        // The blinding factors are wiped on drop.
+        let mut r = Zeroizing::new((0..n - 1).map(|_| C1::ScalarField::rand(rng)).collect::<Vec<_>>());
+        let mut s = Zeroizing::new((0..n - 1).map(|_| C2::ScalarField::rand(rng)).collect::<Vec<_>>());
+        let (mut sum_r, mut sum_s) = (C1::ScalarField::zero(), C2::ScalarField::zero());
+        let (mut pow_r, mut pow_s) = (C1::ScalarField::one(), C2::ScalarField::one());
+        for i in 0..n - 1 {
+            sum_r += pow_r * r[i];
+            sum_s += pow_s * s[i];
+            pow_r.double_in_place();
+            pow_s.double_in_place();
+        }
+        r.push(-sum_r * pow_r.inverse().unwrap());
+        s.push(-sum_s * pow_s.inverse().unwrap());
+
+        // Commit to the bits, and run the real branch honestly and simulate the other.
+        let mut first_moves = vec![];
+        let mut secrets = vec![];
+        for i in 0..n {
+            let bit = repr.get_bit(i);
+            let mut c_i = self.h1.mul(r[i].into_repr());
+            let mut d_i = self.h2.mul(s[i].into_repr());
+            if bit {
+                c_i.add_assign_mixed(&self.g1);
+                d_i.add_assign_mixed(&self.g2);
+            }
+            let (c_i, d_i) = (c_i.into_affine(), d_i.into_affine());
+
+            let (k1, k2) = (C1::ScalarField::rand(rng), C2::ScalarField::rand(rng));
+            let real = (self.h1.mul(k1.into_repr()).into_affine(), self.h2.mul(k2.into_repr()).into_affine());
+
+            let sim_c: u128 = rng.gen();
+            let (z1, z2) = (C1::ScalarField::rand(rng), C2::ScalarField::rand(rng));
+            let (p1, p2) = if bit { (c_i, d_i) } else { ((c_i.into_projective() - self.g1.into_projective()).into_affine(), (d_i.into_projective() - self.g2.into_projective()).into_affine()) };
+            let simulated = (
+                (self.h1.mul(z1.into_repr()) + p1.mul(C1::ScalarField::from(sim_c).into_repr())).into_affine(),
+                (self.h2.mul(z2.into_repr()) + p2.mul(C2::ScalarField::from(sim_c).into_repr())).into_affine(),
+            );
+
+            let nonces = if bit { (simulated, real) } else { (real, simulated) };
+            first_moves.push(((c_i, d_i), nonces));
+            secrets.push((bit, k1, k2, sim_c, z1, z2));
+        }
+
+        let c = self.challenge(&statement, &first_moves)?;
+
+        let bits = first_moves
+            .into_iter()
+            .zip(secrets)
+            .enumerate()
+            .map(|(i, ((commitments, nonces), (bit, k1, k2, sim_c, sim_z1, sim_z2)))| {
+                let real_c = c ^ sim_c;
+                let z1 = k1 - C1::ScalarField::from(real_c) * r[i];
+                let z2 = k2 - C2::ScalarField::from(real_c) * s[i];
+
+                let (challenge, responses1, responses2) = if bit {
+                    (sim_c, (sim_z1, z1), (sim_z2, z2))
+                } else {
+                    (real_c, (z1, sim_z1), (z2, sim_z2))
+                };
+                BitProof { commitments, nonces, challenge: split(challenge), responses1, responses2 }
+            })
+            .collect();
+
+        Ok(CrossGroupProof { bits })
+    }
+
+    // Function for verifying a proof that the public keys in statement share the secret.
+    pub fn verify(&self, statement: &(C1, C2), proof: &CrossGroupProof<C1, C2>) -> Result<(), NIZKError> {
+        if proof.bits.len() != Self::bits() {
+            return Err(NIZKError::CrossGroupVerify);
+        }
+
+        let first_moves = proof.bits.iter().map(|bit| (bit.commitments, bit.nonces)).collect::<Vec<_>>();
+        let c = self.challenge(statement, &first_moves)?;
+
+        let (mut sum_c, mut sum_d) = (C1::Projective::zero(), C2::Projective::zero());
+        let (mut pow1, mut pow2) = (C1::ScalarField::one(), C2::ScalarField::one());
+
+        for bit in proof.bits.iter() {
+            let (c_i, d_i) = bit.commitments;
+            let c0 = join(bit.challenge);
+            let branches = [
+                (c_i.into_projective(), d_i.into_projective(), c0, bit.nonces.0, bit.responses1.0, bit.responses2.0),
+                (c_i.into_projective() - self.g1.into_projective(), d_i.into_projective() - self.g2.into_projective(),
+                 c ^ c0, bit.nonces.1, bit.responses1.1, bit.responses2.1),
+            ];
+
+            // Each branch must satisfy z * h + c_j * P_j == A_j in both groups.
+            for (p1, p2, c_j, (a1, a2), z1, z2) in branches.iter() {
+                let lhs1 = self.h1.mul(z1.into_repr()) + p1.into_affine().mul(C1::ScalarField::from(*c_j).into_repr());
+                let lhs2 = self.h2.mul(z2.into_repr()) + p2.into_affine().mul(C2::ScalarField::from(*c_j).into_repr());
+                if lhs1.into_affine() != *a1 || lhs2.into_affine() != *a2 {
+                    return Err(NIZKError::CrossGroupVerify);
+                }
+            }
+
+            sum_c += &c_i.mul(pow1.into_repr());
+            sum_d += &d_i.mul(pow2.into_repr());
+            pow1.double_in_place();
+            pow2.double_in_place();
+        }
+
+        // The bits must recompose to the secrets of both public keys.
+        if sum_c.into_affine() != statement.0 || sum_d.into_affine() != statement.1 {
+            return Err(NIZKError::CrossGroupVerify);
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Fr, G1Affine, G2Affine};
+    use ark_ec::AffineCurve;
+    use ark_ff::One;
+
+    use crate::nizk::cross::CrossGroupDLEQ;
+    use crate::nizk::utils::errors::NIZKError;
+    use crate::signature::utils::tests::check_serialization;
+
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test_cross_group_dleq() {
+        let rng = &mut thread_rng();
+        let system = CrossGroupDLEQ::<G1Affine, G2Affine>::new(
+            G1Affine::prime_subgroup_generator(),
+            G2Affine::prime_subgroup_generator(),
+        ).unwrap();
+
+        // A secret of 192 bits.
+        let x = Fr::from(rng.gen::<u128>()) * Fr::from(rng.gen::<u64>());
+        let statement = system.statement(&x);
+        let proof = system.prove(rng, &x).unwrap();
+        system.verify(&statement, &proof).unwrap();
+        check_serialization(proof.clone());
+
+        // Keys of different secrets are rejected.
+        let other = system.statement(&(x + Fr::from(1u64)));
+        assert!(system.verify(&(statement.0, other.1), &proof).is_err());
+        assert!(system.verify(&(other.0, statement.1), &proof).is_err());
+
+        // Secrets must fit in bits() bits.
+        assert!(matches!(system.prove(rng, &-Fr::one()), Err(NIZKError::WitnessTooLarge(254))));
+    }
+}
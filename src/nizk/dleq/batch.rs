@@ -0,0 +1,149 @@
+use crate::nizk::{scheme::NIZKProof,
+		  utils::{errors::NIZKError, hash::hash_to_field},
+		  dleq::{DLEQProof, srs::SRS}};
+
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
+
+use rand::{CryptoRng, Rng};
+
+const PERSONALIZATION: &[u8] = b"DLEQBTCH";   // persona for the batching coefficients
+
+/* Batch DLEQ proofs. For a witness w, a generator g and bases h_1, ..., h_m, a single
+   proof shows that x = g^w and y_i = h_i^w for all i. The statements are folded with
+   coefficients rho_i derived by hashing all bases and statements:
+
+       M = sum_i rho_i * h_i,    Z = sum_i rho_i * y_i
+
+   and a plain DLEQ proof is given for (g, x) and (M, Z). If some y_i != h_i^w, then
+   Z != M^w except with probability about 1/|F| over the coefficients, so the proof
+   costs the same as a single DLEQ proof plus two multi-scalar multiplications.
+*/
+
+// BatchDLEQProof type holds the generator and the bases the batch is proven for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchDLEQProof<C1, C2>
+where
+    C1: AffineCurve + CanonicalSerialize + CanonicalDeserialize,
+    C2: AffineCurve<ScalarField = C1::ScalarField> + CanonicalSerialize + CanonicalDeserialize,
+{
+    pub g_public_key: C1,         // generator of the first group
+    pub h_public_keys: Vec<C2>,   // bases of the second group
+}
+
+// A batch statement: x = g^w and y_i = h_i^w for all i.
+pub type BatchStatement<C1, C2> = (C1, Vec<C2>);
+
+// Batch proofs are single DLEQ proofs for the folded statement.
+pub type BatchProof<C1, C2> = <DLEQProof<C1, C2> as NIZKProof>::Proof;
+
+// A batch statement together with its proof.
+pub type ProvenBatch<C1, C2> = (BatchStatement<C1, C2>, BatchProof<C1, C2>);
+
+// A folded batch: the DLEQ instance over (g, M) and its statement (x, Z).
+type FoldedBatch<C1, C2> = (DLEQProof<C1, C2>, (C1, C2));
+
+impl<C1, C2> BatchDLEQProof<C1, C2>
+where
+    C1: AffineCurve + CanonicalSerialize + CanonicalDeserialize,
+    C2: AffineCurve<ScalarField = C1::ScalarField> + CanonicalSerialize + CanonicalDeserialize,
+{
+
+    // Function for creating a batch over generator g and the given bases.
+    pub fn new(g_public_key: C1, h_public_keys: Vec<C2>) -> Self {
+        Self { g_public_key, h_public_keys }
+    }
+
+    // Computes the batch statement for a given witness.
+    pub fn from_witness(&self, w: &C1::ScalarField) -> BatchStatement<C1, C2> {
+        let w = w.into_repr();
+        let ys = self.h_public_keys.iter().map(|h| h.mul(w)).collect::<Vec<_>>();
+        (self.g_public_key.mul(w).into_affine(), C2::Projective::batch_normalization_into_affine(&ys))
+    }
+
+    // Utility method folding the batch statement into a single DLEQ instance and statement.
+    fn fold(&self, statement: &BatchStatement<C1, C2>) -> Result<FoldedBatch<C1, C2>, NIZKError> {
+        let (x, ys) = statement;
+        if self.h_public_keys.is_empty() || ys.len() != self.h_public_keys.len() {
+            return Err(NIZKError::BatchLengthMismatch(self.h_public_keys.len(), ys.len()));
+        }
+
+        // Derive the coefficients from everything the prover committed to.
+        let mut bytes = vec![];
+        self.g_public_key.serialize(&mut bytes)?;
+        x.serialize(&mut bytes)?;
+        self.h_public_keys.serialize(&mut bytes)?;
+        ys.serialize(&mut bytes)?;
+
+        let rhos = (0..ys.len())
+            .map(|i| hash_to_field::<C1::ScalarField>(PERSONALIZATION, &[&bytes[..], &(i as u64).to_be_bytes()].concat())
+                .map(|rho| rho.into_repr()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let m = VariableBaseMSM::multi_scalar_mul(&self.h_public_keys, &rhos).into_affine();
+        let z = VariableBaseMSM::multi_scalar_mul(ys, &rhos).into_affine();
+
+        let dleq = DLEQProof { srs: SRS { g_public_key: self.g_public_key, h_public_key: m } };
+        Ok((dleq, (*x, z)))
+    }
+
+    // Function for proving the batch statement of witness w, which is returned along
+    // with the proof.
+    pub fn prove_batch<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        w: &C1::ScalarField,
+    ) -> Result<ProvenBatch<C1, C2>, NIZKError> {
+        let statement = self.from_witness(w);
+        let (dleq, _) = self.fold(&statement)?;
+        let proof = dleq.prove(rng, w)?;
+        Ok((statement, proof))
+    }
+
+    // Function for verifying a batch proof against a batch statement.
+    pub fn verify_batch(
+        &self,
+        statement: &BatchStatement<C1, C2>,
+        proof: &BatchProof<C1, C2>,
+    ) -> Result<(), NIZKError> {
+        let (dleq, folded) = self.fold(statement)?;
+        dleq.verify(&folded, proof)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Fr, G1Affine, G1Projective};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::UniformRand;
+
+    use crate::nizk::dleq::batch::BatchDLEQProof;
+    use crate::nizk::utils::errors::NIZKError;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_batch_dleq() {
+        let rng = &mut thread_rng();
+        let g = G1Projective::rand(rng).into_affine();
+        let hs = (0..5).map(|_| G1Projective::rand(rng).into_affine()).collect::<Vec<_>>();
+        let batch = BatchDLEQProof::<G1Affine, G1Affine>::new(g, hs);
+
+        let w = Fr::rand(rng);
+        let (statement, proof) = batch.prove_batch(rng, &w).unwrap();
+        batch.verify_batch(&statement, &proof).unwrap();
+
+        // a single wrong statement invalidates the batch
+        let mut wrong = statement.clone();
+        wrong.1[3] = G1Projective::rand(rng).into_affine();
+        assert!(batch.verify_batch(&wrong, &proof).is_err());
+
+        // so does a statement of the wrong length
+        wrong.1.truncate(4);
+        assert!(matches!(batch.verify_batch(&wrong, &proof), Err(NIZKError::BatchLengthMismatch(5, 4))));
+    }
+}
@@ -1,15 +1,18 @@
-use crate::nizk::{scheme::NIZKProof, 
+use crate::nizk::{scheme::{BatchVerifiableNIZK, NIZKProof}, 
 		  utils::{errors::NIZKError, hash::hash_to_field},
 		  dleq::srs::SRS};
+use crate::secrets::SecretScalar;
+use crate::transcript::Transcript;
 
-use ark_ec::{AffineCurve, ProjectiveCurve};
-use ark_ff::{PrimeField, UniformRand};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
 use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
 
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 use std::fmt::Debug;
 
 pub mod srs;
+pub mod batch;
 
 const PERSONALIZATION: &[u8] = b"DLEQNIZK";   // persona for the DLEQ NIZK proof system
 
@@ -44,7 +47,7 @@ where
     }
 
     // Generates a witness-statement pair using a specified RNG.
-    fn generate_pair<R: Rng>(
+    fn generate_pair<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
     ) -> Result<(Self::Witness, Self::Statement), NIZKError> {
@@ -61,7 +64,7 @@ where
     }
 
     // Function for generating a NIZK proof of discrete logarithm equality.
-    fn prove<R: Rng>(
+    fn prove<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         w: &Self::Witness,
@@ -73,14 +76,14 @@ where
 	// Compute the public key corresponding to generator h of the second group
 	let h_w = self.srs.h_public_key.mul(w.into_repr()).into_affine();
 
-        // Sample a random nonce
-        let r = Self::Witness::rand(rng);
+        // Sample a random nonce (wiped on drop)
+        let r = SecretScalar::new(Self::Witness::rand(rng));
 
         // Compute commitment to nonce as: g_r := r * g
-        let g_r = self.srs.g_public_key.mul(r.into_repr()).into_affine();
+        let g_r = self.srs.g_public_key.mul(r.expose().into_repr()).into_affine();
 
 	// Compute commitment to nonce as: h_r := r * h
-        let h_r = self.srs.h_public_key.mul(r.into_repr()).into_affine();
+        let h_r = self.srs.h_public_key.mul(r.expose().into_repr()).into_affine();
 
         // serialize g_r into writer g_r_bytes
         let mut g_r_bytes = vec![];
@@ -112,7 +115,7 @@ where
         )?;
 
         // Compute the "response" part of the proof
-        let z = r - &(*w * &hashed_message);
+        let z = *r.expose() - (*w * hashed_message);
 
         // Form and return the result
 	let proof = ((g_r, h_r), hashed_message, z);
@@ -126,8 +129,148 @@ where
         proof: &Self::Proof,
     ) -> Result<(), NIZKError> {
 
-	// parse nonce commitments from the supplied proof
-	let (g_r, h_r) = proof.0;
+	// ensure the challenge corresponding to what was provided matches the
+	// supplied challenge
+        if self.challenge(statement, &proof.0)? != proof.1 {
+            return Err(NIZKError::DLEQVerify);
+        }
+
+        self.verify_transcript(statement, proof)
+    }
+}
+
+
+// DLEQProof implements the BatchVerifiableNIZK trait.
+impl<C1, C2> BatchVerifiableNIZK for DLEQProof<C1, C2>
+where
+    C1: AffineCurve + CanonicalSerialize + CanonicalDeserialize,
+    C2: AffineCurve<ScalarField = C1::ScalarField> + CanonicalSerialize + CanonicalDeserialize,
+{
+
+    // Function for verifying many NIZK proofs of discrete logarithm equality (under
+    // the same SRS) at once. Every challenge is recomputed individually, whereas the
+    // verification conditions of each group are combined with powers of a random
+    // alpha, i.e., for the first group
+    //     g*(sum_i alpha^i*z_i) + sum_i (alpha^i*c_i)*g_w_i - sum_i alpha^i*g_r_i == 0,
+    // which costs one MSM per group for the whole batch.
+    fn verify_batch<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        statements: &[Self::Statement],
+        proofs: &[Self::Proof],
+    ) -> Result<(), NIZKError> {
+        if statements.len() != proofs.len() {
+            return Err(NIZKError::BatchSizeMismatch(statements.len(), proofs.len()));
+        }
+
+        let alpha = C1::ScalarField::rand(rng);
+        let mut current_alpha = C1::ScalarField::one();
+        let mut z_total = C1::ScalarField::zero();
+
+        let mut bases_g = vec![];
+        let mut bases_h = vec![];
+        let mut scalars = vec![];
+
+        for (statement, proof) in statements.iter().zip(proofs.iter()) {
+            // ensure the computed challenge matches the supplied challenge
+            if self.challenge(statement, &proof.0)? != proof.1 {
+                return Err(NIZKError::DLEQVerify);
+            }
+
+            z_total += proof.2 * current_alpha;
+
+            bases_g.push(statement.0);
+            bases_h.push(statement.1);
+            scalars.push((proof.1 * current_alpha).into_repr());
+
+            bases_g.push((proof.0).0);
+            bases_h.push((proof.0).1);
+            scalars.push((-current_alpha).into_repr());
+
+            current_alpha *= &alpha;
+        }
+
+        bases_g.push(self.srs.g_public_key);
+        bases_h.push(self.srs.h_public_key);
+        scalars.push(z_total.into_repr());
+
+        if !VariableBaseMSM::multi_scalar_mul(&bases_g, &scalars).is_zero()
+            || !VariableBaseMSM::multi_scalar_mul(&bases_h, &scalars).is_zero() {
+            return Err(NIZKError::DLEQVerify);
+        }
+
+        Ok(())
+    }
+}
+
+
+impl<C1, C2> DLEQProof<C1, C2>
+where
+    C1: AffineCurve + CanonicalSerialize + CanonicalDeserialize,
+    C2: AffineCurve<ScalarField = C1::ScalarField> + CanonicalSerialize + CanonicalDeserialize,
+{
+
+    // Method for generating a NIZK proof of discrete logarithm equality whose
+    // Fiat-Shamir challenge is squeezed from the given transcript, after absorbing
+    // both generators, the statement and the nonce commitments. The proof only
+    // verifies against a transcript in the same state.
+    pub fn prove_in_transcript<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &mut Transcript,
+        w: &C1::ScalarField,
+    ) -> Result<<Self as NIZKProof>::Proof, NIZKError> {
+        let (_, statement) = self.from_witness(w)?;
+
+        // Sample a random nonce and commit to it in both groups
+        let r = SecretScalar::new(C1::ScalarField::rand(rng));
+        let g_r = self.srs.g_public_key.mul(r.expose().into_repr()).into_affine();
+        let h_r = self.srs.h_public_key.mul(r.expose().into_repr()).into_affine();
+
+        let c = self.transcript_challenge(transcript, &statement, &(g_r, h_r))?;
+        Ok(((g_r, h_r), c, *r.expose() - (*w * c)))
+    }
+
+    // Method for verifying a NIZK proof of discrete logarithm equality generated
+    // with prove_in_transcript, against a transcript in the same state.
+    pub fn verify_in_transcript(
+        &self,
+        transcript: &mut Transcript,
+        statement: &(C1, C2),
+        proof: &<Self as NIZKProof>::Proof,
+    ) -> Result<(), NIZKError> {
+        if self.transcript_challenge(transcript, statement, &proof.0)? != proof.1 {
+            return Err(NIZKError::DLEQVerify);
+        }
+
+        self.verify_transcript(statement, proof)
+    }
+
+    // Utility method absorbing a proof's public values into a transcript and
+    // squeezing its challenge.
+    fn transcript_challenge(
+        &self,
+        transcript: &mut Transcript,
+        statement: &(C1, C2),
+        nonce_commitments: &(C1, C2),
+    ) -> Result<C1::ScalarField, NIZKError> {
+        transcript.append(b"dleq-g", &self.srs.g_public_key)?;
+        transcript.append(b"dleq-h", &self.srs.h_public_key)?;
+        transcript.append(b"dleq-g-statement", &statement.0)?;
+        transcript.append(b"dleq-h-statement", &statement.1)?;
+        transcript.append(b"dleq-g-nonce", &nonce_commitments.0)?;
+        transcript.append(b"dleq-h-nonce", &nonce_commitments.1)?;
+        Ok(transcript.challenge_scalar(b"dleq-challenge"))
+    }
+
+    // Method computing the Fiat-Shamir challenge for a statement and the nonce
+    // commitments (g_r, h_r) of a proof.
+    fn challenge(
+        &self,
+        statement: &(C1, C2),
+        nonce_commitments: &(C1, C2),
+    ) -> Result<C1::ScalarField, NIZKError> {
+	let (g_r, h_r) = nonce_commitments;
 
 	// serialize g_w into g_w_bytes
 	let mut g_w_bytes = vec![];
@@ -153,10 +296,19 @@ where
 	let mut h_r_bytes = vec![];
         h_r.serialize(&mut h_r_bytes)?;
 
-	// compute the challenge corresponding to what was provided
-        let hashed_message = hash_to_field::<Self::Challenge>(
+        hash_to_field::<C1::ScalarField>(
             PERSONALIZATION, &[&g_bytes[..], &g_w_bytes, &h_bytes, &h_w_bytes, &g_r_bytes, &h_r_bytes].concat()
-        )?;
+        )
+    }
+
+    // Method for checking the verification conditions of the underlying sigma
+    // protocol, without recomputing the Fiat-Shamir challenge.
+    pub fn verify_transcript(
+        &self,
+        statement: &(C1, C2),
+        proof: &<Self as NIZKProof>::Proof,
+    ) -> Result<(), NIZKError> {
+	let ((g_r, h_r), c, z) = proof;
 
 	/* By construction, the verification conditions are:
 	 * g*z + (g*w)*c == g*r
@@ -164,29 +316,41 @@ where
 	 */
 
 	// compute LHS of the first verification condition
-	let lhs1 = (self.srs.g_public_key.mul(proof.2.into_repr())
-            + &statement.0.mul(hashed_message.into_repr()))
+	let lhs1 = (self.srs.g_public_key.mul(z.into_repr())
+            + statement.0.mul(c.into_repr()))
             .into_affine();
 
-	// compute RHS of the first verification condition
-	let rhs1 = g_r;
-
 	// compute LHS of the second verification condition
-	let lhs2 = (self.srs.h_public_key.mul(proof.2.into_repr())
-            + &statement.1.mul(hashed_message.into_repr()))
+	let lhs2 = (self.srs.h_public_key.mul(z.into_repr())
+            + statement.1.mul(c.into_repr()))
             .into_affine();
 
-	// compute RHS of the second verification condition
-	let rhs2 = h_r;
-
-	// Compare LHSs against their respective RHSs and ensure
-	// the computed challenge matches the supplied challenge
-        if lhs1 != rhs1 || lhs2 != rhs2 || hashed_message != proof.1 {
+	// Compare LHSs against their respective RHSs
+        if lhs1 != *g_r || lhs2 != *h_r {
             return Err(NIZKError::DLEQVerify);
         }
 
         Ok(())
     }
+
+    // Method for simulating a proof for a statement without knowing its witness
+    // (which need not even exist): the challenge and response are sampled uniformly
+    // and both nonce commitments are solved for. As for DLKProof::simulate, the
+    // result passes verify_transcript, and passes verify only where the random
+    // oracle can be programmed.
+    pub fn simulate<R: Rng + CryptoRng>(
+        &self,
+        statement: &(C1, C2),
+        rng: &mut R,
+    ) -> <Self as NIZKProof>::Proof {
+        let c = C1::ScalarField::rand(rng);
+        let z = C1::ScalarField::rand(rng);
+
+        let g_r = (self.srs.g_public_key.mul(z.into_repr()) + statement.0.mul(c.into_repr())).into_affine();
+        let h_r = (self.srs.h_public_key.mul(z.into_repr()) + statement.1.mul(c.into_repr())).into_affine();
+
+        ((g_r, h_r), c, z)
+    }
 }
 
 
@@ -197,8 +361,9 @@ mod test {
     use ark_ec::{AffineCurve, ProjectiveCurve};
 
     use crate::signature::{utils::tests::check_serialization};
-    use crate::nizk::scheme::NIZKProof;
+    use crate::nizk::scheme::{BatchVerifiableNIZK, NIZKProof};
     use crate::nizk::dleq::{DLEQProof, srs::SRS};
+    use crate::transcript::Transcript;
 
     use rand::thread_rng;
     use ark_ff::{PrimeField, UniformRand};
@@ -208,6 +373,64 @@ mod test {
         test_simple_nizk::<G1Affine, G1Affine>();
     }
 
+    #[test]
+    fn test_simulated_nizk() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine, G2Affine>::setup(rng).unwrap();
+        let dleq = DLEQProof { srs };
+        let (w, statement) = dleq.generate_pair(rng).unwrap();
+
+        dleq.verify_transcript(&statement, &dleq.prove(rng, &w).unwrap()).unwrap();
+        let simulated = dleq.simulate(&statement, rng);
+        dleq.verify_transcript(&statement, &simulated).unwrap();
+        assert!(dleq.verify(&statement, &simulated).is_err());
+
+        // Simulation works even for false statements, which is what makes it
+        // usable for the simulated branches of OR-proofs.
+        let (_, other) = dleq.generate_pair(rng).unwrap();
+        let false_statement = (statement.0, other.1);
+        let simulated = dleq.simulate(&false_statement, rng);
+        dleq.verify_transcript(&false_statement, &simulated).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_nizk() {
+        let rng = &mut thread_rng();
+        let dleq = DLEQProof { srs: SRS::<G1Affine, G2Affine>::setup(rng).unwrap() };
+        let (w, statement) = dleq.generate_pair(rng).unwrap();
+
+        let proof = dleq.prove_in_transcript(rng, &mut Transcript::new(b"test"), &w).unwrap();
+        dleq.verify_in_transcript(&mut Transcript::new(b"test"), &statement, &proof).unwrap();
+
+        // Transcript-bound proofs are not plain proofs, and vice versa.
+        assert!(dleq.verify(&statement, &proof).is_err());
+        let plain = dleq.prove(rng, &w).unwrap();
+        assert!(dleq.verify_in_transcript(&mut Transcript::new(b"test"), &statement, &plain).is_err());
+    }
+
+    #[test]
+    fn test_batch_nizk() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine, G2Affine>::setup(rng).unwrap();
+        let dleq = DLEQProof { srs };
+
+        let (statements, proofs): (Vec<_>, Vec<_>) = (0..8)
+            .map(|_| {
+                let (w, statement) = dleq.generate_pair(rng).unwrap();
+                (statement, dleq.prove(rng, &w).unwrap())
+            })
+            .unzip();
+        dleq.verify_batch(rng, &statements, &proofs).unwrap();
+
+        // A proof for a false statement spoils the batch, even if only one of its
+        // halves is off.
+        let mut bad = statements.clone();
+        let (_, other) = dleq.generate_pair(rng).unwrap();
+        bad[5] = (bad[5].0, other.1);
+        assert!(dleq.verify_batch(rng, &bad, &proofs).is_err());
+        assert!(dleq.verify_batch(rng, &statements, &proofs[1..]).is_err());
+    }
+
     #[test]
     fn test_simple_nizk_g1_g2() {
         test_simple_nizk::<G1Affine, G2Affine>();
@@ -436,7 +659,7 @@ mod test {
         let proof = dleq.prove(rng, &pair.0).unwrap();
 
         check_serialization(srs.clone());
-        check_serialization(pair.clone());
-        check_serialization(proof.clone());
+        check_serialization(pair);
+        check_serialization(proof);
     }
 }
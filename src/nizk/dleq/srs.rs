@@ -2,7 +2,7 @@ use crate::nizk::utils::errors::NIZKError;
 use ark_ec::AffineCurve;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize, Clone, PartialEq)]
 pub struct SRS<C1, C2>
@@ -21,7 +21,7 @@ where
 {
 
     // Function setup samples the SRS generators
-    pub fn setup<R: Rng>(_: &mut R) -> Result<Self, NIZKError> {
+    pub fn setup<R: Rng + CryptoRng>(_: &mut R) -> Result<Self, NIZKError> {
         let srs = Self {
             g_public_key: C1::prime_subgroup_generator(),
 	    h_public_key: C2::prime_subgroup_generator(),
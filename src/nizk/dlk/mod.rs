@@ -1,12 +1,14 @@
 //use crate::signature::schnorr::srs::SRS;   // Same SRS as Schnorr's signature scheme
 use super::dlk::srs::SRS;
-use crate::nizk::{scheme::NIZKProof, utils::{errors::NIZKError, hash::hash_to_field}};
+use crate::nizk::{scheme::{BatchVerifiableNIZK, NIZKProof}, utils::{errors::NIZKError, hash::hash_to_field}};
+use crate::secrets::SecretScalar;
+use crate::transcript::Transcript;
 
-use ark_ec::{ProjectiveCurve, AffineCurve};
-use ark_ff::{PrimeField, UniformRand};
+use ark_ec::{msm::VariableBaseMSM, ProjectiveCurve, AffineCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
 
 use std::fmt::Debug;
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 
 pub mod srs;
 
@@ -35,7 +37,7 @@ impl<C: AffineCurve> NIZKProof for DLKProof<C> {
     }
 
     // Generates a witness, statement pair using a specified RNG.
-    fn generate_pair<R: Rng>(
+    fn generate_pair<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
     ) -> Result<(Self::Witness, Self::Statement), NIZKError> {
@@ -52,17 +54,56 @@ impl<C: AffineCurve> NIZKProof for DLKProof<C> {
     }
 
     // Function for generating a NIZKPoK of discrete logarithm.
-    fn prove<R: Rng>(
+    fn prove<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         w: &Self::Witness,
     ) -> Result<Self::Proof, NIZKError> {
+        self.prove_with_context(rng, w, &[])
+    }
 
-        // Sample a random nonce
-        let r = Self::Witness::rand(rng);
+    // Function for verifying a NIZKPoK of discrete logarithm.
+    fn verify(
+        &self,
+        stmnt: &Self::Statement,
+        proof: &Self::Proof,
+    ) -> Result<(), NIZKError> {
+        self.verify_with_context(stmnt, proof, &[])
+    }
+}
+
+// DLKProof implements the BatchVerifiableNIZK trait.
+impl<C: AffineCurve> BatchVerifiableNIZK for DLKProof<C> {
+
+    // Function for verifying many NIZKPoKs of discrete logarithm at once.
+    fn verify_batch<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        statements: &[Self::Statement],
+        proofs: &[Self::Proof],
+    ) -> Result<(), NIZKError> {
+        self.verify_batch_with_context(rng, statements, proofs, &vec![&[][..]; proofs.len()])
+    }
+}
+
+
+impl<C: AffineCurve> DLKProof<C> {
+
+    // Method for generating a NIZKPoK of discrete logarithm whose Fiat-Shamir
+    // challenge also commits to the given context, so that the proof only verifies
+    // along with that same context. An empty context yields a plain proof.
+    pub fn prove_with_context<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        w: &C::ScalarField,
+        context: &[u8],
+    ) -> Result<<Self as NIZKProof>::Proof, NIZKError> {
+
+        // Sample a random nonce (wiped on drop)
+        let r = SecretScalar::new(C::ScalarField::rand(rng));
 
         // Compute commitment to nonce as: g_r := r * g
-        let g_r = self.srs.g_public_key.mul(r.into_repr()).into_affine();
+        let g_r = self.srs.g_public_key.mul(r.expose().into_repr()).into_affine();
         
         // serialize g into writer g_bytes
         let mut g_bytes = vec![];
@@ -73,23 +114,25 @@ impl<C: AffineCurve> NIZKProof for DLKProof<C> {
         g_r.serialize(&mut g_r_bytes)?;
 
         // Compute the "challenge" part of the proof
-        let hashed_message = hash_to_field::<Self::Challenge>(
-            PERSONALIZATION, &[&g_bytes[..], &g_r_bytes].concat()
+        let hashed_message = hash_to_field::<C::ScalarField>(
+            PERSONALIZATION, &[&g_bytes[..], &g_r_bytes, context].concat()
         )?;
 
         // Compute the "response" part of the proof
-        let z = r - &(*w * &hashed_message);
+        let z = *r.expose() - (*w * hashed_message);
 
         // Form and return the result
 	let proof = (g_r, hashed_message, z);
         Ok(proof)
     }
 
-    // Function for verifying a NIZKPoK of discrete logarithm.
-    fn verify(
+    // Method for verifying a NIZKPoK of discrete logarithm generated with
+    // prove_with_context, under the given context.
+    pub fn verify_with_context(
         &self,
-        stmnt: &Self::Statement,
-        proof: &Self::Proof,
+        stmnt: &C,
+        proof: &<Self as NIZKProof>::Proof,
+        context: &[u8],
     ) -> Result<(), NIZKError> {
 
         // serialize g into writer g_bytes
@@ -101,23 +144,168 @@ impl<C: AffineCurve> NIZKProof for DLKProof<C> {
         proof.0.serialize(&mut g_r_bytes)?;
 
 	// compute the challenge corresponding to what was provided
-        let hashed_message = hash_to_field::<Self::Challenge>(
-            PERSONALIZATION, &[&g_bytes[..], &g_r_bytes].concat()
+        let hashed_message = hash_to_field::<C::ScalarField>(
+            PERSONALIZATION, &[&g_bytes[..], &g_r_bytes, context].concat()
         )?;
 
-	// compute LHS of the verification condition
+	// ensure the computed challenge matches the supplied challenge
+        if hashed_message != proof.1 {
+            return Err(NIZKError::DLKVerify);
+        }
+
+        self.verify_transcript(stmnt, proof)
+    }
+
+    // Method for generating a NIZKPoK of discrete logarithm whose Fiat-Shamir
+    // challenge is squeezed from the given transcript, after absorbing the
+    // generator, the statement and the nonce commitment. The proof only verifies
+    // against a transcript in the same state (e.g., bound to the same session).
+    pub fn prove_in_transcript<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &mut Transcript,
+        w: &C::ScalarField,
+    ) -> Result<<Self as NIZKProof>::Proof, NIZKError> {
+        let stmnt = self.srs.g_public_key.mul(w.into_repr()).into_affine();
+
+        // Sample a random nonce and commit to it
+        let r = SecretScalar::new(C::ScalarField::rand(rng));
+        let g_r = self.srs.g_public_key.mul(r.expose().into_repr()).into_affine();
+
+        let c = self.transcript_challenge(transcript, &stmnt, &g_r)?;
+        Ok((g_r, c, *r.expose() - (*w * c)))
+    }
+
+    // Method for verifying a NIZKPoK of discrete logarithm generated with
+    // prove_in_transcript, against a transcript in the same state.
+    pub fn verify_in_transcript(
+        &self,
+        transcript: &mut Transcript,
+        stmnt: &C,
+        proof: &<Self as NIZKProof>::Proof,
+    ) -> Result<(), NIZKError> {
+        if self.transcript_challenge(transcript, stmnt, &proof.0)? != proof.1 {
+            return Err(NIZKError::DLKVerify);
+        }
+
+        self.verify_transcript(stmnt, proof)
+    }
+
+    // Utility method absorbing a proof's public values into a transcript and
+    // squeezing its challenge.
+    fn transcript_challenge(
+        &self,
+        transcript: &mut Transcript,
+        stmnt: &C,
+        g_r: &C,
+    ) -> Result<C::ScalarField, NIZKError> {
+        transcript.append(b"dlk-generator", &self.srs.g_public_key)?;
+        transcript.append(b"dlk-statement", stmnt)?;
+        transcript.append(b"dlk-nonce", g_r)?;
+        Ok(transcript.challenge_scalar(b"dlk-challenge"))
+    }
+
+    // Method for batch verifying NIZKPoKs of discrete logarithm generated with
+    // prove_with_context, each under its own context. Every challenge is recomputed
+    // individually, whereas the verification conditions are combined with powers
+    // of a random alpha into the single check
+    //     g*(sum_i alpha^i*z_i) + sum_i (alpha^i*c_i)*stmnt_i - sum_i alpha^i*g_r_i == 0,
+    // which costs one MSM for the whole batch.
+    pub fn verify_batch_with_context<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        statements: &[C],
+        proofs: &[<Self as NIZKProof>::Proof],
+        contexts: &[&[u8]],
+    ) -> Result<(), NIZKError> {
+        if statements.len() != proofs.len() {
+            return Err(NIZKError::BatchSizeMismatch(statements.len(), proofs.len()));
+        }
+        if contexts.len() != proofs.len() {
+            return Err(NIZKError::BatchSizeMismatch(contexts.len(), proofs.len()));
+        }
+
+        // serialize g into writer g_bytes
+        let mut g_bytes = vec![];
+        self.srs.g_public_key.serialize(&mut g_bytes)?;
+
+        let alpha = C::ScalarField::rand(rng);
+        let mut current_alpha = C::ScalarField::one();
+        let mut z_total = C::ScalarField::zero();
+
+        let mut bases = vec![];
+        let mut scalars = vec![];
+
+        for ((stmnt, proof), context) in statements.iter().zip(proofs.iter()).zip(contexts.iter()) {
+            // serialize g_r into writer g_r_bytes
+            let mut g_r_bytes = vec![];
+            proof.0.serialize(&mut g_r_bytes)?;
+
+            // ensure the computed challenge matches the supplied challenge
+            let hashed_message = hash_to_field::<C::ScalarField>(
+                PERSONALIZATION, &[&g_bytes[..], &g_r_bytes, context].concat()
+            )?;
+            if hashed_message != proof.1 {
+                return Err(NIZKError::DLKVerify);
+            }
+
+            z_total += proof.2 * current_alpha;
+
+            bases.push(*stmnt);
+            scalars.push((proof.1 * current_alpha).into_repr());
+
+            bases.push(proof.0);
+            scalars.push((-current_alpha).into_repr());
+
+            current_alpha *= &alpha;
+        }
+
+        bases.push(self.srs.g_public_key);
+        scalars.push(z_total.into_repr());
+
+        if !VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
+            return Err(NIZKError::DLKVerify);
+        }
+
+        Ok(())
+    }
+
+    // Method for checking the verification condition g*z + stmnt*c == g_r of the
+    // underlying sigma protocol, without recomputing the Fiat-Shamir challenge.
+    pub fn verify_transcript(
+        &self,
+        stmnt: &C,
+        proof: &<Self as NIZKProof>::Proof,
+    ) -> Result<(), NIZKError> {
 	let check = (self.srs.g_public_key.mul(proof.2.into_repr())
-            + &stmnt.mul(hashed_message.into_repr()))
+            + stmnt.mul(proof.1.into_repr()))
             .into_affine();
 
-	// Compare LHS against RHS as per the verification condition and ensure
-	// the computed challenge matches the supplied challenge
-        if check != proof.0 || hashed_message != proof.1 {
+        if check != proof.0 {
             return Err(NIZKError::DLKVerify);
         }
 
         Ok(())
     }
+
+    // Method for simulating a proof for a statement without knowing its witness:
+    // the challenge and response are sampled uniformly and the nonce commitment is
+    // solved for. Simulated proofs are distributed exactly as honest ones, and pass
+    // verify_transcript; they pass verify only where the random oracle can be
+    // programmed to return the sampled challenge (e.g., in security proofs or the
+    // simulated branches of OR-proofs).
+    pub fn simulate<R: Rng + CryptoRng>(
+        &self,
+        stmnt: &C,
+        rng: &mut R,
+    ) -> <Self as NIZKProof>::Proof {
+        let c = C::ScalarField::rand(rng);
+        let z = C::ScalarField::rand(rng);
+
+        let g_r = (self.srs.g_public_key.mul(z.into_repr()) + stmnt.mul(c.into_repr())).into_affine();
+
+        (g_r, c, z)
+    }
 }
 
 
@@ -127,7 +315,9 @@ impl<C: AffineCurve> NIZKProof for DLKProof<C> {
 #[cfg(test)]
 mod test {
     use crate::signature::{utils::tests::check_serialization};   // schnorr::srs::SRS
-    use crate::nizk::{dlk::{DLKProof, srs::SRS}, scheme::NIZKProof};
+    use crate::nizk::{dlk::{DLKProof, srs::SRS}, scheme::{BatchVerifiableNIZK, NIZKProof}};
+    use crate::transcript::{Session, Transcript};
+    use crate::Digest;
 
     use ark_ff::{PrimeField, UniformRand};
     use ark_bls12_381::{G1Affine, G2Affine};
@@ -140,6 +330,73 @@ mod test {
         test_simple_nizk::<G1Affine>();
     }
 
+    #[test]
+    fn test_simulated_nizk() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine>::setup(rng).unwrap();
+        let dlk = DLKProof { srs };
+        let (w, stmnt) = dlk.generate_pair(rng).unwrap();
+
+        // Honest and simulated transcripts satisfy the same sigma-protocol check...
+        dlk.verify_transcript(&stmnt, &dlk.prove(rng, &w).unwrap()).unwrap();
+        let simulated = dlk.simulate(&stmnt, rng);
+        dlk.verify_transcript(&stmnt, &simulated).unwrap();
+
+        // ...but without programming the random oracle, simulations do not verify,
+        // and they are tied to the simulated statement.
+        assert!(dlk.verify(&stmnt, &simulated).is_err());
+        let (_, other) = dlk.generate_pair(rng).unwrap();
+        assert!(dlk.verify_transcript(&other, &simulated).is_err());
+    }
+
+    #[test]
+    fn test_transcript_nizk() {
+        let rng = &mut thread_rng();
+        let dlk = DLKProof { srs: SRS::<G1Affine>::setup(rng).unwrap() };
+        let (w, stmnt) = dlk.generate_pair(rng).unwrap();
+        let session = Session { config_digest: Digest([0u8; 32]), epoch: 1, participant_id: 0 };
+
+        let proof = dlk.prove_in_transcript(rng, &mut Transcript::for_session(b"test", &session), &w).unwrap();
+        dlk.verify_in_transcript(&mut Transcript::for_session(b"test", &session), &stmnt, &proof).unwrap();
+
+        // The proof cannot be replayed in another session or protocol.
+        let replayed = Session { epoch: 2, ..session };
+        assert!(dlk.verify_in_transcript(&mut Transcript::for_session(b"test", &replayed), &stmnt, &proof).is_err());
+        assert!(dlk.verify_in_transcript(&mut Transcript::for_session(b"other", &session), &stmnt, &proof).is_err());
+    }
+
+    #[test]
+    fn test_batch_nizk() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine>::setup(rng).unwrap();
+        let dlk = DLKProof { srs };
+
+        let (statements, proofs): (Vec<_>, Vec<_>) = (0..8)
+            .map(|_| {
+                let (w, stmnt) = dlk.generate_pair(rng).unwrap();
+                (stmnt, dlk.prove(rng, &w).unwrap())
+            })
+            .unzip();
+        dlk.verify_batch(rng, &statements, &proofs).unwrap();
+        dlk.verify_batch(rng, &[], &[]).unwrap();
+
+        // A single bad proof, a swapped statement or a length mismatch fail the batch.
+        let mut bad = proofs.clone();
+        bad[3] = dlk.simulate(&statements[3], rng);
+        assert!(dlk.verify_batch(rng, &statements, &bad).is_err());
+
+        let mut swapped = statements.clone();
+        swapped.swap(0, 1);
+        assert!(dlk.verify_batch(rng, &swapped, &proofs).is_err());
+        assert!(dlk.verify_batch(rng, &statements[1..], &proofs).is_err());
+
+        // Contexts are honoured.
+        let (w, stmnt) = dlk.generate_pair(rng).unwrap();
+        let bound = dlk.prove_with_context(rng, &w, b"context").unwrap();
+        dlk.verify_batch_with_context(rng, &[stmnt], &[bound], &[&b"context"[..]]).unwrap();
+        assert!(dlk.verify_batch_with_context(rng, &[stmnt], &[bound], &[&b"other"[..]]).is_err());
+    }
+
     #[test]
     fn test_simple_nizk_g2() {
         test_simple_nizk::<G2Affine>();
@@ -294,8 +551,8 @@ mod test {
         let proof = dlk.prove(rng, &pair.0).unwrap();
 
         check_serialization(srs.clone());
-        check_serialization(pair.clone());
-        check_serialization(proof.clone());
+        check_serialization(pair);
+        check_serialization(proof);
     }
 
 }
@@ -1,4 +1,6 @@
 pub mod scheme;
 pub mod dlk;
 pub mod dleq;
+pub mod or;
+pub mod cross;
 pub mod utils;
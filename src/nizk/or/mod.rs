@@ -0,0 +1,245 @@
+use super::dlk::srs::SRS;
+use crate::nizk::{scheme::NIZKProof, utils::{errors::NIZKError, hash::hash_to_field}};
+use crate::secrets::SecretScalar;
+
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+use rand::{CryptoRng, Rng};
+use std::fmt::Debug;
+
+const PERSONALIZATION: &[u8] = b"DLKORZK";   // persona for the OR-composed DLK NIZK proof system
+
+/* OR-composition (Cramer-Damgard-Schoenmakers) of two DLK sigma protocols: a proof
+   of knowledge of the discrete log (w.r.t. the SRS generator g) of one of the two
+   points of the statement, without revealing which. The prover simulates the
+   branch it has no witness for (see DLKProof::simulate), runs the other branch
+   honestly, and splits the Fiat-Shamir challenge c = c_0 + c_1 between them.
+*/
+
+// DLKOrProof type wraps around the SRS and represents the scheme's
+// system-wide parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DLKOrProof<C: AffineCurve> {
+    pub srs: SRS<C>,   // same SRS as the DLK proof system
+}
+
+// Struct OrWitness models the prover's knowledge: the discrete log of the point at
+// position branch (0 or 1) of the statement, along with the other point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrWitness<C: AffineCurve> {
+    pub branch: usize,            // index of the statement point the secret belongs to
+    pub secret: C::ScalarField,   // discrete log of that point
+    pub other: C,                 // the other point of the statement
+}
+
+// Struct OrProof models the actual OR proof.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OrProof<C: AffineCurve> {
+    pub commitments: (C, C),                           // nonce commitments of both branches
+    pub challenges: (C::ScalarField, C::ScalarField),  // branch challenges, summing to the FS challenge
+    pub responses: (C::ScalarField, C::ScalarField),   // branch responses
+}
+
+
+impl<C: AffineCurve> DLKOrProof<C> {
+
+    // Utility method computing the Fiat-Shamir challenge of a proof.
+    fn challenge(&self, statement: &(C, C), commitments: &(C, C)) -> Result<C::ScalarField, NIZKError> {
+        let mut bytes = vec![];
+        self.srs.g_public_key.serialize(&mut bytes)?;
+        statement.serialize(&mut bytes)?;
+        commitments.serialize(&mut bytes)?;
+        hash_to_field::<C::ScalarField>(PERSONALIZATION, &bytes)
+    }
+
+    // Method for verifying many proofs at once. The Fiat-Shamir challenges are
+    // checked per proof, while the 2k verification conditions of the branches are
+    // combined with random weights into a single multi-scalar multiplication.
+    pub fn batch_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        statements: &[(C, C)],
+        proofs: &[OrProof<C>],
+    ) -> Result<(), NIZKError> {
+        if statements.len() != proofs.len() {
+            return Err(NIZKError::OrVerify);
+        }
+
+        // Every branch condition g*z + X*c - a == 0 gets weight rho.
+        let mut bases = vec![self.srs.g_public_key];
+        let mut scalars = vec![C::ScalarField::zero()];
+
+        for (statement, proof) in statements.iter().zip(proofs.iter()) {
+            let c = self.challenge(statement, &proof.commitments)?;
+            if proof.challenges.0 + proof.challenges.1 != c {
+                return Err(NIZKError::OrVerify);
+            }
+
+            let branches = [
+                (statement.0, proof.commitments.0, proof.challenges.0, proof.responses.0),
+                (statement.1, proof.commitments.1, proof.challenges.1, proof.responses.1),
+            ];
+            for (x, a, c, z) in branches.iter() {
+                let rho = C::ScalarField::rand(rng);
+                scalars[0] += &(rho * z);
+                bases.push(*x);
+                scalars.push(rho * c);
+                bases.push(*a);
+                scalars.push(-rho);
+            }
+        }
+
+        let scalars = scalars.iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+        if !VariableBaseMSM::multi_scalar_mul(&bases, &scalars).is_zero() {
+            return Err(NIZKError::OrVerify);
+        }
+
+        Ok(())
+    }
+}
+
+
+// DLKOrProof implements the NIZKProof trait.
+impl<C: AffineCurve> NIZKProof for DLKOrProof<C> {
+
+    type SRS = SRS<C>;                    // SRS is just a generator (i.e., an EC point)
+    type Witness = OrWitness<C>;          // witnesses are a secret for one of the two points
+    type Challenge = C::ScalarField;      // challenges are scalars from the field underlying C
+    type Statement = (C, C);              // public statements are pairs of elliptic curve points
+    type Proof = OrProof<C>;              // proof format: see OrProof
+
+    // Creates a DLKOrProof from a given SRS.
+    fn from_srs(srs: Self::SRS) -> Result<Self, NIZKError> {
+        Ok(Self { srs })
+    }
+
+    // Generates a witness, statement pair using a specified RNG, where the
+    // branch and the other point are random.
+    fn generate_pair<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::Witness, Self::Statement), NIZKError> {
+        let w = OrWitness {
+            branch: rng.gen_range(0, 2),
+            secret: C::ScalarField::rand(rng),
+            other: self.srs.g_public_key.mul(C::ScalarField::rand(rng).into_repr()).into_affine(),
+        };
+        self.from_witness(&w)
+    }
+
+    // Computes a witness, statement pair, given only the witness.
+    fn from_witness(
+        &self,
+        w: &Self::Witness,
+    ) -> Result<(Self::Witness, Self::Statement), NIZKError> {
+        let known = self.srs.g_public_key.mul(w.secret.into_repr()).into_affine();
+        let statement = match w.branch {
+            0 => (known, w.other),
+            1 => (w.other, known),
+            _ => return Err(NIZKError::OrVerify),
+        };
+        Ok((w.clone(), statement))
+    }
+
+    // Function for generating an OR proof of knowledge of one of two discrete logarithms.
+    fn prove<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        w: &Self::Witness,
+    ) -> Result<Self::Proof, NIZKError> {
+        let (_, statement) = self.from_witness(w)?;
+        let g = self.srs.g_public_key;
+
+        // Simulate the branch we know no secret for.
+        let c_sim = C::ScalarField::rand(rng);
+        let z_sim = C::ScalarField::rand(rng);
+        let x_sim = if w.branch == 0 { statement.1 } else { statement.0 };
+        let a_sim = (g.mul(z_sim.into_repr()) + x_sim.mul(c_sim.into_repr())).into_affine();
+
+        // Commit to a nonce for the branch we know the secret for.
+        let r = SecretScalar::new(C::ScalarField::rand(rng));
+        let a_real = g.mul(r.expose().into_repr()).into_affine();
+
+        let commitments = if w.branch == 0 { (a_real, a_sim) } else { (a_sim, a_real) };
+        let c = self.challenge(&statement, &commitments)?;
+
+        // Answer the remainder of the challenge honestly, as in DLKProof::prove.
+        let c_real = c - c_sim;
+        let z_real = *r.expose() - (w.secret * c_real);
+
+        let (challenges, responses) = if w.branch == 0 {
+            ((c_real, c_sim), (z_real, z_sim))
+        } else {
+            ((c_sim, c_real), (z_sim, z_real))
+        };
+
+        Ok(OrProof { commitments, challenges, responses })
+    }
+
+    // Function for verifying an OR proof of knowledge of one of two discrete logarithms.
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        proof: &Self::Proof,
+    ) -> Result<(), NIZKError> {
+        let c = self.challenge(statement, &proof.commitments)?;
+        if proof.challenges.0 + proof.challenges.1 != c {
+            return Err(NIZKError::OrVerify);
+        }
+
+        // Both branches must satisfy the DLK verification condition g*z + X*c == a.
+        let g = self.srs.g_public_key;
+        let check_0 = (g.mul(proof.responses.0.into_repr()) + statement.0.mul(proof.challenges.0.into_repr())).into_affine();
+        let check_1 = (g.mul(proof.responses.1.into_repr()) + statement.1.mul(proof.challenges.1.into_repr())).into_affine();
+
+        if check_0 != proof.commitments.0 || check_1 != proof.commitments.1 {
+            return Err(NIZKError::OrVerify);
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::signature::utils::tests::check_serialization;
+    use crate::nizk::{dlk::srs::SRS, or::DLKOrProof, scheme::NIZKProof};
+
+    use ark_bls12_381::G1Affine;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{PrimeField, UniformRand};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_or_proof() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine>::setup(rng).unwrap();
+        let or = DLKOrProof { srs };
+
+        let mut statements = vec![];
+        let mut proofs = vec![];
+        for _ in 0..6 {
+            let (w, statement) = or.generate_pair(rng).unwrap();
+            let proof = or.prove(rng, &w).unwrap();
+            or.verify(&statement, &proof).unwrap();
+            check_serialization(proof.clone());
+            statements.push(statement);
+            proofs.push(proof);
+        }
+        or.batch_verify(rng, &statements, &proofs).unwrap();
+
+        // A proof for one statement does not carry over to another, individually
+        // or within a batch.
+        let random_point = or.srs.g_public_key.mul(<G1Affine as AffineCurve>::ScalarField::rand(rng).into_repr()).into_affine();
+        statements[2].1 = random_point;
+        assert!(or.verify(&statements[2], &proofs[2]).is_err());
+        assert!(or.batch_verify(rng, &statements, &proofs).is_err());
+        assert!(or.batch_verify(rng, &statements[..3], &proofs).is_err());
+    }
+}
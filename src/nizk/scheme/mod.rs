@@ -1,6 +1,6 @@
 use crate::nizk::utils::errors::NIZKError;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 use std::fmt::Debug;
 
 // NIZK proof systems must implement the NIZKProof trait.
@@ -15,17 +15,19 @@ pub trait NIZKProof: Debug + Clone + PartialEq + Sized {
     fn from_srs(srs: Self::SRS) -> Result<Self, NIZKError>;
 
     // Method for generating a witness, statement pair by sampling an input RNG.
-    fn generate_pair<R: Rng>(
+    fn generate_pair<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
     ) -> Result<(Self::Witness, Self::Statement), NIZKError>;
 
-    // Method for computing a key pair, given only the secret key.
+    // Method for computing a key pair, given only the secret key. It takes self for
+    // the scheme's parameters, which clippy's naming convention does not expect.
+    #[allow(clippy::wrong_self_convention)]
     fn from_witness(&self, w: &Self::Witness)
         -> Result<(Self::Witness, Self::Statement), NIZKError>;
 
     // Method for creating a proof for a statement, using witness w, and a specified RNG.
-    fn prove<R: Rng>(
+    fn prove<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         w: &Self::Witness,
@@ -38,3 +40,18 @@ pub trait NIZKProof: Debug + Clone + PartialEq + Sized {
         proof: &Self::Proof,
     ) -> Result<(), NIZKError>;
 }
+
+// NIZK proof systems whose proofs can be verified in bulk (more cheaply than one
+// by one) must implement the BatchVerifiableNIZK trait.
+pub trait BatchVerifiableNIZK: NIZKProof {
+
+    // Method for verifying a slice of proofs against matching public statements,
+    // e.g., by checking a random linear combination of their verification
+    // conditions. Succeeds only if (with overwhelming probability) all proofs verify.
+    fn verify_batch<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        statements: &[Self::Statement],
+        proofs: &[Self::Proof],
+    ) -> Result<(), NIZKError>;
+}
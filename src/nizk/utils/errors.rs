@@ -13,6 +13,16 @@ pub enum NIZKError {
     DLKVerify,
     #[error("Failed verifying DLEQ proof")]
     DLEQVerify,
+    #[error("Failed verifying OR proof")]
+    OrVerify,
+    #[error("Batch has {0} bases but {1} statements")]
+    BatchLengthMismatch(usize, usize),
+    #[error("Batch has {0} statements but {1} proofs")]
+    BatchSizeMismatch(usize, usize),
+    #[error("Failed verifying cross-group DLEQ proof")]
+    CrossGroupVerify,
+    #[error("Witness does not fit in {0} bits")]
+    WitnessTooLarge(usize),
     #[error("SerializationError: {0}")]
     SerializationError(#[from] SerializationError),
 }
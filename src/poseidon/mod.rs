@@ -0,0 +1,229 @@
+use crate::modified_scrape::{errors::PVSSError, share::PVSSTranscript};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{GT, Scalar};
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+use blake2s_simd::Params;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+
+const PERSONALIZATION: &[u8] = b"OPTRPSDN";   // persona for deriving the round constants
+
+/* SNARK-friendly digest of a transcript's commitment vector and beacon output, so
+   that zk applications (e.g., Groth16 circuits over the scalar field of E) can
+   prove statements about beacon values without hashing non-algebraic data.
+
+   The digest is a Poseidon sponge (width 3, rate 2) over Scalar<E>. Inputs are the
+   uncompressed canonical encodings of the commitments and the beacon, packed into
+   field elements 31 bytes at a time, so that unpacking them in a circuit takes only
+   linear constraints. Round constants are derived from a fixed seed by ChaCha20
+   (rather than the Grain LFSR of the reference parameters) and the MDS matrix is
+   the Cauchy matrix 1 / (i + j + WIDTH). The S-box exponent is the smallest of
+   5, 7, 11, 13, 17 that is coprime to p - 1, with 8 full and 57 partial rounds.
+   A circuit gadget must use the same parameters (see PoseidonParameters::new).
+*/
+
+pub const WIDTH: usize = 3;            // state size
+pub const RATE: usize = 2;             // field elements absorbed per permutation
+pub const FULL_ROUNDS: usize = 8;      // rounds with a full S-box layer
+pub const PARTIAL_ROUNDS: usize = 57;  // rounds with a single S-box
+const PACKED_BYTES: usize = 31;        // bytes packed into each field element
+
+// Struct PoseidonParameters models the parameters of the Poseidon permutation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoseidonParameters<F: PrimeField> {
+    pub alpha: u64,                         // S-box exponent
+    pub mds: Vec<Vec<F>>,                   // WIDTH x WIDTH MDS matrix
+    pub round_constants: Vec<Vec<F>>,       // WIDTH constants per round
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+
+    // Function for deriving the (fixed) parameters for field F.
+    pub fn new() -> Self {
+        // The S-box x^alpha is a permutation iff gcd(alpha, p - 1) = 1.
+        let alpha = [5u64, 7, 11, 13, 17]
+            .iter()
+            .cloned()
+            .find(|a| modulus_mod(F::characteristic(), *a) != 1)
+            .expect("no suitable S-box exponent");
+
+        let mds = (0..WIDTH)
+            .map(|i| {
+                (0..WIDTH)
+                    .map(|j| F::from((i + j + WIDTH) as u64).inverse().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        let hash = Params::new()
+            .hash_length(32)
+            .personal(PERSONALIZATION)
+            .to_state()
+            .update(&[WIDTH as u8, FULL_ROUNDS as u8, PARTIAL_ROUNDS as u8])
+            .finalize();
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(hash.as_bytes());
+        let rng = &mut ChaChaRng::from_seed(seed);
+
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|_| (0..WIDTH).map(|_| F::rand(rng)).collect())
+            .collect();
+
+        Self { alpha, mds, round_constants }
+    }
+
+    // Method applying the Poseidon permutation to a state.
+    pub fn permute(&self, state: &mut [F; WIDTH]) {
+        let half = FULL_ROUNDS / 2;
+
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants.iter()) {
+                *s += c;
+            }
+
+            if round < half || round >= half + PARTIAL_ROUNDS {
+                for s in state.iter_mut() {
+                    *s = s.pow([self.alpha]);
+                }
+            } else {
+                state[0] = state[0].pow([self.alpha]);
+            }
+
+            let mut mixed = [F::zero(); WIDTH];
+            for (i, row) in self.mds.iter().enumerate() {
+                for (m, s) in row.iter().zip(state.iter()) {
+                    mixed[i] += *m * s;
+                }
+            }
+            *state = mixed;
+        }
+    }
+
+    // Method for hashing a sequence of field elements. The capacity element is
+    // initialized with the input length, so that inputs of different lengths
+    // (and thus zero-padding) cannot collide.
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut state = [F::zero(); WIDTH];
+        state[RATE] = F::from(inputs.len() as u64);
+
+        for chunk in inputs.chunks(RATE) {
+            for (s, x) in state.iter_mut().zip(chunk.iter()) {
+                *s += x;
+            }
+            self.permute(&mut state);
+        }
+        if inputs.is_empty() {
+            self.permute(&mut state);
+        }
+
+        state[0]
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonParameters<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Utility function computing p mod a for a modulus given as little-endian u64 limbs.
+fn modulus_mod(limbs: &[u64], a: u64) -> u64 {
+    limbs
+        .iter()
+        .rev()
+        .fold(0u128, |rem, limb| ((rem << 64) + *limb as u128) % a as u128) as u64
+}
+
+// Utility function for packing bytes into field elements, 31 bytes (little-endian) each.
+pub fn pack_bytes<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    bytes.chunks(PACKED_BYTES).map(F::from_le_bytes_mod_order).collect()
+}
+
+
+// Function computing the SNARK-friendly digest of a transcript's commitments and
+// the beacon value derived from it.
+pub fn commitments_digest<E, SSIG>(
+    transcript: &PVSSTranscript<E, SSIG>,
+    beacon: &GT<E>,
+) -> Result<Scalar<E>, PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    let mut bytes = vec![];
+    for comm in E::G2Projective::batch_normalization_into_affine(&transcript.pvss_share.comms) {
+        comm.serialize_uncompressed(&mut bytes)?;
+    }
+    let mut inputs = vec![Scalar::<E>::from(transcript.pvss_share.comms.len() as u64)];
+    inputs.extend(pack_bytes::<Scalar<E>>(&bytes));
+
+    let mut bytes = vec![];
+    beacon.serialize_uncompressed(&mut bytes)?;
+    inputs.extend(pack_bytes::<Scalar<E>>(&bytes));
+
+    Ok(PoseidonParameters::<Scalar<E>>::new().hash(&inputs))
+}
+
+// Function for checking a claimed digest of a transcript's commitments and beacon.
+pub fn verify_commitments_digest<E, SSIG>(
+    transcript: &PVSSTranscript<E, SSIG>,
+    beacon: &GT<E>,
+    digest: &Scalar<E>,
+) -> Result<(), PVSSError<E>>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    if commitments_digest(transcript, beacon)? != *digest {
+        return Err(PVSSError::CommitmentsDigestError);
+    }
+    Ok(())
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E, Fr};
+    use ark_ec::PairingEngine;
+    use ark_ff::{Field, UniformRand};
+
+    use crate::modified_scrape::{share::PVSSTranscript, tests::{setup_nodes, SSIG}};
+    use crate::poseidon::{commitments_digest, verify_commitments_digest, PoseidonParameters};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_poseidon_hash() {
+        let params = PoseidonParameters::<Fr>::new();
+        assert_eq!(params, PoseidonParameters::<Fr>::new());
+        assert_eq!(params.alpha, 5);
+
+        let (a, b) = (Fr::from(1u64), Fr::from(2u64));
+        assert_eq!(params.hash(&[a, b]), params.hash(&[a, b]));
+        assert_ne!(params.hash(&[a, b]), params.hash(&[b, a]));
+        assert_ne!(params.hash(&[a]), params.hash(&[a, Fr::from(0u64)]));
+    }
+
+    #[test]
+    fn test_commitments_digest() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let share = nodes[0].share(rng).unwrap();
+        let mut transcript = PVSSTranscript::<E, SSIG>::from_share(1, 4, &share);
+        let beacon = <E as PairingEngine>::Fqk::rand(rng);
+
+        let digest = commitments_digest(&transcript, &beacon).unwrap();
+        verify_commitments_digest(&transcript, &beacon, &digest).unwrap();
+        assert!(verify_commitments_digest(&transcript, &beacon.square(), &digest).is_err());
+
+        transcript.pvss_share.comms.swap(0, 1);
+        assert!(verify_commitments_digest(&transcript, &beacon, &digest).is_err());
+    }
+}
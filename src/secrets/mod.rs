@@ -0,0 +1,110 @@
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/* Wrappers for secret material (signing keys, sharing polynomials, proof nonces),
+   which wipe their contents from memory when dropped. The wrapped values are only
+   reachable through expose(), so that reads of secrets stand out in the code, and
+   they are redacted from Debug output.
+
+   Field elements are Copy: any copy taken out of a wrapper (e.g., to compute with
+   it) is not wiped, so copies should be short-lived locals.
+*/
+
+// Struct SecretScalar wraps a secret field element.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretScalar<F: Field>(F);
+
+impl<F: Field> SecretScalar<F> {
+
+    // Function for wrapping a secret scalar.
+    pub fn new(value: F) -> Self {
+        Self(value)
+    }
+
+    // Method exposing the wrapped scalar.
+    pub fn expose(&self) -> &F {
+        &self.0
+    }
+}
+
+impl<F: Field> fmt::Debug for SecretScalar<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretScalar(<redacted>)")
+    }
+}
+
+impl<F: Field> PartialEq for SecretScalar<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+// Struct SecretPolynomial wraps a secret polynomial, e.g., a dealer's sharing
+// polynomial, whose coefficients are wiped on drop.
+#[derive(Clone)]
+pub struct SecretPolynomial<F: Field>(DensePolynomial<F>);
+
+impl<F: Field> SecretPolynomial<F> {
+
+    // Function for wrapping a secret polynomial.
+    pub fn new(poly: DensePolynomial<F>) -> Self {
+        Self(poly)
+    }
+
+    // Method exposing the wrapped polynomial.
+    pub fn expose(&self) -> &DensePolynomial<F> {
+        &self.0
+    }
+}
+
+impl<F: Field> Zeroize for SecretPolynomial<F> {
+    fn zeroize(&mut self) {
+        self.0.coeffs.zeroize();
+    }
+}
+
+impl<F: Field> Drop for SecretPolynomial<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: Field> ZeroizeOnDrop for SecretPolynomial<F> {}
+
+impl<F: Field> fmt::Debug for SecretPolynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretPolynomial(<redacted>)")
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::secrets::{SecretPolynomial, SecretScalar};
+
+    use ark_bls12_381::Fr;
+    use ark_ff::{One, Zero};
+    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+    use zeroize::Zeroize;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_zeroize() {
+        let mut scalar = SecretScalar::new(Fr::one());
+        assert_eq!(*scalar.expose(), Fr::one());
+        assert_eq!(format!("{:?}", scalar), "SecretScalar(<redacted>)");
+        scalar.zeroize();
+        assert!(scalar.expose().is_zero());
+
+        let mut poly = SecretPolynomial::new(DensePolynomial::<Fr>::rand(3, &mut thread_rng()));
+        assert_eq!(poly.expose().coeffs.len(), 4);
+        poly.zeroize();
+        assert!(poly.expose().is_zero());
+    }
+}
@@ -0,0 +1,299 @@
+use crate::modified_scrape::{
+    config::{Config, EvaluationPoints, ShareLayout},
+    decomp::DecompProof,
+    participant::{Participant, ParticipantState},
+    pvss::PVSSShare,
+    share::PVSSTranscript,
+    srs::SRS,
+};
+use crate::signature::scheme::BatchVerifiableSignatureScheme;
+use crate::{Digest, Scalar};
+
+use ark_ec::PairingEngine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/* Serde support (behind the serde feature), for services that keep protocol objects
+   in JSON/CBOR stores. Cryptographic objects are bridged through their canonical
+   (compressed) arkworks serialization: they are encoded as lowercase hex strings in
+   human-readable formats, and as byte strings otherwise. Deserialization goes
+   through the same checks as CanonicalDeserialize.
+
+   Types defined elsewhere (curve points, signatures, public keys such as PublicKey
+   or SSIG::Signature) can be wrapped in Canonical, or annotated with
+   #[serde(with = "optrand_pvss::serde_support::canonical")] in downstream structs.
+*/
+
+// Utility function encoding bytes in lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Utility function decoding lowercase or uppercase hex.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| s.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+// Visitor accepting a hex string, a byte string or a sequence of bytes.
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a hex string or a byte string")
+    }
+
+    fn visit_str<Err: de::Error>(self, s: &str) -> Result<Self::Value, Err> {
+        from_hex(s).ok_or_else(|| Err::custom("invalid hex encoding"))
+    }
+
+    fn visit_bytes<Err: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, Err> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<Err: de::Error>(self, bytes: Vec<u8>) -> Result<Self::Value, Err> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = vec![];
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+// Serde adapters for any type with a canonical arkworks serialization.
+pub mod canonical {
+    use super::{to_hex, BytesVisitor};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{de, ser, Deserializer, Serializer};
+
+    // Function serializing an item through its canonical bytes.
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(item: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![];
+        item.serialize(&mut bytes).map_err(ser::Error::custom)?;
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&to_hex(&bytes))
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+
+    // Function deserializing an item from its canonical bytes, which must be consumed entirely.
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+
+        let mut reader = &bytes[..];
+        let item = T::deserialize(&mut reader).map_err(de::Error::custom)?;
+        if !reader.is_empty() {
+            return Err(de::Error::custom("trailing bytes"));
+        }
+        Ok(item)
+    }
+}
+
+// Struct Canonical wraps any canonically serializable value (e.g., a PublicKey or an
+// SSIG::Signature), giving it serde support.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Canonical<T>(pub T);
+
+impl<T: CanonicalSerialize> Serialize for Canonical<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        canonical::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: CanonicalDeserialize> Deserialize<'de> for Canonical<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        canonical::deserialize(deserializer).map(Canonical)
+    }
+}
+
+
+// Implements Serialize and Deserialize for types with a canonical serialization.
+macro_rules! impl_serde_via_canonical {
+    ($ty:ty, [$($generics:tt)*], [$($bounds:tt)*]) => {
+        impl<$($generics)*> Serialize for $ty where $($bounds)* {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                canonical::serialize(self, serializer)
+            }
+        }
+
+        impl<'de, $($generics)*> Deserialize<'de> for $ty where $($bounds)* {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                canonical::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+impl_serde_via_canonical!(Digest, [], []);
+impl_serde_via_canonical!(PVSSShare<E>, [E], [E: PairingEngine]);
+impl_serde_via_canonical!(DecompProof<E>, [E], [E: PairingEngine]);
+impl_serde_via_canonical!(PVSSTranscript<E, SSIG>, [E, SSIG], [
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+]);
+
+
+// Struct-shaped representations of the types that have no canonical serialization.
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct SRSRepr<E: PairingEngine> {
+    #[serde(with = "canonical")]
+    g1: E::G1Affine,
+    #[serde(with = "canonical")]
+    g2: E::G2Affine,
+    #[serde(with = "canonical")]
+    g2_prime: E::G2Affine,
+}
+
+impl<E: PairingEngine> Serialize for SRS<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SRSRepr::<E> { g1: self.g1, g2: self.g2, g2_prime: self.g2_prime }.serialize(serializer)
+    }
+}
+
+impl<'de, E: PairingEngine> Deserialize<'de> for SRS<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SRSRepr::<E>::deserialize(deserializer)?;
+        Ok(SRS { g1: repr.g1, g2: repr.g2, g2_prime: repr.g2_prime })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct ConfigRepr<E: PairingEngine> {
+    srs: SRS<E>,
+    degree: usize,
+    num_participants: usize,
+    layout: ShareLayout,
+    #[serde(default)]
+    points: EvaluationPoints,
+}
+
+impl<E: PairingEngine> Serialize for Config<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConfigRepr::<E> {
+            srs: self.srs.clone(),
+            degree: self.degree,
+            num_participants: self.num_participants,
+            layout: self.layout,
+            points: self.points,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, E: PairingEngine> Deserialize<'de> for Config<E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ConfigRepr::<E>::deserialize(deserializer)?;
+        Ok(Config {
+            srs: repr.srs,
+            degree: repr.degree,
+            num_participants: repr.num_participants,
+            layout: repr.layout,
+            points: repr.points,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct ParticipantRepr<E: PairingEngine> {
+    id: usize,
+    #[serde(with = "canonical")]
+    public_key_sig: E::G1Affine,
+    state: ParticipantState,
+}
+
+impl<E, SSIG> Serialize for Participant<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ParticipantRepr::<E> { id: self.id, public_key_sig: self.public_key_sig, state: self.state.clone() }
+            .serialize(serializer)
+    }
+}
+
+impl<'de, E, SSIG> Deserialize<'de> for Participant<E, SSIG>
+where
+    E: PairingEngine,
+    SSIG: BatchVerifiableSignatureScheme<PublicKey = E::G1Affine, Secret = Scalar<E>>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ParticipantRepr::<E>::deserialize(deserializer)?;
+        let mut participant = Participant::new(repr.id, repr.public_key_sig);
+        participant.state = repr.state;
+        Ok(participant)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::modified_scrape::{config::Config, participant::Participant, share::PVSSTranscript, tests::{setup_nodes, SSIG}};
+    use crate::serde_support::Canonical;
+    use crate::{Digest, Hash};
+
+    use ark_bls12_381::{Bls12_381 as E, Fr, G1Affine};
+    use ark_serialize::CanonicalSerialize;
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+        let share = nodes[0].share(rng).unwrap();
+        let transcript = PVSSTranscript::from_share(1, 4, &share);
+
+        // Digests are hex strings, as are all canonically bridged objects.
+        let digest = Digest([0xab; 32]);
+        assert_eq!(serde_json::to_string(&digest).unwrap(), format!("\"{}\"", digest));
+
+        let json = serde_json::to_string(&transcript).unwrap();
+        let decoded: PVSSTranscript<E, SSIG> = serde_json::from_str(&json).unwrap();
+        let (mut expected, mut actual) = (vec![], vec![]);
+        transcript.serialize(&mut expected).unwrap();
+        decoded.serialize(&mut actual).unwrap();
+        assert_eq!(expected, actual);
+
+        let config = &nodes[0].aggregator.config;
+        let decoded: Config<E> = serde_json::from_str(&serde_json::to_string(config).unwrap()).unwrap();
+        assert_eq!(decoded.digest(), config.digest());
+
+        let participant = &nodes[2].dealer.participant;
+        let decoded: Participant<E, SSIG> = serde_json::from_str(&serde_json::to_string(participant).unwrap()).unwrap();
+        assert_eq!((decoded.id, decoded.public_key_sig), (participant.id, participant.public_key_sig));
+
+        let signature = Canonical(share.signature_on_decomp);
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(serde_json::from_str::<Canonical<(G1Affine, Fr)>>(&json).unwrap(), signature);
+
+        // Malformed encodings are rejected.
+        assert!(serde_json::from_str::<Digest>("\"abc\"").is_err());
+        assert!(serde_json::from_str::<Canonical<G1Affine>>(&format!("\"{}\"", digest)).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::signature::{
+    scheme::{AggregatableSignatureScheme, BatchVerifiableSignatureScheme, SignatureScheme},
+    utils::{errors::SignatureError, hash::hash_to_group}
+};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::{CryptoRng, Rng};
+use srs::SRS;
+use std::{fmt::Debug, ops::Neg};
+
+
+pub mod srs;
+
+const PERSONALIZATION: &[u8] = b"BLSSIGNA";       // persona for hashing messages to G_1
+const POP_PERSONALIZATION: &[u8] = b"BLSPOPRF";   // persona for proofs of possession
+
+/* BLS signatures over a pairing engine, with public keys pk = g_2^{sk} in G_2 and
+   signatures sigma = H(m)^{sk} in G_1, verified by checking e(sigma, g_2) == e(H(m), pk).
+   Signatures (and public keys) aggregate by multiplication, so that a single pairing
+   check covers a whole committee signing the same message.
+
+   NOTE: Aggregating the public keys of signers of a common message is only sound if
+   every key comes with a proof of possession of its secret key (see prove_possession),
+   checked when the key is registered; otherwise rogue keys can forge aggregates.
+*/
+
+// BLSSignature type wraps around the SRS and represents the scheme's
+// system-wide parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BLSSignature<E: PairingEngine> {
+    pub srs: SRS<E>,   // SRS for the BLS signature
+}
+
+impl<E: PairingEngine> BLSSignature<E> {
+
+    // Utility function hashing a message to G_1 under the given persona.
+    fn hash_message(personalization: &[u8], message: &[u8]) -> Result<E::G1Affine, SignatureError> {
+        Ok(hash_to_group::<E::G1Affine>(personalization, message)?.into_affine())
+    }
+
+    // Utility method checking e(signature, g_2) == e(H, pk).
+    fn check(&self, pk: &E::G2Affine, hashed: E::G1Affine, signature: &E::G1Affine) -> Result<(), SignatureError> {
+        let product = E::product_of_pairings(&[
+            ((*signature).into(), self.srs.g_public_key.neg().into()),
+            (hashed.into(), (*pk).into()),
+        ]);
+
+        if !product.is_one() {
+            return Err(SignatureError::BLSVerify);
+        }
+
+        Ok(())
+    }
+
+    // Method for computing a proof of possession of sk, i.e., a signature on the
+    // matching public key under a dedicated persona.
+    pub fn prove_possession(&self, sk: &E::Fr) -> Result<E::G1Affine, SignatureError> {
+        let (_, pk) = self.from_sk(sk)?;
+        let mut pk_bytes = vec![];
+        pk.serialize(&mut pk_bytes)?;
+
+        let hashed = Self::hash_message(POP_PERSONALIZATION, &pk_bytes)?;
+        Ok(hashed.mul(sk.into_repr()).into_affine())
+    }
+
+    // Method for verifying a proof of possession of the secret key of pk.
+    pub fn verify_possession(&self, pk: &E::G2Affine, proof: &E::G1Affine) -> Result<(), SignatureError> {
+        if pk.is_zero() {
+            return Err(SignatureError::InvalidPublicKey("identity element"));
+        }
+
+        let mut pk_bytes = vec![];
+        pk.serialize(&mut pk_bytes)?;
+
+        self.check(pk, Self::hash_message(POP_PERSONALIZATION, &pk_bytes)?, proof)
+    }
+}
+
+
+// BLSSignature implements the SignatureScheme trait.
+impl<E: PairingEngine> SignatureScheme for BLSSignature<E> {
+    type SRS = SRS<E>;                   // SRS for BLS is a generator of G_2
+    type Secret = E::Fr;                 // secret keys are scalars
+    type PublicKey = E::G2Affine;        // public keys are points in G_2
+    type Signature = E::G1Affine;        // signatures are points in G_1
+
+    // Creates a BLSSignature from a given SRS.
+    fn from_srs(srs: Self::SRS) -> Result<Self, SignatureError> {
+        Ok(Self { srs })
+    }
+
+    // Samples a key pair using a specified RNG.
+    fn generate_keypair<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::Secret, Self::PublicKey), SignatureError> {
+        let sk = Self::Secret::rand(rng);
+        self.from_sk(&sk)
+    }
+
+    // Computes a key pair, given only the secret key.
+    fn from_sk(
+        &self,
+        sk: &Self::Secret,
+    ) -> Result<(Self::Secret, Self::PublicKey), SignatureError> {
+        Ok((*sk, self.srs.g_public_key.mul(sk.into_repr()).into_affine()))
+    }
+
+    // BLS signing algorithm: sigma = H(message)^{sk}. Deterministic, hence the
+    // RNG is not used.
+    fn sign<R: Rng + CryptoRng>(
+        &self,
+        _: &mut R,
+        sk: &Self::Secret,
+        message: &[u8],
+    ) -> Result<Self::Signature, SignatureError> {
+        let hashed = Self::hash_message(PERSONALIZATION, message)?;
+        Ok(hashed.mul(sk.into_repr()).into_affine())
+    }
+
+    // BLS verification algorithm.
+    // Verifies input signature on message, against public_key.
+    fn verify(
+        &self,
+        pk: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), SignatureError> {
+        self.check(pk, Self::hash_message(PERSONALIZATION, message)?, signature)
+    }
+}
+
+// BLSSignature implements the AggregatableSignatureScheme trait.
+impl<E: PairingEngine> AggregatableSignatureScheme for BLSSignature<E> {
+
+    // Method for aggregating public keys, by adding them up in G_2.
+    fn aggregate_public_keys(
+        &self,
+        public_keys: &[&Self::PublicKey],
+    ) -> Result<Self::PublicKey, SignatureError> {
+        Ok(public_keys
+            .iter()
+            .fold(E::G2Projective::zero(), |acc, pk| acc + pk.into_projective())
+            .into_affine())
+    }
+
+    // Method for aggregating signatures, by adding them up in G_1.
+    fn aggregate_signatures(
+        &self,
+        signatures: &[&Self::Signature],
+    ) -> Result<Self::Signature, SignatureError> {
+        Ok(signatures
+            .iter()
+            .fold(E::G1Projective::zero(), |acc, sig| acc + sig.into_projective())
+            .into_affine())
+    }
+}
+
+// BLSSignature implements the BatchVerifiableSignatureScheme trait.
+impl<E: PairingEngine> BatchVerifiableSignatureScheme for BLSSignature<E> {
+
+    // Method for verifying a batch of BLS signatures w.r.t. matching messages and
+    // public keys, by checking a random linear combination of the equations:
+    // e(sum_i alpha^i * sigma_i, g_2) == prod_i e(H(m_i), pk_i^{alpha^i}).
+    fn batch_verify<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        public_keys: &[&Self::PublicKey],
+        messages: &[&[u8]],
+        signatures: &[&Self::Signature],
+    ) -> Result<(), SignatureError> {
+        if public_keys.len() != messages.len() || public_keys.len() != signatures.len() {
+            return Err(SignatureError::BatchVerification(
+                public_keys.len(),
+                messages.len(),
+                signatures.len(),
+            ));
+        }
+
+	// Probabilistic verification
+        let alpha = E::Fr::rand(rng);
+        let mut current_alpha = E::Fr::one();
+
+        let mut signature_sum = E::G1Projective::zero();
+        let mut pairs = vec![];
+
+        for i in 0..public_keys.len() {
+            let hashed = Self::hash_message(PERSONALIZATION, messages[i])?;
+
+            signature_sum += &signatures[i].mul(current_alpha.into_repr());
+            pairs.push((hashed.into(), public_keys[i].mul(current_alpha.into_repr()).into_affine().into()));
+
+            current_alpha *= &alpha;
+        }
+
+        pairs.push((signature_sum.into_affine().into(), self.srs.g_public_key.neg().into()));
+
+        if !E::product_of_pairings(&pairs).is_one() {
+            return Err(SignatureError::BLSVerify);
+        }
+
+        Ok(())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::Bls12_381;
+
+    use super::{BLSSignature, SRS};
+    use crate::signature::{
+        scheme::{AggregatableSignatureScheme, BatchVerifiableSignatureScheme, SignatureScheme},
+        utils::tests::check_serialization,
+    };
+
+    use rand::thread_rng;
+
+    fn scheme() -> BLSSignature<Bls12_381> {
+        let rng = &mut thread_rng();
+        BLSSignature { srs: SRS::setup(rng).unwrap() }
+    }
+
+    #[test]
+    fn test_simple_sig() {
+        let rng = &mut thread_rng();
+        let bls = scheme();
+        let keypair = bls.generate_keypair(rng).unwrap();
+        let message = b"hello";
+
+        let signature = bls.sign(rng, &keypair.0, &message[..]).unwrap();
+        bls.verify(&keypair.1, &message[..], &signature).unwrap();
+
+        // wrong message and wrong key are rejected
+        assert!(bls.verify(&keypair.1, &b"goodbye"[..], &signature).is_err());
+        let keypair2 = bls.generate_keypair(rng).unwrap();
+        assert!(bls.verify(&keypair2.1, &message[..], &signature).is_err());
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let rng = &mut thread_rng();
+        let bls = scheme();
+        let message = b"epoch 7";
+
+        let keypairs = (0..4).map(|_| bls.generate_keypair(rng).unwrap()).collect::<Vec<_>>();
+        for (sk, pk) in keypairs.iter() {
+            bls.verify_possession(pk, &bls.prove_possession(sk).unwrap()).unwrap();
+        }
+        let signatures = keypairs
+            .iter()
+            .map(|(sk, _)| bls.sign(rng, sk, &message[..]).unwrap())
+            .collect::<Vec<_>>();
+
+        let apk = bls.aggregate_public_keys(&keypairs.iter().map(|(_, pk)| pk).collect::<Vec<_>>()).unwrap();
+        let asig = bls.aggregate_signatures(&signatures.iter().collect::<Vec<_>>()).unwrap();
+        bls.verify(&apk, &message[..], &asig).unwrap();
+
+        // an aggregate missing a signer is rejected
+        let partial = bls.aggregate_signatures(&signatures[1..].iter().collect::<Vec<_>>()).unwrap();
+        assert!(bls.verify(&apk, &message[..], &partial).is_err());
+    }
+
+    #[test]
+    fn test_batch() {
+        let rng = &mut thread_rng();
+        let bls = scheme();
+
+        let keypair = bls.generate_keypair(rng).unwrap();
+        let message = b"hello";
+        let signature = bls.sign(rng, &keypair.0, &message[..]).unwrap();
+
+        let keypair2 = bls.generate_keypair(rng).unwrap();
+        let message2 = b"hello2";
+        let signature2 = bls.sign(rng, &keypair2.0, &message2[..]).unwrap();
+
+        bls.batch_verify(
+            rng,
+            &[&keypair.1, &keypair2.1],
+            &[&message[..], &message2[..]],
+            &[&signature, &signature2],
+        ).unwrap();
+
+        // swapped signatures are rejected
+        assert!(bls.batch_verify(
+            rng,
+            &[&keypair.1, &keypair2.1],
+            &[&message[..], &message2[..]],
+            &[&signature2, &signature],
+        ).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let rng = &mut thread_rng();
+        let bls = scheme();
+        let keypair = bls.generate_keypair(rng).unwrap();
+        let signature = bls.sign(rng, &keypair.0, &b"hello"[..]).unwrap();
+
+        check_serialization(bls.srs.clone());
+        check_serialization(keypair);
+        check_serialization(signature);
+    }
+}
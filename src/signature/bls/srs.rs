@@ -0,0 +1,20 @@
+use crate::signature::utils::errors::SignatureError;
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::{CryptoRng, Rng};
+
+#[derive(Debug, CanonicalSerialize, CanonicalDeserialize, Clone, PartialEq)]
+pub struct SRS<E: PairingEngine> {
+    pub g_public_key: E::G2Affine,   // generator of the public key group G_2
+}
+
+impl<E: PairingEngine> SRS<E> {
+
+    // Function setup samples the SRS generator
+    pub fn setup<R: Rng + CryptoRng>(_: &mut R) -> Result<Self, SignatureError> {
+        let srs = Self {
+            g_public_key: E::G2Affine::prime_subgroup_generator(),
+        };
+        Ok(srs)
+    }
+}
@@ -0,0 +1,398 @@
+use crate::signature::{
+    scheme::{BatchVerifiableSignatureScheme, SignatureScheme},
+    utils::errors::SignatureError,
+};
+use crate::Digest;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use blake2s_simd::Params;
+use ed25519_dalek::{ExpandedSecretKey, Verifier};
+use rand::{CryptoRng, Rng};
+use std::convert::TryFrom;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+
+/* Ed25519 identity signatures. Nodes may sign their decomposition proofs with
+   Ed25519 (as in the original Optrand prototype) instead of Schnorr over the
+   pairing group. The wrapper types below give the dalek types the canonical
+   (de)serialization the rest of the crate expects, and EdDSASignature plugs
+   them into the SignatureScheme traits so generic code treats them uniformly.
+*/
+
+pub const PUBLIC_KEY_LENGTH: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;   // 32 bytes
+pub const SECRET_KEY_LENGTH: usize = ed25519_dalek::SECRET_KEY_LENGTH;   // 32 bytes
+pub const SIGNATURE_LENGTH: usize = ed25519_dalek::SIGNATURE_LENGTH;     // 64 bytes
+
+const PREHASH_PERSONALIZATION: &[u8] = b"OPTREDSA";   // persona for prehashing arbitrary messages
+
+// Ed25519 public key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(pub ed25519_dalek::PublicKey);
+
+// Ed25519 secret key (the 32-byte seed), wiped on drop. Like SecretScalar, it is
+// redacted from Debug output, and it cannot be compared (which would not be done in
+// constant time).
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(pub [u8; SECRET_KEY_LENGTH]);
+
+// Ed25519 signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature(pub ed25519_dalek::Signature);
+
+
+impl SecretKey {
+
+    // Function for sampling a fresh secret key using a specified RNG.
+    pub fn generate<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        let mut sk = Self([0u8; SECRET_KEY_LENGTH]);
+        rng.fill_bytes(&mut sk.0);
+        sk
+    }
+
+    // Method for computing the public key matching a secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(ed25519_dalek::PublicKey::from(&self.expand()))
+    }
+
+    // Utility method for expanding the seed into a signing key.
+    fn expand(&self) -> ExpandedSecretKey {
+        // from_bytes only fails on slices of the wrong length
+        let secret = ed25519_dalek::SecretKey::from_bytes(&self.0).unwrap();
+        ExpandedSecretKey::from(&secret)
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretKey(<redacted>)")
+    }
+}
+
+impl Signature {
+
+    // Function for signing a message (typically a digest) with a secret key.
+    pub fn new(message: &[u8], sk: &SecretKey) -> Self {
+        let expanded = sk.expand();
+        let pk = ed25519_dalek::PublicKey::from(&expanded);
+        Self(expanded.sign(message, &pk))
+    }
+
+    // Method for verifying a signature on a message against a public key.
+    pub fn verify(&self, message: &[u8], pk: &PublicKey) -> Result<(), SignatureError> {
+        pk.0.verify(message, &self.0).map_err(|_| SignatureError::EdDSAVerify)
+    }
+
+    // Function for signing an arbitrary protocol message (e.g., a wire envelope or a
+    // complaint). The message is prehashed under a dedicated personalization, so
+    // that such signatures cannot be passed off as signatures on digests.
+    pub fn sign_bytes(message: &[u8], sk: &SecretKey) -> Self {
+        Self::new(&Self::prehash(message), sk)
+    }
+
+    // Method for verifying a signature produced with sign_bytes.
+    pub fn verify_bytes(&self, message: &[u8], pk: &PublicKey) -> Result<(), SignatureError> {
+        self.verify(&Self::prehash(message), pk)
+    }
+
+    // Utility function prehashing an arbitrary message for sign_bytes.
+    fn prehash(message: &[u8]) -> [u8; 32] {
+        let hash = Params::new()
+            .hash_length(32)
+            .personal(PREHASH_PERSONALIZATION)
+            .to_state()
+            .update(message)
+            .finalize();
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(hash.as_bytes());
+        bytes
+    }
+
+    // Function for verifying a batch of signatures w.r.t. matching messages and public keys.
+    pub fn verify_batch(
+        messages: &[&[u8]],
+        signatures: &[&Signature],
+        public_keys: &[&PublicKey],
+    ) -> Result<(), SignatureError> {
+        if public_keys.len() != messages.len() || public_keys.len() != signatures.len() {
+            return Err(SignatureError::BatchVerification(
+                public_keys.len(),
+                messages.len(),
+                signatures.len(),
+            ));
+        }
+
+        let signatures = signatures.iter().map(|s| s.0).collect::<Vec<_>>();
+        let public_keys = public_keys.iter().map(|pk| pk.0).collect::<Vec<_>>();
+
+        ed25519_dalek::verify_batch(messages, &signatures, &public_keys)
+            .map_err(|_| SignatureError::EdDSAVerify)
+    }
+
+    // Function for verifying a batch of signatures on distinct digests (e.g., those
+    // of the decomposition proofs in a transcript), w.r.t. matching public keys.
+    pub fn verify_batch_distinct(
+        digests: &[Digest],
+        signatures: &[&Signature],
+        public_keys: &[&PublicKey],
+    ) -> Result<(), SignatureError> {
+        let messages = digests.iter().map(|digest| &digest.0[..]).collect::<Vec<_>>();
+        Self::verify_batch(&messages, signatures, public_keys)
+    }
+}
+
+
+// PublicKey implements the CanonicalSerialize trait.
+impl CanonicalSerialize for PublicKey {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(self.0.as_bytes())?;
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        PUBLIC_KEY_LENGTH
+    }
+}
+
+// PublicKey implements the CanonicalDeserialize trait.
+impl CanonicalDeserialize for PublicKey {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; PUBLIC_KEY_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        let pk = ed25519_dalek::PublicKey::from_bytes(&bytes).map_err(|_| SerializationError::InvalidData)?;
+        Ok(Self(pk))
+    }
+}
+
+// SecretKey implements the CanonicalSerialize trait.
+impl CanonicalSerialize for SecretKey {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.0)?;
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        SECRET_KEY_LENGTH
+    }
+}
+
+// SecretKey implements the CanonicalDeserialize trait.
+impl CanonicalDeserialize for SecretKey {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut sk = Self([0u8; SECRET_KEY_LENGTH]);
+        reader.read_exact(&mut sk.0)?;
+        Ok(sk)
+    }
+}
+
+// Signature implements the CanonicalSerialize trait.
+impl CanonicalSerialize for Signature {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        writer.write_all(&self.0.to_bytes())?;
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        SIGNATURE_LENGTH
+    }
+}
+
+// Signature implements the CanonicalDeserialize trait.
+impl CanonicalDeserialize for Signature {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; SIGNATURE_LENGTH];
+        reader.read_exact(&mut bytes)?;
+        let sig = ed25519_dalek::Signature::try_from(&bytes[..]).map_err(|_| SerializationError::InvalidData)?;
+        Ok(Self(sig))
+    }
+}
+
+
+// EdDSASignature represents the Ed25519 signature scheme. It has no
+// system-wide parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdDSASignature;
+
+// Ed25519Scheme is an alias of EdDSASignature, naming the scheme after its curve.
+pub type Ed25519Scheme = EdDSASignature;
+
+// EdDSASignature implements the SignatureScheme trait.
+impl SignatureScheme for EdDSASignature {
+    type SRS = ();                  // Ed25519 has no setup
+    type Secret = SecretKey;
+    type PublicKey = PublicKey;
+    type Signature = Signature;
+
+    // Creates an EdDSASignature (the SRS is ignored).
+    fn from_srs(_srs: Self::SRS) -> Result<Self, SignatureError> {
+        Ok(Self)
+    }
+
+    // Samples a key pair using a specified RNG.
+    fn generate_keypair<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::Secret, Self::PublicKey), SignatureError> {
+        let sk = SecretKey::generate(rng);
+        let pk = sk.public_key();
+        Ok((sk, pk))
+    }
+
+    // Computes a key pair, given only the secret key.
+    fn from_sk(
+        &self,
+        sk: &Self::Secret,
+    ) -> Result<(Self::Secret, Self::PublicKey), SignatureError> {
+        Ok((sk.clone(), sk.public_key()))
+    }
+
+    // Ed25519 signing is deterministic, hence rng is not used.
+    fn sign<R: Rng + CryptoRng>(
+        &self,
+        _rng: &mut R,
+        sk: &Self::Secret,
+        message: &[u8],
+    ) -> Result<Self::Signature, SignatureError> {
+        Ok(Signature::new(message, sk))
+    }
+
+    // Verifies input signature on message, against public_key.
+    fn verify(
+        &self,
+        pk: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), SignatureError> {
+        signature.verify(message, pk)
+    }
+}
+
+// EdDSASignature implements the BatchVerifiableSignatureScheme trait.
+impl BatchVerifiableSignatureScheme for EdDSASignature {
+
+    // dalek samples its own randomness for batch verification.
+    fn batch_verify<R: Rng + CryptoRng>(
+        &self,
+        _rng: &mut R,
+        public_keys: &[&Self::PublicKey],
+        messages: &[&[u8]],
+        signatures: &[&Self::Signature],
+    ) -> Result<(), SignatureError> {
+        Signature::verify_batch(messages, signatures, public_keys)
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use super::{EdDSASignature, SecretKey, Signature};
+    use crate::signature::{
+        scheme::{BatchVerifiableSignatureScheme, SignatureScheme},
+        utils::tests::check_serialization,
+    };
+    use crate::Digest;
+
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_simple_sig() {
+        let rng = &mut thread_rng();
+        let eddsa = EdDSASignature::from_srs(()).unwrap();
+        let keypair = eddsa.generate_keypair(rng).unwrap();
+        let message = b"hello";
+
+        let signature = eddsa.sign(rng, &keypair.0, &message[..]).unwrap();
+        eddsa.verify(&keypair.1, &message[..], &signature).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_simple_sig_wrong_message() {
+        let rng = &mut thread_rng();
+        let eddsa = EdDSASignature::from_srs(()).unwrap();
+        let keypair = eddsa.generate_keypair(rng).unwrap();
+        let message = b"hello";
+
+        let signature = eddsa.sign(rng, &keypair.0, &message[..]).unwrap();
+
+        let wrong_message = b"goodbye";
+        eddsa.verify(&keypair.1, &wrong_message[..], &signature).unwrap();
+    }
+
+    #[test]
+    fn test_simple_sig_batch() {
+        let rng = &mut thread_rng();
+        let eddsa = EdDSASignature::from_srs(()).unwrap();
+
+        let keypair = eddsa.generate_keypair(rng).unwrap();
+        let message = b"hello";
+        let signature = eddsa.sign(rng, &keypair.0, &message[..]).unwrap();
+
+        let keypair2 = eddsa.generate_keypair(rng).unwrap();
+        let message2 = b"hello2";
+        let signature2 = eddsa.sign(rng, &keypair2.0, &message2[..]).unwrap();
+
+        eddsa
+            .batch_verify(
+                rng,
+                &[&keypair.1, &keypair2.1],
+                &[&message[..], &message2[..]],
+                &[&signature, &signature2],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_batch_distinct() {
+        let rng = &mut thread_rng();
+        let keys = (0..3).map(|_| SecretKey::generate(rng)).collect::<Vec<_>>();
+        let public_keys = keys.iter().map(|sk| sk.public_key()).collect::<Vec<_>>();
+        let digests = (0..3u8).map(|i| Digest([i; 32])).collect::<Vec<_>>();
+        let signatures = keys.iter().zip(digests.iter()).map(|(sk, digest)| Signature::new(&digest.0, sk)).collect::<Vec<_>>();
+
+        let (signatures, public_keys) = (signatures.iter().collect::<Vec<_>>(), public_keys.iter().collect::<Vec<_>>());
+        Signature::verify_batch_distinct(&digests, &signatures, &public_keys).unwrap();
+
+        // Swapping two digests breaks the batch.
+        let swapped = [digests[1], digests[0], digests[2]];
+        assert!(Signature::verify_batch_distinct(&swapped, &signatures, &public_keys).is_err());
+        assert!(Signature::verify_batch_distinct(&digests[..2], &signatures, &public_keys).is_err());
+    }
+
+    #[test]
+    fn test_sign_bytes() {
+        let rng = &mut thread_rng();
+        let sk = SecretKey::generate(rng);
+        let pk = sk.public_key();
+        let message = b"an envelope of arbitrary length";
+
+        let signature = Signature::sign_bytes(&message[..], &sk);
+        signature.verify_bytes(&message[..], &pk).unwrap();
+        assert!(signature.verify_bytes(b"another envelope", &pk).is_err());
+
+        // Prehashed signatures do not verify as plain ones, nor vice versa.
+        assert!(signature.verify(&message[..], &pk).is_err());
+        assert!(Signature::new(&message[..], &sk).verify_bytes(&message[..], &pk).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let rng = &mut thread_rng();
+        let eddsa = EdDSASignature::from_srs(()).unwrap();
+        let keypair = eddsa.generate_keypair(rng).unwrap();
+        let signature = eddsa.sign(rng, &keypair.0, &b"hello"[..]).unwrap();
+
+        check_serialization(keypair.1);
+        check_serialization(signature);
+
+        // Secret keys cannot be compared, so their round trip is checked through the
+        // public key; nor are they printed.
+        let mut bytes = vec![];
+        keypair.0.serialize(&mut bytes).unwrap();
+        let sk = SecretKey::deserialize(&bytes[..]).unwrap();
+        assert_eq!(sk.public_key(), keypair.1);
+        assert_eq!(format!("{:?}", sk), "SecretKey(<redacted>)");
+    }
+}
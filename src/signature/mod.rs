@@ -1,3 +1,7 @@
 pub mod scheme;
 pub mod schnorr;
+pub mod bls;
 pub mod utils;
+
+#[cfg(feature = "eddsa")]
+pub mod eddsa;
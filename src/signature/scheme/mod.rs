@@ -1,6 +1,6 @@
 use crate::signature::utils::errors::SignatureError;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 use std::fmt::Debug;
 
 // All signature schemes must implement the SignatureScheme trait.
@@ -14,19 +14,21 @@ pub trait SignatureScheme: Debug + Clone + PartialEq + Sized {
     fn from_srs(srs: Self::SRS) -> Result<Self, SignatureError>;
 
     // Method for generating a key pair by sampling an input RNG.
-    fn generate_keypair<R: Rng>(
+    fn generate_keypair<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
     ) -> Result<(Self::Secret, Self::PublicKey), SignatureError>;
 
-    // Method for computing a key pair, given only the secret key.
+    // Method for computing a key pair, given only the secret key. It takes self for
+    // the scheme's parameters, which clippy's naming convention does not expect.
+    #[allow(clippy::wrong_self_convention)]
     fn from_sk(&self, sk: &Self::Secret)
                -> Result<(Self::Secret, Self::PublicKey), SignatureError>;
 
     // Method for creating a digital signature on an
     // input message, using the signer's secret key, and a
     // specified RNG.
-    fn sign<R: Rng>(
+    fn sign<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         sk: &Self::Secret,
@@ -66,7 +68,7 @@ pub trait BatchVerifiableSignatureScheme: SignatureScheme {
 
     // Method for allowing batch verification of a slice of signatures,
     // w.r.t. matching pablic keys and messages.
-    fn batch_verify<R: Rng>(
+    fn batch_verify<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         public_keys: &[&Self::PublicKey],
@@ -2,24 +2,219 @@ use crate::signature::{
     scheme::{BatchVerifiableSignatureScheme, SignatureScheme},
     utils::{errors::SignatureError, hash::hash_to_field}
 };
+use crate::secrets::SecretScalar;
+use crate::transcript::Transcript;
 use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
-use ark_ff::{One, PrimeField, UniformRand, Zero};
-use rand::Rng;
+use ark_ff::{FpParameters, One, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use rand::{CryptoRng, Rng};
 use srs::SRS;
 use std::{fmt::Debug, ops::Neg};
+use zeroize::Zeroize;
 
 
 pub mod srs;
 
-const PERSONALIZATION: &[u8] = b"SCHSIGNA";   // persona for the Schnorr signature scheme
+const PERSONALIZATION: &[u8] = b"SCHSIGNA";         // persona for the Schnorr signature scheme
+const NONCE_PERSONALIZATION: &[u8] = b"SCHNONCE";   // persona for deriving signing nonces
+
+// Enum SchnorrVersion selects what the challenge of a signature is computed over.
+// Legacy challenges hash the message, generator and nonce commitment only, so that
+// a signature does not commit to its signer's key (and may be re-targeted at
+// related keys). KeyBound challenges also hash the public key. Legacy is only
+// meant for verifying signatures produced before the switch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchnorrVersion {
+    Legacy,     // H(message || g || v_g)
+    #[default]
+    KeyBound,   // H(message || g || pk || v_g)
+}
 
 // SchnorrSignature type wraps around the SRS and represents the scheme's
 // system-wide parameters.
 #[derive(Clone, Debug, PartialEq)]
 pub struct SchnorrSignature<C: AffineCurve> {
-    pub srs: SRS<C>,   // SRS for the Schnorr signature
+    pub srs: SRS<C>,                  // SRS for the Schnorr signature
+    pub version: SchnorrVersion,      // what challenges are computed over
+}
+
+impl<C: AffineCurve> SchnorrSignature<C> {
+
+    // Function for decoding a public key from its standard encoding, i.e., the
+    // compressed canonical serialization of the point, as produced for instance
+    // by an HSM. The point must lie in the prime-order subgroup, must not be the
+    // identity, and the encoding must not carry trailing bytes.
+    pub fn public_key_from_bytes(bytes: &[u8]) -> Result<C, SignatureError> {
+        let mut reader = bytes;
+        let pk = C::deserialize(&mut reader)?;
+
+        if !reader.is_empty() {
+            return Err(SignatureError::InvalidPublicKey("trailing bytes"));
+        }
+        if pk.is_zero() {
+            return Err(SignatureError::InvalidPublicKey("identity element"));
+        }
+        if !pk.mul(<C::ScalarField as PrimeField>::Params::MODULUS).is_zero() {
+            return Err(SignatureError::InvalidPublicKey("not in the prime-order subgroup"));
+        }
+
+        Ok(pk)
+    }
+
+    // Function for encoding a public key in the standard encoding.
+    pub fn public_key_to_bytes(pk: &C) -> Result<Vec<u8>, SignatureError> {
+        let mut bytes = vec![];
+        pk.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // Function for creating a SchnorrSignature in the legacy (compatibility) mode,
+    // for verifying signatures whose challenges do not bind the public key.
+    pub fn legacy(srs: SRS<C>) -> Self {
+        Self { srs, version: SchnorrVersion::Legacy }
+    }
+
+    // Utility method computing the challenge of a signature by nonce commitment v_g,
+    // under public key pk, on message, as per the scheme's version.
+    fn challenge(&self, pk: &C, message: &[u8], v_g: &C) -> Result<C::ScalarField, SignatureError> {
+        // serialize the SRS generator, the public key (if bound) and the commitment
+        // to nonce into vectors of bytes
+        let mut g_bytes = vec![];
+        self.srs.g_public_key.serialize(&mut g_bytes)?;
+
+        let mut pk_bytes = vec![];
+        if self.version == SchnorrVersion::KeyBound {
+            pk.serialize(&mut pk_bytes)?;
+        }
+
+        let mut v_g_bytes = vec![];
+        v_g.serialize(&mut v_g_bytes)?;
+
+        // hash together the personalization, message, SRS generator, public key
+        // and commitment
+        hash_to_field::<C::ScalarField>(
+            PERSONALIZATION,
+            &[message, &g_bytes, &pk_bytes, &v_g_bytes].concat(),
+        )
+    }
+
+    // Utility method deriving the nonce for signing message with sk, by hashing sk,
+    // the scheme's version, the message and (unless deterministic) fresh randomness.
+    // The version is included since signing the same message under two versions
+    // with the same nonce, but different challenges, would reveal sk.
+    fn derive_nonce(
+        &self,
+        sk: &C::ScalarField,
+        message: &[u8],
+        entropy: Option<&[u8; 32]>,
+    ) -> Result<SecretScalar<C::ScalarField>, SignatureError> {
+        let mut input = vec![];
+        sk.serialize(&mut input)?;
+        input.push(self.version as u8);
+        input.push(entropy.is_some() as u8);
+        input.extend_from_slice(message);
+        if let Some(entropy) = entropy {
+            input.extend_from_slice(entropy);
+        }
+
+        let v = hash_to_field::<C::ScalarField>(NONCE_PERSONALIZATION, &input);
+        input.zeroize();
+        Ok(SecretScalar::new(v?))
+    }
+
+    // Utility method computing the signature on message with sk, using nonce v.
+    fn sign_with_nonce(
+        &self,
+        sk: &C::ScalarField,
+        message: &[u8],
+        v: SecretScalar<C::ScalarField>,
+    ) -> Result<(C, C::ScalarField), SignatureError> {
+        // compute commitment to nonce
+        let v_g = self.srs.g_public_key.mul(v.expose().into_repr()).into_affine();
+
+        // compute challenge (over the signer's public key, if bound)
+        let pk = self.srs.g_public_key.mul(sk.into_repr()).into_affine();
+        let hashed_message = self.challenge(&pk, message, &v_g)?;
+
+        // compute "response"
+        let r = *v.expose() - (*sk * hashed_message);
+
+        Ok((v_g, r))
+    }
+
+    // Method for signing a message deterministically (as in RFC 6979), with the
+    // nonce derived from sk and the message alone. Signing the same message twice
+    // yields the same signature, and no randomness is needed.
+    pub fn sign_deterministic(
+        &self,
+        sk: &C::ScalarField,
+        message: &[u8],
+    ) -> Result<(C, C::ScalarField), SignatureError> {
+        let v = self.derive_nonce(sk, message, None)?;
+        self.sign_with_nonce(sk, message, v)
+    }
+
+    // Method for signing a message with a challenge squeezed from the given
+    // transcript, after absorbing the generator, the public key, the message and
+    // the nonce commitment. The signature only verifies against a transcript in
+    // the same state (e.g., bound to the same session).
+    pub fn sign_in_transcript<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+        transcript: &mut Transcript,
+        sk: &C::ScalarField,
+        message: &[u8],
+    ) -> Result<(C, C::ScalarField), SignatureError> {
+        let pk = self.srs.g_public_key.mul(sk.into_repr()).into_affine();
+
+        // sample nonce (wiped on drop) and commit to it
+        let v = SecretScalar::new(C::ScalarField::rand(rng));
+        let v_g = self.srs.g_public_key.mul(v.expose().into_repr()).into_affine();
+
+        let c = self.transcript_challenge(transcript, &pk, message, &v_g)?;
+        Ok((v_g, *v.expose() - (*sk * c)))
+    }
+
+    // Method for verifying a signature produced with sign_in_transcript, against
+    // a transcript in the same state.
+    pub fn verify_in_transcript(
+        &self,
+        transcript: &mut Transcript,
+        pk: &C,
+        message: &[u8],
+        signature: &(C, C::ScalarField),
+    ) -> Result<(), SignatureError> {
+        let c = self.transcript_challenge(transcript, pk, message, &signature.0)?;
+
+        let check = (self.srs.g_public_key.mul(signature.1.into_repr())
+            + pk.mul(c.into_repr()))
+            .into_affine();
+
+        if check != signature.0 {
+            return Err(SignatureError::SchnorrVerify);
+        }
+
+        Ok(())
+    }
+
+    // Utility method absorbing a signature's public values into a transcript and
+    // squeezing its challenge.
+    fn transcript_challenge(
+        &self,
+        transcript: &mut Transcript,
+        pk: &C,
+        message: &[u8],
+        v_g: &C,
+    ) -> Result<C::ScalarField, SignatureError> {
+        transcript.append(b"schnorr-generator", &self.srs.g_public_key)?;
+        transcript.append(b"schnorr-public-key", pk)?;
+        transcript.append_message(b"schnorr-message", message);
+        transcript.append(b"schnorr-nonce", v_g)?;
+        Ok(transcript.challenge_scalar(b"schnorr-challenge"))
+    }
 }
 
+
 // SchnorrSignature implements the SignatureScheme trait.
 impl<C: AffineCurve> SignatureScheme for SchnorrSignature<C> {
     type SRS = SRS<C>;                      // SRS for Schnorr is just a generator (i.e., an EC point)
@@ -29,11 +224,11 @@ impl<C: AffineCurve> SignatureScheme for SchnorrSignature<C> {
 
     // Creates a SchnorrSignature from a given SRS.
     fn from_srs(srs: Self::SRS) -> Result<Self, SignatureError> {
-        Ok(Self { srs })
+        Ok(Self { srs, version: SchnorrVersion::default() })
     }
 
     // Samples a key pair using a specified RNG.
-    fn generate_keypair<R: Rng>(
+    fn generate_keypair<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
     ) -> Result<(Self::Secret, Self::PublicKey), SignatureError> {
@@ -50,42 +245,24 @@ impl<C: AffineCurve> SignatureScheme for SchnorrSignature<C> {
     }
 
     // Schnorr signing algorithm.
-    // Computes a signature on message, using secret key sk,
-    // and sampling randomness using rng.
-    fn sign<R: Rng>(
+    // Computes a signature on message, using secret key sk. The nonce is hedged:
+    // it is derived from sk and the message as well as from randomness sampled
+    // using rng, so that a broken rng does not leak sk.
+    fn sign<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         sk: &Self::Secret,
         message: &[u8],
     ) -> Result<Self::Signature, SignatureError> {
 
-        // sample nonce
-        let v = C::ScalarField::rand(rng);
-
-        // compute commitment to nonce
-        let v_g = self.srs.g_public_key.mul(v.into_repr()).into_affine();
-
-	// serialize the SRS generator into a vector of bytes
-        let mut g_bytes = vec![];
-        self.srs.g_public_key.serialize(&mut g_bytes)?;
-
-        // serialize commitment to nonce into a vector of bytes
-        let mut v_g_bytes = vec![];
-        v_g.serialize(&mut v_g_bytes)?;
-
-        // compute challenge by hashing together the personalization, message,
-        // commitment, and the SRS generator.
-        let hashed_message = hash_to_field::<C::ScalarField>(
-            PERSONALIZATION,
-            &[message, &g_bytes, &v_g_bytes].concat(),
-        )?;
-
-        // compute "response"
-        let r = v - &(*sk * &hashed_message);
+        // derive nonce (wiped on drop)
+        let mut entropy = [0u8; 32];
+        rng.fill_bytes(&mut entropy);
+        let v = self.derive_nonce(sk, message, Some(&entropy));
+        entropy.zeroize();
 
         // compute and return the Schnorr signature
-        let sig = (v_g, r);
-        Ok(sig)
+        self.sign_with_nonce(sk, message, v?)
     }
 
     // Schnorr verification algorithm.
@@ -97,24 +274,12 @@ impl<C: AffineCurve> SignatureScheme for SchnorrSignature<C> {
         signature: &Self::Signature,
     ) -> Result<(), SignatureError> {
 
-        // serialize the SRS generator into a vector of bytes
-        let mut g_bytes = vec![];
-        self.srs.g_public_key.serialize(&mut g_bytes)?;
-
-	// serialize the "response" part of the input signature into
-        // a vector of bytes
-        let mut v_g_bytes = vec![];
-        signature.0.serialize(&mut v_g_bytes)?;
-
-        // hash personalization, message, nonce commitment, and the SRS generator
-        let hashed_message = hash_to_field::<C::ScalarField>(
-            PERSONALIZATION,
-            &[message, &g_bytes, &v_g_bytes].concat(),
-        )?;
+        // recompute the challenge from the nonce commitment part of the signature
+        let hashed_message = self.challenge(pk, message, &signature.0)?;
 
         // compute LHS of the verification condition
         let check = (self.srs.g_public_key.mul(signature.1.into_repr())
-            + &pk.mul(hashed_message.into_repr()))
+            + pk.mul(hashed_message.into_repr()))
             .into_affine();
 
         // Compare LHS against RHS as per the verification condition
@@ -131,7 +296,7 @@ impl<C: AffineCurve> BatchVerifiableSignatureScheme for SchnorrSignature<C> {
 
     // Method for verifying a batch of Schnorr signatures w.r.t. matching messages
     // and public keys.
-    fn batch_verify<R: Rng>(
+    fn batch_verify<R: Rng + CryptoRng>(
         &self,
         rng: &mut R,
         public_keys: &[&Self::PublicKey],
@@ -150,32 +315,20 @@ impl<C: AffineCurve> BatchVerifiableSignatureScheme for SchnorrSignature<C> {
         let alpha = C::ScalarField::rand(rng);
         let mut current_alpha = C::ScalarField::one();
 
-	// Serialize the SRS generator into a vector of bytes
-        let mut g_bytes = vec![];
-        self.srs.g_public_key.serialize(&mut g_bytes)?;
-
 	// Initialize vectors for bases and scalars
         let mut bases = vec![];
         let mut scalars = vec![];
 
 	// For each provided public key
         for i in 0..public_keys.len() {
-	    // Serialize the "response" part of the input signature into
-            // a vector of bytes
-            let mut v_g_bytes = vec![];
-            signatures[i].0.serialize(&mut v_g_bytes)?;
-
-	    // Hash the message, generator, and response
-            let hashed_message = hash_to_field::<C::ScalarField>(
-                PERSONALIZATION,
-                &[messages[i], &g_bytes, &v_g_bytes].concat(),
-            )?;
+	    // Recompute the challenge, under the signer's public key
+            let hashed_message = self.challenge(public_keys[i], messages[i], &signatures[i].0)?;
 
             bases.push(self.srs.g_public_key.into_projective());
-            scalars.push((signatures[i].1 * &current_alpha).into_repr());
+            scalars.push((signatures[i].1 * current_alpha).into_repr());
 
             bases.push(public_keys[i].into_projective());
-            scalars.push((hashed_message * &current_alpha).into_repr());
+            scalars.push((hashed_message * current_alpha).into_repr());
 
             bases.push(signatures[i].0.into_projective());
             scalars.push(current_alpha.neg().into_repr());
@@ -200,22 +353,44 @@ impl<C: AffineCurve> BatchVerifiableSignatureScheme for SchnorrSignature<C> {
 
 #[cfg(test)]
 mod test {
-    use ark_bls12_381::{G1Affine, G2Affine};
-    use ark_ec::AffineCurve;
+    use ark_bls12_381::{Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{FpParameters, PrimeField, Zero};
 
-    use super::{SchnorrSignature, SRS};
+    use super::{SchnorrSignature, SchnorrVersion, SRS};
     use crate::signature::{
         scheme::{BatchVerifiableSignatureScheme, SignatureScheme},
         utils::tests::check_serialization,
     };
 
-    use rand::thread_rng;
+    use crate::transcript::{Session, Transcript};
+    use crate::Digest;
+
+    use rand::{thread_rng, Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
 
     #[test]
     fn test_simple_sig_g1() {
         test_simple_sig::<G1Affine>();
     }
 
+    #[test]
+    fn test_transcript_sig() {
+        let rng = &mut thread_rng();
+        let schnorr = SchnorrSignature::from_srs(SRS::<G1Affine>::setup(rng).unwrap()).unwrap();
+        let (sk, pk) = schnorr.generate_keypair(rng).unwrap();
+        let session = Session { config_digest: Digest([0u8; 32]), epoch: 1, participant_id: 3 };
+        let message = b"hello";
+
+        let signature = schnorr.sign_in_transcript(rng, &mut Transcript::for_session(b"test", &session), &sk, message).unwrap();
+        schnorr.verify_in_transcript(&mut Transcript::for_session(b"test", &session), &pk, message, &signature).unwrap();
+
+        // The signature is bound to its signer's session.
+        let impersonated = Session { participant_id: 4, ..session };
+        assert!(schnorr.verify_in_transcript(&mut Transcript::for_session(b"test", &impersonated), &pk, message, &signature).is_err());
+        assert!(schnorr.verify(&pk, message, &signature).is_err());
+    }
+
     #[test]
     fn test_simple_sig_g2() {
         test_simple_sig::<G2Affine>();
@@ -224,7 +399,7 @@ mod test {
     fn test_simple_sig<C: AffineCurve>() {
         let rng = &mut thread_rng();
         let srs = SRS::<C>::setup(rng).unwrap();
-        let schnorr = SchnorrSignature { srs };
+        let schnorr = SchnorrSignature::from_srs(srs).unwrap();
         let keypair = schnorr.generate_keypair(rng).unwrap();
         let message = b"hello";
 
@@ -234,6 +409,35 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn test_public_key_from_bytes() {
+        let rng = &mut thread_rng();
+        let schnorr = SchnorrSignature::from_srs(SRS::<G1Affine>::setup(rng).unwrap()).unwrap();
+        let (_, pk) = schnorr.generate_keypair(rng).unwrap();
+
+        let bytes = SchnorrSignature::public_key_to_bytes(&pk).unwrap();
+        assert_eq!(SchnorrSignature::<G1Affine>::public_key_from_bytes(&bytes).unwrap(), pk);
+
+        // trailing bytes and the identity are rejected
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(SchnorrSignature::<G1Affine>::public_key_from_bytes(&extended).is_err());
+        let identity = SchnorrSignature::public_key_to_bytes(&G1Affine::zero()).unwrap();
+        assert!(SchnorrSignature::<G1Affine>::public_key_from_bytes(&identity).is_err());
+
+        // so is a point on the curve but outside the prime-order subgroup
+        let outside = loop {
+            let bytes = (0..48).map(|_| rng.gen()).collect::<Vec<u8>>();
+            if let Some(p) = G1Affine::from_random_bytes(&bytes) {
+                if !p.mul(<Fr as PrimeField>::Params::MODULUS).is_zero() {
+                    break p;
+                }
+            }
+        };
+        let bytes = SchnorrSignature::public_key_to_bytes(&outside).unwrap();
+        assert!(SchnorrSignature::<G1Affine>::public_key_from_bytes(&bytes).is_err());
+    }
+
     #[test]
     #[should_panic]
     fn test_simple_sig_wrong_pk_g1() {
@@ -249,7 +453,7 @@ mod test {
     fn test_simple_sig_wrong_pk<C: AffineCurve>() {
         let rng = &mut thread_rng();
         let srs = SRS::<C>::setup(rng).unwrap();
-        let schnorr = SchnorrSignature { srs };
+        let schnorr = SchnorrSignature::from_srs(srs).unwrap();
         let keypair = schnorr.generate_keypair(rng).unwrap();
         let message = b"hello";
 
@@ -276,7 +480,7 @@ mod test {
     fn test_simple_sig_wrong_message<C: AffineCurve>() {
         let rng = &mut thread_rng();
         let srs = SRS::<C>::setup(rng).unwrap();
-        let schnorr = SchnorrSignature { srs };
+        let schnorr = SchnorrSignature::from_srs(srs).unwrap();
         let keypair = schnorr.generate_keypair(rng).unwrap();
         let message = b"hello";
 
@@ -301,7 +505,7 @@ mod test {
     fn test_simple_sig_batch<C: AffineCurve>() {
         let rng = &mut thread_rng();
         let srs = SRS::<C>::setup(rng).unwrap();
-        let schnorr = SchnorrSignature { srs };
+        let schnorr = SchnorrSignature::from_srs(srs).unwrap();
 
         let keypair = schnorr.generate_keypair(rng).unwrap();
         let message = b"hello";
@@ -321,6 +525,62 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn test_key_bound_challenge() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine>::setup(rng).unwrap();
+        let (schnorr, legacy) = (SchnorrSignature::from_srs(srs.clone()).unwrap(), SchnorrSignature::legacy(srs));
+        assert_eq!(schnorr.version, SchnorrVersion::KeyBound);
+        let (sk, pk) = schnorr.generate_keypair(rng).unwrap();
+        let message = b"hello";
+
+        // Signatures of either version only verify in the same version.
+        let signature = schnorr.sign(rng, &sk, &message[..]).unwrap();
+        let old = legacy.sign(rng, &sk, &message[..]).unwrap();
+        legacy.verify(&pk, &message[..], &old).unwrap();
+        legacy.batch_verify(rng, &[&pk], &[&message[..]], &[&old]).unwrap();
+        assert!(schnorr.verify(&pk, &message[..], &old).is_err());
+        assert!(legacy.verify(&pk, &message[..], &signature).is_err());
+
+        // A legacy signature verifies under a related key pk * g^d once its response
+        // is shifted by d * c, whereas a key-bound one does not.
+        let d = Fr::from(5u64);
+        let related = (pk.into_projective() + schnorr.srs.g_public_key.mul(d.into_repr())).into_affine();
+        let shift = |signature: &(G1Affine, Fr), scheme: &SchnorrSignature<G1Affine>| {
+            let c = scheme.challenge(&related, &message[..], &signature.0).unwrap();
+            (signature.0, signature.1 - d * c)
+        };
+        legacy.verify(&related, &message[..], &shift(&old, &legacy)).unwrap();
+        assert!(schnorr.verify(&related, &message[..], &shift(&signature, &schnorr)).is_err());
+        assert!(schnorr.batch_verify(rng, &[&related], &[&message[..]], &[&shift(&signature, &schnorr)]).is_err());
+    }
+
+    #[test]
+    fn test_nonces() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<G1Affine>::setup(rng).unwrap();
+        let (schnorr, legacy) = (SchnorrSignature::from_srs(srs.clone()).unwrap(), SchnorrSignature::legacy(srs));
+        let (sk, pk) = schnorr.generate_keypair(rng).unwrap();
+        let message = b"hello";
+
+        // Deterministic signatures verify, and repeat on the same message only.
+        let signature = schnorr.sign_deterministic(&sk, &message[..]).unwrap();
+        schnorr.verify(&pk, &message[..], &signature).unwrap();
+        assert_eq!(schnorr.sign_deterministic(&sk, &message[..]).unwrap(), signature);
+        assert_ne!(schnorr.sign_deterministic(&sk, b"goodbye").unwrap().0, signature.0);
+        assert_ne!(legacy.sign_deterministic(&sk, &message[..]).unwrap().0, signature.0);
+
+        // Hedged signatures do not repeat, even on the same message...
+        let hedged = schnorr.sign(rng, &sk, &message[..]).unwrap();
+        assert_ne!(schnorr.sign(rng, &sk, &message[..]).unwrap().0, hedged.0);
+
+        // ...and, with a broken rng repeating its output, still differ across messages.
+        let first = schnorr.sign(&mut ChaChaRng::from_seed([0u8; 32]), &sk, &message[..]).unwrap();
+        let second = schnorr.sign(&mut ChaChaRng::from_seed([0u8; 32]), &sk, b"goodbye").unwrap();
+        assert_ne!(first.0, second.0);
+        schnorr.verify(&pk, b"goodbye", &second).unwrap();
+    }
+
     #[test]
     fn test_serialization_g1() {
         test_serialization::<G1Affine>();
@@ -334,13 +594,13 @@ mod test {
     fn test_serialization<C: AffineCurve>() {
         let rng = &mut thread_rng();
         let srs = SRS::<C>::setup(rng).unwrap();
-        let schnorr = SchnorrSignature { srs: srs.clone() };
+        let schnorr = SchnorrSignature::from_srs(srs.clone()).unwrap();
         let keypair = schnorr.generate_keypair(rng).unwrap();
         let message = b"hello";
         let signature = schnorr.sign(rng, &keypair.0, &message[..]).unwrap();
 
         check_serialization(srs.clone());
-        check_serialization(keypair.clone());
-        check_serialization(signature.clone());
+        check_serialization(keypair);
+        check_serialization(signature);
     }
 }
@@ -1,7 +1,7 @@
 use crate::signature::utils::errors::SignatureError;
 use ark_ec::AffineCurve;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
-use rand::Rng;
+use rand::{CryptoRng, Rng};
 
 #[derive(Debug, CanonicalSerialize, CanonicalDeserialize, Clone, PartialEq)]
 pub struct SRS<C: AffineCurve> {
@@ -11,7 +11,7 @@ pub struct SRS<C: AffineCurve> {
 impl<C: AffineCurve> SRS<C> {
 
     // Function setup samples the SRS generator
-    pub fn setup<R: Rng>(_: &mut R) -> Result<Self, SignatureError> {
+    pub fn setup<R: Rng + CryptoRng>(_: &mut R) -> Result<Self, SignatureError> {
         let srs = Self {
             g_public_key: C::prime_subgroup_generator(),
         };
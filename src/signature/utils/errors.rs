@@ -34,6 +34,8 @@ pub enum SignatureError {
     BLSVerify,
     #[error("Failed verifying Schnorr equation")]
     SchnorrVerify,
+    #[error("Failed verifying EdDSA signature")]
+    EdDSAVerify,
     #[error("Signature doesn't have an inverse")]
     SignatureDoesNotHaveInverse,
     #[error("SRS is different")]
@@ -42,4 +44,6 @@ pub enum SignatureError {
     SerializationError(#[from] SerializationError),
     #[error("Different lengths in batch verification: {0}, {1}, {2}")]
     BatchVerification(usize, usize, usize),
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(&'static str),
 }
@@ -0,0 +1,416 @@
+use crate::broadcast::{BroadcastMessage, MessageKind};
+use crate::ceremony::SSIG;
+use crate::it::{errors::HarnessError, Cluster, Faults};
+use crate::modified_scrape::{
+    complaint::Complaint,
+    decryption::DecryptedShare,
+    share::{PVSSAugmentedShare, PVSSTranscript},
+    slashing::SignedDealing,
+};
+use crate::GT;
+
+use ark_ec::{PairingEngine, ProjectiveCurve};
+use ark_serialize::*;
+use ark_std::collections::{BTreeMap, BTreeSet};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/* Multi-node simulation. Runs complete epochs of a committee in-process, over the
+   simulated network of the integration harness (see it/mod.rs), so that a choice
+   of n, t and network conditions can be tried out without any real networking.
+   Every epoch proceeds in three phases, each run until the network quiesces:
+
+       dealing:     every node reliably broadcasts its share, and every node
+                    verifies and aggregates the shares it delivers
+       complaints:  every node whose encryption in an aggregated dealing does not
+                    match the commitment sends a complaint to all, and every node
+                    excludes the dealers of the valid complaints it receives
+       decryption:  every node sends the decryption of its share of its transcript
+                    to all, and every node verifies the decryptions it receives and
+                    reconstructs the beacon from t + 1 of them
+
+   Verifying a share only checks the encryption for its dealer (see complaint.rs),
+   so a bad encryption for some other node is only caught by the complaints phase.
+   Dealers sign their dealings in full for that purpose.
+
+   Nodes may be scripted to misbehave (see Behavior). The report of an epoch only
+   covers the honest nodes, and tells whether they agreed on the contributors and
+   on the beacon value despite the faults and the misbehaving nodes.
+*/
+
+// Enumeration whose variants model the behaviors a node can be scripted with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Behavior {
+    #[default]
+    Honest,
+    Equivocate,            // deals two different shares, each to half of the nodes
+    BadEncryption,         // deals a share in which the next node's encryption is corrupted
+    WithholdDecryption,    // deals honestly, but never releases its decryption
+}
+
+// Struct EpochReport summarizes an epoch, as seen by the honest nodes.
+#[derive(Clone, Debug)]
+pub struct EpochReport<E: PairingEngine> {
+    pub epoch: u64,                                         // the epoch reported on
+    pub contributors: BTreeMap<usize, BTreeSet<usize>>,     // dealers in each honest node's transcript
+    pub excluded: BTreeMap<usize, BTreeSet<usize>>,         // dealers each honest node excluded on a complaint
+    pub beacons: BTreeMap<usize, GT<E>>,                    // beacon value of each honest node which reconstructed one
+    pub rejected: BTreeMap<usize, usize>,                   // shares and decryptions each honest node rejected
+}
+
+impl<E: PairingEngine> EpochReport<E> {
+
+    // Method returning the dealers all honest nodes aggregated, if they agree.
+    pub fn contributors(&self) -> Option<&BTreeSet<usize>> {
+        let first = self.contributors.values().next()?;
+        if self.contributors.values().all(|contributors| contributors == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+
+    // Method returning the beacon value, if every honest node reconstructed the same one.
+    pub fn agreed(&self) -> Option<GT<E>> {
+        let first = *self.beacons.values().next()?;
+        if self.beacons.len() == self.contributors.len() && self.beacons.values().all(|beacon| *beacon == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+// Struct Simulation models a committee running epochs over a simulated network.
+pub struct Simulation<E: PairingEngine> {
+    pub cluster: Cluster<E>,                     // the nodes and the network connecting them
+    pub behaviors: BTreeMap<usize, Behavior>,    // behaviors of the misbehaving nodes; all others are honest
+    pub max_ticks: u64,                          // ticks each phase may take before timing out
+    epoch: u64,                                  // the next epoch to run
+    rng: ChaChaRng,                              // source of the nodes' randomness
+}
+
+impl<E: PairingEngine> Simulation<E> {
+
+    // Function for setting up a simulation of n nodes with threshold t, over a network
+    // injecting the given faults. All randomness is drawn from the given seed.
+    pub fn new(n: usize, t: usize, faults: Faults, seed: u64) -> Result<Self, HarnessError<E>> {
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let cluster = Cluster::new(n, t, faults, rng.gen())?;
+        Ok(Self { cluster, behaviors: BTreeMap::new(), max_ticks: 1_000, epoch: 0, rng })
+    }
+
+    // Method for scripting the behavior of a node in the following epochs.
+    pub fn script(&mut self, id: usize, behavior: Behavior) -> Result<(), HarnessError<E>> {
+        if id >= self.cluster.nodes.len() {
+            return Err(HarnessError::InvalidNodeId(id));
+        }
+        match behavior {
+            Behavior::Honest => self.behaviors.remove(&id),
+            _ => self.behaviors.insert(id, behavior),
+        };
+        Ok(())
+    }
+
+    // Method returning the behavior of a node.
+    pub fn behavior(&self, id: usize) -> Behavior {
+        self.behaviors.get(&id).cloned().unwrap_or_default()
+    }
+
+    // Method returning the ids of the honest nodes which have not crashed.
+    pub fn honest(&self) -> Vec<usize> {
+        self.cluster.live().into_iter().filter(|id| self.behavior(*id) == Behavior::Honest).collect()
+    }
+
+    // Method for running the next epoch, and reporting on its outcome.
+    pub fn run_epoch(&mut self) -> Result<EpochReport<E>, HarnessError<E>> {
+        let epoch = self.epoch;
+        self.epoch += 1;
+        self.start_epoch(epoch)?;
+
+        // 1. Dealing.
+        let rejected_before = self.cluster.rejected.clone();
+        let dealings = self.deal(epoch)?;
+        self.cluster.run(self.max_ticks)?;
+        let mut rejected = self
+            .cluster
+            .rejected
+            .iter()
+            .zip(rejected_before.iter())
+            .map(|(after, before)| after - before)
+            .collect::<Vec<_>>();
+
+        // 2. Complaints.
+        self.exchange_complaints(&dealings, &mut rejected)?;
+
+        // 3. Decryption and reconstruction.
+        let decryptions = self.exchange_decryptions(&mut rejected)?;
+
+        let mut report = EpochReport {
+            epoch,
+            contributors: BTreeMap::new(),
+            excluded: BTreeMap::new(),
+            beacons: BTreeMap::new(),
+            rejected: BTreeMap::new(),
+        };
+        for id in self.honest() {
+            report.contributors.insert(id, self.cluster.contributors(id));
+            report.excluded.insert(id, self.cluster.nodes[id].aggregator.excluded.clone());
+            report.rejected.insert(id, rejected[id]);
+            if let Ok((_, beacon)) = self.cluster.nodes[id].reconstruct(&decryptions[id]) {
+                report.beacons.insert(id, beacon);
+            }
+        }
+        Ok(report)
+    }
+
+    // Utility method moving every node to the given epoch, with an empty transcript.
+    fn start_epoch(&mut self, epoch: u64) -> Result<(), HarnessError<E>> {
+        for node in self.cluster.nodes.iter_mut() {
+            node.aggregator.start_epoch(epoch)?;
+            let (degree, n) = (node.aggregator.config.degree, node.aggregator.config.num_participants);
+            node.aggregator.transcript = PVSSTranscript::empty(degree, n);
+        }
+        Ok(())
+    }
+
+    // Utility method having every live node deal a share under the given tag, as
+    // its behavior dictates. Returns the dealings sent, signed in full.
+    fn deal(&mut self, tag: u64) -> Result<Vec<SignedDealing<E, SSIG<E>>>, HarnessError<E>> {
+        let n = self.cluster.nodes.len();
+        let mut dealings = vec![];
+        for id in self.cluster.live() {
+            let mut share = self.cluster.nodes[id].share(&mut self.rng)?;
+            match self.behavior(id) {
+                Behavior::Equivocate => {
+                    let other = self.cluster.nodes[id].share(&mut self.rng)?;
+                    self.send_share(id, tag, &share, 0..n / 2);
+                    self.send_share(id, tag, &other, n / 2..n);
+                    dealings.push(self.sign_dealing(id, other)?);
+                },
+                Behavior::BadEncryption => {
+                    share.pvss_share.encs[(id + 1) % n] += E::G1Projective::prime_subgroup_generator();
+                    self.send_share(id, tag, &share, 0..n);
+                },
+                _ => self.send_share(id, tag, &share, 0..n),
+            }
+            dealings.push(self.sign_dealing(id, share)?);
+        }
+        Ok(dealings)
+    }
+
+    // Utility method having a node sign one of its dealings in full.
+    fn sign_dealing(
+        &mut self,
+        id: usize,
+        share: PVSSAugmentedShare<E, SSIG<E>>,
+    ) -> Result<SignedDealing<E, SSIG<E>>, HarnessError<E>> {
+        let node = &self.cluster.nodes[id];
+        Ok(SignedDealing::sign(&mut self.rng, &node.aggregator.scheme_sig, node.dealer.private_key_sig.expose(), share)?)
+    }
+
+    // Utility method initiating the reliable broadcast of a node's share, with the
+    // given recipients only. Honest nodes send to all nodes, themselves included.
+    fn send_share<T, I>(&mut self, id: usize, tag: u64, share: &T, recipients: I)
+    where
+        T: CanonicalSerialize,
+        I: Iterator<Item = usize>,
+    {
+        let mut payload = Vec::with_capacity(share.serialized_size());
+        // serializing into a Vec cannot fail
+        share.serialize(&mut payload).unwrap();
+
+        let message = BroadcastMessage { kind: MessageKind::Send, origin: id, tag, payload }.to_bytes();
+        for to in recipients {
+            self.cluster.network.submit(id, to, message.clone());
+        }
+    }
+
+    // Utility method having every live node complain about the aggregated dealings
+    // whose encryption for it does not match the commitment (i.e., exactly those
+    // against which its complaint verifies), and running the network until it
+    // quiesces. Every node excludes the dealers of the valid complaints it receives;
+    // complaints that do not deserialize or fail verification are counted as rejected.
+    fn exchange_complaints(
+        &mut self,
+        dealings: &[SignedDealing<E, SSIG<E>>],
+        rejected: &mut [usize],
+    ) -> Result<(), HarnessError<E>> {
+        let n = self.cluster.nodes.len();
+        for id in self.cluster.live() {
+            for dealing in dealings.iter() {
+                let node = &self.cluster.nodes[id];
+                let aggregated = node
+                    .aggregator
+                    .transcript
+                    .contributions
+                    .get(&dealing.share.participant_id)
+                    .is_some_and(|contribution| contribution.decomp_proof == dealing.share.decomp_proof);
+                if !aggregated {
+                    continue;
+                }
+
+                let complaint = node.complain(&mut self.rng, dealing.clone())?;
+                if node.aggregator.verify_complaint(&complaint).is_err() {
+                    continue;
+                }
+                let mut message = Vec::with_capacity(complaint.serialized_size());
+                // serializing into a Vec cannot fail
+                complaint.serialize(&mut message).unwrap();
+                for to in 0..n {
+                    self.cluster.network.submit(id, to, message.clone());
+                }
+            }
+        }
+
+        let start = self.cluster.network.now();
+        while !self.cluster.network.is_idle() {
+            if self.cluster.network.now() - start >= self.max_ticks {
+                return Err(HarnessError::Timeout(self.max_ticks));
+            }
+
+            for envelope in self.cluster.network.tick() {
+                if self.cluster.crashed.contains(&envelope.to) {
+                    continue;
+                }
+                let aggregator = &mut self.cluster.nodes[envelope.to].aggregator;
+                let complaint = match Complaint::<E, SSIG<E>>::deserialize(&envelope.message[..]) {
+                    Ok(complaint) => complaint,
+                    Err(_) => {
+                        rejected[envelope.to] += 1;
+                        continue;
+                    },
+                };
+                // complaints against a dealer already excluded (e.g., duplicates) are ignored
+                if aggregator.excluded.contains(&complaint.dealing.share.participant_id) {
+                    continue;
+                }
+                if aggregator.exclude_dealer(&complaint).is_err() {
+                    rejected[envelope.to] += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Utility method having every live node send the decryption of its share to all,
+    // unless it withholds it, and running the network until it quiesces. Returns the
+    // valid decryptions each node received, by node id. Decryptions that are not
+    // sent by their originator, or fail verification, are counted as rejected.
+    fn exchange_decryptions(&mut self, rejected: &mut [usize]) -> Result<Vec<Vec<DecryptedShare<E>>>, HarnessError<E>> {
+        let n = self.cluster.nodes.len();
+        for id in self.cluster.live() {
+            if self.behavior(id) == Behavior::WithholdDecryption {
+                continue;
+            }
+            // a node whose transcript lacks contributions has nothing to decrypt
+            let transcript = match self.cluster.nodes[id].aggregator.finalize() {
+                Ok(transcript) => transcript.clone(),
+                Err(_) => continue,
+            };
+
+            let decryption = self.cluster.nodes[id].decrypt(&mut self.rng, &transcript)?;
+            let mut message = Vec::with_capacity(decryption.serialized_size());
+            // serializing into a Vec cannot fail
+            decryption.serialize(&mut message).unwrap();
+            for to in 0..n {
+                self.cluster.network.submit(id, to, message.clone());
+            }
+        }
+
+        let mut received = vec![BTreeMap::new(); n];
+        let start = self.cluster.network.now();
+        while !self.cluster.network.is_idle() {
+            if self.cluster.network.now() - start >= self.max_ticks {
+                return Err(HarnessError::Timeout(self.max_ticks));
+            }
+
+            for envelope in self.cluster.network.tick() {
+                if self.cluster.crashed.contains(&envelope.to) {
+                    continue;
+                }
+                let node = &self.cluster.nodes[envelope.to];
+                let decryption = DecryptedShare::<E>::deserialize(&envelope.message[..])
+                    .ok()
+                    .filter(|d| d.origin == envelope.from && node.verify_decryption(&node.aggregator.transcript, d).is_ok());
+                match decryption {
+                    Some(decryption) => {
+                        received[envelope.to].entry(decryption.origin).or_insert(decryption);
+                    },
+                    None => rejected[envelope.to] += 1,
+                }
+            }
+        }
+
+        Ok(received.into_iter().map(|decryptions| decryptions.into_values().collect()).collect())
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+
+    use crate::it::Faults;
+    use crate::simulation::{Behavior, Simulation};
+
+    use ark_std::collections::BTreeSet;
+
+    #[test]
+    fn test_simulation() {
+        let (n, t) = (4, 1);
+        let all = (0..n).collect::<BTreeSet<_>>();
+
+        // Honest epochs over a delaying, reordering and duplicating network agree on
+        // all contributions, and yield fresh beacon values.
+        let faults = Faults { duplicate_rate: 0.2, max_delay: 3, reorder: true, ..Default::default() };
+        let mut simulation = Simulation::<E>::new(n, t, faults, 1).unwrap();
+        let first = simulation.run_epoch().unwrap();
+        let second = simulation.run_epoch().unwrap();
+        assert_eq!((first.epoch, second.epoch), (0, 1));
+        assert_eq!(first.contributors(), Some(&all));
+        assert!(first.agreed().is_some() && second.agreed().is_some());
+        assert_ne!(first.agreed(), second.agreed());
+        assert!(first.rejected.values().all(|rejected| *rejected == 0));
+
+        // An equivocating dealer gets none of its shares delivered.
+        let mut simulation = Simulation::<E>::new(n, t, Faults::default(), 2).unwrap();
+        simulation.script(3, Behavior::Equivocate).unwrap();
+        let report = simulation.run_epoch().unwrap();
+        assert_eq!(simulation.honest(), vec![0, 1, 2]);
+        assert_eq!(report.contributors(), Some(&(0..3).collect()));
+        assert!(report.agreed().is_some());
+
+        // A share with a bad encryption passes share verification, but the node it
+        // wronged complains, and every honest node excludes the dealer.
+        let mut simulation = Simulation::<E>::new(n, t, Faults::default(), 3).unwrap();
+        simulation.script(1, Behavior::BadEncryption).unwrap();
+        let report = simulation.run_epoch().unwrap();
+        let excluded = [1].iter().cloned().collect::<BTreeSet<_>>();
+        assert_eq!(report.contributors(), Some(&[0, 2, 3].iter().cloned().collect()));
+        assert!(report.excluded.values().all(|dealers| *dealers == excluded));
+        assert!(report.rejected.values().all(|rejected| *rejected == 0));
+        assert!(report.agreed().is_some());
+
+        // Withheld decryptions are tolerated as long as t + 1 are released.
+        let mut simulation = Simulation::<E>::new(n, t, Faults::default(), 4).unwrap();
+        simulation.script(2, Behavior::WithholdDecryption).unwrap();
+        simulation.script(3, Behavior::WithholdDecryption).unwrap();
+        assert!(simulation.run_epoch().unwrap().agreed().is_some());
+
+        let mut simulation = Simulation::<E>::new(n, 2, Faults::default(), 5).unwrap();
+        simulation.script(2, Behavior::WithholdDecryption).unwrap();
+        simulation.script(3, Behavior::WithholdDecryption).unwrap();
+        let report = simulation.run_epoch().unwrap();
+        assert_eq!(report.contributors(), Some(&all));
+        assert!(report.beacons.is_empty() && report.agreed().is_none());
+
+        simulation.script(2, Behavior::Honest).unwrap();
+        assert_eq!(simulation.behavior(2), Behavior::Honest);
+        assert!(simulation.script(n, Behavior::Equivocate).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{digest_bytes, Digest};
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/* Fiat-Shamir transcripts with domain separation, in the style of Merlin. A
+   transcript is a running digest which absorbs labeled, length-prefixed messages,
+   so that no two different sequences of messages (or labels) can collide, and from
+   which labeled challenges are squeezed. Every challenge is absorbed back into the
+   state, so later challenges depend on earlier ones.
+
+   Transcripts created for a session additionally absorb the digest of the config,
+   the epoch and the id of the prover, so that proofs and signatures produced in one
+   session (or by one participant) cannot be replayed in another, nor in another
+   protocol, which gets a different protocol label.
+*/
+
+const TRANSCRIPT_DOMAIN: &[u8] = b"OPTRAND-TRANSCRIPT-V1";
+
+// Struct Session identifies the protocol run a proof or signature belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub config_digest: Digest,   // digest of the config of the run
+    pub epoch: u64,              // the epoch (round) of the run
+    pub participant_id: usize,   // id of the prover (or signer)
+}
+
+// Struct Transcript models the running state of a Fiat-Shamir transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+    state: Digest,   // digest of everything absorbed so far
+}
+
+impl Transcript {
+
+    // Function for starting a transcript for the protocol with the given label.
+    pub fn new(protocol: &[u8]) -> Self {
+        let mut transcript = Self { state: digest_bytes(TRANSCRIPT_DOMAIN) };
+        transcript.append_message(b"protocol", protocol);
+        transcript
+    }
+
+    // Function for starting a transcript for the given protocol, bound to a session.
+    pub fn for_session(protocol: &[u8], session: &Session) -> Self {
+        let mut transcript = Self::new(protocol);
+        transcript.append_message(b"config", &session.config_digest.0);
+        transcript.append_u64(b"epoch", session.epoch);
+        transcript.append_u64(b"participant", session.participant_id as u64);
+        transcript
+    }
+
+    // Method for absorbing a labeled message. Both the label and the message are
+    // length-prefixed, which makes the encoding of the transcript unambiguous.
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        let mut bytes = self.state.0.to_vec();
+        bytes.extend_from_slice(&(label.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(label);
+        bytes.extend_from_slice(&(message.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(message);
+        self.state = digest_bytes(&bytes);
+    }
+
+    // Method for absorbing a labeled integer.
+    pub fn append_u64(&mut self, label: &[u8], value: u64) {
+        self.append_message(label, &value.to_be_bytes());
+    }
+
+    // Method for absorbing the canonical serialization of a labeled item.
+    pub fn append<T: CanonicalSerialize>(&mut self, label: &[u8], item: &T) -> Result<(), SerializationError> {
+        let mut bytes = vec![];
+        item.serialize(&mut bytes)?;
+        self.append_message(label, &bytes);
+        Ok(())
+    }
+
+    // Method for squeezing a labeled 32-byte challenge, which is absorbed back.
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> Digest {
+        self.append_message(b"challenge", label);
+        let challenge = self.state;
+        self.append_message(b"squeezed", &challenge.0);
+        challenge
+    }
+
+    // Method for squeezing a labeled challenge scalar, sampled uniformly from the
+    // field by rejection sampling from the challenge bytes (as in hash_to_field).
+    pub fn challenge_scalar<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        let mut rng = ChaChaRng::from_seed(self.challenge_bytes(label).0);
+        loop {
+            let bytes: Vec<u8> = (0..F::zero().serialized_size())
+                .map(|_| rng.gen())
+                .collect();
+            if let Some(c) = F::from_random_bytes(&bytes) {
+                return c;
+            }
+        }
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use crate::transcript::{Session, Transcript};
+    use crate::Digest;
+
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_domain_separation() {
+        let challenge = |transcript: &mut Transcript| transcript.challenge_scalar::<Fr>(b"c");
+
+        // Identical transcripts yield identical challenges.
+        let mut first = Transcript::new(b"test");
+        let mut second = Transcript::new(b"test");
+        first.append_message(b"m", b"ab");
+        second.append_message(b"m", b"ab");
+        assert_eq!(challenge(&mut first), challenge(&mut second));
+
+        // Shifting bytes between label and message, or between messages, changes them.
+        let mut shifted = Transcript::new(b"test");
+        shifted.append_message(b"ma", b"b");
+        let mut split = Transcript::new(b"test");
+        split.append_message(b"m", b"a");
+        split.append_message(b"m", b"b");
+        let mut reference = Transcript::new(b"test");
+        reference.append_message(b"m", b"ab");
+        let expected = challenge(&mut reference);
+        assert_ne!(challenge(&mut shifted), expected);
+        assert_ne!(challenge(&mut split), expected);
+
+        // Challenges depend on the protocol, on the session and on earlier challenges.
+        let session = Session { config_digest: Digest([1u8; 32]), epoch: 3, participant_id: 2 };
+        let mut other_epoch = Transcript::for_session(b"test", &Session { epoch: 4, ..session });
+        let mut other_protocol = Transcript::for_session(b"other", &session);
+        let mut reference = Transcript::for_session(b"test", &session);
+        let expected = challenge(&mut reference.clone());
+        assert_ne!(challenge(&mut other_epoch), expected);
+        assert_ne!(challenge(&mut other_protocol), expected);
+        assert_eq!(challenge(&mut reference), expected);
+        assert_ne!(challenge(&mut reference), expected);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::beacon::proof::BeaconProof;
+use crate::ceremony::SSIG;
+use crate::modified_scrape::{
+    aggregator::PVSSAggregator,
+    config::{Config, EvaluationPoints, ShareLayout},
+    participant::Participant,
+    share::PVSSTranscript,
+    srs::SRS,
+};
+use crate::signature::schnorr::{srs::SRS as SchnorrSRS, SchnorrVersion};
+use crate::{digest_bytes, Hash, GT};
+
+use ark_bls12_381::{Bls12_381 as E, G1Affine, G2Affine};
+use ark_serialize::*;
+use ark_std::collections::BTreeMap;
+
+use rand::thread_rng;
+use wasm_bindgen::prelude::*;
+
+/* JavaScript bindings for client-side verification of committee randomness, over
+   BLS12-381 with Schnorr identity keys. Inputs are the canonical (compressed)
+   serializations of the bundles below; errors are surfaced as JS exceptions
+   carrying the error message, while a well-formed but invalid input yields false.
+   Identity keys are assumed to share the SRS generator g_1.
+*/
+
+// Struct TranscriptBundle is everything needed to verify a transcript on its own.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct TranscriptBundle {
+    pub g1: G1Affine,
+    pub g2: G2Affine,
+    pub g2_prime: G2Affine,
+    pub degree: u64,
+    pub public_keys: Vec<G1Affine>,              // identity keys of participants 0, ..., n-1
+    pub transcript: PVSSTranscript<E, SSIG<E>>,
+}
+
+// Struct BeaconBundle is everything needed to verify a beacon value on its own.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct BeaconBundle {
+    pub g1: G1Affine,
+    pub g2: G2Affine,
+    pub g2_prime: G2Affine,
+    pub proof: BeaconProof<E>,
+    pub beacon: GT<E>,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_DEFINITIONS: &'static str = r#"
+/** Canonical serialization of a TranscriptBundle: (g1, g2, g2_prime, degree, public_keys, transcript). */
+export type TranscriptBundleBytes = Uint8Array;
+/** Canonical serialization of a BeaconBundle: (g1, g2, g2_prime, proof, beacon). */
+export type BeaconBundleBytes = Uint8Array;
+/** Lowercase hex encoding of a 32-byte digest. */
+export type HexDigest = string;
+"#;
+
+// Utility function for turning a Rust error into a JS exception.
+fn js_error<T: ToString>(e: T) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+// Utility function for recovering the config described by a bundle.
+fn config(g1: G1Affine, g2: G2Affine, g2_prime: G2Affine, degree: u64, num_participants: usize) -> Config<E> {
+    Config {
+        srs: SRS { g1, g2, g2_prime },
+        degree: degree as usize,
+        num_participants,
+        layout: ShareLayout::Dense,
+        points: EvaluationPoints::Integers,
+    }
+}
+
+// Function for verifying a serialized TranscriptBundle.
+#[wasm_bindgen(js_name = verifyTranscript)]
+pub fn verify_transcript(bytes: &[u8]) -> Result<bool, JsValue> {
+    let bundle = TranscriptBundle::deserialize(bytes).map_err(js_error)?;
+
+    let participants = bundle
+        .public_keys
+        .iter()
+        .enumerate()
+        .map(|(id, pk)| (id, Participant::new(id, *pk)))
+        .collect::<BTreeMap<_, _>>();
+
+    let config = config(bundle.g1, bundle.g2, bundle.g2_prime, bundle.degree, participants.len());
+    let scheme_sig = SSIG::<E> { srs: SchnorrSRS { g_public_key: bundle.g1 }, version: SchnorrVersion::KeyBound };
+    let aggregator = PVSSAggregator::new(config, scheme_sig, participants);
+
+    Ok(aggregator.transcript_verify(&mut thread_rng(), &bundle.transcript).is_ok())
+}
+
+// Function for verifying a serialized BeaconBundle.
+#[wasm_bindgen(js_name = verifyBeacon)]
+pub fn verify_beacon(bytes: &[u8]) -> Result<bool, JsValue> {
+    let bundle = BeaconBundle::deserialize(bytes).map_err(js_error)?;
+    let config = config(bundle.g1, bundle.g2, bundle.g2_prime, 0, 0);
+
+    Ok(bundle.proof.verify(&config, &bundle.beacon).is_ok())
+}
+
+// Function returning the protocol digest of arbitrary bytes.
+#[wasm_bindgen(js_name = digestBytes)]
+pub fn digest(bytes: &[u8]) -> String {
+    digest_bytes(bytes).to_string()
+}
+
+// Function returning the digest of a serialized transcript, as used in certificates
+// and logs.
+#[wasm_bindgen(js_name = transcriptDigest)]
+pub fn transcript_digest(bytes: &[u8]) -> Result<String, JsValue> {
+    let transcript = PVSSTranscript::<E, SSIG<E>>::deserialize(bytes).map_err(js_error)?;
+    Ok(transcript.digest().to_string())
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use ark_bls12_381::{Bls12_381 as E};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+
+    use crate::beacon::proof::BeaconProof;
+    use crate::modified_scrape::{share::PVSSTranscript, srs::SRS, tests::{setup_nodes, SSIG}};
+    use crate::wasm::{transcript_digest, verify_beacon, verify_transcript, BeaconBundle, TranscriptBundle};
+    use crate::{Hash, Scalar};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_verify_beacon() {
+        let rng = &mut thread_rng();
+        let srs = SRS::<E>::setup(rng).unwrap();
+
+        let s = Scalar::<E>::rand(rng).into_repr();
+        let proof = BeaconProof::<E> {
+            point: srs.g1.mul(s).into_affine(),
+            commitment: srs.g2.mul(s).into_affine(),
+        };
+        let beacon = E::pairing(proof.point, srs.g2_prime);
+
+        let mut bundle = BeaconBundle { g1: srs.g1, g2: srs.g2, g2_prime: srs.g2_prime, proof, beacon };
+        let mut bytes = vec![];
+        bundle.serialize(&mut bytes).unwrap();
+        assert!(verify_beacon(&bytes).unwrap());
+
+        bundle.beacon = beacon * beacon;
+        let mut bytes = vec![];
+        bundle.serialize(&mut bytes).unwrap();
+        assert!(!verify_beacon(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_verify_transcript() {
+        let rng = &mut thread_rng();
+        let (mut nodes, _) = setup_nodes(4, 1);
+
+        let share = nodes[0].share(rng).unwrap();
+        let transcript = PVSSTranscript::<E, SSIG>::from_share(1, 4, &share);
+        let config = &nodes[0].aggregator.config;
+
+        let bundle = TranscriptBundle {
+            g1: config.srs.g1,
+            g2: config.srs.g2,
+            g2_prime: config.srs.g2_prime,
+            degree: 1,
+            public_keys: nodes[0].aggregator.participants.values().map(|p| p.public_key_sig).collect(),
+            transcript: transcript.clone(),
+        };
+        let mut bytes = vec![];
+        bundle.serialize(&mut bytes).unwrap();
+        assert!(verify_transcript(&bytes).unwrap());
+
+        let mut bytes = vec![];
+        transcript.serialize(&mut bytes).unwrap();
+        assert_eq!(transcript_digest(&bytes).unwrap(), transcript.digest().to_string());
+    }
+}
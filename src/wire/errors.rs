@@ -0,0 +1,16 @@
+use ark_serialize::SerializationError;
+use thiserror::Error;
+
+// Enumeration whose variants model the various errors that can occur
+// while opening a message envelope.
+#[derive(Error, Debug)]
+pub enum WireError {
+    #[error("Message is too short to hold an envelope header")]
+    TruncatedHeader,
+    #[error("Protocol version {0} is not supported (supported: {1} to {2})")]
+    UnsupportedVersion(u8, u8, u8),
+    #[error("Message uses unsupported features: {0:#x}")]
+    UnsupportedFeatures(u64),
+    #[error("Malformed payload: {0}")]
+    PayloadError(#[from] SerializationError),
+}
@@ -0,0 +1,200 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use errors::WireError;
+
+use std::convert::TryInto;
+
+pub mod errors;
+
+/* Message envelopes. Every protocol message (shares, transcripts, epoch keys,
+   slashing evidence, beacon proofs) travels wrapped in an Envelope:
+
+   version (1 byte) || features (8 bytes, little-endian) || canonical payload
+
+   The version byte selects the encoding and checks of the payload, while the
+   feature bitmap flags optional behaviour the payload relies on. Nodes advertise
+   their Capabilities to each other, and senders pick the highest version and the
+   features both sides understand, so that changes to proof encodings or checks can
+   roll out one node at a time instead of requiring a synchronized upgrade.
+*/
+
+// The protocol version this build speaks natively.
+pub const PROTOCOL_VERSION: u8 = 1;
+
+// The oldest protocol version this build still accepts.
+pub const MIN_PROTOCOL_VERSION: u8 = 1;
+
+const HEADER_SIZE: usize = 9;   // version byte and feature bitmap
+
+// Feature bits.
+pub const FEATURE_FIAT_SHAMIR_DEGREE_CHECK: u64 = 1 << 0;   // coding check seeded from the commitments
+pub const FEATURE_EPOCH_KEYS: u64 = 1 << 1;                 // encryptions under per-epoch keys
+pub const FEATURE_WEIGHTED_CONTRIBUTIONS: u64 = 1 << 2;     // transcripts carry contribution weights
+
+// The features this build understands.
+pub const SUPPORTED_FEATURES: u64 =
+    FEATURE_FIAT_SHAMIR_DEGREE_CHECK | FEATURE_EPOCH_KEYS | FEATURE_WEIGHTED_CONTRIBUTIONS;
+
+
+// Struct Capabilities describes the versions and features a node can handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    pub min_version: u8,   // oldest version accepted
+    pub max_version: u8,   // newest version spoken
+    pub features: u64,     // bitmap of understood features
+}
+
+impl Capabilities {
+
+    // Function returning the capabilities of this build.
+    pub fn local() -> Self {
+        Self {
+            min_version: MIN_PROTOCOL_VERSION,
+            max_version: PROTOCOL_VERSION,
+            features: SUPPORTED_FEATURES,
+        }
+    }
+
+    // Method checking whether a version is within the supported range.
+    pub fn supports_version(&self, version: u8) -> bool {
+        self.min_version <= version && version <= self.max_version
+    }
+}
+
+
+// Function returning the highest version both parties support, if any.
+pub fn max_common_version(ours: &Capabilities, theirs: &Capabilities) -> Option<u8> {
+    let version = ours.max_version.min(theirs.max_version);
+    if ours.supports_version(version) && theirs.supports_version(version) {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+
+// Function returning the features both parties understand.
+pub fn common_features(ours: &Capabilities, theirs: &Capabilities) -> u64 {
+    ours.features & theirs.features
+}
+
+
+// Function returning the capabilities to use when talking to every member of a
+// committee: the highest version and the features all of them support.
+pub fn negotiate<'a, I>(ours: &Capabilities, peers: I) -> Option<Capabilities>
+where
+    I: IntoIterator<Item = &'a Capabilities>,
+{
+    peers.into_iter().try_fold(*ours, |acc, peer| {
+        let version = max_common_version(&acc, peer)?;
+        Some(Capabilities {
+            min_version: acc.min_version.max(peer.min_version),
+            max_version: version,
+            features: common_features(&acc, peer),
+        })
+    })
+}
+
+
+// Struct Envelope wraps a protocol message with its version and feature bitmap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope<T> {
+    pub version: u8,      // protocol version of the payload
+    pub features: u64,    // features the payload relies on
+    pub payload: T,       // the wrapped message
+}
+
+impl<T: CanonicalSerialize + CanonicalDeserialize> Envelope<T> {
+
+    // Function for wrapping a message with the given version and features.
+    pub fn new(version: u8, features: u64, payload: T) -> Self {
+        Self { version, features, payload }
+    }
+
+    // Function for wrapping a message as negotiated with its recipients.
+    pub fn negotiated(negotiated: &Capabilities, payload: T) -> Self {
+        Self::new(negotiated.max_version, negotiated.features, payload)
+    }
+
+    // Method for encoding the envelope.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WireError> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.payload.serialized_size());
+        bytes.push(self.version);
+        bytes.extend_from_slice(&self.features.to_le_bytes());
+        self.payload.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // Function for decoding an envelope, rejecting versions and features outside
+    // of the given capabilities before the payload is parsed.
+    pub fn from_bytes(bytes: &[u8], capabilities: &Capabilities) -> Result<Self, WireError> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(WireError::TruncatedHeader);
+        }
+
+        let version = bytes[0];
+        if !capabilities.supports_version(version) {
+            return Err(WireError::UnsupportedVersion(version, capabilities.min_version, capabilities.max_version));
+        }
+
+        let features = u64::from_le_bytes(bytes[1..HEADER_SIZE].try_into().unwrap());
+        let unknown = features & !capabilities.features;
+        if unknown != 0 {
+            return Err(WireError::UnsupportedFeatures(unknown));
+        }
+
+        let payload = T::deserialize(&bytes[HEADER_SIZE..])?;
+        Ok(Self { version, features, payload })
+    }
+
+    // Method checking whether the envelope relies on the given feature.
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature != 0
+    }
+}
+
+
+/* Unit tests: */
+
+#[cfg(test)]
+mod test {
+    use super::{
+        errors::WireError, max_common_version, negotiate, Capabilities, Envelope,
+        FEATURE_EPOCH_KEYS, FEATURE_FIAT_SHAMIR_DEGREE_CHECK, FEATURE_WEIGHTED_CONTRIBUTIONS,
+        MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+    };
+    use crate::{digest_bytes, digest_bytes_with, DigestAlgorithm};
+
+    #[test]
+    fn test_negotiation_and_envelopes() {
+        let ours = Capabilities { min_version: 1, max_version: 3, features: FEATURE_EPOCH_KEYS | FEATURE_WEIGHTED_CONTRIBUTIONS };
+        let old = Capabilities { min_version: 1, max_version: 2, features: FEATURE_EPOCH_KEYS | FEATURE_FIAT_SHAMIR_DEGREE_CHECK };
+        let new = Capabilities { min_version: 3, max_version: 4, features: FEATURE_EPOCH_KEYS };
+
+        assert_eq!(max_common_version(&ours, &old), Some(2));
+        assert_eq!(max_common_version(&ours, &new), Some(3));
+        assert_eq!(max_common_version(&old, &new), None);
+
+        let committee = negotiate(&ours, &[old]).unwrap();
+        assert_eq!((committee.max_version, committee.features), (2, FEATURE_EPOCH_KEYS));
+        assert!(negotiate(&ours, &[old, new]).is_none());
+
+        // Envelopes round-trip, but only within the receiver's capabilities.
+        let envelope = Envelope::negotiated(&committee, 42u64);
+        let bytes = envelope.to_bytes().unwrap();
+        assert_eq!(Envelope::<u64>::from_bytes(&bytes, &old).unwrap(), envelope);
+        assert!(matches!(Envelope::<u64>::from_bytes(&bytes, &new), Err(WireError::UnsupportedVersion(2, 3, 4))));
+
+        let weighted = Envelope::new(1, FEATURE_WEIGHTED_CONTRIBUTIONS, 42u64).to_bytes().unwrap();
+        assert!(matches!(Envelope::<u64>::from_bytes(&weighted, &old), Err(WireError::UnsupportedFeatures(FEATURE_WEIGHTED_CONTRIBUTIONS))));
+        assert!(matches!(Envelope::<u64>::from_bytes(&bytes[..4], &old), Err(WireError::TruncatedHeader)));
+    }
+
+    #[test]
+    fn test_digest_algorithms() {
+        // Every version we accept fixes its digest algorithm, whatever the features.
+        assert!((MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).all(|version| DigestAlgorithm::for_version(version).is_some()));
+        assert_eq!(DigestAlgorithm::native(), DigestAlgorithm::Blake2s);
+        assert_eq!(digest_bytes(b"optrand"), digest_bytes_with(DigestAlgorithm::Blake2s, b"optrand"));
+        assert!(DigestAlgorithm::for_version(PROTOCOL_VERSION + 1).is_none());
+    }
+}